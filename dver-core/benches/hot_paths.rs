@@ -0,0 +1,99 @@
+//! Benchmark dei percorsi più usati: parsing e risoluzione delle versioni,
+//! deserializzazione dei metadati dei rilasci e lettura degli SDK installati.
+//!
+//! `cargo bench -p dver-core`; per confrontare con un riferimento salvato:
+//! `cargo bench -p dver-core -- --save-baseline main` e poi `--baseline main`.
+
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use dver_core::inventory::{resolve_sdk, scan_root_sdks};
+use dver_core::project::GlobalJson;
+use dver_core::releases::ChannelReleases;
+use dver_core::resolve::newest_matching;
+use dver_core::version::{sort_versions, SdkVersion, VersionSpec};
+
+const FIXTURE: &str = include_str!("../tests/fixtures/8.0/releases.json");
+
+/// Versioni di SDK come quelle di un indice completo, previews comprese.
+fn sdk_versions() -> Vec<String> {
+    let mut versions = Vec::new();
+    for major in 3..=10 {
+        for band in 1..=4 {
+            for patch in 0..30 {
+                versions.push(format!("{}.0.{}{:02}", major, band, patch));
+            }
+        }
+        versions.push(format!("{}.0.100-preview.7.24407.12", major));
+        versions.push(format!("{}.0.100-rc.2.24474.11", major));
+    }
+    versions
+}
+
+/// Un releases.json di oltre 5 MB, come quelli dei canali con molti rilasci: i
+/// rilasci della fixture ripetuti con versioni diverse.
+fn large_releases_json() -> String {
+    let mut document: Value = serde_json::from_str(FIXTURE).expect("valid fixture");
+    let template = document["releases"].as_array().expect("releases array").clone();
+    let mut releases = Vec::new();
+    for copy in 0..1200 {
+        for release in &template {
+            let mut release = release.clone();
+            release["release-version"] = Value::String(format!("8.0.{}", copy));
+            releases.push(release);
+        }
+    }
+    document["releases"] = Value::Array(releases);
+    serde_json::to_string_pretty(&document).expect("serializable")
+}
+
+fn versions(c: &mut Criterion) {
+    let versions = sdk_versions();
+    let mut group = c.benchmark_group("versions");
+    group.throughput(Throughput::Elements(versions.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| versions.iter().filter_map(|v| v.parse::<SdkVersion>().ok()).count())
+    });
+    group.bench_function("sort", |b| {
+        b.iter_batched(|| versions.iter().rev().cloned().collect(), |mut v| sort_versions(&mut v), BatchSize::SmallInput)
+    });
+    let spec: VersionSpec = "8.0.4xx".parse().expect("valid spec");
+    group.bench_function("newest_matching", |b| b.iter(|| newest_matching(black_box(&spec), &versions)));
+    let pin = GlobalJson {
+        path: Path::new("global.json").to_path_buf(),
+        version: Some("8.0.300".to_string()),
+        roll_forward: None,
+        ..Default::default()
+    };
+    group.bench_function("resolve_global_json", |b| b.iter(|| resolve_sdk(&versions, Some(black_box(&pin)))));
+    group.finish();
+}
+
+fn metadata(c: &mut Criterion) {
+    let large = large_releases_json();
+    let mut group = c.benchmark_group("metadata");
+    group.throughput(Throughput::Bytes(large.len() as u64));
+    group.sample_size(20);
+    group.bench_function("releases_json", |b| {
+        b.iter(|| serde_json::from_str::<ChannelReleases>(black_box(&large)).expect("valid releases.json"))
+    });
+    group.finish();
+}
+
+fn inventory(c: &mut Criterion) {
+    let root = TempDir::new().expect("temp root");
+    for version in sdk_versions().iter().step_by(4) {
+        let dir = root.path().join("sdk").join(version);
+        fs::create_dir_all(&dir).expect("sdk dir");
+        fs::write(dir.join("dotnet.dll"), b"").expect("sdk file");
+    }
+    c.bench_function("inventory/scan_root_sdks", |b| b.iter(|| scan_root_sdks(root.path()).expect("readable root")));
+}
+
+criterion_group!(benches, versions, metadata, inventory);
+criterion_main!(benches);
@@ -0,0 +1,151 @@
+//! SDK installati senza dver (script, pacchetti, installer) e registrati con
+//! `dver adopt`. I record stanno in `adopted.json` nella cartella di stato.
+//!
+//! Un SDK adottato è *gestito* se la sua radice è tra quelle configurate
+//! (`install_root` o `[roots]`), e allora `uninstall` può rimuoverlo; altrimenti è
+//! *esterno* e dver si limita a conoscerne l'origine.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::expand_home;
+use crate::provenance::same_root;
+use crate::{Config, DverError, ResultExt};
+
+/// Chi gestisce un SDK adottato.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ownership {
+    /// La radice è configurata: dver può rimuovere l'SDK
+    Managed,
+    /// La radice non è configurata: dver non la tocca
+    External,
+}
+
+impl std::fmt::Display for Ownership {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Ownership::Managed => "managed",
+            Ownership::External => "external",
+        })
+    }
+}
+
+/// Un SDK trovato sul disco e registrato da `dver adopt`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Adoption {
+    pub version: String,
+    /// Radice che contiene `sdk/<versione>`
+    pub root: PathBuf,
+    pub ownership: Ownership,
+    pub adopted_at: DateTime<Utc>,
+    /// Radice originale, se l'SDK è stato spostato nella radice gestita
+    pub moved_from: Option<PathBuf>,
+}
+
+impl Adoption {
+    /// Record adottato adesso.
+    pub fn new(version: String, root: PathBuf, ownership: Ownership, moved_from: Option<PathBuf>) -> Adoption {
+        Adoption { version, root, ownership, adopted_at: DateTime::<Utc>::from(SystemTime::now()), moved_from }
+    }
+}
+
+/// Il file di stato con gli SDK adottati.
+pub fn state_file(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("adopted.json"))
+}
+
+/// Tutti i record salvati; un file mancante equivale a nessun record.
+pub fn load_all(config: &Config) -> Result<Vec<Adoption>, DverError> {
+    let Some(path) = state_file(config).filter(|p| p.is_file()) else {
+        return Ok(Vec::new());
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid adoption records in {}", path.display()))
+}
+
+fn save_all(config: &Config, records: &[Adoption]) -> Result<(), DverError> {
+    let path = state_file(config)
+        .ok_or_else(|| DverError::NotFound("Cannot determine the dver state directory".to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(records)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Record dell'SDK `version` nella radice `root`, se è stato adottato.
+pub fn find(config: &Config, root: &Path, version: &str) -> Result<Option<Adoption>, DverError> {
+    Ok(load_all(config)?.into_iter().find(|a| a.version == version && same_root(&a.root, root)))
+}
+
+/// Salva `adoptions`, sostituendo i record precedenti degli stessi SDK.
+pub fn record(config: &Config, adoptions: &[Adoption]) -> Result<(), DverError> {
+    let mut records = load_all(config)?;
+    records.retain(|r| !adoptions.iter().any(|a| a.version == r.version && same_root(&a.root, &r.root)));
+    records.extend_from_slice(adoptions);
+    save_all(config, &records)
+}
+
+/// Dimentica l'SDK `version` in `root`, es. dopo la disinstallazione.
+pub fn forget(config: &Config, root: &Path, version: &str) -> Result<(), DverError> {
+    let mut records = load_all(config)?;
+    let before = records.len();
+    records.retain(|a| !(a.version == version && same_root(&a.root, root)));
+    if records.len() == before {
+        return Ok(());
+    }
+    save_all(config, &records)
+}
+
+/// Gestito se `root` è una delle radici configurate.
+pub fn ownership_of(config: &Config, root: &Path) -> Ownership {
+    if config.managed_roots().iter().any(|r| same_root(r, root)) {
+        Ownership::Managed
+    } else {
+        Ownership::External
+    }
+}
+
+/// Nome per `root` in `[roots]`: quello già usato per la stessa cartella, altrimenti
+/// uno ricavato dal percorso (`/usr/share/dotnet` -> `usr-share-dotnet`) e reso unico.
+pub fn root_name(root: &Path, roots: &BTreeMap<String, PathBuf>) -> String {
+    if let Some((name, _)) = roots.iter().find(|(_, path)| same_root(&expand_home(path), root)) {
+        return name.clone();
+    }
+    let mut slug = String::new();
+    for c in root.to_string_lossy().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = match slug.trim_end_matches('-') {
+        "" => "adopted".to_string(),
+        s => s.to_string(),
+    };
+    let mut name = slug.clone();
+    let mut n = 2;
+    while roots.contains_key(&name) {
+        name = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    name
+}
+
+/// Sposta il contenuto della radice `src` in `dst` e rimuove quello che resta (i
+/// file già presenti in `dst`). Dove si può, al posto di `src` resta un link a `dst`,
+/// così i link esistenti come `/usr/bin/dotnet` continuano a funzionare.
+pub fn relocate(src: &Path, dst: &Path) -> Result<(), DverError> {
+    crate::install::merge_root(src, dst).with_context(|| format!(
+        "Failed to move {} into {} (both roots must be on the same file system)", src.display(), dst.display()))?;
+    fs::remove_dir_all(src).with_context(|| format!("Failed to remove {}", src.display()))?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(dst, src).with_context(|| format!("Failed to link {} to {}", src.display(), dst.display()))?;
+    Ok(())
+}
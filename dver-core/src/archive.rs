@@ -0,0 +1,256 @@
+//! Estrazione degli archivi ufficiali degli SDK (`.tar.gz` su Linux e macOS, `.zip`
+//! su Windows), usata da `install_method = "archive"`.
+//!
+//! L'archivio si legge dal disco in streaming: si decomprime un blocco alla volta e
+//! ogni file va direttamente nella destinazione, senza copie intermedie in memoria.
+//! Si conservano i permessi di esecuzione e i link simbolici; le voci che uscirebbero
+//! dalla destinazione (`..`, percorsi assoluti, link che puntano fuori) sono rifiutate.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use sha2::{Digest, Sha512};
+
+use crate::http::Progress;
+use crate::{DverError, ResultExt};
+
+/// Dimensione del buffer di lettura dell'archivio compresso.
+const READ_BUFFER: usize = 256 * 1024;
+
+/// Formato dell'archivio, dedotto dal nome del file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Formato di `name`, se è uno di quelli supportati.
+    pub fn of(name: &str) -> Option<ArchiveKind> {
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Cosa è stato estratto.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Extracted {
+    /// File regolari e link scritti
+    pub files: u64,
+    /// Byte scritti, una volta decompressi
+    pub bytes: u64,
+}
+
+/// SHA-512 in esadecimale del contenuto di `path`, il formato degli hash dei metadati.
+pub fn sha512_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha512::new();
+    let mut buffer = vec![0u8; READ_BUFFER];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Su Windows aggiunge il prefisso `\\?\` ai percorsi assoluti, così i file degli SDK
+/// più annidati non si fermano al limite di 260 caratteri; altrove non cambia nulla.
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let text = path.as_os_str().to_string_lossy();
+        if path.is_absolute() && !text.starts_with(r"\\?\") {
+            return match text.strip_prefix(r"\\") {
+                Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+                None => PathBuf::from(format!(r"\\?\{}", text)),
+            };
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Lettore che riporta a `progress` i byte compressi letti finora.
+struct Counting<'a, R> {
+    inner: R,
+    read: u64,
+    total: Option<u64>,
+    progress: &'a Progress<'a>,
+}
+
+impl<R: Read> Read for Counting<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read as u64;
+        (self.progress)(self.read, self.total);
+        Ok(read)
+    }
+}
+
+/// Estrae `archive` in `dest`, creandola se serve. `progress` riceve i byte
+/// dell'archivio già letti e la sua dimensione.
+pub fn extract(archive: &Path, dest: &Path, progress: &Progress<'_>) -> Result<Extracted, DverError> {
+    let name = archive.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let kind = ArchiveKind::of(&name)
+        .ok_or_else(|| DverError::Usage(format!("Unsupported archive {}: expected .tar.gz or .zip", archive.display())))?;
+    let dest = long_path(dest);
+    fs::create_dir_all(&dest).with_context(|| format!("Failed to create {}", dest.display()))?;
+    let file = File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    let total = file.metadata().ok().map(|m| m.len());
+    let context = || format!("Failed to extract {} into {}", archive.display(), dest.display());
+    match kind {
+        ArchiveKind::TarGz => {
+            let reader = Counting { inner: BufReader::with_capacity(READ_BUFFER, file), read: 0, total, progress };
+            extract_tar_gz(reader, &dest).with_context(context)
+        }
+        ArchiveKind::Zip => extract_zip(file, total, &dest, progress).with_context(context),
+    }
+}
+
+fn extract_tar_gz(reader: impl Read, dest: &Path) -> io::Result<Extracted> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    archive.set_preserve_permissions(true);
+    archive.set_preserve_mtime(true);
+    archive.set_unpack_xattrs(false);
+    archive.set_overwrite(true);
+
+    let mut extracted = Extracted::default();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+        let size = entry.header().size().unwrap_or(0);
+        if !entry.unpack_in(dest)? {
+            let path = entry.path().map(|p| p.display().to_string()).unwrap_or_default();
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("entry {} is outside the destination", path)));
+        }
+        if kind.is_file() || kind.is_symlink() || kind.is_hard_link() {
+            extracted.files += 1;
+            extracted.bytes += size;
+        }
+    }
+    Ok(extracted)
+}
+
+fn extract_zip(file: File, total: Option<u64>, dest: &Path, progress: &Progress<'_>) -> io::Result<Extracted> {
+    let mut archive = zip::ZipArchive::new(BufReader::with_capacity(READ_BUFFER, file)).map_err(io::Error::other)?;
+    // I link già estratti possono portare fuori anche un percorso lecito nel nome: si
+    // confrontano le cartelle risolte con la destinazione risolta, come fa `tar`
+    let root = fs::canonicalize(dest)?;
+    let mut extracted = Extracted::default();
+    let mut read = 0;
+    // Link il cui bersaglio non esiste ancora: si ricontrollano alla fine
+    let mut dangling: Vec<PathBuf> = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(io::Error::other)?;
+        let relative = entry.enclosed_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("entry {} is outside the destination", entry.name()))
+        })?;
+        let path = dest.join(relative);
+        read += entry.compressed_size();
+
+        check_inside(&root, &path)?;
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+                check_inside(&root, &path)?;
+            }
+            if entry.is_symlink() {
+                let mut target = String::new();
+                entry.read_to_string(&mut target)?;
+                if !write_symlink(dest, &root, &path, Path::new(&target))? {
+                    dangling.push(path.clone());
+                }
+            } else {
+                // Un link già estratto con lo stesso nome porterebbe la scrittura altrove
+                if path.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+                    fs::remove_file(&path)?;
+                }
+                let mut out = File::create(&path)?;
+                extracted.bytes += io::copy(&mut entry, &mut out)?;
+                set_mode(&path, entry.unix_mode())?;
+            }
+            extracted.files += 1;
+        }
+        progress(read.min(total.unwrap_or(u64::MAX)), total);
+    }
+    for link in dangling {
+        if fs::canonicalize(&link).is_ok_and(|resolved| !resolved.starts_with(&root)) {
+            let _ = fs::remove_file(&link);
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("link {} points outside the destination", link.display())));
+        }
+    }
+    Ok(extracted)
+}
+
+/// Errore se la cartella di `path`, risolta seguendo i link fin dove esiste, non sta
+/// dentro `root` (la destinazione già risolta).
+fn check_inside(root: &Path, path: &Path) -> io::Result<()> {
+    let mut existing = path.parent();
+    while let Some(dir) = existing.filter(|dir| dir.symlink_metadata().is_err()) {
+        existing = dir.parent();
+    }
+    let inside = match existing {
+        Some(dir) => fs::canonicalize(dir)?.starts_with(root),
+        None => false,
+    };
+    if !inside {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("entry {} is outside the destination", path.display())));
+    }
+    Ok(())
+}
+
+/// Crea il link `path` verso `target`, purché resti dentro `dest`, sia nel nome sia
+/// seguendo i link già estratti (`root` è `dest` risolta). Falso se il bersaglio non
+/// esiste ancora e quindi non si è potuto risolvere.
+fn write_symlink(dest: &Path, root: &Path, path: &Path, target: &Path) -> io::Result<bool> {
+    let relative = path.parent().and_then(|p| p.strip_prefix(dest).ok()).unwrap_or(Path::new("")).join(target);
+    let mut depth: i32 = 0;
+    let inside = !target.is_absolute()
+        && relative.components().all(|component| {
+            match component {
+                Component::Normal(_) => depth += 1,
+                Component::ParentDir => depth -= 1,
+                Component::CurDir => {}
+                _ => depth = -1,
+            }
+            depth >= 0
+        });
+    let resolved = path.parent().and_then(|parent| fs::canonicalize(parent.join(target)).ok());
+    if !inside || resolved.as_ref().is_some_and(|resolved| !resolved.starts_with(root)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("link {} points outside the destination", path.display())));
+    }
+    let _ = fs::remove_file(path);
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(target, path)?;
+    #[cfg(windows)]
+    std::os::windows::fs::symlink_file(target, path)?;
+    Ok(resolved.is_some())
+}
+
+/// Permessi Unix salvati nello zip, se ci sono.
+fn set_mode(path: &Path, mode: Option<u32>) -> io::Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o7777))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Confronto tra le versioni installate e le CVE elencate nei metadati dei rilasci.
+//!
+//! Una versione è esposta a tutte le CVE corrette dai rilasci successivi del suo
+//! canale: per ognuna si riporta il rilascio che la corregge e, per gli SDK, l'SDK
+//! della stessa feature band pubblicato con quel rilascio.
+//!
+//! [`check_sdk_in_use`] applica lo stesso confronto, più la fine del supporto del
+//! canale, all'SDK scelto da `current` e `use`, leggendo solo i metadati in cache.
+
+use std::time::SystemTime;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::{cached_channel_releases, cached_release_index};
+use crate::releases::{ChannelReleases, Release, ReleaseIndex, SupportPhase};
+use crate::version::SdkVersion;
+use crate::{Config, DverError};
+
+/// Cosa fare quando l'SDK in uso è di un canale fuori supporto o ha correzioni di
+/// sicurezza disponibili.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutdatedSdkPolicy {
+    /// Nessun controllo
+    #[default]
+    Off,
+    /// Una riga di avviso su stderr
+    Warn,
+    /// Il comando fallisce con l'exit code di `audit`
+    Error,
+}
+
+/// Tipo di componente verificato.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentKind {
+    Sdk,
+    Runtime,
+}
+
+/// Esito della verifica di una versione.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    /// Almeno una CVE è corretta da un rilascio successivo
+    Affected,
+    /// Nessuna CVE nota dopo questo rilascio
+    Ok,
+    /// La versione non compare nei metadati scaricati
+    Unknown,
+}
+
+/// Una CVE che interessa la versione installata e dove è corretta.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Advisory {
+    pub cve_id: String,
+    pub cve_url: String,
+    /// Rilascio (versione del runtime) che corregge la CVE
+    pub fixed_in: String,
+    /// SDK della stessa feature band pubblicato con la correzione
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_sdk: Option<String>,
+}
+
+/// Risultato per un SDK o un runtime installato.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditFinding {
+    pub kind: ComponentKind,
+    /// "sdk" per gli SDK, il nome del framework per i runtime
+    pub name: String,
+    pub version: String,
+    pub status: AuditStatus,
+    pub advisories: Vec<Advisory>,
+    /// Versione a cui aggiornare per correggere tutte le CVE
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_to: Option<String>,
+}
+
+/// Rilascio del canale che contiene `version`, cercato con `contains`.
+fn find_release(channels: &[ChannelReleases], contains: impl Fn(&Release) -> bool) -> Option<(&ChannelReleases, &Release)> {
+    channels
+        .iter()
+        .find_map(|channel| channel.releases.iter().find(|r| contains(r)).map(|r| (channel, r)))
+}
+
+/// Rilasci del canale successivi a `base`, dal più vecchio.
+fn later_releases<'a>(channel: &'a ChannelReleases, base: &Release) -> Vec<&'a Release> {
+    let Some(base_version) = &base.version else {
+        return Vec::new();
+    };
+    let mut later: Vec<&Release> = channel.releases
+        .iter()
+        .filter(|r| r.version.as_ref().is_some_and(|v| v > base_version))
+        .collect();
+    later.sort_by(|a, b| a.version.cmp(&b.version));
+    later
+}
+
+/// SDK di `release` nella stessa feature band di `sdk`.
+fn sdk_in_band(release: &Release, sdk: &SdkVersion) -> Option<String> {
+    release
+        .sdk_versions()
+        .filter(|v| v.major == sdk.major && v.minor == sdk.minor && v.feature_band() == sdk.feature_band())
+        .max()
+        .map(|v| v.to_string())
+}
+
+fn finding(kind: ComponentKind, name: &str, version: &str, channels: &[ChannelReleases]) -> AuditFinding {
+    let parsed: Option<SdkVersion> = version.parse().ok();
+    let found = parsed.as_ref().and_then(|v| match kind {
+        ComponentKind::Sdk => find_release(channels, |r| r.sdk_versions().any(|s| s == v)),
+        ComponentKind::Runtime => find_release(channels, |r| {
+            r.version.as_ref() == Some(v) || r.runtime.as_ref().and_then(|rt| rt.version.as_ref()) == Some(v)
+        }),
+    });
+    let (Some(parsed), Some((channel, base))) = (parsed, found) else {
+        return AuditFinding {
+            kind,
+            name: name.to_string(),
+            version: version.to_string(),
+            status: AuditStatus::Unknown,
+            advisories: Vec::new(),
+            update_to: None,
+        };
+    };
+
+    let later = later_releases(channel, base);
+    let fixed_sdk = |release: &Release| match kind {
+        ComponentKind::Sdk => sdk_in_band(release, &parsed),
+        ComponentKind::Runtime => None,
+    };
+    let mut advisories: Vec<Advisory> = Vec::new();
+    for release in &later {
+        for cve in &release.cve_list {
+            if advisories.iter().any(|a| a.cve_id == cve.cve_id) {
+                continue;
+            }
+            advisories.push(Advisory {
+                cve_id: cve.cve_id.clone(),
+                cve_url: cve.cve_url.clone(),
+                fixed_in: release.version.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+                fixed_sdk: fixed_sdk(release),
+            });
+        }
+    }
+
+    let update_to = match (advisories.is_empty(), later.last()) {
+        (false, Some(newest)) => match kind {
+            ComponentKind::Sdk => sdk_in_band(newest, &parsed)
+                .or_else(|| newest.sdk.as_ref().and_then(|s| s.version.as_ref()).map(|v| v.to_string())),
+            ComponentKind::Runtime => newest.version.as_ref().map(|v| v.to_string()),
+        },
+        _ => None,
+    };
+    AuditFinding {
+        kind,
+        name: name.to_string(),
+        version: version.to_string(),
+        status: if advisories.is_empty() { AuditStatus::Ok } else { AuditStatus::Affected },
+        advisories,
+        update_to,
+    }
+}
+
+/// Verifica gli SDK (`sdks`) e i runtime (`runtimes`, coppie nome e versione) installati
+/// contro i rilasci dei canali in `channels`.
+pub fn audit(channels: &[ChannelReleases], sdks: &[String], runtimes: &[(String, String)]) -> Vec<AuditFinding> {
+    sdks.iter()
+        .map(|version| finding(ComponentKind::Sdk, "sdk", version, channels))
+        .chain(runtimes.iter().map(|(name, version)| finding(ComponentKind::Runtime, name, version, channels)))
+        .collect()
+}
+
+/// Canali (`A.B`) delle versioni indicate, senza duplicati: quelli da scaricare per l'audit.
+pub fn channels_of<'a>(versions: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut channels: Vec<String> = versions
+        .into_iter()
+        .filter_map(|v| v.parse::<SdkVersion>().ok())
+        .map(|v| format!("{}.{}", v.major, v.minor))
+        .collect();
+    channels.sort();
+    channels.dedup();
+    channels
+}
+
+/// Vero se il canale `channel_version` (`A.B`) è fuori supporto alla data `today`,
+/// per la fase indicata nell'indice o perché la data di fine supporto è passata;
+/// `None` se il canale non è nell'indice.
+pub fn is_end_of_support(index: &ReleaseIndex, channel_version: &str, today: NaiveDate) -> Option<bool> {
+    let channel = index.releases_index.iter().find(|c| c.channel_version.as_deref() == Some(channel_version))?;
+    Some(channel.support_phase == Some(SupportPhase::Eol) || channel.eol_date.is_some_and(|date| date <= today))
+}
+
+/// Motivi per cui `sdk` non andrebbe più usato: canale in fine supporto alla data
+/// `today` o CVE corrette da rilasci successivi. Vuoto se l'SDK è aggiornato o non
+/// compare nei metadati.
+pub fn outdated_reasons(index: &ReleaseIndex, channels: &[ChannelReleases], sdk: &str, today: NaiveDate) -> Vec<String> {
+    let Ok(version) = sdk.parse::<SdkVersion>() else {
+        return Vec::new();
+    };
+    let channel_version = format!("{}.{}", version.major, version.minor);
+    let mut reasons = Vec::new();
+
+    let channel = index.releases_index.iter().find(|c| c.channel_version.as_deref() == Some(channel_version.as_str()));
+    if let Some(channel) = channel.filter(|_| is_end_of_support(index, &channel_version, today) == Some(true)) {
+        reasons.push(match channel.eol_date {
+            Some(date) => format!(".NET {} reached end of support on {}", channel_version, date),
+            None => format!(".NET {} is out of support", channel_version),
+        });
+    }
+
+    if let Some(found) = audit(channels, &[sdk.to_string()], &[]).into_iter().next() {
+        if found.status == AuditStatus::Affected {
+            let count = found.advisories.len();
+            let mut reason = format!("{} known CVE{} fixed in later releases", count, if count == 1 { " is" } else { "s are" });
+            if let Some(update) = found.update_to {
+                reason.push_str(&format!(", update to {}", update));
+            }
+            reasons.push(reason);
+        }
+    }
+    reasons
+}
+
+/// Applica `outdated_sdk_policy` all'SDK `sdk` risolto da un comando. Usa solo i
+/// metadati già in cache: senza cache non segnala nulla.
+pub fn check_sdk_in_use(config: &Config, sdk: &str) -> Result<(), DverError> {
+    if config.outdated_sdk_policy == OutdatedSdkPolicy::Off {
+        return Ok(());
+    }
+    let Some(index) = cached_release_index(config) else {
+        return Ok(());
+    };
+    let channels: Vec<ChannelReleases> = sdk
+        .parse::<SdkVersion>()
+        .ok()
+        .and_then(|v| {
+            let wanted = format!("{}.{}", v.major, v.minor);
+            index.releases_index.iter().find(|c| c.channel_version.as_deref() == Some(wanted.as_str()))
+        })
+        .and_then(|channel| cached_channel_releases(config, &channel.releases_json))
+        .into_iter()
+        .collect();
+    let today = DateTime::<Utc>::from(SystemTime::now()).date_naive();
+    let reasons = outdated_reasons(&index, &channels, sdk, today);
+    if reasons.is_empty() {
+        return Ok(());
+    }
+    let message = format!("SDK {}: {}", sdk, reasons.join("; "));
+    match config.outdated_sdk_policy {
+        OutdatedSdkPolicy::Error => Err(DverError::Vulnerable(format!(
+            "{}\nhint: set outdated_sdk_policy to `warn` or `off` to keep using it", message))),
+        _ => {
+            eprintln!("Warning: {}", message);
+            Ok(())
+        }
+    }
+}
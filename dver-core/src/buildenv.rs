@@ -0,0 +1,118 @@
+//! Ambiente di build dell'SDK risolto da dver, per `dver build-env`: le versioni di
+//! MSBuild e NuGet che porta, i resolver degli SDK di MSBuild, i `nuget.config` in
+//! vigore e le variabili che fanno usare a MSBuild un altro SDK. Sono la causa tipica
+//! di progetti che compilano da riga di comando ma non nell'IDE, o viceversa.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::paths;
+
+/// Variabili che spostano MSBuild su un altro SDK, con la cartella in cui devono
+/// puntare: `true` per la cartella dell'SDK, `false` per la radice di dotnet.
+pub const OVERRIDE_VARS: &[(&str, bool)] = &[
+    ("MSBuildSDKsPath", true),
+    ("MSBuildExtensionsPath", true),
+    ("MSBUILD_EXE_PATH", true),
+    ("DOTNET_MSBUILD_SDK_RESOLVER_SDKS_DIR", true),
+    ("DOTNET_MSBUILD_SDK_RESOLVER_CLI_DIR", false),
+    ("DOTNET_HOST_PATH", false),
+    ("DOTNET_ROOT", false),
+];
+
+/// Strumenti di build che un SDK porta con sé.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SdkTooling {
+    pub msbuild_version: Option<String>,
+    pub nuget_version: Option<String>,
+    /// Resolver degli SDK di MSBuild in `<sdk>/SdkResolvers`
+    pub sdk_resolvers: Vec<String>,
+}
+
+/// Una variabile d'ambiente che influisce sull'SDK usato da MSBuild.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildOverride {
+    pub name: String,
+    pub value: String,
+    /// Vero se punta all'SDK (o alla radice) risolto da dver
+    pub matches: bool,
+}
+
+/// Versione della libreria `name` nei `*.deps.json` dell'SDK, es. `Microsoft.Build/17.11.4`.
+fn library_version(sdk: &Path, name: &str) -> Option<String> {
+    let prefix = format!("{}/", name);
+    ["MSBuild.deps.json", "dotnet.deps.json"].iter().find_map(|file| {
+        let content = fs::read_to_string(sdk.join(file)).ok()?;
+        let deps: serde_json::Value = serde_json::from_str(&content).ok()?;
+        deps.get("libraries")?
+            .as_object()?
+            .keys()
+            .find_map(|key| key.strip_prefix(&prefix).map(str::to_string))
+    })
+}
+
+/// Legge gli strumenti di build dalla cartella dell'SDK, senza avviare dotnet.
+pub fn sdk_tooling(sdk: &Path) -> SdkTooling {
+    let mut sdk_resolvers: Vec<String> = fs::read_dir(sdk.join("SdkResolvers"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    sdk_resolvers.sort();
+    SdkTooling {
+        msbuild_version: library_version(sdk, "Microsoft.Build"),
+        nuget_version: library_version(sdk, "NuGet.Build.Tasks").or_else(|| library_version(sdk, "NuGet.Commands")),
+        sdk_resolvers,
+    }
+}
+
+/// Il `nuget.config` dell'utente: `%APPDATA%\NuGet\NuGet.Config` su Windows,
+/// `~/.nuget/NuGet/NuGet.Config` altrove.
+pub fn user_nuget_config() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("NuGet").join("NuGet.Config"))
+    } else {
+        paths::home_dir().map(|home| home.join(".nuget").join("NuGet").join("NuGet.Config"))
+    }
+}
+
+/// File `nuget.config` che NuGet applica in `start`, dal più vicino (che prevale)
+/// fino a quello dell'utente `user_config`, se esiste.
+pub fn nuget_configs(start: &Path, user_config: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut configs: Vec<PathBuf> = start
+        .ancestors()
+        .filter_map(|dir| {
+            fs::read_dir(dir).ok()?.filter_map(Result::ok).find_map(|entry| {
+                let is_config = entry.file_name().to_string_lossy().eq_ignore_ascii_case("nuget.config");
+                (is_config && entry.path().is_file()).then(|| entry.path())
+            })
+        })
+        .collect();
+    configs.extend(user_config.filter(|p| p.is_file() && !configs.contains(p)));
+    configs
+}
+
+/// Vero se `path` è `dir` o si trova al suo interno, dopo aver risolto i link.
+fn is_within(path: &Path, dir: &Path) -> bool {
+    let resolve = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    resolve(path).starts_with(resolve(dir))
+}
+
+/// Variabili di [`OVERRIDE_VARS`] impostate secondo `var`, confrontate con la cartella
+/// dell'SDK `sdk` e con la radice `root` che lo contiene.
+pub fn check_overrides(var: impl Fn(&str) -> Option<String>, root: &Path, sdk: &Path) -> Vec<BuildOverride> {
+    OVERRIDE_VARS
+        .iter()
+        .filter_map(|(name, in_sdk)| {
+            let value = var(name).filter(|v| !v.is_empty())?;
+            let matches = is_within(Path::new(&value), if *in_sdk { sdk } else { root });
+            Some(BuildOverride { name: name.to_string(), value, matches })
+        })
+        .collect()
+}
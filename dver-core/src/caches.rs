@@ -0,0 +1,133 @@
+//! Le cache sul disco: quella di dver (metadati dei rilasci, script e archivi
+//! scaricati) e quelle di NuGet (cartella dei pacchetti e cache HTTP).
+//!
+//! Sugli agent di CI effimeri le cache di NuGet si spostano su un volume montato con
+//! `nuget_packages` e `nuget_http_cache`, che dver passa ai processi dotnet come
+//! `NUGET_PACKAGES` e `NUGET_HTTP_CACHE_PATH`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::expand_home;
+use crate::{disk, paths, platform, Config, DverError, ResultExt};
+
+/// Quale cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheKind {
+    /// La cache di dver
+    Dver,
+    /// I pacchetti estratti da NuGet (`NUGET_PACKAGES`)
+    NugetPackages,
+    /// Le risposte dei feed scaricate da NuGet (`NUGET_HTTP_CACHE_PATH`)
+    NugetHttpCache,
+}
+
+impl std::fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CacheKind::Dver => "dver",
+            CacheKind::NugetPackages => "nuget-packages",
+            CacheKind::NugetHttpCache => "nuget-http-cache",
+        })
+    }
+}
+
+/// Da dove viene la posizione di una cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheSource {
+    /// La configurazione di dver
+    Config,
+    /// Una variabile d'ambiente, es. `NUGET_PACKAGES`
+    Environment,
+    /// La posizione predefinita
+    Default,
+}
+
+impl std::fmt::Display for CacheSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CacheSource::Config => "config",
+            CacheSource::Environment => "environment",
+            CacheSource::Default => "default",
+        })
+    }
+}
+
+/// Una cache con la sua posizione e, se misurata, lo spazio che occupa.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheLocation {
+    pub kind: CacheKind,
+    pub path: PathBuf,
+    pub source: CacheSource,
+    /// `None` se non è stata misurata o la cartella non esiste
+    pub size_bytes: Option<u64>,
+}
+
+/// Posizione di una cache di NuGet: la chiave `configured`, poi la variabile `var`,
+/// poi `default`.
+fn nuget_location(configured: Option<&Path>, var: &str, default: Option<PathBuf>) -> Option<(PathBuf, CacheSource)> {
+    if let Some(path) = configured {
+        return Some((expand_home(path), CacheSource::Config));
+    }
+    if let Some(value) = std::env::var_os(var).filter(|v| !v.is_empty()) {
+        return Some((PathBuf::from(value), CacheSource::Environment));
+    }
+    default.map(|path| (path, CacheSource::Default))
+}
+
+/// Cartella dei pacchetti NuGet, in cui finiscono anche gli strumenti locali
+/// ripristinati: `nuget_packages`, `NUGET_PACKAGES`, altrimenti `~/.nuget/packages`.
+pub fn nuget_packages_dir(config: &Config) -> Option<(PathBuf, CacheSource)> {
+    let default = paths::home_dir().map(|home| home.join(".nuget").join("packages"));
+    nuget_location(config.nuget_packages.as_deref(), "NUGET_PACKAGES", default)
+}
+
+/// Cache HTTP di NuGet: `nuget_http_cache`, `NUGET_HTTP_CACHE_PATH`, altrimenti
+/// `http-cache` nella cartella dei dati di NuGet.
+pub fn nuget_http_cache_dir(config: &Config) -> Option<(PathBuf, CacheSource)> {
+    let default = paths::home_dir().map(|home| platform::current().nuget_data_dir(&home).join("http-cache"));
+    nuget_location(config.nuget_http_cache.as_deref(), "NUGET_HTTP_CACHE_PATH", default)
+}
+
+/// Le cache note, senza misurarle.
+pub fn cache_locations(config: &Config) -> Vec<CacheLocation> {
+    let dver = config.cache_dir().map(|path| {
+        let source = if config.cache_dir.is_some() { CacheSource::Config } else { CacheSource::Default };
+        (CacheKind::Dver, path, source)
+    });
+    let nuget = [
+        nuget_packages_dir(config).map(|(path, source)| (CacheKind::NugetPackages, path, source)),
+        nuget_http_cache_dir(config).map(|(path, source)| (CacheKind::NugetHttpCache, path, source)),
+    ];
+    std::iter::once(dver)
+        .chain(nuget)
+        .flatten()
+        .map(|(kind, path, source)| CacheLocation { kind, path, source, size_bytes: None })
+        .collect()
+}
+
+/// Le cache note con lo spazio occupato; le cartelle che non esistono restano senza.
+pub fn measured_cache_locations(config: &Config) -> Vec<CacheLocation> {
+    cache_locations(config)
+        .into_iter()
+        .map(|location| CacheLocation { size_bytes: disk::dir_size(&location.path).ok(), ..location })
+        .collect()
+}
+
+/// Svuota la cartella `dir` lasciandola al suo posto: può essere il punto di mount
+/// di un volume. Una cartella che non esiste è già vuota.
+pub fn clear_dir(dir: &Path) -> Result<(), DverError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let path = entry.with_context(|| format!("Failed to read {}", dir.display()))?.path();
+        let removed = if path.is_dir() && !path.is_symlink() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        removed.with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Riconoscimento dei sistemi di CI e comandi che i loro agent interpretano.
+//!
+//! In CI dver non fa domande e non usa colori; `dver env --ci` passa l'ambiente .NET
+//! ai passi successivi con i comandi dell'agent (`##vso[...]`, `##teamcity[...]`, i
+//! file di GitHub Actions) invece che con `export`, e gli errori diventano anche
+//! annotazioni del build.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::environment::DotnetEnvironment;
+
+/// Sistema di CI in cui gira dver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CiProvider {
+    AzurePipelines,
+    GithubActions,
+    Gitlab,
+    Jenkins,
+    Teamcity,
+    /// Solo `CI` è impostata
+    Other,
+}
+
+/// Variabili che identificano ogni sistema, nell'ordine in cui si controllano.
+const MARKERS: &[(&str, CiProvider)] = &[
+    ("TF_BUILD", CiProvider::AzurePipelines),
+    ("GITHUB_ACTIONS", CiProvider::GithubActions),
+    ("GITLAB_CI", CiProvider::Gitlab),
+    ("JENKINS_URL", CiProvider::Jenkins),
+    ("TEAMCITY_VERSION", CiProvider::Teamcity),
+    ("CI", CiProvider::Other),
+];
+
+impl CiProvider {
+    /// Sistema di CI secondo le variabili lette da `var`; una variabile vuota o
+    /// `false` non conta.
+    pub fn detect_from(var: impl Fn(&str) -> Option<String>) -> Option<CiProvider> {
+        MARKERS
+            .iter()
+            .find(|(name, _)| var(name).is_some_and(|v| !v.is_empty() && !v.eq_ignore_ascii_case("false")))
+            .map(|(_, provider)| *provider)
+    }
+
+    /// Sistema di CI dell'ambiente corrente.
+    pub fn detect() -> Option<CiProvider> {
+        CiProvider::detect_from(|name| std::env::var(name).ok())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CiProvider::AzurePipelines => "Azure Pipelines",
+            CiProvider::GithubActions => "GitHub Actions",
+            CiProvider::Gitlab => "GitLab CI",
+            CiProvider::Jenkins => "Jenkins",
+            CiProvider::Teamcity => "TeamCity",
+            CiProvider::Other => "CI",
+        }
+    }
+
+    /// Riga che l'agent mostra come errore del build, se il sistema ne prevede una.
+    pub fn error_annotation(&self, message: &str) -> Option<String> {
+        match self {
+            CiProvider::AzurePipelines => Some(format!("##vso[task.logissue type=error]{}", azure_escape(message))),
+            CiProvider::GithubActions => Some(format!("::error::{}", github_escape(message))),
+            CiProvider::Teamcity => Some(format!("##teamcity[buildProblem description='{}']", teamcity_escape(message))),
+            _ => None,
+        }
+    }
+}
+
+/// Come rendere `env` disponibile ai passi successivi del build.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CiEnvironment {
+    /// Comandi da stampare su stdout, che l'agent interpreta
+    pub commands: Vec<String>,
+    /// Righe da aggiungere ai file indicati (`$GITHUB_ENV`, `$GITHUB_PATH`)
+    pub files: BTreeMap<PathBuf, Vec<String>>,
+}
+
+/// Traduce `env` nei comandi dell'agent di `provider`; `None` se il sistema non ne
+/// ha (GitLab, Jenkins, CI generica) o se mancano i file di GitHub Actions. `var`
+/// legge le variabili dell'ambiente del build.
+pub fn render_env(provider: CiProvider, env: &DotnetEnvironment, var: impl Fn(&str) -> Option<String>) -> Option<CiEnvironment> {
+    let mut out = CiEnvironment::default();
+    match provider {
+        CiProvider::AzurePipelines => {
+            for (key, value) in &env.vars {
+                out.commands.push(format!("##vso[task.setvariable variable={}]{}", key, azure_escape(value)));
+            }
+            if let Some(dir) = &env.path_prepend {
+                out.commands.push(format!("##vso[task.prependpath]{}", dir.display()));
+            }
+        }
+        CiProvider::Teamcity => {
+            for (key, value) in &env.vars {
+                out.commands.push(format!("##teamcity[setParameter name='env.{}' value='{}']", key, teamcity_escape(value)));
+            }
+            if let Some(dir) = &env.path_prepend {
+                let current = var("PATH").unwrap_or_default();
+                let path = std::env::join_paths(std::iter::once(dir.clone()).chain(std::env::split_paths(&current)))
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .unwrap_or_else(|_| dir.display().to_string());
+                out.commands.push(format!("##teamcity[setParameter name='env.PATH' value='{}']", teamcity_escape(&path)));
+            }
+        }
+        CiProvider::GithubActions => {
+            let env_file = PathBuf::from(var("GITHUB_ENV").filter(|v| !v.is_empty())?);
+            let lines = env.vars.iter().map(|(key, value)| github_env_line(key, value)).collect();
+            out.files.insert(env_file, lines);
+            if let Some(dir) = &env.path_prepend {
+                let path_file = PathBuf::from(var("GITHUB_PATH").filter(|v| !v.is_empty())?);
+                out.files.insert(path_file, vec![dir.display().to_string()]);
+            }
+        }
+        CiProvider::Gitlab | CiProvider::Jenkins | CiProvider::Other => return None,
+    }
+    Some(out)
+}
+
+/// Riga di `$GITHUB_ENV`; i valori su più righe usano la sintassi con delimitatore.
+fn github_env_line(key: &str, value: &str) -> String {
+    if value.contains('\n') {
+        format!("{}<<DVER_EOF\n{}\nDVER_EOF", key, value)
+    } else {
+        format!("{}={}", key, value)
+    }
+}
+
+/// Escape dei dati dei logging command di Azure Pipelines.
+fn azure_escape(value: &str) -> String {
+    value.replace('%', "%AZP25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape dei messaggi dei workflow command di GitHub Actions.
+fn github_escape(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape dei valori dei service message di TeamCity.
+fn teamcity_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' => out.push_str("||"),
+            '\'' => out.push_str("|'"),
+            '[' => out.push_str("|["),
+            ']' => out.push_str("|]"),
+            '\n' => out.push_str("|n"),
+            '\r' => out.push_str("|r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
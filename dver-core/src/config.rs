@@ -0,0 +1,693 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use crate::audit::OutdatedSdkPolicy;
+use crate::platform::Arch;
+use crate::signature::SignaturePolicy;
+use crate::updates::UpdateNotifications;
+use crate::version::SdkVersion;
+use crate::{paths, platform, DverError};
+
+pub const DEFAULT_INDEX_URL: &str = "https://dotnetcli.blob.core.windows.net/dotnet/release-metadata/releases-index.json";
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_RETRIES: u32 = 2;
+pub const DEFAULT_INSTALL_JOBS: u32 = 3;
+pub const DEFAULT_UPDATE_NOTICE_INTERVAL_HOURS: u32 = 24;
+
+/// Variabile con cui gli script ufficiali `dotnet-install` (e molte immagini CI)
+/// scelgono la cartella di installazione.
+pub const DOTNET_INSTALL_DIR: &str = "DOTNET_INSTALL_DIR";
+
+/// Formato dell'output dei comandi che stampano elenchi.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable text
+    Human,
+    Json,
+    Yaml,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Certificati radice di cui si fidano i client HTTP.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsRoots {
+    /// Archivio del sistema operativo, con la libreria TLS nativa
+    #[default]
+    Native,
+    /// Radici Mozilla incluse in dver, con rustls
+    Webpki,
+}
+
+/// Versione minima di TLS accettata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TlsVersion {
+    #[serde(rename = "1.2")]
+    Tls12,
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+/// Come `install` ottiene un SDK di cui è nota la versione esatta.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMethod {
+    /// Lo script ufficiale dotnet-install
+    #[default]
+    Script,
+    /// L'archivio ufficiale indicato dai metadati dei rilasci, verificato con il suo
+    /// SHA-512 ed estratto da dver
+    Archive,
+}
+
+/// Qualità delle build di un canale, come il parametro `-Quality` dello script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Quality {
+    /// Released builds
+    Ga,
+    /// Previews and release candidates
+    Preview,
+    /// Unsupported daily builds from the CI feed
+    Daily,
+}
+
+impl Quality {
+    /// Valore atteso dal parametro `-Quality` dello script dotnet-install.
+    pub fn as_script_arg(&self) -> &'static str {
+        match self {
+            Quality::Ga => "GA",
+            Quality::Preview => "preview",
+            Quality::Daily => "daily",
+        }
+    }
+}
+
+/// Impostazioni lette da `config.toml`; ogni chiave è facoltativa e i flag
+/// della riga di comando hanno sempre la precedenza.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Radice gestita usata da `install`, `list` e `uninstall` quando `--install-path`
+    /// manca: un percorso oppure il nome di una voce di `[roots]`
+    pub install_root: Option<PathBuf>,
+    /// La [`DOTNET_INSTALL_DIR`] quando `install_root` manca: vi si installa e se ne
+    /// leggono gli SDK, ma non è una radice scelta dall'utente e dver non vi cancella nulla
+    #[serde(skip)]
+    pub env_install_root: Option<PathBuf>,
+    /// Radici di installazione con nome, es. `ci = "/opt/dotnet-ci"`
+    pub roots: BTreeMap<String, PathBuf>,
+    /// Architettura degli SDK da installare ed eseguire; una diversa da quella
+    /// dell'host usa la radice `<install_root>/<arch>` (vedi [`Config::arch_root`])
+    pub arch: Option<Arch>,
+    /// Canale installato da un `install` senza `--lts` né `--version` (es. "8.0")
+    pub default_channel: Option<String>,
+    /// Qualità delle build del canale predefinito: "ga", "preview" o "daily"
+    /// (build giornaliere, non supportate)
+    pub quality: Option<Quality>,
+    /// Canali seguiti, es. `["8.0", "9.0"]`: se ce ne sono, `remote`, `check-updates`,
+    /// `upgrade` e l'avviso sugli aggiornamenti ignorano gli altri
+    pub channels: Vec<String>,
+    /// Proxy HTTP(S) per tutte le richieste
+    pub proxy: Option<String>,
+    /// Non usa il proxy delle impostazioni di sistema quando `proxy` manca
+    pub ignore_system_proxy: bool,
+    /// Indice dei rilasci, per usare un mirror interno
+    pub index_url: Option<String>,
+    /// Feed da cui lo script di installazione scarica gli SDK (`-AzureFeed`)
+    pub feed_url: Option<String>,
+    /// URL alternativo dello script dotnet-install
+    pub install_script_url: Option<String>,
+    /// File PEM con certificati radice aggiuntivi, es. quelli di un proxy aziendale
+    pub ca_bundle: Option<PathBuf>,
+    /// Archivio dei certificati radice: "native" (sistema operativo) o "webpki"
+    pub tls_roots: TlsRoots,
+    /// Versione minima di TLS: "1.2" o "1.3"
+    pub min_tls_version: Option<TlsVersion>,
+    /// Cosa fare se la firma dello script o degli eseguibili installati non si può verificare
+    pub signature_policy: SignaturePolicy,
+    /// URL della firma PGP staccata dello script dotnet-install
+    pub install_script_signature_url: Option<String>,
+    /// Keyring GnuPG (`gpg --export`) con le chiavi accettate per quella firma
+    pub signing_keyring: Option<PathBuf>,
+    /// Cosa fare quando `current` o `use` scelgono un SDK fuori supporto o con CVE corrette
+    pub outdated_sdk_policy: OutdatedSdkPolicy,
+    /// Aggiornamenti annunciati alla fine dei comandi: "all", "security" o "off"
+    pub update_notifications: UpdateNotifications,
+    /// Ore minime tra due avvisi di aggiornamento (predefinito 24)
+    pub update_notice_interval_hours: Option<u32>,
+    /// Timeout delle richieste HTTP in secondi
+    pub timeout_secs: Option<u64>,
+    /// Timeout della connessione in secondi
+    pub connect_timeout_secs: Option<u64>,
+    /// Tentativi aggiuntivi su errori di rete e risposte 5xx
+    pub retries: Option<u32>,
+    /// User agent inviato con ogni richiesta
+    pub user_agent: Option<String>,
+    /// Patch tenute per ogni feature band da `maintain`; senza, non rimuove nulla
+    pub keep_patches: Option<u32>,
+    /// Spazio massimo della cache di dver in MB: `maintain` la svuota quando lo supera
+    pub cache_budget_mb: Option<u64>,
+    /// Installazioni eseguite in parallelo quando `install` riceve più versioni
+    pub install_jobs: Option<u32>,
+    /// "script" (dotnet-install) o "archive" (archivio ufficiale estratto da dver)
+    pub install_method: InstallMethod,
+    /// Formato di output predefinito
+    pub output: Option<OutputFormat>,
+    /// Colori nei prompt interattivi
+    pub color: ColorMode,
+    /// Vieta ogni accesso alla rete, come `--no-network`
+    pub no_network: bool,
+    /// Usa solo i metadati in cache, come `--offline`
+    pub offline: bool,
+    /// Stampa su stderr l'output di dotnet che non si riesce a interpretare, come `--debug-parse`
+    pub debug_parse: bool,
+    /// Cartella della cache dei metadati e degli script scaricati
+    pub cache_dir: Option<PathBuf>,
+    /// Cartella per stato persistente, log e backup
+    pub state_dir: Option<PathBuf>,
+    /// Cartella dei pacchetti NuGet (`NUGET_PACKAGES`), es. su un volume montato nella CI
+    pub nuget_packages: Option<PathBuf>,
+    /// Cache HTTP di NuGet (`NUGET_HTTP_CACHE_PATH`)
+    pub nuget_http_cache: Option<PathBuf>,
+    /// Dove `dver sync` salva il profilo: un repository git o un URL HTTP(S) di una cartella
+    pub sync_remote: Option<String>,
+    /// Disattiva la telemetria della CLI (`DOTNET_CLI_TELEMETRY_OPTOUT=1`)
+    pub telemetry_optout: bool,
+    /// Modalità globalization-invariant (`DOTNET_SYSTEM_GLOBALIZATION_INVARIANT=1`)
+    pub invariant_globalization: bool,
+    /// Dopo `install` e `uninstall` svuota le cache dei template di `dotnet new` degli
+    /// SDK rimossi e di quelli appena installati
+    pub template_cache_cleanup: bool,
+    /// Variabili d'ambiente passate ai processi dotnet avviati da dver
+    /// (es. `DOTNET_CLI_TELEMETRY_OPTOUT = "1"`)
+    pub env: BTreeMap<String, String>,
+    /// Strumenti .NET globali per `dver tool sync`, con la versione voluta
+    /// (es. `dotnet-ef = "8.0.11"`; `"*"` accetta qualsiasi versione)
+    pub tools: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Percorso del file di configurazione, `config.toml` in [`paths::config_dir`].
+    pub fn path() -> Option<PathBuf> {
+        paths::config_dir().map(|d| d.join("config.toml"))
+    }
+
+    /// Carica la configurazione; un file mancante equivale a quella predefinita.
+    pub fn load() -> Result<Config, DverError> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Config, DverError> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| DverError::Parse(format!("Invalid config file {:?}: {}", path, e)))
+    }
+
+    /// Applica le variabili `DVER_<CHIAVE>` (es. `DVER_INSTALL_ROOT`), che hanno
+    /// la precedenza sul file ma non sui flag della riga di comando, e in mancanza di
+    /// `install_root` la [`DOTNET_INSTALL_DIR`] come `env_install_root`.
+    pub fn with_env_overrides(self) -> Result<Config, DverError> {
+        let mut table = Table::try_from(&self)
+            .map_err(|e| DverError::Parse(e.to_string()))?;
+        for (key, _) in KEYS {
+            let var = format!("DVER_{}", key.to_uppercase());
+            if let Some(raw) = std::env::var(&var).ok().filter(|v| !v.is_empty()) {
+                let value = parse_value(key, &raw)
+                    .map_err(|e| DverError::Usage(format!("{}: {}", var, e)))?;
+                table.insert(key.to_string(), value);
+            }
+        }
+        if std::env::var_os("DVER_NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            table.insert("color".to_string(), Value::String("never".to_string()));
+        }
+        let mut config = Config::deserialize(Value::Table(table))
+            .map_err(|e| DverError::Usage(format!("Invalid DVER_* environment: {}", e)))?;
+        // Senza una radice configurata si usa quella degli script ufficiali, così dver e
+        // lo script non installano gli stessi SDK in due posti
+        if config.install_root.is_none() {
+            config.env_install_root = dotnet_install_dir();
+        }
+        Ok(config)
+    }
+
+    /// Cartella della cache: `cache_dir`, altrimenti [`paths::cache_dir`].
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir.as_deref().map(expand_home).or_else(paths::cache_dir)
+    }
+
+    /// Cartella dello stato: `state_dir`, altrimenti [`paths::state_dir`].
+    pub fn state_dir(&self) -> Option<PathBuf> {
+        self.state_dir.as_deref().map(expand_home).or_else(paths::state_dir)
+    }
+
+    /// Radice gestita effettiva, con i nomi di `[roots]` e `~` risolti, per
+    /// l'architettura `arch` della configurazione.
+    pub fn managed_root(&self) -> Option<PathBuf> {
+        self.arch_root(self.arch)
+    }
+
+    /// Radice gestita per l'architettura `arch`: quella di `install_root` per l'host
+    /// (o con `None`), la sottocartella `<arch>` per le altre, come fanno gli
+    /// installer Microsoft con `dotnet/x64` su ARM. Senza `install_root` le altre
+    /// architetture usano la sottocartella della radice dell'utente, per non
+    /// sovrascrivere il `dotnet` nativo.
+    pub fn arch_root(&self, arch: Option<Arch>) -> Option<PathBuf> {
+        self.root_for_arch(self.install_root.as_ref().or(self.env_install_root.as_ref()), arch)
+    }
+
+    fn root_for_arch(&self, root: Option<&PathBuf>, arch: Option<Arch>) -> Option<PathBuf> {
+        let Some(root) = root else {
+            let arch = arch.filter(|a| !a.is_native())?;
+            return paths::home_dir().map(|home| platform::current().user_dotnet_root(&home).join(arch.name()));
+        };
+        let path = expand_home(root.to_str().and_then(|name| self.roots.get(name)).unwrap_or(root));
+        match arch.filter(|a| !a.is_native()) {
+            Some(arch) => Some(path.join(arch.name())),
+            None => Some(path),
+        }
+    }
+
+    /// Tutte le radici configurate esplicitamente: `install_root` e i valori di `[roots]`.
+    /// Sono le sole cartelle in cui dver accetta di cancellare qualcosa, quindi non
+    /// comprendono `env_install_root`.
+    pub fn managed_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = self.root_for_arch(self.install_root.as_ref(), None)
+            .into_iter()
+            .chain(self.root_for_arch(self.install_root.as_ref(), self.arch))
+            .chain(self.roots.values().map(|p| expand_home(p)))
+            .collect();
+        roots.sort();
+        roots.dedup();
+        roots
+    }
+
+    /// Variabili d'ambiente per i processi .NET: la tabella `[env]` più quelle
+    /// derivate da `telemetry_optout`, `invariant_globalization` e dalle cache di NuGet.
+    pub fn dotnet_env(&self) -> BTreeMap<String, String> {
+        let mut vars = self.env.clone();
+        if self.telemetry_optout {
+            vars.insert("DOTNET_CLI_TELEMETRY_OPTOUT".to_string(), "1".to_string());
+        }
+        if self.invariant_globalization {
+            vars.insert("DOTNET_SYSTEM_GLOBALIZATION_INVARIANT".to_string(), "1".to_string());
+        }
+        if let Some(dir) = &self.nuget_packages {
+            vars.insert("NUGET_PACKAGES".to_string(), expand_home(dir).display().to_string());
+        }
+        if let Some(dir) = &self.nuget_http_cache {
+            vars.insert("NUGET_HTTP_CACHE_PATH".to_string(), expand_home(dir).display().to_string());
+        }
+        vars
+    }
+
+    /// Nome della radice gestita, se `install_root` si riferisce a una voce di `[roots]`.
+    pub fn install_root_name(&self) -> Option<String> {
+        let name = self.install_root.as_ref()?.to_str()?;
+        self.roots.contains_key(name).then(|| name.to_string())
+    }
+
+    /// Bundle di certificati aggiuntivi, con `~` risolto.
+    pub fn ca_bundle(&self) -> Option<PathBuf> {
+        self.ca_bundle.as_deref().map(expand_home)
+    }
+
+    /// Keyring per le firme PGP, con `~` risolto.
+    pub fn signing_keyring(&self) -> Option<PathBuf> {
+        self.signing_keyring.as_deref().map(expand_home)
+    }
+
+    /// Vero se il canale `channel` (es. "8.0") è tra i `channels` seguiti, o se non se
+    /// ne segue nessuno in particolare.
+    pub fn follows_channel(&self, channel: &str) -> bool {
+        self.channels.is_empty() || self.channels.iter().any(|c| c == channel)
+    }
+
+    /// Come [`Config::follows_channel`], per il canale dell'SDK `version`; una versione
+    /// che non si riconosce si segue.
+    pub fn follows_sdk(&self, version: &str) -> bool {
+        match version.parse::<SdkVersion>() {
+            Ok(v) => self.follows_channel(&format!("{}.{}", v.major, v.minor)),
+            Err(_) => true,
+        }
+    }
+
+    pub fn index_url(&self) -> &str {
+        self.index_url.as_deref().unwrap_or(DEFAULT_INDEX_URL)
+    }
+
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    pub fn connect_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.connect_timeout_secs.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS))
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(DEFAULT_RETRIES)
+    }
+
+    pub fn install_jobs(&self) -> usize {
+        self.install_jobs.unwrap_or(DEFAULT_INSTALL_JOBS).max(1) as usize
+    }
+
+    pub fn user_agent(&self) -> String {
+        self.user_agent.clone()
+            .unwrap_or_else(|| format!("dver/{} (dotnet-version-manager)", env!("CARGO_PKG_VERSION")))
+    }
+}
+
+/// La cartella di [`DOTNET_INSTALL_DIR`], se impostata.
+pub fn dotnet_install_dir() -> Option<PathBuf> {
+    std::env::var_os(DOTNET_INSTALL_DIR).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// Espande un `~` iniziale nella home dell'utente.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => paths::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| path.to_path_buf()),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+// --- Modifica del file ---
+#[derive(Debug, Clone, Copy)]
+enum KeyKind {
+    Text,
+    Url,
+    Path,
+    Integer,
+    Count,
+    Bool,
+    Choice(&'static [&'static str]),
+    Channel,
+    /// Elenco di canali `A.B` separati da virgole
+    Channels,
+}
+
+/// Canali accettati dallo script dotnet-install: `LTS`, `STS`, `A.B` o `A.B.Cxx`.
+pub fn is_valid_channel(channel: &str) -> bool {
+    if channel.eq_ignore_ascii_case("lts") || channel.eq_ignore_ascii_case("sts") {
+        return true;
+    }
+    let parts: Vec<&str> = channel.split('.').collect();
+    let numeric = |p: &str| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit());
+    match parts.as_slice() {
+        [major, minor] => numeric(major) && numeric(minor),
+        [major, minor, band] => numeric(major) && numeric(minor)
+            && band.len() == 3 && band.ends_with("xx") && numeric(&band[..1]),
+        _ => false,
+    }
+}
+
+/// Chiavi modificabili con `dver config set`, con il tipo usato per validarle.
+const KEYS: &[(&str, KeyKind)] = &[
+    ("install_root", KeyKind::Path),
+    ("arch", KeyKind::Choice(&["x64", "arm64", "x86"])),
+    ("default_channel", KeyKind::Channel),
+    ("quality", KeyKind::Choice(&["ga", "preview", "daily"])),
+    ("channels", KeyKind::Channels),
+    ("proxy", KeyKind::Url),
+    ("ignore_system_proxy", KeyKind::Bool),
+    ("index_url", KeyKind::Url),
+    ("feed_url", KeyKind::Url),
+    ("install_script_url", KeyKind::Url),
+    ("ca_bundle", KeyKind::Text),
+    ("tls_roots", KeyKind::Choice(&["native", "webpki"])),
+    ("min_tls_version", KeyKind::Choice(&["1.2", "1.3"])),
+    ("signature_policy", KeyKind::Choice(&["require", "warn", "off"])),
+    ("install_script_signature_url", KeyKind::Url),
+    ("signing_keyring", KeyKind::Text),
+    ("outdated_sdk_policy", KeyKind::Choice(&["off", "warn", "error"])),
+    ("update_notifications", KeyKind::Choice(&["all", "security", "off"])),
+    ("update_notice_interval_hours", KeyKind::Count),
+    ("timeout_secs", KeyKind::Integer),
+    ("connect_timeout_secs", KeyKind::Integer),
+    ("retries", KeyKind::Count),
+    ("user_agent", KeyKind::Text),
+    ("install_jobs", KeyKind::Count),
+    ("keep_patches", KeyKind::Integer),
+    ("cache_budget_mb", KeyKind::Integer),
+    ("install_method", KeyKind::Choice(&["script", "archive"])),
+    ("output", KeyKind::Choice(&["human", "json", "yaml", "csv"])),
+    ("color", KeyKind::Choice(&["auto", "always", "never"])),
+    ("no_network", KeyKind::Bool),
+    ("offline", KeyKind::Bool),
+    ("debug_parse", KeyKind::Bool),
+    ("telemetry_optout", KeyKind::Bool),
+    ("invariant_globalization", KeyKind::Bool),
+    ("template_cache_cleanup", KeyKind::Bool),
+    ("cache_dir", KeyKind::Path),
+    ("state_dir", KeyKind::Path),
+    ("nuget_packages", KeyKind::Path),
+    ("nuget_http_cache", KeyKind::Path),
+    ("sync_remote", KeyKind::Text),
+];
+
+/// Tabelle le cui voci si indirizzano come `<tabella>.<nome>`.
+const TABLES: &[(&str, KeyKind)] = &[
+    ("env", KeyKind::Text),
+    ("roots", KeyKind::Path),
+    ("tools", KeyKind::Text),
+];
+
+/// Divide `env.FOO` in (`env`, `FOO`) se la chiave appartiene a una tabella.
+fn split_table_key(key: &str) -> Option<(&'static str, &str)> {
+    TABLES.iter().find_map(|(table, _)| {
+        key.strip_prefix(table)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .map(|name| (*table, name))
+    })
+}
+
+fn key_kind(key: &str) -> Result<KeyKind, DverError> {
+    if let Some((table, name)) = split_table_key(key) {
+        if name.is_empty() {
+            return Err(DverError::Usage(format!("Missing name after `{}.`", table)));
+        }
+        return Ok(TABLES.iter().find(|(t, _)| *t == table).map(|(_, k)| *k).unwrap_or(KeyKind::Text));
+    }
+    KEYS.iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| {
+            let names: Vec<&str> = KEYS.iter().map(|(name, _)| *name).collect();
+            DverError::Usage(format!("Unknown config key {:?}; valid keys are {}, env.<VAR>, roots.<NAME> and tools.<ID>", key, names.join(", ")))
+        })
+}
+
+/// Verifica che `path`, o il suo antenato più vicino già esistente, sia scrivibile.
+fn check_writable(path: &Path) -> Result<(), DverError> {
+    let existing = path.ancestors().find(|p| p.exists()).unwrap_or(path);
+    let probe = existing.join(format!(".dver-write-test-{}", std::process::id()));
+    match fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) => Err(DverError::Permission(format!("{:?} is not writable: {}", path, e))),
+    }
+}
+
+fn parse_value(key: &str, raw: &str) -> Result<Value, DverError> {
+    let invalid = |expected: &str| DverError::Usage(format!("Invalid value {:?} for {}: expected {}", raw, key, expected));
+    match key_kind(key)? {
+        KeyKind::Text => Ok(Value::String(raw.to_string())),
+        KeyKind::Url => {
+            if raw.starts_with("http://") || raw.starts_with("https://") {
+                Ok(Value::String(raw.to_string()))
+            } else {
+                Err(invalid("an http:// or https:// URL"))
+            }
+        }
+        KeyKind::Path => Ok(Value::String(raw.to_string())),
+        KeyKind::Integer => raw.parse::<i64>()
+            .ok()
+            .filter(|n| *n > 0)
+            .map(Value::Integer)
+            .ok_or_else(|| invalid("a positive integer")),
+        KeyKind::Count => raw.parse::<u32>()
+            .map(|n| Value::Integer(n.into()))
+            .map_err(|_| invalid("a non-negative integer")),
+        KeyKind::Bool => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(Value::Boolean(true)),
+            "false" | "0" | "no" | "off" => Ok(Value::Boolean(false)),
+            _ => Err(invalid("true or false")),
+        },
+        KeyKind::Channel => {
+            if is_valid_channel(raw) {
+                Ok(Value::String(raw.to_string()))
+            } else {
+                Err(invalid("LTS, STS, A.B or A.B.Cxx"))
+            }
+        }
+        KeyKind::Channels => {
+            let channels: Vec<&str> = raw.split(',').map(str::trim).filter(|c| !c.is_empty()).collect();
+            if channels.iter().all(|c| c.split('.').count() == 2 && is_valid_channel(c)) {
+                Ok(Value::Array(channels.into_iter().map(|c| Value::String(c.to_string())).collect()))
+            } else {
+                Err(invalid("comma-separated channels such as 8.0,9.0"))
+            }
+        }
+        KeyKind::Choice(choices) => {
+            if choices.contains(&raw) {
+                Ok(Value::String(raw.to_string()))
+            } else {
+                Err(invalid(&choices.join(", ")))
+            }
+        }
+    }
+}
+
+/// Sostituisce il file di configurazione con `table`, se è una configurazione valida.
+pub fn save_table(table: &Table) -> Result<(), DverError> {
+    write_table(&require_path()?, table)
+}
+
+fn require_path() -> Result<PathBuf, DverError> {
+    Config::path().ok_or_else(|| DverError::NotFound("Cannot determine the config directory (HOME/APPDATA not set)".to_string()))
+}
+
+fn read_table(path: &Path) -> Result<Table, DverError> {
+    if !path.exists() {
+        return Ok(Table::new());
+    }
+    let content = fs::read_to_string(path)?;
+    content.parse::<Table>()
+        .map_err(|e| DverError::Parse(format!("Invalid config file {:?}: {}", path, e)))
+}
+
+fn write_table(path: &Path, table: &Table) -> Result<(), DverError> {
+    // Rifiuta di salvare un file che poi non si riuscirebbe a caricare
+    Config::deserialize(Value::Table(table.clone()))
+        .map_err(|e| DverError::Usage(format!("Resulting config would be invalid: {}", e)))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = toml::to_string_pretty(table)
+        .map_err(|e| DverError::Parse(e.to_string()))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Coppie chiave/valore presenti nel file, con le voci delle tabelle come `env.<VAR>`.
+pub fn list_entries() -> Result<Vec<(String, String)>, DverError> {
+    let table = read_table(&require_path()?)?;
+    let mut entries = Vec::new();
+    for (key, value) in &table {
+        match value {
+            Value::Table(vars) if TABLES.iter().any(|(t, _)| t == key) => {
+                for (var, v) in vars {
+                    entries.push((format!("{}.{}", key, var), display_value(v)));
+                }
+            }
+            _ => entries.push((key.clone(), display_value(value))),
+        }
+    }
+    Ok(entries)
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn get_key(key: &str) -> Result<String, DverError> {
+    key_kind(key)?;
+    list_entries()?
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v)
+        .ok_or_else(|| DverError::NotFound(format!("Config key {} is not set", key)))
+}
+
+pub fn set_key(key: &str, raw: &str) -> Result<(), DverError> {
+    let value = parse_value(key, raw)?;
+    if let KeyKind::Path = key_kind(key)? {
+        // `install_root` può anche essere il nome di una radice definita in `[roots]`
+        let is_root_name = key == "install_root" && Config::load()?.roots.contains_key(raw);
+        if !is_root_name {
+            check_writable(&expand_home(Path::new(raw)))?;
+        }
+    }
+    let path = require_path()?;
+    let mut table = read_table(&path)?;
+    match split_table_key(key) {
+        Some((name, var)) => {
+            let entries = table.entry(name).or_insert_with(|| Value::Table(Table::new()));
+            match entries {
+                Value::Table(vars) => { vars.insert(var.to_string(), value); }
+                _ => return Err(DverError::Parse(format!("`{}` in {:?} is not a table", name, path))),
+            }
+        }
+        None => { table.insert(key.to_string(), value); }
+    }
+    write_table(&path, &table)
+}
+
+pub fn unset_key(key: &str) -> Result<(), DverError> {
+    key_kind(key)?;
+    let path = require_path()?;
+    let mut table = read_table(&path)?;
+    let removed = match split_table_key(key) {
+        Some((name, var)) => match table.get_mut(name) {
+            Some(Value::Table(vars)) => vars.remove(var).is_some(),
+            _ => false,
+        },
+        None => table.remove(key).is_some(),
+    };
+    if !removed {
+        return Err(DverError::NotFound(format!("Config key {} is not set", key)));
+    }
+    write_table(&path, &table)
+}
+
+/// Apre il file in `$VISUAL`/`$EDITOR` e controlla che il risultato sia valido.
+pub fn edit() -> Result<PathBuf, DverError> {
+    let path = require_path()?;
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, "")?;
+    }
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| platform::current().default_editor().to_string());
+    // L'editor può contenere argomenti, es. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .map_err(|e| DverError::Process(format!("Failed to start editor {:?}: {}", editor, e)))?;
+    if !status.success() {
+        return Err(DverError::Process(format!("Editor {:?} exited with {}", editor, status)));
+    }
+    Config::load_from(&path)?;
+    Ok(path)
+}
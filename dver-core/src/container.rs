@@ -0,0 +1,214 @@
+//! Immagini container allineate all'SDK fissato dal progetto, per `dver containerize`.
+//!
+//! Le immagini ufficiali di `mcr.microsoft.com/dotnet` hanno un tag per ogni versione
+//! esatta di SDK e runtime: dai metadati dei rilasci si ricava il runtime che
+//! accompagna l'SDK, così build locale e build nel container usano la stessa toolchain.
+//! Per le build senza rete il bundle offline contiene i metadati e l'archivio dell'SDK
+//! nella struttura della cache di dver. Per i dev container (Codespaces compresi) c'è
+//! una feature locale che installa lo stesso SDK con dver.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::releases::ChannelReleases;
+use crate::version::SdkVersion;
+use crate::DverError;
+
+/// Registro delle immagini ufficiali di .NET.
+pub const REGISTRY: &str = "mcr.microsoft.com/dotnet";
+
+/// Cartella in cui il Dockerfile copia il bundle offline dentro l'immagine.
+pub const BUNDLE_MOUNT: &str = "/opt/dver";
+
+/// Radice .NET nelle immagini ufficiali, usata anche per gli SDK installati dal bundle.
+pub const IMAGE_DOTNET_ROOT: &str = "/usr/share/dotnet";
+
+/// Versioni e immagini che corrispondono a un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContainerImages {
+    pub sdk_version: String,
+    pub runtime_version: String,
+    /// Versione di ASP.NET Core dello stesso rilascio, se i metadati la riportano
+    pub aspnet_version: Option<String>,
+    /// Immagine per la build, es. `mcr.microsoft.com/dotnet/sdk:8.0.404`
+    pub sdk_image: String,
+    /// Immagine finale: `aspnet` se nota, altrimenti `runtime`
+    pub runtime_image: String,
+    /// Immagine senza .NET su cui il bundle offline installa l'SDK
+    pub runtime_deps_image: String,
+}
+
+/// Immagini per l'SDK `sdk`, cercato nei rilasci di `channels`.
+pub fn images_for(channels: &[ChannelReleases], sdk: &SdkVersion) -> Result<ContainerImages, DverError> {
+    let release = channels
+        .iter()
+        .flat_map(|channel| &channel.releases)
+        .find(|release| release.sdk_versions().any(|v| v == sdk))
+        .ok_or_else(|| DverError::NotFound(format!("SDK {} is not in the release metadata", sdk)))?;
+    let runtime = release
+        .sdks
+        .iter()
+        .chain(&release.sdk)
+        .filter(|s| s.version.as_ref() == Some(sdk))
+        .find_map(|s| s.runtime_version.clone())
+        .or_else(|| release.runtime.as_ref().and_then(|r| r.version.clone()))
+        .or_else(|| release.version.clone())
+        .ok_or_else(|| DverError::NotFound(format!("The release metadata names no runtime for SDK {}", sdk)))?;
+    let aspnet = release.aspnetcore_runtime.as_ref().and_then(|r| r.version.clone());
+    let runtime_image = match &aspnet {
+        Some(version) => format!("{}/aspnet:{}", REGISTRY, version),
+        None => format!("{}/runtime:{}", REGISTRY, runtime),
+    };
+    Ok(ContainerImages {
+        sdk_version: sdk.to_string(),
+        runtime_version: runtime.to_string(),
+        aspnet_version: aspnet.map(|v| v.to_string()),
+        sdk_image: format!("{}/sdk:{}", REGISTRY, sdk),
+        runtime_image,
+        runtime_deps_image: format!("{}/runtime-deps:{}", REGISTRY, runtime),
+    })
+}
+
+/// RID Linux delle immagini per l'architettura di questa macchina, es. `linux-x64`.
+pub fn container_rid() -> String {
+    let rid = crate::platform::rid();
+    let arch = rid.rsplit('-').next().unwrap_or("x64");
+    format!("linux-{}", arch)
+}
+
+/// Dockerfile a due stadi che compila con l'SDK fissato e pubblica sul runtime
+/// corrispondente. Con `bundle` (la cartella del bundle, relativa al contesto della
+/// build) l'SDK si installa dal bundle con dver, senza rete.
+pub fn dockerfile(images: &ContainerImages, bundle: Option<&Path>) -> String {
+    let mut out = String::new();
+    match bundle {
+        None => {
+            out.push_str(&format!("# SDK {} and runtime {}, as pinned by the project (generated by `dver containerize`)\n",
+                images.sdk_version, images.runtime_version));
+            out.push_str(&format!("FROM {} AS build\n", images.sdk_image));
+        }
+        Some(dir) => {
+            out.push_str(&format!("# SDK {} installed offline from the dver bundle (generated by `dver containerize --bundle`)\n",
+                images.sdk_version));
+            out.push_str(&format!("FROM {} AS build\n", images.runtime_deps_image));
+            out.push_str(&format!("COPY {} {}\n", dir.display(), BUNDLE_MOUNT));
+            out.push_str(&format!(
+                "ENV DVER_CACHE_DIR={mount}/cache DVER_INSTALL_METHOD=archive DVER_OFFLINE=true \\\n    DOTNET_ROOT={root} PATH={root}:$PATH\n",
+                mount = BUNDLE_MOUNT, root = IMAGE_DOTNET_ROOT));
+        }
+    }
+    out.push_str("WORKDIR /src\nCOPY . .\n");
+    if bundle.is_some() {
+        out.push_str(&format!(
+            "RUN {}/dver --non-interactive install --from-project --install-path {}\n", BUNDLE_MOUNT, IMAGE_DOTNET_ROOT));
+    }
+    out.push_str("RUN dotnet publish -c Release -o /app\n\n");
+    out.push_str(&format!("FROM {}\n", images.runtime_image));
+    out.push_str("WORKDIR /app\nCOPY --from=build /app .\n");
+    out
+}
+
+/// Scrive in `dir` il bundle offline per l'SDK `sdk`: i metadati del suo canale e
+/// l'archivio per `rid`, verificato con lo SHA-512 dei metadati, nella struttura della
+/// cache di dver. Restituisce il percorso dell'archivio.
+#[cfg(feature = "async")]
+pub async fn write_bundle(
+    client: &impl crate::http::HttpClient,
+    config: &crate::Config,
+    sdk: &SdkVersion,
+    rid: &str,
+    dir: &Path,
+) -> Result<std::path::PathBuf, DverError> {
+    use std::fs;
+
+    use crate::install::{channel_of, verify_archive};
+    use crate::metadata::{fetch_channel, fetch_release_index};
+    use crate::ResultExt;
+
+    let bundle = crate::Config { cache_dir: Some(dir.join("cache")), offline: false, ..config.clone() };
+    let index = fetch_release_index(client, &bundle).await?;
+    let releases = fetch_channel(client, &bundle, channel_of(&index, sdk)?).await?;
+    let file = crate::sbom::sdk_archive(std::slice::from_ref(&releases), rid, sdk)
+        .cloned()
+        .ok_or_else(|| DverError::NotFound(format!("The release metadata lists no {} archive of SDK {}", rid, sdk)))?;
+
+    let archives = dir.join("cache").join("archives");
+    fs::create_dir_all(&archives).with_context(|| format!("Failed to create {}", archives.display()))?;
+    let path = archives.join(&file.name);
+    let download = client
+        .download(&file.url, &path, &|_, _| {})
+        .await
+        .with_context(|| format!("Failed to download SDK {} from {}", sdk, file.url))?;
+    if let Err(e) = verify_archive(&file, &download.path) {
+        let _ = fs::remove_file(&download.path);
+        return Err(e);
+    }
+    Ok(download.path)
+}
+
+// --- Dev container ---
+
+/// Radice in cui la feature per i dev container installa gli SDK.
+pub const DEVCONTAINER_DOTNET_ROOT: &str = "/usr/local/share/dotnet";
+
+/// `devcontainer-feature.json` di una feature locale che installa con dver l'SDK
+/// `version` (l'opzione predefinita, modificabile da devcontainer.json). `id` deve
+/// coincidere con il nome della cartella della feature.
+pub fn devcontainer_feature(id: &str, version: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "version": "1.0.0",
+        "name": ".NET SDK (dver)",
+        "description": "Installs the .NET SDK pinned by the project with dver",
+        "options": {
+            "version": {
+                "type": "string",
+                "default": version,
+                "description": "Exact SDK version to install",
+            },
+        },
+        "containerEnv": {
+            "DOTNET_ROOT": DEVCONTAINER_DOTNET_ROOT,
+            "DVER_INSTALL_ROOT": DEVCONTAINER_DOTNET_ROOT,
+            "PATH": format!("{}:${{PATH}}", DEVCONTAINER_DOTNET_ROOT),
+        },
+    })
+}
+
+/// `install.sh` della feature: usa il dver copiato accanto allo script o quello nel
+/// `PATH`, installa l'SDK e lascia la radice all'utente del container, così
+/// `dver install --from-project` funziona anche dopo la creazione.
+pub fn devcontainer_install_script() -> String {
+    format!(r#"#!/bin/sh
+# Generated by `dver devcontainer --feature`. VERSION comes from the feature options.
+set -e
+
+here="$(cd "$(dirname "$0")" && pwd)"
+if [ -x "$here/dver" ]; then
+    install -m 0755 "$here/dver" /usr/local/bin/dver
+fi
+if ! command -v dver >/dev/null 2>&1; then
+    echo "dver is not available: copy a Linux build of dver next to install.sh" >&2
+    exit 1
+fi
+
+mkdir -p {root}
+dver --non-interactive install --version "$VERSION" --install-path {root}
+if [ -n "$_REMOTE_USER" ] && [ "$_REMOTE_USER" != "root" ]; then
+    chown -R "$_REMOTE_USER" {root}
+fi
+"#, root = DEVCONTAINER_DOTNET_ROOT)
+}
+
+/// Blocco da unire a `devcontainer.json`: la feature locale `feature` (es. `./dver`)
+/// e l'installazione dell'SDK fissato alla creazione del container, così un global.json
+/// aggiornato dopo la generazione vale anche nel container.
+pub fn devcontainer_block(feature: &str, version: &str) -> serde_json::Value {
+    serde_json::json!({
+        "features": {
+            feature: { "version": version },
+        },
+        "postCreateCommand": "dver --non-interactive install --from-project",
+    })
+}
@@ -0,0 +1,140 @@
+//! Confronto tra due SDK per `dver diff`: cosa cambia nei metadati dei rilasci
+//! (runtime, framework inclusi, versioni dei linguaggi, date) e quali CVE sono
+//! corrette passando dal primo al secondo; per gli SDK installati anche i file.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::audit::audit;
+use crate::manifest::InstallManifest;
+use crate::releases::{ChannelReleases, DotnetSdk, Release};
+use crate::version::SdkVersion;
+use crate::DverError;
+
+/// Un SDK come lo descrivono i metadati del suo rilascio.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SdkRelease {
+    pub version: String,
+    pub channel: Option<String>,
+    /// Versione del rilascio, cioè del runtime con cui è uscito
+    pub release: Option<String>,
+    pub release_date: Option<NaiveDate>,
+    pub runtime: Option<String>,
+    pub aspnetcore: Option<String>,
+    pub windowsdesktop: Option<String>,
+    pub csharp: Option<String>,
+    pub fsharp: Option<String>,
+    pub vb: Option<String>,
+}
+
+/// Una CVE corretta tra i due SDK e il rilascio che la corregge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FixedCve {
+    pub cve_id: String,
+    pub cve_url: String,
+    pub fixed_in: String,
+}
+
+/// Esito di `dver diff`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SdkDiff {
+    pub from: SdkRelease,
+    pub to: SdkRelease,
+    /// CVE a cui `from` è esposto e `to` no; vuoto se `to` non è più recente
+    pub fixed_cves: Vec<FixedCve>,
+}
+
+/// Differenze tra i file di due SDK installati, per percorso relativo.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    /// Solo nel secondo SDK
+    pub added: Vec<String>,
+    /// Solo nel primo SDK
+    pub removed: Vec<String>,
+    /// In entrambi, con contenuto diverso
+    pub changed: Vec<String>,
+    pub unchanged: usize,
+}
+
+/// Il rilascio di `channels` che ha pubblicato l'SDK `version`, con la voce dell'SDK.
+fn find_sdk<'a>(channels: &'a [ChannelReleases], version: &SdkVersion) -> Option<(&'a ChannelReleases, &'a Release, Option<&'a DotnetSdk>)> {
+    channels.iter().find_map(|channel| {
+        channel.releases.iter().find(|r| r.sdk_versions().any(|v| v == version)).map(|release| {
+            let sdk = release.sdks.iter().chain(release.sdk.as_ref()).find(|s| s.version.as_ref() == Some(version));
+            (channel, release, sdk)
+        })
+    })
+}
+
+/// Descrive l'SDK `version` con i metadati di `channels`; `None` se non vi compare.
+pub fn sdk_release(channels: &[ChannelReleases], version: &str) -> Option<SdkRelease> {
+    let parsed: SdkVersion = version.parse().ok()?;
+    let (channel, release, sdk) = find_sdk(channels, &parsed)?;
+    // Le voci di `sdks` spesso non ripetono le versioni dei linguaggi di `sdk`
+    let text = |field: fn(&DotnetSdk) -> &Option<String>| {
+        sdk.and_then(|s| field(s).clone()).or_else(|| release.sdk.as_ref().and_then(|s| field(s).clone()))
+    };
+    let runtime = sdk
+        .and_then(|s| s.runtime_version.as_ref())
+        .or_else(|| release.runtime.as_ref().and_then(|r| r.version.as_ref()))
+        .or(release.version.as_ref());
+    Some(SdkRelease {
+        version: version.to_string(),
+        channel: channel.channel_version.clone(),
+        release: release.version.as_ref().map(|v| v.to_string()),
+        release_date: release.release_date,
+        runtime: runtime.map(|v| v.to_string()),
+        aspnetcore: release.aspnetcore_runtime.as_ref().and_then(|r| r.version.as_ref()).map(|v| v.to_string()),
+        windowsdesktop: release.windowsdesktop.as_ref().and_then(|w| w.version.as_ref()).map(|v| v.to_string()),
+        csharp: text(|s| &s.csharp_version),
+        fsharp: text(|s| &s.fsharp_version),
+        vb: text(|s| &s.vb_version),
+    })
+}
+
+/// Confronta gli SDK `from` e `to`; è un errore se uno dei due non compare nei metadati.
+pub fn diff_sdks(channels: &[ChannelReleases], from: &str, to: &str) -> Result<SdkDiff, DverError> {
+    let described = |version: &str| {
+        sdk_release(channels, version).ok_or_else(|| {
+            DverError::NotFound(format!(
+                "SDK {} is not in the release metadata\nhint: run `dver remote` to list the published SDKs",
+                version
+            ))
+        })
+    };
+    let from_release = described(from)?;
+    let to_release = described(to)?;
+    let newer = from.parse::<SdkVersion>().ok() < to.parse::<SdkVersion>().ok();
+    let same_channel = from_release.channel == to_release.channel;
+    let to_version: Option<SdkVersion> = to_release.release.as_deref().and_then(|v| v.parse().ok());
+    let fixed_cves = if newer {
+        audit(channels, &[from.to_string()], &[])
+            .remove(0)
+            .advisories
+            .into_iter()
+            // Nello stesso canale contano solo le correzioni arrivate entro il rilascio di `to`
+            .filter(|a| !same_channel || a.fixed_in.parse::<SdkVersion>().ok() <= to_version)
+            .map(|a| FixedCve { cve_id: a.cve_id, cve_url: a.cve_url, fixed_in: a.fixed_in })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(SdkDiff { from: from_release, to: to_release, fixed_cves })
+}
+
+/// Confronta i file di due SDK installati.
+pub fn diff_files(from: &InstallManifest, to: &InstallManifest) -> FileDiff {
+    let mut remaining: BTreeMap<&str, &str> = to.files.iter().map(|f| (f.path.as_str(), f.sha256.as_str())).collect();
+    let mut diff = FileDiff::default();
+    for file in &from.files {
+        match remaining.remove(file.path.as_str()) {
+            None => diff.removed.push(file.path.clone()),
+            Some(sha256) if sha256 != file.sha256 => diff.changed.push(file.path.clone()),
+            Some(_) => diff.unchanged += 1,
+        }
+    }
+    diff.added = remaining.into_keys().map(str::to_string).collect();
+    diff
+}
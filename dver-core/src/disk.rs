@@ -0,0 +1,146 @@
+//! Spazio occupato sul disco dagli SDK installati.
+//!
+//! Una cartella `sdk/<versione>` contiene migliaia di file: la visita usa più thread
+//! che si contendono le sottocartelle ancora da leggere. Le dimensioni calcolate si
+//! salvano in `sizes.json` nella cartella di stato insieme alla data di modifica
+//! della cartella, e si ricalcolano solo quando questa cambia: un SDK non viene
+//! modificato dopo l'installazione, e reinstallarlo crea una cartella nuova.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Config, DverError, ResultExt};
+
+/// Dimensione salvata di una cartella.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedSize {
+    bytes: u64,
+    /// Data di modifica della cartella quando è stata misurata
+    modified: SystemTime,
+}
+
+/// Cartelle ancora da leggere e quanti thread ne stanno leggendo una.
+struct Pending {
+    dirs: Vec<PathBuf>,
+    busy: usize,
+}
+
+/// Somma delle dimensioni dei file sotto `dir`. I link simbolici non si seguono e
+/// contano per la dimensione del link; le voci illeggibili si saltano.
+pub fn dir_size(dir: &Path) -> io::Result<u64> {
+    fs::read_dir(dir)?;
+    let workers = std::thread::available_parallelism().map_or(4, |n| n.get()).min(16);
+    let pending = Mutex::new(Pending { dirs: vec![dir.to_path_buf()], busy: 0 });
+    let wake = Condvar::new();
+
+    let total = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers).map(|_| scope.spawn(|| walk(&pending, &wake))).collect();
+        handles.into_iter().map(|h| h.join().unwrap_or(0)).sum()
+    });
+    Ok(total)
+}
+
+/// Un thread della visita: prende una cartella, somma i file e accoda le
+/// sottocartelle, finché non restano cartelle né thread al lavoro.
+fn walk(pending: &Mutex<Pending>, wake: &Condvar) -> u64 {
+    let mut total = 0;
+    loop {
+        let dir = {
+            let mut state = pending.lock().unwrap();
+            loop {
+                if let Some(dir) = state.dirs.pop() {
+                    state.busy += 1;
+                    break dir;
+                }
+                if state.busy == 0 {
+                    return total;
+                }
+                state = wake.wait(state).unwrap();
+            }
+        };
+
+        let mut subdirs = Vec::new();
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let Ok(metadata) = entry.metadata() else { continue };
+            if metadata.is_dir() {
+                subdirs.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+
+        let mut state = pending.lock().unwrap();
+        state.dirs.extend(subdirs);
+        state.busy -= 1;
+        wake.notify_all();
+    }
+}
+
+fn cache_file(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("sizes.json"))
+}
+
+fn load_cache(path: &Path) -> BTreeMap<PathBuf, CachedSize> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Dimensioni delle cartelle `dirs`, nello stesso ordine. Quelle già misurate e non
+/// modificate vengono dalla cache; le altre si calcolano e si aggiungono alla cache.
+pub fn sizes(config: &Config, dirs: &[PathBuf]) -> Result<Vec<u64>, DverError> {
+    let file = cache_file(config);
+    let mut cache = file.as_deref().map(load_cache).unwrap_or_default();
+    let mut changed = false;
+
+    let mut sizes = Vec::with_capacity(dirs.len());
+    for dir in dirs {
+        let modified = fs::metadata(dir)
+            .and_then(|m| m.modified())
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        let bytes = match cache.get(dir) {
+            Some(cached) if cached.modified == modified => cached.bytes,
+            _ => {
+                let bytes = dir_size(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+                cache.insert(dir.clone(), CachedSize { bytes, modified });
+                changed = true;
+                bytes
+            }
+        };
+        sizes.push(bytes);
+    }
+
+    // Le cartelle sparite non servono più
+    let before = cache.len();
+    cache.retain(|dir, _| dir.exists());
+    changed |= cache.len() != before;
+
+    if let (Some(file), true) = (file, changed) {
+        // La cache è solo un'ottimizzazione: gli errori di scrittura non sono fatali
+        let _ = file.parent().map(fs::create_dir_all);
+        let _ = serde_json::to_vec_pretty(&cache).map(|json| fs::write(&file, json));
+    }
+    Ok(sizes)
+}
+
+/// Dimensione leggibile in unità binarie, es. `212.4 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -0,0 +1,179 @@
+//! .NET installato dai pacchetti di sistema su Linux: lo snap `dotnet-sdk` e i pacchetti
+//! apt di Ubuntu (in `/usr/lib/dotnet`) e di Microsoft (da packages.microsoft.com, in
+//! `/usr/share/dotnet`). Si contendono `/usr/bin/dotnet` e il `PATH` tra loro e con le
+//! radici di dver; `doctor` spiega i conflitti e `adopt --replace-system` prepara i
+//! comandi che disattivano i loro punti d'ingresso senza disinstallarli.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::process::ProcessRunner;
+
+/// Il link dei pacchetti apt che porta `dotnet` in `PATH`.
+pub const SYSTEM_DOTNET_LINK: &str = "/usr/bin/dotnet";
+
+/// Dove `dpkg-divert` sposta [`SYSTEM_DOTNET_LINK`], anche negli aggiornamenti successivi.
+pub const DIVERTED_DOTNET_LINK: &str = "/usr/bin/dotnet.distrib";
+
+/// Chi ha pubblicato un pacchetto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageSource {
+    Snap,
+    /// Pacchetti dell'archivio di Ubuntu, con `ubuntu` nella versione
+    Ubuntu,
+    /// Pacchetti del repository packages.microsoft.com
+    Microsoft,
+}
+
+impl PackageSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            PackageSource::Snap => "snap",
+            PackageSource::Ubuntu => "Ubuntu",
+            PackageSource::Microsoft => "Microsoft",
+        }
+    }
+}
+
+/// Un pacchetto .NET installato.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SystemPackage {
+    pub name: String,
+    pub version: String,
+    pub source: PackageSource,
+}
+
+impl SystemPackage {
+    /// La radice .NET in cui il pacchetto installa i suoi file.
+    pub fn root(&self) -> PathBuf {
+        match self.source {
+            PackageSource::Snap => Path::new("/snap").join(&self.name).join("current"),
+            PackageSource::Ubuntu => PathBuf::from("/usr/lib/dotnet"),
+            PackageSource::Microsoft => PathBuf::from("/usr/share/dotnet"),
+        }
+    }
+}
+
+/// Interpreta l'output di `snap list`, tenendo gli snap di .NET.
+pub fn parse_snap_list(output: &str) -> Vec<SystemPackage> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let (name, version) = (columns.next()?, columns.next()?);
+            name.starts_with("dotnet").then(|| SystemPackage {
+                name: name.to_string(),
+                version: version.to_string(),
+                source: PackageSource::Snap,
+            })
+        })
+        .collect()
+}
+
+/// Interpreta l'output di `dpkg-query -W -f '${Package}\t${Version}\t${db:Status-Abbrev}\n'`,
+/// tenendo i pacchetti installati.
+pub fn parse_dpkg_query(output: &str) -> Vec<SystemPackage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let (name, version, status) = (fields.next()?, fields.next()?, fields.next()?);
+            if !status.starts_with("ii") {
+                return None;
+            }
+            let source = if version.contains("ubuntu") { PackageSource::Ubuntu } else { PackageSource::Microsoft };
+            Some(SystemPackage { name: name.to_string(), version: version.to_string(), source })
+        })
+        .collect()
+}
+
+/// Gli snap e i pacchetti apt di .NET installati; vuoto dove `snap` e `dpkg-query` mancano.
+pub fn system_packages(runner: &dyn ProcessRunner) -> Vec<SystemPackage> {
+    let mut snap = Command::new("snap");
+    snap.arg("list");
+    let mut dpkg = Command::new("dpkg-query");
+    dpkg.args(["-W", "-f", "${Package}\t${Version}\t${db:Status-Abbrev}\n", "dotnet*"]);
+    let mut packages = Vec::new();
+    if let Some(output) = runner.output(&mut snap).ok().filter(|output| output.status.success()) {
+        packages.extend(parse_snap_list(&String::from_utf8_lossy(&output.stdout)));
+    }
+    // `dpkg-query` esce con 1 se il modello non trova nulla, ma stampa comunque ciò che trova
+    if let Ok(output) = runner.output(&mut dpkg) {
+        packages.extend(parse_dpkg_query(&String::from_utf8_lossy(&output.stdout)));
+    }
+    packages
+}
+
+/// I problemi noti delle combinazioni di `packages`, da mostrare all'utente.
+pub fn conflicts(packages: &[SystemPackage]) -> Vec<String> {
+    let mut messages = Vec::new();
+    for snap in packages.iter().filter(|p| p.source == PackageSource::Snap) {
+        messages.push(format!(
+            "The {} snap ships its own dotnet in /snap/bin: it only sees the SDKs inside the snap, and whichever of /snap/bin and the dver root comes first in PATH wins.",
+            snap.name
+        ));
+    }
+    let has = |source| packages.iter().any(|p| p.source == source);
+    if has(PackageSource::Ubuntu) && has(PackageSource::Microsoft) {
+        messages.push(format!(
+            "Ubuntu's and Microsoft's .NET packages are both installed: they share {} but install into /usr/lib/dotnet and /usr/share/dotnet, so dotnet misses SDKs or fails with \"The folder [/usr/share/dotnet/host/fxr] does not exist\".",
+            SYSTEM_DOTNET_LINK
+        ));
+    }
+    if has(PackageSource::Ubuntu) || has(PackageSource::Microsoft) {
+        messages.push(format!(
+            "{} from the apt packages shadows the dver root whenever /usr/bin comes first in PATH, and it ignores the SDKs installed by dver.",
+            SYSTEM_DOTNET_LINK
+        ));
+    }
+    messages
+}
+
+/// Vero se dver gira come root, e quindi non serve `sudo`.
+pub fn is_root(runner: &dyn ProcessRunner) -> bool {
+    let mut command = Command::new("id");
+    command.arg("-u");
+    runner.output(&mut command).is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "0")
+}
+
+fn privileged(sudo: bool, args: &[&str]) -> Command {
+    let (program, rest) = if sudo { ("sudo", args) } else { (args[0], &args[1..]) };
+    let mut command = Command::new(program);
+    command.args(rest);
+    command
+}
+
+/// I comandi che disattivano i punti d'ingresso di `packages` senza disinstallarli: gli
+/// snap vengono disabilitati e `/usr/bin/dotnet` spostato con `dpkg-divert`, così gli
+/// aggiornamenti dei pacchetti non lo ripristinano. Con `sudo` i comandi passano per `sudo`.
+pub fn replacement_commands(packages: &[SystemPackage], link_exists: bool, sudo: bool) -> Vec<Command> {
+    let mut commands: Vec<Command> = packages
+        .iter()
+        .filter(|p| p.source == PackageSource::Snap)
+        .map(|p| privileged(sudo, &["snap", "disable", &p.name]))
+        .collect();
+    if link_exists && packages.iter().any(|p| p.source != PackageSource::Snap) {
+        commands.push(privileged(
+            sudo,
+            &["dpkg-divert", "--local", "--rename", "--divert", DIVERTED_DOTNET_LINK, "--add", SYSTEM_DOTNET_LINK],
+        ));
+    }
+    commands
+}
+
+/// I comandi che annullano [`replacement_commands`].
+pub fn restore_commands(packages: &[SystemPackage], sudo: bool) -> Vec<Command> {
+    let mut commands: Vec<Command> = packages
+        .iter()
+        .filter(|p| p.source == PackageSource::Snap)
+        .map(|p| privileged(sudo, &["snap", "enable", &p.name]))
+        .collect();
+    if packages.iter().any(|p| p.source != PackageSource::Snap) {
+        commands.push(privileged(sudo, &["dpkg-divert", "--rename", "--remove", SYSTEM_DOTNET_LINK]));
+    }
+    commands
+}
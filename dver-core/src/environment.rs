@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::config::Config;
+use crate::platform::{Arch, Platform};
+use crate::{platform, DverError};
+
+/// Shell per cui generare le istruzioni di `dver env`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+    Cmd,
+}
+
+impl ShellKind {
+    /// Deduce la shell da `$SHELL`; su Windows usa PowerShell.
+    pub fn detect() -> ShellKind {
+        if let Some(shell) = platform::current().default_shell() {
+            return shell;
+        }
+        let shell = std::env::var("SHELL").unwrap_or_default();
+        match shell.rsplit('/').next().unwrap_or("") {
+            "zsh" => ShellKind::Zsh,
+            "fish" => ShellKind::Fish,
+            "pwsh" | "powershell" => ShellKind::Powershell,
+            _ => ShellKind::Bash,
+        }
+    }
+}
+
+/// Variabili e voci di PATH che dver applica ai processi .NET.
+#[derive(Debug, Clone, Default)]
+pub struct DotnetEnvironment {
+    pub vars: BTreeMap<String, String>,
+    pub path_prepend: Option<PathBuf>,
+}
+
+impl DotnetEnvironment {
+    /// `DOTNET_ROOT` è sempre la radice nativa, `PATH` porta al `dotnet` dell'architettura
+    /// scelta; ogni radice di un'altra architettura presente (es. `<radice>/x64` su
+    /// ARM) finisce in `DOTNET_ROOT_<ARCH>`, così le app di quell'architettura trovano
+    /// i loro runtime.
+    pub fn from_config(config: &Config) -> DotnetEnvironment {
+        let mut vars = config.dotnet_env();
+        let root = config.managed_root();
+        if let Some(native) = config.arch_root(None) {
+            vars.insert("DOTNET_ROOT".to_string(), native.display().to_string());
+        }
+        for arch in Arch::ALL.into_iter().filter(|a| !a.is_native()) {
+            let Some(dir) = config.arch_root(Some(arch)) else { continue };
+            if dir.is_dir() || config.arch == Some(arch) {
+                for var in platform::current().arch_root_vars(arch) {
+                    vars.insert(var.to_string(), dir.display().to_string());
+                }
+            }
+        }
+        DotnetEnvironment { vars, path_prepend: root }
+    }
+
+    /// Istruzioni da valutare nella shell indicata, es. `eval "$(dver env)"`.
+    pub fn render(&self, shell: ShellKind) -> String {
+        self.render_for(shell, platform::current())
+    }
+
+    /// Come [`render`](Self::render) per la piattaforma `platform`: su Windows le shell
+    /// POSIX (Git Bash, MSYS2) vogliono la voce di `PATH` nella forma `/c/...`, mentre
+    /// le variabili restano percorsi Windows perché le legge `dotnet.exe`.
+    pub fn render_for(&self, shell: ShellKind, platform: &dyn Platform) -> String {
+        let mut out = String::new();
+        for (key, value) in &self.vars {
+            out.push_str(&match shell {
+                ShellKind::Bash | ShellKind::Zsh => format!("export {}={}\n", key, posix_quote(value)),
+                ShellKind::Fish => format!("set -gx {} {}\n", key, posix_quote(value)),
+                ShellKind::Powershell => format!("$env:{} = {}\n", key, powershell_quote(value)),
+                ShellKind::Cmd => format!("set \"{}={}\"\n", key, value),
+            });
+        }
+        if let Some(dir) = &self.path_prepend {
+            let dir = dir.display().to_string();
+            let posix_dir = if platform.path_separator() == ';' { msys_path(&dir) } else { dir.clone() };
+            out.push_str(&match shell {
+                ShellKind::Bash | ShellKind::Zsh => format!("export PATH={}:\"$PATH\"\n", posix_quote(&posix_dir)),
+                ShellKind::Fish => format!("set -gx PATH {} $PATH\n", posix_quote(&posix_dir)),
+                ShellKind::Powershell => format!(
+                    "$env:PATH = {} + [IO.Path]::PathSeparator + $env:PATH\n", powershell_quote(&dir)),
+                ShellKind::Cmd => format!("set \"PATH={};%PATH%\"\n", dir),
+            });
+        }
+        out
+    }
+
+    /// Applica l'ambiente a un comando da eseguire.
+    pub fn apply(&self, cmd: &mut std::process::Command) {
+        cmd.envs(&self.vars);
+        if let Some(dir) = &self.path_prepend {
+            let current = std::env::var_os("PATH").unwrap_or_default();
+            let paths = std::iter::once(dir.clone()).chain(std::env::split_paths(&current));
+            if let Ok(joined) = std::env::join_paths(paths) {
+                cmd.env("PATH", joined);
+            }
+        }
+    }
+}
+
+/// Un percorso Windows nella forma delle shell MSYS, es. `C:\Users\ada` -> `/c/Users/ada`.
+pub fn msys_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(drive), Some(':')) if drive.is_ascii_alphabetic() => {
+            format!("/{}{}", drive.to_ascii_lowercase(), chars.as_str())
+        }
+        _ => path,
+    }
+}
+
+// --- PATH ---
+
+/// Le voci del `PATH` del processo, divise con il separatore del sistema;
+/// `std::env::split_paths` toglie anche le virgolette delle voci di Windows.
+pub fn path_entries() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).filter(|dir| !dir.as_os_str().is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Posizione di `dir` tra `entries`, confrontando le cartelle come
+/// [`same_root`](crate::provenance::same_root): conta la cartella, non come è scritta.
+pub fn path_position(entries: &[PathBuf], dir: &Path) -> Option<usize> {
+    entries.iter().position(|entry| crate::provenance::same_root(entry, dir))
+}
+
+pub(crate) fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+pub(crate) fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// --- VS Code ---
+
+/// Chiave di `settings.json` con l'ambiente del terminale integrato su questo sistema.
+pub fn vscode_terminal_env_key() -> &'static str {
+    if cfg!(windows) {
+        "terminal.integrated.env.windows"
+    } else if cfg!(target_os = "macos") {
+        "terminal.integrated.env.osx"
+    } else {
+        "terminal.integrated.env.linux"
+    }
+}
+
+impl DotnetEnvironment {
+    /// Chiavi di `.vscode/settings.json` che puntano l'estensione C# (e OmniSharp) e
+    /// il terminale integrato alla radice `root`, con le stesse variabili di `dver env`.
+    pub fn vscode_settings(&self, root: &Path) -> Map<String, Value> {
+        let mut terminal: Map<String, Value> = self.vars.iter().map(|(k, v)| (k.clone(), Value::from(v.as_str()))).collect();
+        terminal.insert("DOTNET_ROOT".to_string(), Value::from(root.display().to_string()));
+        terminal.insert(
+            "PATH".to_string(),
+            Value::from(format!("{}{}${{env:PATH}}", root.display(), platform::current().path_separator())),
+        );
+        let mut settings = Map::new();
+        settings.insert("dotnet.dotnetPath".to_string(), Value::from(root.display().to_string()));
+        settings.insert("omnisharp.dotnetPath".to_string(), Value::from(root.display().to_string()));
+        settings.insert(vscode_terminal_env_key().to_string(), Value::Object(terminal));
+        settings
+    }
+}
+
+/// Unisce `settings` al contenuto di un `settings.json` esistente, lasciando le altre
+/// chiavi come sono; l'ambiente del terminale si unisce variabile per variabile. I
+/// file con commenti (JSONC) non si possono riscrivere senza perderli e danno errore.
+pub fn merge_vscode_settings(existing: Option<&str>, settings: Map<String, Value>) -> Result<String, DverError> {
+    let mut merged = match existing.map(str::trim).filter(|c| !c.is_empty()) {
+        None => Map::new(),
+        Some(content) => match serde_json::from_str::<Value>(content) {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => return Err(DverError::Parse("settings.json is not a JSON object".to_string())),
+            Err(e) => return Err(DverError::Parse(format!("Cannot update settings.json (comments are not supported): {}", e))),
+        },
+    };
+    for (key, value) in settings {
+        match (merged.get_mut(&key), value) {
+            (Some(Value::Object(current)), Value::Object(vars)) => current.extend(vars),
+            (_, value) => {
+                merged.insert(key, value);
+            }
+        }
+    }
+    Ok(serde_json::to_string_pretty(&Value::Object(merged))? + "\n")
+}
@@ -0,0 +1,161 @@
+use std::process::Output;
+
+/// Errori con un codice stabile e un exit code dedicato, così gli script possono
+/// distinguere il tipo di fallimento senza analizzare i messaggi.
+#[derive(Debug, thiserror::Error)]
+pub enum DverError {
+    /// Argomenti mancanti o incompatibili
+    #[error("{0}")]
+    Usage(String),
+    /// Versione, file o SDK non trovati
+    #[error("{0}")]
+    NotFound(String),
+    /// Impossibile determinare quale versione usare
+    #[error("{0}")]
+    Resolution(String),
+    /// Errore HTTP o di connessione
+    #[error("{0}")]
+    Network(String),
+    /// Accesso alla rete vietato da `--no-network`
+    #[error("{0}")]
+    NetworkDisabled(String),
+    /// Il contenuto scaricato non corrisponde all'hash atteso
+    #[error("{0}")]
+    HashMismatch(String),
+    /// Firma mancante o non valida con `signature_policy = "require"`
+    #[error("{0}")]
+    Signature(String),
+    /// `dver audit` ha trovato versioni installate esposte a CVE note
+    #[error("{0}")]
+    Vulnerable(String),
+    /// Permessi insufficienti sul filesystem
+    #[error("{0}")]
+    Permission(String),
+    /// Un processo esterno (dotnet, script di installazione) è fallito
+    #[error("{0}")]
+    Process(String),
+    /// Metadati o output non interpretabili
+    #[error("{0}")]
+    Parse(String),
+    /// Altri errori di I/O
+    #[error("{0}")]
+    Io(std::io::Error),
+    /// Un errore con la descrizione dell'operazione che lo ha causato; codice ed
+    /// exit code restano quelli dell'errore originale
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        source: Box<DverError>,
+    },
+}
+
+impl DverError {
+    /// Codice stabile, es. `not-found`, riportato nell'output JSON degli errori.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DverError::Context { source, .. } => source.code(),
+            DverError::Usage(_) => "usage",
+            DverError::NotFound(_) => "not-found",
+            DverError::Resolution(_) => "resolution",
+            DverError::Network(_) => "network",
+            DverError::NetworkDisabled(_) => "network-disabled",
+            DverError::HashMismatch(_) => "hash-mismatch",
+            DverError::Permission(_) => "permission",
+            DverError::Process(_) => "process",
+            DverError::Parse(_) => "parse",
+            DverError::Io(_) => "io",
+            DverError::Signature(_) => "signature",
+            DverError::Vulnerable(_) => "vulnerable",
+        }
+    }
+
+    /// Errore per un processo terminato con insuccesso: riporta il comando, l'exit
+    /// code, lo stderr e, se c'è, un suggerimento su come rimediare.
+    pub fn process(command: &str, output: &Output, hint: Option<&str>) -> DverError {
+        let mut message = match output.status.code() {
+            Some(code) => format!("`{}` exited with status {}", command, code),
+            None => format!("`{}` was terminated by a signal", command),
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            message.push_str(": ");
+            message.push_str(stderr.trim());
+        }
+        if let Some(hint) = hint {
+            message.push_str("\nhint: ");
+            message.push_str(hint);
+        }
+        DverError::Process(message)
+    }
+
+    /// Exit code con cui la CLI termina per questo errore.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            DverError::Context { source, .. } => source.exit_code(),
+            DverError::Usage(_) => 2,
+            DverError::NotFound(_) => 3,
+            DverError::Resolution(_) => 4,
+            DverError::Network(_) => 5,
+            DverError::HashMismatch(_) => 6,
+            DverError::Permission(_) => 7,
+            DverError::Process(_) => 8,
+            DverError::Parse(_) => 9,
+            DverError::Io(_) => 10,
+            DverError::NetworkDisabled(_) => 11,
+            DverError::Signature(_) => 12,
+            DverError::Vulnerable(_) => 13,
+        }
+    }
+}
+
+impl From<std::io::Error> for DverError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => DverError::Permission(e.to_string()),
+            std::io::ErrorKind::NotFound => DverError::NotFound(e.to_string()),
+            _ => DverError::Io(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for DverError {
+    /// L'URL della richiesta compare senza credenziali né query string, che possono
+    /// contenere token (es. `sync_remote` con una firma SAS).
+    fn from(e: reqwest::Error) -> Self {
+        let url = e.url().map(|url| crate::support::scrub_value(url.as_str()));
+        let decode = e.is_decode();
+        let e = e.without_url();
+        let message = match url {
+            Some(url) => format!("{} for {}", e, url),
+            None => e.to_string(),
+        };
+        if decode {
+            DverError::Parse(message)
+        } else {
+            DverError::Network(message)
+        }
+    }
+}
+
+impl From<serde_json::Error> for DverError {
+    fn from(e: serde_json::Error) -> Self {
+        DverError::Parse(e.to_string())
+    }
+}
+
+/// Aggiunge a un errore la descrizione dell'operazione in corso, es.
+/// `list_installed_sdks(config).context("Failed to list SDKs")?`.
+pub trait ResultExt<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, DverError>;
+    fn with_context<S: Into<String>>(self, context: impl FnOnce() -> S) -> Result<T, DverError>;
+}
+
+impl<T, E: Into<DverError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, DverError> {
+        self.map_err(|e| DverError::Context { context: context.into(), source: Box::new(e.into()) })
+    }
+
+    fn with_context<S: Into<String>>(self, context: impl FnOnce() -> S) -> Result<T, DverError> {
+        self.map_err(|e| DverError::Context { context: context().into(), source: Box::new(e.into()) })
+    }
+}
@@ -0,0 +1,504 @@
+//! Accesso HTTP dietro un trait, così i test possono sostituire la rete con
+//! risposte preparate o con un server locale.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::config::{TlsRoots, TlsVersion};
+use crate::proxy::system_proxy;
+use crate::support::scrub_value;
+use crate::{Config, DverError, ResultExt};
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("dver-core needs a TLS backend: enable the `native-tls` or `rustls` feature");
+
+/// Scarica documenti via HTTP GET.
+#[cfg(feature = "async")]
+pub trait HttpClient: Sync {
+    /// Corpo della risposta a una GET su `url`; le risposte non 2xx sono errori.
+    fn get(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, DverError>> + Send;
+
+    /// Come [`HttpClient::get`], interpretando il corpo come UTF-8.
+    fn get_text(&self, url: &str) -> impl Future<Output = Result<String, DverError>> + Send {
+        async move { into_text(url, self.get(url).await?) }
+    }
+
+    /// Scarica `url` nel file `dest`. L'implementazione predefinita passa da
+    /// [`HttpClient::get`]; i client reali scrivono il corpo a blocchi man mano che arriva.
+    fn download(&self, url: &str, dest: &Path, progress: &Progress<'_>) -> impl Future<Output = Result<Download, DverError>> + Send {
+        async move {
+            let body = self.get(url).await?;
+            let mut writer = DownloadWriter::create(dest, Some(body.len() as u64))?;
+            writer.write(&body, progress)?;
+            writer.finish()
+        }
+    }
+
+    /// Dimensione del documento `url` senza scaricarlo, se il server la dichiara.
+    /// L'implementazione predefinita passa da [`HttpClient::get`]; i client reali usano una HEAD.
+    fn content_length(&self, url: &str) -> impl Future<Output = Result<Option<u64>, DverError>> + Send {
+        async move { Ok(Some(self.get(url).await?.len() as u64)) }
+    }
+}
+
+/// Avanzamento di un download: byte scritti e dimensione totale, se il server la dichiara.
+pub type Progress<'a> = dyn Fn(u64, Option<u64>) + Sync + 'a;
+
+/// Un file scaricato con [`HttpClient::download`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    pub path: PathBuf,
+    pub size: u64,
+    /// SHA-256 in esadecimale, calcolato durante la scrittura
+    pub sha256: String,
+}
+
+/// Ogni quanti byte scritti si forza il flush su disco.
+const SYNC_EVERY: u64 = 8 * 1024 * 1024;
+
+/// Scrive un download a blocchi in `<dest>.part`, calcolando l'hash man mano, e lo
+/// rinomina in `dest` solo a download completo: un file a metà non prende mai il
+/// posto di quello finale.
+struct DownloadWriter {
+    dest: PathBuf,
+    part: PathBuf,
+    file: Option<File>,
+    hasher: Sha256,
+    written: u64,
+    unsynced: u64,
+    total: Option<u64>,
+}
+
+impl DownloadWriter {
+    fn create(dest: &Path, total: Option<u64>) -> Result<DownloadWriter, DverError> {
+        let mut part = dest.as_os_str().to_owned();
+        part.push(".part");
+        let part = PathBuf::from(part);
+        let file = File::create(&part).with_context(|| format!("Failed to create {}", part.display()))?;
+        Ok(DownloadWriter {
+            dest: dest.to_path_buf(),
+            part,
+            file: Some(file),
+            hasher: Sha256::new(),
+            written: 0,
+            unsynced: 0,
+            total,
+        })
+    }
+
+    fn write(&mut self, chunk: &[u8], progress: &Progress<'_>) -> Result<(), DverError> {
+        let file = self.file.as_mut().expect("download already finished");
+        file.write_all(chunk).with_context(|| format!("Failed to write {}", self.part.display()))?;
+        self.hasher.update(chunk);
+        self.written += chunk.len() as u64;
+        self.unsynced += chunk.len() as u64;
+        if self.unsynced >= SYNC_EVERY {
+            file.sync_data().with_context(|| format!("Failed to write {}", self.part.display()))?;
+            self.unsynced = 0;
+        }
+        progress(self.written, self.total);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Download, DverError> {
+        // Il file va chiuso prima di rinominarlo, altrimenti Windows rifiuta
+        let file = self.file.take().expect("download already finished");
+        let synced = file.sync_all();
+        drop(file);
+        if let Err(e) = synced.and_then(|_| fs::rename(&self.part, &self.dest)) {
+            let _ = fs::remove_file(&self.part);
+            return Err(e).with_context(|| format!("Failed to save the download to {}", self.dest.display()));
+        }
+        let sha256 = std::mem::take(&mut self.hasher).finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        Ok(Download { path: self.dest.clone(), size: self.written, sha256 })
+    }
+}
+
+impl Drop for DownloadWriter {
+    /// Un download interrotto non lascia file parziali.
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = fs::remove_file(&self.part);
+        }
+    }
+}
+
+fn into_text(url: &str, body: Vec<u8>) -> Result<String, DverError> {
+    String::from_utf8(body).map_err(|e| DverError::Parse(format!("Response from {} is not UTF-8: {}", scrub_value(url), e)))
+}
+
+fn status_error(url: &str, status: reqwest::StatusCode) -> DverError {
+    DverError::Network(format!("Failed to fetch {}: HTTP {}", scrub_value(url), status))
+}
+
+/// Keepalive TCP delle connessioni tenute nel pool tra una richiesta e l'altra.
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Attesa prima del tentativo numero `attempt` (da 1): 0,5 s raddoppiati ogni volta.
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_millis(500 * 2u64.pow(attempt - 1))
+}
+
+/// Con `--no-network` ogni richiesta fallisce prima di costruire il client.
+fn ensure_network(config: &Config, url: &str) -> Result<(), DverError> {
+    if config.no_network {
+        return Err(DverError::NetworkDisabled(format!("Network access is disabled by --no-network (attempted to fetch {})", url)));
+    }
+    Ok(())
+}
+
+/// Il proxy della configurazione o, senza, quello delle impostazioni di sistema (vedi
+/// [`crate::proxy`]); `None` lascia a reqwest le variabili `HTTPS_PROXY` e simili.
+fn proxy(config: &Config) -> Result<Option<reqwest::Proxy>, DverError> {
+    if let Some(proxy) = &config.proxy {
+        return reqwest::Proxy::all(proxy)
+            .map(Some)
+            .map_err(|e| DverError::Usage(format!("Invalid proxy {:?} in config: {}", proxy, e)));
+    }
+    // Un proxy di sistema illeggibile non deve impedire le connessioni dirette
+    let Some((url, system)) = system_proxy(config).and_then(|system| Some((system.url.as_ref()?, system))) else {
+        return Ok(None);
+    };
+    Ok(reqwest::Proxy::all(url).ok().map(|proxy| proxy.no_proxy(reqwest::NoProxy::from_string(&system.no_proxy.join(",")))))
+}
+
+/// Impostazioni TLS comuni ai client async e bloccante.
+struct Tls {
+    certificates: Vec<reqwest::Certificate>,
+    #[cfg_attr(not(feature = "rustls"), allow(dead_code))]
+    rustls: bool,
+    min_version: Option<reqwest::tls::Version>,
+}
+
+fn tls(config: &Config) -> Result<Tls, DverError> {
+    let certificates = match config.ca_bundle() {
+        Some(path) => {
+            let pem = std::fs::read(&path).with_context(|| format!("Failed to read the CA bundle {}", path.display()))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| DverError::Parse(format!("Invalid CA bundle {}: {}", path.display(), e)))?;
+            if certificates.is_empty() {
+                return Err(DverError::Parse(format!("CA bundle {} contains no PEM certificates", path.display())));
+            }
+            certificates
+        }
+        None => Vec::new(),
+    };
+    // Le radici webpki sono incluse solo con rustls; senza native-tls invece rustls è
+    // l'unico backend e `native` non cambia nulla
+    let rustls = config.tls_roots == TlsRoots::Webpki;
+    if rustls && !cfg!(feature = "rustls") {
+        return Err(DverError::Usage(
+            "tls_roots = \"webpki\" needs rustls, but this build of dver only has native TLS\nhint: set tls_roots to \"native\"".to_string()));
+    }
+    Ok(Tls {
+        certificates,
+        rustls,
+        min_version: config.min_tls_version.map(|v| match v {
+            TlsVersion::Tls12 => reqwest::tls::Version::TLS_1_2,
+            TlsVersion::Tls13 => reqwest::tls::Version::TLS_1_3,
+        }),
+    })
+}
+
+/// Errore di costruzione del client: quasi sempre impostazioni TLS non supportate.
+fn build_error(e: reqwest::Error) -> DverError {
+    DverError::Usage(format!(
+        "Cannot set up the HTTP client: {}\nhint: check ca_bundle, tls_roots and min_tls_version in the config", e))
+}
+
+/// Client reale basato su reqwest, con timeout, proxy, user agent e retry della
+/// configurazione. I cloni condividono lo stesso pool di connessioni.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct ReqwestClient {
+    client: reqwest::Client,
+    retries: u32,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestClient {
+    pub fn new(config: &Config) -> Result<ReqwestClient, DverError> {
+        // Il client è pensato per essere riusato: le connessioni restano nel pool e
+        // HTTP/2, negoziato via ALPN, multiplexa le richieste allo stesso host
+        let mut builder = reqwest::Client::builder()
+            .timeout(config.timeout())
+            .connect_timeout(config.connect_timeout())
+            .user_agent(config.user_agent())
+            .tcp_keepalive(TCP_KEEPALIVE)
+            .http2_adaptive_window(true);
+        if let Some(proxy) = proxy(config)? {
+            builder = builder.proxy(proxy);
+        }
+        let tls = tls(config)?;
+        #[cfg(feature = "rustls")]
+        if tls.rustls {
+            builder = builder.use_rustls_tls();
+        }
+        for certificate in tls.certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(version) = tls.min_version {
+            builder = builder.min_tls_version(version);
+        }
+        Ok(ReqwestClient { client: builder.build().map_err(build_error)?, retries: config.retries() })
+    }
+
+    /// Esegue una GET ripetendola fino a `retries` volte su errori di rete e risposte 5xx,
+    /// con attesa crescente tra un tentativo e l'altro.
+    pub async fn get_with_retries(&self, url: &str) -> Result<reqwest::Response, DverError> {
+        let resp = self.send_with_retries(url).await?;
+        if !resp.status().is_success() {
+            return Err(status_error(url, resp.status()));
+        }
+        Ok(resp)
+    }
+
+    /// Come [`HttpClient::get_text`], ma una risposta 404 dà `None`: per i file che
+    /// possono non esistere ancora, come quelli del profilo di `dver sync`.
+    pub async fn get_text_if_exists(&self, url: &str) -> Result<Option<String>, DverError> {
+        let resp = self.send_with_retries(url).await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(status_error(url, resp.status()));
+        }
+        into_text(url, resp.bytes().await?.to_vec()).map(Some)
+    }
+
+    /// La risposta all'ultimo tentativo, qualunque sia il suo stato.
+    async fn send_with_retries(&self, url: &str) -> Result<reqwest::Response, DverError> {
+        let mut attempt = 0;
+        loop {
+            let result = self.client.get(url).send().await;
+            let retryable = match &result {
+                Ok(resp) => resp.status().is_server_error(),
+                Err(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            };
+            if !retryable || attempt >= self.retries {
+                return Ok(result?);
+            }
+            attempt += 1;
+            tokio::time::sleep(retry_delay(attempt)).await;
+        }
+    }
+
+    /// Carica `body` su `url` con una PUT, come fanno WebDAV e gli archivi di blob.
+    /// Le credenziali nell'URL diventano autenticazione Basic.
+    pub async fn put(&self, url: &str, body: Vec<u8>) -> Result<(), DverError> {
+        let resp = self.client.put(url).body(body).send().await?;
+        if !resp.status().is_success() {
+            return Err(DverError::Network(format!("Failed to upload {}: HTTP {}", scrub_value(url), resp.status())));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpClient for ReqwestClient {
+    async fn get(&self, url: &str) -> Result<Vec<u8>, DverError> {
+        let resp = self.get_with_retries(url).await?;
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    async fn download(&self, url: &str, dest: &Path, progress: &Progress<'_>) -> Result<Download, DverError> {
+        let mut resp = self.get_with_retries(url).await?;
+        let mut writer = DownloadWriter::create(dest, resp.content_length())?;
+        while let Some(chunk) = resp.chunk().await? {
+            writer.write(&chunk, progress)?;
+        }
+        writer.finish()
+    }
+
+    async fn content_length(&self, url: &str) -> Result<Option<u64>, DverError> {
+        let resp = self.client.head(url).send().await?;
+        if !resp.status().is_success() {
+            return Err(status_error(url, resp.status()));
+        }
+        Ok(declared_length(resp.headers()))
+    }
+}
+
+/// Il `Content-Length` dichiarato; quello della risposta a una HEAD, che non ha corpo,
+/// si legge solo dall'intestazione.
+fn declared_length(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers.get(reqwest::header::CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+}
+
+/// Unico punto da cui passano le richieste HTTP: con `--no-network` fallisce subito.
+/// Conviene crearne uno per processo e riusarlo per tutte le richieste.
+#[cfg(feature = "async")]
+pub fn http_client(config: &Config, url: &str) -> Result<ReqwestClient, DverError> {
+    ensure_network(config, url)?;
+    ReqwestClient::new(config)
+}
+
+/// Client senza rete che risponde con contenuti preparati; gli URL sconosciuti danno HTTP 404.
+#[derive(Debug, Clone, Default)]
+pub struct StaticClient {
+    responses: HashMap<String, Vec<u8>>,
+}
+
+impl StaticClient {
+    pub fn new() -> StaticClient {
+        StaticClient::default()
+    }
+
+    /// Registra il corpo da restituire per `url`.
+    pub fn with(mut self, url: impl Into<String>, body: impl Into<Vec<u8>>) -> StaticClient {
+        self.responses.insert(url.into(), body.into());
+        self
+    }
+
+    fn response(&self, url: &str) -> Result<Vec<u8>, DverError> {
+        self.responses
+            .get(url)
+            .cloned()
+            .ok_or_else(|| DverError::Network(format!("Failed to fetch {}: HTTP 404 Not Found", url)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpClient for StaticClient {
+    async fn get(&self, url: &str) -> Result<Vec<u8>, DverError> {
+        self.response(url)
+    }
+}
+
+/// Variante bloccante dell'accesso HTTP, per chi non vuole un runtime async.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use std::io::Read;
+    use std::path::Path;
+
+    use super::{
+        build_error, declared_length, ensure_network, into_text, proxy, retry_delay, status_error, tls, Download, DownloadWriter, Progress,
+        StaticClient, TCP_KEEPALIVE,
+    };
+    use crate::{Config, DverError, ResultExt};
+
+    /// Scarica documenti via HTTP GET bloccando il thread corrente.
+    pub trait HttpClient {
+        /// Corpo della risposta a una GET su `url`; le risposte non 2xx sono errori.
+        fn get(&self, url: &str) -> Result<Vec<u8>, DverError>;
+
+        /// Come [`HttpClient::get`], interpretando il corpo come UTF-8.
+        fn get_text(&self, url: &str) -> Result<String, DverError> {
+            into_text(url, self.get(url)?)
+        }
+
+        /// Vedi [`super::HttpClient::download`].
+        fn download(&self, url: &str, dest: &Path, progress: &Progress<'_>) -> Result<Download, DverError> {
+            let body = self.get(url)?;
+            let mut writer = DownloadWriter::create(dest, Some(body.len() as u64))?;
+            writer.write(&body, progress)?;
+            writer.finish()
+        }
+
+        /// Vedi [`super::HttpClient::content_length`].
+        fn content_length(&self, url: &str) -> Result<Option<u64>, DverError> {
+            Ok(Some(self.get(url)?.len() as u64))
+        }
+    }
+
+    /// Come [`super::ReqwestClient`], basato su `reqwest::blocking`.
+    #[derive(Debug, Clone)]
+    pub struct ReqwestClient {
+        client: reqwest::blocking::Client,
+        retries: u32,
+    }
+
+    impl ReqwestClient {
+        pub fn new(config: &Config) -> Result<ReqwestClient, DverError> {
+            let mut builder = reqwest::blocking::Client::builder()
+                .timeout(config.timeout())
+                .connect_timeout(config.connect_timeout())
+                .user_agent(config.user_agent())
+                .tcp_keepalive(TCP_KEEPALIVE)
+                .http2_adaptive_window(true);
+            if let Some(proxy) = proxy(config)? {
+                builder = builder.proxy(proxy);
+            }
+            let tls = tls(config)?;
+            #[cfg(feature = "rustls")]
+            if tls.rustls {
+                builder = builder.use_rustls_tls();
+            }
+            for certificate in tls.certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+            if let Some(version) = tls.min_version {
+                builder = builder.min_tls_version(version);
+            }
+            Ok(ReqwestClient { client: builder.build().map_err(build_error)?, retries: config.retries() })
+        }
+
+        /// Esegue una GET con gli stessi retry della variante async.
+        pub fn get_with_retries(&self, url: &str) -> Result<reqwest::blocking::Response, DverError> {
+            let mut attempt = 0;
+            loop {
+                let result = self.client.get(url).send();
+                let retryable = match &result {
+                    Ok(resp) => resp.status().is_server_error(),
+                    Err(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+                };
+                if !retryable || attempt >= self.retries {
+                    let resp = result?;
+                    if !resp.status().is_success() {
+                        return Err(status_error(url, resp.status()));
+                    }
+                    return Ok(resp);
+                }
+                attempt += 1;
+                std::thread::sleep(retry_delay(attempt));
+            }
+        }
+    }
+
+    impl HttpClient for ReqwestClient {
+        fn get(&self, url: &str) -> Result<Vec<u8>, DverError> {
+            Ok(self.get_with_retries(url)?.bytes()?.to_vec())
+        }
+
+        fn download(&self, url: &str, dest: &Path, progress: &Progress<'_>) -> Result<Download, DverError> {
+            let mut resp = self.get_with_retries(url)?;
+            let mut writer = DownloadWriter::create(dest, resp.content_length())?;
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = resp.read(&mut buffer).with_context(|| format!("Failed to download {}", url))?;
+                if read == 0 {
+                    break;
+                }
+                writer.write(&buffer[..read], progress)?;
+            }
+            writer.finish()
+        }
+
+        fn content_length(&self, url: &str) -> Result<Option<u64>, DverError> {
+            let resp = self.client.head(url).send()?;
+            if !resp.status().is_success() {
+                return Err(status_error(url, resp.status()));
+            }
+            Ok(declared_length(resp.headers()))
+        }
+    }
+
+    impl HttpClient for StaticClient {
+        fn get(&self, url: &str) -> Result<Vec<u8>, DverError> {
+            self.response(url)
+        }
+    }
+
+    /// Come [`super::http_client`]: con `--no-network` fallisce subito.
+    pub fn http_client(config: &Config, url: &str) -> Result<ReqwestClient, DverError> {
+        ensure_network(config, url)?;
+        ReqwestClient::new(config)
+    }
+}
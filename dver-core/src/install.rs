@@ -0,0 +1,818 @@
+//! Installazione degli SDK tramite lo script ufficiale `dotnet-install` o, con
+//! `install_method = "archive"`, estraendo l'archivio ufficiale indicato dai metadati.
+
+use std::fs::{self, remove_file};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::{InstallMethod, Quality};
+#[cfg(feature = "async")]
+use crate::http::HttpClient;
+use crate::http::Download;
+use crate::inventory::scan_root_sdks;
+use crate::process::ProcessRunner;
+use crate::provenance::{self, Provenance};
+use crate::releases::{ChannelReleases, FileInfo, ReleaseChannel, ReleaseIndex};
+use crate::runtimeconfig::RuntimeKind;
+use crate::signature::{self, SignaturePolicy, Verification};
+use crate::version::SdkVersion;
+use crate::platform::Arch;
+use crate::archive::long_path;
+use crate::{archive, disk, macos, manifest, sbom, wsl};
+use crate::{paths, platform};
+use crate::{Config, DverError, ResultExt};
+
+/// Cosa installare; senza `lts` né `version` si usa il canale predefinito della configurazione.
+#[derive(Debug, Clone, Default)]
+pub struct InstallRequest {
+    /// Ultima versione del canale LTS
+    pub lts: bool,
+    /// Versione esatta, es. "8.0.404"
+    pub version: Option<String>,
+    /// Cartella di destinazione; di default la radice gestita, se configurata
+    pub install_dir: Option<PathBuf>,
+}
+
+impl InstallRequest {
+    /// Canale da installare, `default_channel`: nessuno con `lts` o `version`.
+    pub fn channel<'a>(&self, config: &'a Config) -> Option<&'a str> {
+        if self.lts || self.version.is_some() {
+            return None;
+        }
+        config.default_channel.as_deref()
+    }
+
+    /// Vero se si installa una build giornaliera del canale, non supportata.
+    pub fn is_daily(&self, config: &Config) -> bool {
+        self.channel(config).is_some() && config.quality == Some(Quality::Daily)
+    }
+}
+
+/// URL dello script di installazione: quello configurato o l'ufficiale per la piattaforma.
+pub fn install_script_url(config: &Config) -> String {
+    config.install_script_url.clone().unwrap_or_else(|| platform::current().install_script_url())
+}
+
+fn download_context(config: &Config) -> String {
+    format!("Failed to download the install script from {}", install_script_url(config))
+}
+
+/// URL della firma staccata da scaricare: solo se configurato e con una policy attiva.
+fn signature_url(config: &Config) -> Option<&str> {
+    if config.signature_policy == SignaturePolicy::Off {
+        return None;
+    }
+    config.install_script_signature_url.as_deref()
+}
+
+/// Scarica lo script di installazione per la piattaforma corrente nella cache di dver
+/// e ne verifica la firma secondo `signature_policy`.
+#[cfg(feature = "async")]
+pub async fn download_install_script(
+    client: &impl HttpClient,
+    runner: &dyn ProcessRunner,
+    config: &Config,
+) -> Result<PathBuf, DverError> {
+    check_host(config)?;
+    let script_path = install_script_path(config);
+    client.download(&install_script_url(config), &script_path, &|_, _| {}).await.with_context(|| download_context(config))?;
+    platform::current().make_executable(&script_path)?;
+    let signature = match signature_url(config) {
+        Some(url) => Some(client.get(url).await),
+        None => None,
+    };
+    verify_install_script(runner, config, &script_path, signature)?;
+    Ok(script_path)
+}
+
+/// Verifica lo script in `script_path`; `signature` è l'esito del download della firma
+/// staccata, se ne è configurata una. Senza firma staccata si usa Authenticode dove
+/// esiste. Se la verifica fallisce lo script viene cancellato.
+fn verify_install_script(
+    runner: &dyn ProcessRunner,
+    config: &Config,
+    script_path: &Path,
+    signature: Option<Result<Vec<u8>, DverError>>,
+) -> Result<(), DverError> {
+    if config.signature_policy == SignaturePolicy::Off {
+        return Ok(());
+    }
+    let verification = match signature {
+        Some(Ok(bytes)) => {
+            let mut sig_path = script_path.as_os_str().to_owned();
+            sig_path.push(".sig");
+            let sig_path = PathBuf::from(sig_path);
+            fs::write(&sig_path, bytes)
+                .with_context(|| format!("Failed to save the script signature to {}", sig_path.display()))?;
+            let verification = match config.signing_keyring() {
+                Some(keyring) => signature::verify_pgp(runner, &keyring, script_path, &sig_path),
+                None => Verification::Unavailable("signing_keyring is not set".to_string()),
+            };
+            let _ = remove_file(&sig_path);
+            verification
+        }
+        Some(Err(e)) => Verification::Unavailable(format!("the signature could not be downloaded: {}", e)),
+        None if platform::current().authenticode_command(script_path).is_some() => {
+            signature::verify_authenticode(runner, script_path)
+        }
+        None => Verification::Unavailable(
+            "no signature is configured; set install_script_signature_url and signing_keyring".to_string()),
+    };
+    let subject = format!("the install script from {}", install_script_url(config));
+    signature::enforce(config.signature_policy, &subject, verification).inspect_err(|_| {
+        let _ = remove_file(script_path);
+    })
+}
+
+/// Cartella in cui lo script installerà: `--install-path`, la radice gestita o
+/// quella predefinita dello script per l'utente.
+fn target_root(request: &InstallRequest, config: &Config) -> Option<PathBuf> {
+    root_for(request.install_dir.clone(), config)
+}
+
+/// Come [`target_root`], a partire da `--install-path`.
+fn root_for(install_dir: Option<PathBuf>, config: &Config) -> Option<PathBuf> {
+    install_dir
+        .or_else(|| config.managed_root())
+        .or_else(|| paths::home_dir().map(|home| platform::current().user_dotnet_root(&home)))
+}
+
+/// `-InstallDir` da passare allo script: `--install-path` o la radice gestita.
+fn install_dir(request: &InstallRequest, config: &Config) -> Option<PathBuf> {
+    request.install_dir.clone().or_else(|| config.managed_root())
+}
+
+/// Versioni presenti in `<root>/sdk`.
+fn sdk_versions_in(root: Option<&Path>) -> Vec<String> {
+    root.and_then(|root| scan_root_sdks(root).ok())
+        .map(|sdks| sdks.into_iter().map(|sdk| sdk.version).collect())
+        .unwrap_or_default()
+}
+
+/// Feed da cui lo script scarica senza `feed_url`.
+fn default_feed(request: &InstallRequest, config: &Config) -> &'static str {
+    if request.is_daily(config) { provenance::DAILY_FEED } else { provenance::DEFAULT_FEED }
+}
+
+/// Salva manifest e provenienza degli SDK appena installati; `source_url` e
+/// `source_sha256` identificano lo script eseguito o l'archivio estratto. Un errore
+/// è solo un avviso, perché l'installazione è comunque riuscita.
+fn record_install(
+    config: &Config,
+    request: &InstallRequest,
+    root: Option<&Path>,
+    before: &[String],
+    source_url: &str,
+    source_sha256: &str,
+) {
+    let Some(root) = root else { return };
+    for version in sdk_versions_in(Some(root)) {
+        let reinstalled = request.version.as_deref() == Some(version.as_str());
+        if before.contains(&version) && !reinstalled {
+            continue;
+        }
+        if let Err(e) = manifest::record(config, root, &version) {
+            eprintln!("Warning: cannot record the install manifest of SDK {}: {}", version, e);
+        }
+        let record = Provenance {
+            version: version.clone(),
+            root: root.to_path_buf(),
+            installed_at: DateTime::<Utc>::from(SystemTime::now()),
+            installed_by: provenance::current_user(),
+            feed_url: config.feed_url.clone().unwrap_or_else(|| default_feed(request, config).to_string()),
+            script_url: source_url.to_string(),
+            script_sha256: source_sha256.to_string(),
+            dver_version: env!("CARGO_PKG_VERSION").to_string(),
+            daily: request.is_daily(config),
+        };
+        if let Err(e) = provenance::record(config, record) {
+            eprintln!("Warning: cannot record where SDK {} came from: {}", version, e);
+        }
+    }
+}
+
+/// Dove esiste Authenticode, verifica la firma del `dotnet` appena installato.
+fn verify_installed_dotnet(runner: &dyn ProcessRunner, request: &InstallRequest, config: &Config) -> Result<(), DverError> {
+    if config.signature_policy == SignaturePolicy::Off {
+        return Ok(());
+    }
+    let platform = platform::current();
+    let Some(dir) = target_root(request, config) else {
+        return Ok(());
+    };
+    let exe = dir.join(platform.dotnet_exe());
+    if !exe.is_file() || platform.authenticode_command(&exe).is_none() {
+        return Ok(());
+    }
+    signature::enforce(config.signature_policy, &exe.display().to_string(), signature::verify_authenticode(runner, &exe))
+}
+
+/// Su macOS toglie la quarantena di Gatekeeper dal `dotnet` e dagli SDK appena
+/// installati, che altrimenti non partirebbero; se non ci riesce è solo un avviso.
+fn clear_quarantine(runner: &dyn ProcessRunner, root: Option<&Path>, before: &[String]) {
+    let Some(root) = root.filter(|_| macos::is_macos()) else { return };
+    let paths: Vec<PathBuf> = std::iter::once(root.join(platform::current().dotnet_exe()))
+        .chain(sdk_versions_in(Some(root)).into_iter().filter(|v| !before.contains(v)).map(|v| root.join("sdk").join(v)))
+        .filter(|path| macos::is_quarantined(runner, path))
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+    let stripped = macos::strip_quarantine(runner, root);
+    if let Some(path) = paths.iter().find(|path| macos::is_quarantined(runner, path)) {
+        let reason = stripped.err().map(|e| format!(": {}", e)).unwrap_or_default();
+        eprintln!(
+            "Warning: {} is still quarantined by Gatekeeper{}\nhint: run `xattr -dr {} {}`",
+            path.display(), reason, macos::QUARANTINE_ATTR, root.display());
+    }
+}
+
+/// Dove scaricare lo script: nella cache di dver, con la cartella temporanea come ripiego.
+fn install_script_path(config: &Config) -> PathBuf {
+    let mut file_path = config.cache_dir()
+        .map(|d| d.join("scripts"))
+        .filter(|d| fs::create_dir_all(d).is_ok())
+        .unwrap_or_else(std::env::temp_dir);
+    let unique = format!("{}_{}", platform::current().install_script_name(), std::process::id());
+    file_path.push(unique);
+    file_path
+}
+
+/// Esegue lo script di installazione e restituisce il suo output. Con
+/// `install_method = "archive"` una versione esatta si installa invece dall'archivio.
+#[cfg(feature = "async")]
+pub async fn install_sdk(
+    client: &impl HttpClient,
+    runner: &dyn ProcessRunner,
+    request: &InstallRequest,
+    config: &Config,
+) -> Result<String, DverError> {
+    if let Some(version) = archive_version(request, config) {
+        return install_from_archive(client, runner, request, config, version).await;
+    }
+    let script_path = download_install_script(client, runner, config).await?;
+    run_install_script(runner, request, config, &script_path)
+}
+
+/// Esegue lo script già scaricato in `script_path`, poi lo rimuove.
+fn run_install_script(
+    runner: &dyn ProcessRunner,
+    request: &InstallRequest,
+    config: &Config,
+    script_path: &Path,
+) -> Result<String, DverError> {
+    let root = target_root(request, config);
+    if let Err(e) = wsl::check_install_root(root.as_deref()) {
+        let _ = remove_file(script_path);
+        return Err(e);
+    }
+    let before = sdk_versions_in(root.as_deref());
+    let script_sha256 = manifest::sha256_file(script_path)
+        .with_context(|| format!("Failed to hash the install script {}", script_path.display()))?;
+    let result = execute_script(runner, &mut script_command(request, config, script_path));
+    let _ = remove_file(script_path);
+    let output = result?;
+
+    clear_quarantine(runner, root.as_deref(), &before);
+    verify_installed_dotnet(runner, request, config)?;
+    record_install(config, request, root.as_deref(), &before, &install_script_url(config), &script_sha256);
+    Ok(output)
+}
+
+/// Installa solo il runtime condiviso `kind` alla versione esatta `version`, sempre
+/// con lo script: serve alle macchine che eseguono le app e non hanno un SDK.
+#[cfg(feature = "async")]
+pub async fn install_runtime(
+    client: &impl HttpClient,
+    runner: &dyn ProcessRunner,
+    kind: RuntimeKind,
+    version: &str,
+    install_dir: Option<PathBuf>,
+    config: &Config,
+) -> Result<String, DverError> {
+    let script_path = download_install_script(client, runner, config).await?;
+    run_runtime_script(runner, kind, version, install_dir, config, &script_path)
+}
+
+/// Esegue lo script già scaricato per un runtime, poi lo rimuove.
+fn run_runtime_script(
+    runner: &dyn ProcessRunner,
+    kind: RuntimeKind,
+    version: &str,
+    install_dir: Option<PathBuf>,
+    config: &Config,
+    script_path: &Path,
+) -> Result<String, DverError> {
+    let request = InstallRequest { version: Some(version.to_string()), install_dir, ..Default::default() };
+    if let Err(e) = wsl::check_install_root(target_root(&request, config).as_deref()) {
+        let _ = remove_file(script_path);
+        return Err(e);
+    }
+    let mut command = script_command(&request, config, script_path);
+    command.arg("-Runtime").arg(kind.as_script_arg());
+    let result = execute_script(runner, &mut command);
+    let _ = remove_file(script_path);
+    let output = result?;
+    verify_installed_dotnet(runner, &request, config)?;
+    Ok(output)
+}
+
+/// Comando che esegue lo script in `script_path` per `request`.
+fn script_command(request: &InstallRequest, config: &Config, script_path: &Path) -> Command {
+    let mut command = platform::current().install_script_command(script_path);
+
+    command.envs(config.dotnet_env());
+
+    if request.lts {
+        command.arg("-Channel").arg("LTS");
+    } else if let Some(v) = &request.version {
+        command.arg("-Version").arg(v);
+    } else if let Some(channel) = request.channel(config) {
+        command.arg("-Channel").arg(channel);
+        if let Some(quality) = config.quality {
+            command.arg("-Quality").arg(quality.as_script_arg());
+        }
+    }
+
+    if let Some(path) = install_dir(request, config) {
+        command.arg("-InstallDir").arg(path);
+    }
+
+    // Sotto Rosetta 2 lo script vedrebbe x86_64 e installerebbe l'SDK emulato
+    if let Some(arch) = config.arch.or_else(|| platform::rosetta_translated().then_some(Arch::Arm64)) {
+        command.arg("-Architecture").arg(arch.name());
+    }
+
+    if let Some(feed) = &config.feed_url {
+        command.arg("-AzureFeed").arg(feed);
+    }
+    command
+}
+
+/// Esegue lo script; un'uscita non riuscita diventa un errore con il suo output.
+fn execute_script(runner: &dyn ProcessRunner, command: &mut Command) -> Result<String, DverError> {
+    let output = runner.output(command).context("Failed to start the dotnet-install script")?;
+
+    if !output.status.success() {
+        let mut message = format!("dotnet installation failed: dotnet-install script exited with status {:?}", output.status.code());
+        for stream in [&output.stderr, &output.stdout] {
+            let text = String::from_utf8_lossy(stream);
+            if !text.trim().is_empty() {
+                message.push('\n');
+                message.push_str(text.trim());
+            }
+        }
+        return Err(DverError::Process(message));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Esito dell'installazione di una delle versioni passate a [`install_sdks`].
+#[derive(Debug)]
+pub struct InstallOutcome {
+    pub version: String,
+    /// Output dello script, o perché l'installazione è fallita
+    pub result: Result<String, DverError>,
+    /// Quanto è durata l'installazione di questa versione
+    pub elapsed: Duration,
+}
+
+/// Installa più SDK esatti insieme: scarica lo script una volta, poi lo esegue per
+/// al massimo `install_jobs` versioni alla volta, ognuna in una radice temporanea
+/// dentro quella di destinazione. Le radici temporanee si uniscono poi a quella di
+/// destinazione una alla volta, così due script non scrivono mai negli stessi file.
+/// Con `install_method = "archive"` gli archivi si installano uno dopo l'altro.
+#[cfg(feature = "async")]
+pub async fn install_sdks(
+    client: &impl HttpClient,
+    runner: &dyn ProcessRunner,
+    versions: &[String],
+    install_dir: Option<PathBuf>,
+    config: &Config,
+) -> Result<Vec<InstallOutcome>, DverError> {
+    if config.install_method == InstallMethod::Archive {
+        let mut outcomes = Vec::new();
+        for version in versions {
+            let started = Instant::now();
+            let request = archive_request(version, install_dir.clone());
+            let result = install_from_archive(client, runner, &request, config, version).await;
+            outcomes.push(InstallOutcome { version: version.clone(), result, elapsed: started.elapsed() });
+        }
+        return Ok(outcomes);
+    }
+    let script_path = download_install_script(client, runner, config).await?;
+    run_install_script_for_each(runner, versions, install_dir, config, &script_path)
+}
+
+/// Radice temporanea in cui si installa `version` prima dell'unione.
+fn staging_root(root: &Path, version: &str) -> PathBuf {
+    root.join(format!(".dver-staging-{}", version))
+}
+
+/// Sposta in `dst` quello che di `src` non c'è ancora: le cartelle nuove (es.
+/// `sdk/<versione>`) per intero, mentre i file già presenti, come l'eseguibile
+/// dotnet, restano quelli di `dst`.
+pub(crate) fn merge_root(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if target.symlink_metadata().is_err() {
+            fs::rename(entry.path(), &target)?;
+        } else if entry.file_type()?.is_dir() && target.is_dir() {
+            merge_root(&entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Vedi [`install_sdks`]; lo script in `script_path` viene rimosso alla fine.
+fn run_install_script_for_each(
+    runner: &dyn ProcessRunner,
+    versions: &[String],
+    install_dir: Option<PathBuf>,
+    config: &Config,
+    script_path: &Path,
+) -> Result<Vec<InstallOutcome>, DverError> {
+    let target = InstallRequest { install_dir, ..InstallRequest::default() };
+    let Some(root) = target_root(&target, config) else {
+        let _ = remove_file(script_path);
+        return Err(DverError::NotFound(
+            "Cannot determine where to install the SDKs\nhint: pass --install-path or set install_root".to_string()));
+    };
+    if let Err(e) = wsl::check_install_root(Some(&root)) {
+        let _ = remove_file(script_path);
+        return Err(e);
+    }
+    let before = sdk_versions_in(Some(&root));
+    let script_sha256 = manifest::sha256_file(script_path)
+        .with_context(|| format!("Failed to hash the install script {}", script_path.display()))?;
+
+    let next = AtomicUsize::new(0);
+    let finished: Mutex<Vec<(usize, InstallOutcome)>> = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..config.install_jobs().min(versions.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(version) = versions.get(index) else { break };
+                let started = Instant::now();
+                let staging = staging_root(&root, version);
+                let _ = fs::remove_dir_all(long_path(&staging));
+                let request = InstallRequest { version: Some(version.clone()), install_dir: Some(staging), lts: false };
+                let result = execute_script(runner, &mut script_command(&request, config, script_path));
+                let outcome = InstallOutcome { version: version.clone(), result, elapsed: started.elapsed() };
+                finished.lock().unwrap_or_else(|e| e.into_inner()).push((index, outcome));
+            });
+        }
+    });
+    let _ = remove_file(script_path);
+
+    let mut finished = finished.into_inner().unwrap_or_else(|e| e.into_inner());
+    finished.sort_by_key(|(index, _)| *index);
+    let mut outcomes = Vec::new();
+    for (_, InstallOutcome { version, result, elapsed }) in finished {
+        let staging = staging_root(&root, &version);
+        let result = result.and_then(|output| {
+            merge_root(&long_path(&staging), &long_path(&root))
+                .with_context(|| format!("Failed to move SDK {} from {} into {}", version, staging.display(), root.display()))?;
+            Ok(output)
+        });
+        let _ = fs::remove_dir_all(long_path(&staging));
+        outcomes.push(InstallOutcome { version, result, elapsed });
+    }
+
+    let merged = InstallRequest { install_dir: Some(root.clone()), ..InstallRequest::default() };
+    if outcomes.iter().any(|o| o.result.is_ok()) {
+        verify_installed_dotnet(runner, &merged, config)?;
+    }
+    record_install(config, &merged, Some(&root), &before, &install_script_url(config), &script_sha256);
+    Ok(outcomes)
+}
+
+// --- Installazione dall'archivio ---
+
+/// Versione da installare dall'archivio: solo con `install_method = "archive"` e una
+/// versione esatta; LTS e canali passano sempre dallo script.
+fn archive_version<'a>(request: &'a InstallRequest, config: &Config) -> Option<&'a str> {
+    if config.install_method != InstallMethod::Archive || request.lts {
+        return None;
+    }
+    request.version.as_deref()
+}
+
+fn archive_request(version: &str, install_dir: Option<PathBuf>) -> InstallRequest {
+    InstallRequest { version: Some(version.to_string()), install_dir, lts: false }
+}
+
+/// Canale dell'indice che contiene l'SDK `version`.
+pub(crate) fn channel_of<'a>(index: &'a ReleaseIndex, version: &SdkVersion) -> Result<&'a ReleaseChannel, DverError> {
+    let wanted = format!("{}.{}", version.major, version.minor);
+    index
+        .releases_index
+        .iter()
+        .find(|c| c.channel_version.as_deref() == Some(wanted.as_str()))
+        .ok_or_else(|| DverError::NotFound(format!("No release channel {} for SDK {}", wanted, version)))
+}
+
+/// Sui sistemi per cui Microsoft non pubblica build lo script scaricherebbe un archivio
+/// che non esiste; con un `feed_url`, che può ospitarle, si tenta comunque.
+fn check_host(config: &Config) -> Result<(), DverError> {
+    if config.feed_url.is_some() {
+        return Ok(());
+    }
+    match platform::unsupported_host_message(platform::current_support(), &platform::rid_for(config.arch)) {
+        Some(message) => Err(DverError::NotFound(message)),
+        None => Ok(()),
+    }
+}
+
+/// Archivio ufficiale dell'SDK `version` per la piattaforma corrente e l'architettura
+/// della configurazione.
+fn archive_file(releases: &ChannelReleases, version: &SdkVersion, config: &Config) -> Result<FileInfo, DverError> {
+    let rid = platform::rid_for(config.arch);
+    sbom::sdk_archive(std::slice::from_ref(releases), &rid, version).cloned().ok_or_else(|| {
+        let message = platform::unsupported_host_message(platform::current_support(), &rid)
+            .unwrap_or_else(|| format!("The release metadata lists no {} archive of SDK {}", rid, version));
+        DverError::NotFound(message)
+    })
+}
+
+/// Dove scaricare l'archivio: nella cache di dver, con la cartella temporanea come ripiego.
+fn archive_path(config: &Config, name: &str) -> PathBuf {
+    config.cache_dir()
+        .map(|d| d.join("archives"))
+        .filter(|d| fs::create_dir_all(d).is_ok())
+        .unwrap_or_else(std::env::temp_dir)
+        .join(name)
+}
+
+/// Controlla l'archivio in `path` con lo SHA-512 riportato dai metadati per `file`.
+pub(crate) fn verify_archive(file: &FileInfo, path: &Path) -> Result<(), DverError> {
+    let expected = file.hash.as_deref().filter(|h| !h.is_empty()).ok_or_else(|| {
+        DverError::HashMismatch(format!("Cannot verify {}: the release metadata has no SHA-512 for it", file.url))
+    })?;
+    let actual = archive::sha512_file(path).with_context(|| format!("Failed to hash {}", path.display()))?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(DverError::HashMismatch(format!(
+            "SHA-512 mismatch for {}\nexpected {}\ngot      {}", file.url, expected, actual)));
+    }
+    Ok(())
+}
+
+/// Archivio già presente nella cache (ad esempio da un bundle offline) e integro:
+/// non serve scaricarlo di nuovo.
+fn cached_archive(config: &Config, file: &FileInfo) -> Option<Download> {
+    let path = archive_path(config, &file.name);
+    if !path.is_file() || verify_archive(file, &path).is_err() {
+        return None;
+    }
+    let size = fs::metadata(&path).ok()?.len();
+    let sha256 = manifest::sha256_file(&path).ok()?;
+    Some(Download { path, size, sha256 })
+}
+
+/// Scarica e installa l'archivio dell'SDK `version`.
+#[cfg(feature = "async")]
+async fn install_from_archive(
+    client: &impl HttpClient,
+    runner: &dyn ProcessRunner,
+    request: &InstallRequest,
+    config: &Config,
+    version: &str,
+) -> Result<String, DverError> {
+    let parsed: SdkVersion = version.parse()?;
+    let index = crate::metadata::fetch_release_index(client, config).await?;
+    let releases = crate::metadata::fetch_channel(client, config, channel_of(&index, &parsed)?).await?;
+    let file = archive_file(&releases, &parsed, config)?;
+    let download = match cached_archive(config, &file) {
+        Some(download) => download,
+        None => client
+            .download(&file.url, &archive_path(config, &file.name), &|_, _| {})
+            .await
+            .with_context(|| format!("Failed to download SDK {} from {}", version, file.url))?,
+    };
+    install_archive(runner, request, config, version, &file, &download)
+}
+
+/// Quanto occupa un SDK estratto rispetto al suo archivio compresso, all'incirca.
+pub const EXTRACTION_RATIO: u64 = 4;
+
+/// Stima di download e spazio su disco per installare un SDK dall'archivio ufficiale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct InstallEstimate {
+    /// Byte da scaricare; zero se l'archivio è già nella cache
+    pub download_bytes: u64,
+    /// Byte occupati una volta estratto, stimati con [`EXTRACTION_RATIO`]
+    pub extracted_bytes: u64,
+}
+
+impl InstallEstimate {
+    fn new(archive_bytes: u64, cached: bool) -> Self {
+        InstallEstimate {
+            download_bytes: if cached { 0 } else { archive_bytes },
+            extracted_bytes: archive_bytes.saturating_mul(EXTRACTION_RATIO),
+        }
+    }
+}
+
+/// Dimensione dell'archivio già nella cache, se c'è; senza verificarlo, è solo una stima.
+fn cached_size(config: &Config, file: &FileInfo) -> Option<u64> {
+    fs::metadata(archive_path(config, &file.name)).ok().filter(|m| m.is_file()).map(|m| m.len())
+}
+
+/// Stima quanto scaricherà e occuperà l'SDK `version`: la dimensione dell'archivio viene
+/// dai metadati, quando la riportano, o da una richiesta HEAD. `None` se il server non la dichiara.
+#[cfg(feature = "async")]
+pub async fn estimate_install(client: &impl HttpClient, config: &Config, version: &str) -> Result<Option<InstallEstimate>, DverError> {
+    let parsed: SdkVersion = version.parse()?;
+    let index = crate::metadata::fetch_release_index(client, config).await?;
+    let releases = crate::metadata::fetch_channel(client, config, channel_of(&index, &parsed)?).await?;
+    let file = archive_file(&releases, &parsed, config)?;
+    if let Some(size) = cached_size(config, &file) {
+        return Ok(Some(InstallEstimate::new(size, true)));
+    }
+    let size = match file.size {
+        Some(size) => Some(size),
+        None => client.content_length(&file.url).await?,
+    };
+    Ok(size.map(|size| InstallEstimate::new(size, false)))
+}
+
+/// Verifica l'archivio scaricato con lo SHA-512 dei metadati e lo estrae in una
+/// radice temporanea, poi unita a quella di destinazione. L'archivio viene rimosso.
+fn install_archive(
+    runner: &dyn ProcessRunner,
+    request: &InstallRequest,
+    config: &Config,
+    version: &str,
+    file: &FileInfo,
+    download: &Download,
+) -> Result<String, DverError> {
+    let result = unpack_archive(runner, request, config, version, file, download);
+    let _ = remove_file(&download.path);
+    result
+}
+
+fn unpack_archive(
+    runner: &dyn ProcessRunner,
+    request: &InstallRequest,
+    config: &Config,
+    version: &str,
+    file: &FileInfo,
+    download: &Download,
+) -> Result<String, DverError> {
+    verify_archive(file, &download.path)?;
+
+    let root = target_root(request, config).ok_or_else(|| DverError::NotFound(
+        "Cannot determine where to install the SDK\nhint: pass --install-path or set install_root".to_string()))?;
+    wsl::check_install_root(Some(&root))?;
+    let before = sdk_versions_in(Some(&root));
+    let staging = staging_root(&root, version);
+    let _ = fs::remove_dir_all(long_path(&staging));
+    let result = archive::extract(&download.path, &staging, &|_, _| {}).and_then(|extracted| {
+        merge_root(&long_path(&staging), &long_path(&root))
+            .with_context(|| format!("Failed to move SDK {} from {} into {}", version, staging.display(), root.display()))?;
+        Ok(extracted)
+    });
+    let _ = fs::remove_dir_all(long_path(&staging));
+    let extracted = result?;
+
+    clear_quarantine(runner, Some(&root), &before);
+    verify_installed_dotnet(runner, request, config)?;
+    record_install(config, request, Some(&root), &before, &file.url, &download.sha256);
+    Ok(format!(
+        "Extracted {} files ({}) from {} into {}",
+        extracted.files, disk::format_size(extracted.bytes), file.name, root.display()))
+}
+
+/// Installazione senza runtime async, basata su [`crate::http::blocking`].
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    use super::{
+        archive_file, archive_path, archive_request, archive_version, cached_archive, cached_size, channel_of, check_host,
+        download_context, install_archive, InstallEstimate,
+        install_script_path, install_script_url, run_install_script, run_install_script_for_each, run_runtime_script,
+        signature_url, verify_install_script, InstallOutcome, InstallRequest,
+    };
+    use crate::config::InstallMethod;
+    use crate::http::blocking::HttpClient;
+    use crate::metadata::blocking::{fetch_channel, fetch_release_index};
+    use crate::process::ProcessRunner;
+    use crate::runtimeconfig::RuntimeKind;
+    use crate::version::SdkVersion;
+    use crate::{platform, Config, DverError, ResultExt};
+
+    /// Vedi [`super::download_install_script`].
+    pub fn download_install_script(
+        client: &impl HttpClient,
+        runner: &dyn ProcessRunner,
+        config: &Config,
+    ) -> Result<PathBuf, DverError> {
+        check_host(config)?;
+        let script_path = install_script_path(config);
+        client.download(&install_script_url(config), &script_path, &|_, _| {}).with_context(|| download_context(config))?;
+        platform::current().make_executable(&script_path)?;
+        let signature = signature_url(config).map(|url| client.get(url));
+        verify_install_script(runner, config, &script_path, signature)?;
+        Ok(script_path)
+    }
+
+    /// Vedi [`super::install_sdk`].
+    pub fn install_sdk(
+        client: &impl HttpClient,
+        runner: &dyn ProcessRunner,
+        request: &InstallRequest,
+        config: &Config,
+    ) -> Result<String, DverError> {
+        if let Some(version) = archive_version(request, config) {
+            return install_from_archive(client, runner, request, config, version);
+        }
+        let script_path = download_install_script(client, runner, config)?;
+        run_install_script(runner, request, config, &script_path)
+    }
+
+    /// Vedi [`super::install_runtime`].
+    pub fn install_runtime(
+        client: &impl HttpClient,
+        runner: &dyn ProcessRunner,
+        kind: RuntimeKind,
+        version: &str,
+        install_dir: Option<PathBuf>,
+        config: &Config,
+    ) -> Result<String, DverError> {
+        let script_path = download_install_script(client, runner, config)?;
+        run_runtime_script(runner, kind, version, install_dir, config, &script_path)
+    }
+
+    /// Vedi [`super::install_sdks`].
+    pub fn install_sdks(
+        client: &impl HttpClient,
+        runner: &dyn ProcessRunner,
+        versions: &[String],
+        install_dir: Option<PathBuf>,
+        config: &Config,
+    ) -> Result<Vec<InstallOutcome>, DverError> {
+        if config.install_method == InstallMethod::Archive {
+            let outcomes = versions
+                .iter()
+                .map(|version| {
+                    let started = Instant::now();
+                    let request = archive_request(version, install_dir.clone());
+                    let result = install_from_archive(client, runner, &request, config, version);
+                    InstallOutcome { version: version.clone(), result, elapsed: started.elapsed() }
+                })
+                .collect();
+            return Ok(outcomes);
+        }
+        let script_path = download_install_script(client, runner, config)?;
+        run_install_script_for_each(runner, versions, install_dir, config, &script_path)
+    }
+
+    /// Vedi [`super::estimate_install`].
+    pub fn estimate_install(client: &impl HttpClient, config: &Config, version: &str) -> Result<Option<InstallEstimate>, DverError> {
+        let parsed: SdkVersion = version.parse()?;
+        let index = fetch_release_index(client, config)?;
+        let releases = fetch_channel(client, config, channel_of(&index, &parsed)?)?;
+        let file = archive_file(&releases, &parsed, config)?;
+        if let Some(size) = cached_size(config, &file) {
+            return Ok(Some(InstallEstimate::new(size, true)));
+        }
+        let size = match file.size {
+            Some(size) => Some(size),
+            None => client.content_length(&file.url)?,
+        };
+        Ok(size.map(|size| InstallEstimate::new(size, false)))
+    }
+
+    /// Vedi [`super::install_from_archive`].
+    fn install_from_archive(
+        client: &impl HttpClient,
+        runner: &dyn ProcessRunner,
+        request: &InstallRequest,
+        config: &Config,
+        version: &str,
+    ) -> Result<String, DverError> {
+        let parsed: SdkVersion = version.parse()?;
+        let index = fetch_release_index(client, config)?;
+        let releases = fetch_channel(client, config, channel_of(&index, &parsed)?)?;
+        let file = archive_file(&releases, &parsed, config)?;
+        let download = match cached_archive(config, &file) {
+            Some(download) => download,
+            None => client
+                .download(&file.url, &archive_path(config, &file.name), &|_, _| {})
+                .with_context(|| format!("Failed to download SDK {} from {}", version, file.url))?,
+        };
+        install_archive(runner, request, config, version, &file, &download)
+    }
+}
@@ -0,0 +1,522 @@
+//! SDK, runtime e workload installati localmente.
+//!
+//! SDK e runtime si leggono dalle cartelle `sdk/` e `shared/` della radice attiva
+//! (quella gestita, `DOTNET_ROOT`, quella del `dotnet` nel `PATH` o una delle posizioni
+//! standard), senza avviare dotnet; `dotnet --list-sdks` resta il ripiego quando
+//! nessuna radice è riconoscibile.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::paths;
+use crate::platform::{self, Arch, Platform};
+use crate::process::ProcessRunner;
+use crate::project::{find_global_json, GlobalJson};
+use crate::version::{compare_version_strings, sort_versions, SdkVersion};
+use crate::{Config, DverError, ResultExt};
+
+/// Un SDK presente sul disco.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct InstalledSdk {
+    pub version: String,
+    /// Cartella dell'SDK, es. `<root>/sdk/8.0.404`
+    pub path: PathBuf,
+    /// Nome della radice in `[roots]` a cui appartiene, se elencata per radice
+    pub root: Option<String>,
+    /// Architettura della radice, vedi [`platform::root_arch`]
+    pub arch: Option<Arch>,
+}
+
+/// Prepara un comando `dotnet` con le variabili d'ambiente della configurazione e i
+/// messaggi in inglese.
+/// Se è configurata una radice gestita che contiene dotnet, usa quell'eseguibile.
+pub fn dotnet_command(config: &Config) -> Command {
+    let program = config.managed_root()
+        .map(|root| root.join(platform::current().dotnet_exe()))
+        .filter(|p| p.is_file())
+        .unwrap_or_else(|| PathBuf::from("dotnet"));
+    let mut cmd = Command::new(program);
+    // L'output si interpreta in inglese, qualunque sia la lingua del sistema
+    cmd.env("DOTNET_CLI_UI_LANGUAGE", "en");
+    cmd.envs(config.dotnet_env());
+    cmd
+}
+
+/// Esegue `dotnet <args>` e ne restituisce lo stdout; se dotnet manca o fallisce,
+/// l'errore riporta il comando, lo stderr e cosa fare.
+pub fn dotnet_output(runner: &dyn ProcessRunner, config: &Config, args: &[&str]) -> Result<String, DverError> {
+    run_dotnet(runner, dotnet_command(config), args)
+}
+
+/// Come [`dotnet_output`], ma lanciato nella cartella `dir`: il global.json che vi
+/// trova dotnet decide l'SDK.
+pub fn dotnet_output_in(runner: &dyn ProcessRunner, config: &Config, dir: &Path, args: &[&str]) -> Result<String, DverError> {
+    let mut cmd = dotnet_command(config);
+    cmd.current_dir(dir);
+    run_dotnet(runner, cmd, args)
+}
+
+fn run_dotnet(runner: &dyn ProcessRunner, mut cmd: Command, args: &[&str]) -> Result<String, DverError> {
+    let command = format!("dotnet {}", args.join(" "));
+    let output = runner.output(cmd.args(args)).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => DverError::NotFound(format!(
+            "Cannot run `{}`: dotnet was not found\nhint: install an SDK with `dver install` or add dotnet to PATH", command)),
+        _ => DverError::Process(format!("Cannot run `{}`: {}", command, e)),
+    })?;
+    if !output.status.success() {
+        return Err(DverError::process(&command, &output, Some("run `dver doctor` to check the installation")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Vero se `dotnet --version` termina con successo.
+pub fn is_dotnet_installed(runner: &dyn ProcessRunner, config: &Config) -> bool {
+    runner
+        .output(dotnet_command(config).arg("--version"))
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Elenca gli SDK presenti in `<root>/sdk` senza avviare dotnet.
+pub fn scan_root_sdks(root: &Path) -> Result<Vec<InstalledSdk>, DverError> {
+    let sdk_dir = root.join("sdk");
+    if !sdk_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let arch = platform::root_arch(root);
+    let mut sdks = Vec::new();
+    for entry in fs::read_dir(&sdk_dir).with_context(|| format!("Failed to read {}", sdk_dir.display()))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() && name.starts_with(|c: char| c.is_ascii_digit()) {
+            sdks.push(InstalledSdk { version: name, path: entry.path(), root: None, arch });
+        }
+    }
+    sdks.sort_by(|a, b| compare_version_strings(&a.version, &b.version));
+    Ok(sdks)
+}
+
+/// Cartella che contiene l'eseguibile dotnet trovato nel `PATH`, seguendo i link
+/// simbolici (es. `/usr/bin/dotnet` -> `/usr/share/dotnet/dotnet`).
+pub fn dotnet_dir_on_path(platform: &dyn Platform) -> Option<PathBuf> {
+    crate::environment::path_entries()
+        .into_iter()
+        .map(|dir| dir.join(platform.dotnet_exe()))
+        .find(|exe| exe.is_file())
+        .and_then(|exe| fs::canonicalize(exe).ok())
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+/// Vero se `dir` ha la struttura di una radice dotnet.
+fn is_dotnet_root(dir: &Path) -> bool {
+    dir.join("sdk").is_dir() || dir.join("shared").is_dir()
+}
+
+/// Radice in cui cercare SDK e runtime senza avviare dotnet: la radice gestita se
+/// configurata, altrimenti la prima riconoscibile tra `DOTNET_ROOT`, la cartella del
+/// `dotnet` nel `PATH`, quella dell'utente e quelle di sistema.
+pub fn active_dotnet_root(config: &Config) -> Option<PathBuf> {
+    if let Some(root) = config.managed_root() {
+        return Some(root);
+    }
+    known_dotnet_roots(config).into_iter().next()
+}
+
+/// Radici riconoscibili nelle posizioni note, nell'ordine di [`active_dotnet_root`]
+/// e senza doppioni: `DOTNET_ROOT`, la cartella del `dotnet` nel `PATH`, quella
+/// dell'utente e quelle di sistema.
+pub fn known_dotnet_roots(config: &Config) -> Vec<PathBuf> {
+    let platform = platform::current();
+    let dotnet_root = config.dotnet_env().get("DOTNET_ROOT").map(PathBuf::from)
+        .or_else(|| std::env::var_os("DOTNET_ROOT").filter(|v| !v.is_empty()).map(PathBuf::from));
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for dir in dotnet_root
+        .into_iter()
+        .chain(dotnet_dir_on_path(platform))
+        .chain(paths::home_dir().map(|home| platform.user_dotnet_root(&home)))
+        .chain(platform.system_dotnet_roots())
+    {
+        if is_dotnet_root(&dir) && !roots.iter().any(|r| crate::provenance::same_root(r, &dir)) {
+            roots.push(dir);
+        }
+    }
+    roots
+}
+
+/// SDK della radice attiva (vedi [`active_dotnet_root`]); senza una radice
+/// riconoscibile, quelli riportati da `dotnet --list-sdks`.
+pub fn list_installed_sdks(runner: &dyn ProcessRunner, config: &Config) -> Result<Vec<InstalledSdk>, DverError> {
+    if let Some(root) = config.managed_root() {
+        let root_name = config.install_root_name();
+        return Ok(scan_root_sdks(&root)?
+            .into_iter()
+            .map(|sdk| InstalledSdk { root: root_name.clone(), ..sdk })
+            .collect());
+    }
+    if let Some(root) = active_dotnet_root(config) {
+        return scan_root_sdks(&root);
+    }
+    let stdout = dotnet_output(runner, config, &["--list-sdks"]).context("Failed to list installed SDKs")?;
+    read_list_sdks(config, &stdout)
+}
+
+/// Gli SDK elencati da `dotnet --list-sdks`. Le righe che non si sanno interpretare non
+/// svuotano l'elenco: si leggono le cartelle tra parentesi quadre e, se nemmeno quelle
+/// bastano, si restituisce un errore [`DverError::Parse`].
+pub fn read_list_sdks(config: &Config, stdout: &str) -> Result<Vec<InstalledSdk>, DverError> {
+    let mut parsed = parse_list_sdks(stdout);
+    if parsed.is_empty() && looks_like_listing(stdout) {
+        // Formato sconosciuto: restano le cartelle tra parentesi quadre, `<root>/sdk`
+        parsed = listed_dirs(stdout)
+            .iter()
+            .filter_map(|dir| dir.parent())
+            .map(scan_root_sdks)
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        if parsed.is_empty() {
+            return Err(parse_failure(config, "dotnet --list-sdks", stdout));
+        }
+        dump_unparsed(config, "dotnet --list-sdks", stdout);
+    }
+    // Le righe servono a trovare le radici: dove le cartelle ci sono, si leggono quelle
+    let mut sdks = Vec::new();
+    let mut scanned: Vec<PathBuf> = Vec::new();
+    for sdk in parsed {
+        match sdk.path.parent().and_then(Path::parent).filter(|root| root.join("sdk").is_dir()) {
+            Some(root) if scanned.iter().any(|r| r == root) => {}
+            Some(root) => {
+                sdks.extend(scan_root_sdks(root)?);
+                scanned.push(root.to_path_buf());
+            }
+            None => sdks.push(sdk),
+        }
+    }
+    sdks.sort_by(|a, b| compare_version_strings(&a.version, &b.version));
+    sdks.dedup_by(|a, b| a.path == b.path);
+    Ok(sdks)
+}
+
+/// Divide una riga di `dotnet --list-sdks` o `--list-runtimes` nelle prime `words`
+/// parole e nella cartella tra parentesi quadre. La cartella può contenere spazi,
+/// caratteri non ASCII e a sua volta parentesi quadre: conta solo la prima `[` dopo
+/// l'intestazione e l'ultima `]` della riga. Altre parole prima della cartella, come
+/// un'annotazione aggiunta da una versione futura di dotnet, si ignorano.
+fn split_listing(line: &str, words: usize) -> Option<(Vec<&str>, &str)> {
+    let line = line.trim_start_matches('\u{feff}').trim();
+    let (header, rest) = line.split_once('[')?;
+    let head: Vec<&str> = header.split_whitespace().take(words).collect();
+    let path = rest.strip_suffix(']')?.trim();
+    (head.len() == words && !path.is_empty()).then_some((head, path))
+}
+
+/// Le cartelle tra parentesi quadre di un elenco di dotnet, qualunque sia il resto della riga.
+fn listed_dirs(stdout: &str) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = stdout
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.trim().split_once('[')?;
+            let dir = rest.strip_suffix(']')?.trim();
+            (!dir.is_empty()).then(|| PathBuf::from(dir))
+        })
+        .filter(|dir| dir.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Vero se `stdout` sembra un elenco di dotnet (righe con una versione o con una
+/// cartella tra parentesi quadre) e non un messaggio come «nessun SDK trovato».
+fn looks_like_listing(stdout: &str) -> bool {
+    stdout.lines().any(|line| {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        (line.contains('[') && line.ends_with(']')) || line.split_whitespace().any(looks_like_version)
+    })
+}
+
+/// Con `debug_parse` stampa su stderr l'output di `command` che non si è saputo
+/// interpretare, da allegare a una segnalazione.
+fn dump_unparsed(config: &Config, command: &str, stdout: &str) {
+    if config.debug_parse {
+        eprintln!("--- raw output of `{}` ---\n{}\n--- end of output ---", command, stdout.trim_end());
+    }
+}
+
+/// L'errore per un output di `command` in un formato sconosciuto, al posto di un
+/// risultato vuoto che farebbe credere che non ci sia nulla.
+fn parse_failure(config: &Config, command: &str, stdout: &str) -> DverError {
+    dump_unparsed(config, command, stdout);
+    DverError::Parse(format!(
+        "Cannot read the output of `{}`: its format is not recognized\nhint: re-run with --debug-parse to print it and include it in a bug report",
+        command
+    ))
+}
+
+/// La versione stampata da `dotnet --version`: l'ultima riga che è solo una versione,
+/// così il benvenuto del primo avvio o gli avvisi stampati prima non contano.
+pub fn parse_version_output(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .rfind(|line| looks_like_version(line))
+        .map(str::to_string)
+}
+
+/// `dotnet --version` nella cartella corrente, letto con [`parse_version_output`].
+pub fn dotnet_version(runner: &dyn ProcessRunner, config: &Config) -> Result<String, DverError> {
+    let stdout = dotnet_output(runner, config, &["--version"])?;
+    parse_version_output(&stdout).ok_or_else(|| parse_failure(config, "dotnet --version", &stdout))
+}
+
+/// Vero se `word` ha la forma di una versione, es. `8.0.404` o `9.0.100-rc.2.24474.11`:
+/// scarta le righe di avviso o di errore, anche tradotte, che dotnet stampa sullo stdout.
+fn looks_like_version(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_digit())
+        && word.contains('.')
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+/// Interpreta l'output di `dotnet --list-sdks`, righe `8.0.404 [/usr/share/dotnet/sdk]`;
+/// le righe di altra forma si ignorano.
+pub fn parse_list_sdks(stdout: &str) -> Vec<InstalledSdk> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (head, base) = split_listing(line, 1)?;
+            let version = head[0];
+            if !looks_like_version(version) {
+                return None;
+            }
+            let base = PathBuf::from(base);
+            let arch = base.parent().and_then(platform::root_arch);
+            Some(InstalledSdk { version: version.to_string(), path: base.join(version), root: None, arch })
+        })
+        .collect()
+}
+
+/// Un runtime condiviso installato, es. `Microsoft.NETCore.App 8.0.11`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledRuntime {
+    pub name: String,
+    pub version: String,
+    /// Cartella del runtime, es. `<root>/shared/Microsoft.NETCore.App/8.0.11`
+    pub path: PathBuf,
+}
+
+/// Elenca i runtime presenti in `<root>/shared/<nome>/<versione>` senza avviare dotnet.
+pub fn scan_root_runtimes(root: &Path) -> Result<Vec<InstalledRuntime>, DverError> {
+    let shared_dir = root.join("shared");
+    if !shared_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut runtimes = Vec::new();
+    for framework in fs::read_dir(&shared_dir).with_context(|| format!("Failed to read {}", shared_dir.display()))? {
+        let framework = framework?;
+        if !framework.path().is_dir() {
+            continue;
+        }
+        let name = framework.file_name().to_string_lossy().to_string();
+        for entry in fs::read_dir(framework.path())? {
+            let entry = entry?;
+            let version = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && version.starts_with(|c: char| c.is_ascii_digit()) {
+                runtimes.push(InstalledRuntime { name: name.clone(), version, path: entry.path() });
+            }
+        }
+    }
+    runtimes.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| compare_version_strings(&a.version, &b.version)));
+    Ok(runtimes)
+}
+
+/// Runtime della radice attiva; senza una radice riconoscibile, quelli riportati da
+/// `dotnet --list-runtimes`.
+pub fn list_installed_runtimes(runner: &dyn ProcessRunner, config: &Config) -> Result<Vec<InstalledRuntime>, DverError> {
+    if let Some(root) = active_dotnet_root(config) {
+        return scan_root_runtimes(&root);
+    }
+    let stdout = dotnet_output(runner, config, &["--list-runtimes"]).context("Failed to list installed runtimes")?;
+    read_list_runtimes(config, &stdout)
+}
+
+/// I runtime elencati da `dotnet --list-runtimes`, con le stesse ripiegature di
+/// [`read_list_sdks`].
+pub fn read_list_runtimes(config: &Config, stdout: &str) -> Result<Vec<InstalledRuntime>, DverError> {
+    let mut parsed = parse_list_runtimes(stdout);
+    if parsed.is_empty() && looks_like_listing(stdout) {
+        // Come per gli SDK: le cartelle sono `<root>/shared/<nome>`
+        parsed = listed_dirs(stdout)
+            .iter()
+            .filter_map(|dir| dir.ancestors().nth(2))
+            .map(scan_root_runtimes)
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        if parsed.is_empty() {
+            return Err(parse_failure(config, "dotnet --list-runtimes", stdout));
+        }
+        dump_unparsed(config, "dotnet --list-runtimes", stdout);
+    }
+    // Come per gli SDK, le cartelle delle radici trovate valgono più delle righe
+    let mut runtimes = Vec::new();
+    let mut scanned: Vec<PathBuf> = Vec::new();
+    for runtime in parsed {
+        let root = runtime.path.ancestors().nth(3).filter(|root| root.join("shared").is_dir());
+        match root {
+            Some(root) if scanned.iter().any(|r| r == root) => {}
+            Some(root) => {
+                runtimes.extend(scan_root_runtimes(root)?);
+                scanned.push(root.to_path_buf());
+            }
+            None => runtimes.push(runtime),
+        }
+    }
+    runtimes.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| compare_version_strings(&a.version, &b.version)));
+    runtimes.dedup_by(|a, b| a.path == b.path);
+    Ok(runtimes)
+}
+
+/// Interpreta l'output di `dotnet --list-runtimes`, righe
+/// `Microsoft.NETCore.App 8.0.11 [/usr/share/dotnet/shared/Microsoft.NETCore.App]`.
+pub fn parse_list_runtimes(stdout: &str) -> Vec<InstalledRuntime> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (head, base) = split_listing(line, 2)?;
+            let (name, version) = (head[0], head[1]);
+            if !looks_like_version(version) {
+                return None;
+            }
+            Some(InstalledRuntime { name: name.to_string(), version: version.to_string(), path: PathBuf::from(base).join(version) })
+        })
+        .collect()
+}
+
+/// Versioni installate senza duplicati, dalla più recente.
+pub fn installed_versions_newest_first(runner: &dyn ProcessRunner, config: &Config) -> Result<Vec<String>, DverError> {
+    let mut versions: Vec<String> = list_installed_sdks(runner, config)?.into_iter().map(|sdk| sdk.version).collect();
+    sort_versions(&mut versions);
+    versions.reverse();
+    Ok(versions)
+}
+
+/// SDK che dotnet sceglierebbe tra gli `installed` secondo il global.json `pin` (vedi
+/// [`roll_forward_sdk`]); senza una versione fissata, il più recente. `None` se nessun
+/// SDK soddisfa il global.json: lì decide `dotnet --version`.
+pub fn resolve_sdk(installed: &[String], pin: Option<&GlobalJson>) -> Option<String> {
+    match pin.and_then(|p| p.version.as_deref()) {
+        Some(wanted) => roll_forward_sdk(installed, wanted, pin.and_then(|p| p.roll_forward.as_deref())),
+        None => installed.iter().filter_map(|v| Some((v.parse::<SdkVersion>().ok()?, v))).max().map(|(_, v)| v.clone()),
+    }
+}
+
+/// SDK che dotnet sceglierebbe tra gli `installed` per la versione `wanted` di un
+/// global.json, con ogni politica `roll_forward` (senza, `latestPatch`). Le politiche
+/// senza `latest` prendono la band più bassa che basta e ne usano l'ultima patch.
+/// `None` se nessun SDK la soddisfa o la politica è sconosciuta.
+pub fn roll_forward_sdk(installed: &[String], wanted: &str, roll_forward: Option<&str>) -> Option<String> {
+    let wanted: SdkVersion = wanted.parse().ok()?;
+    let versions: Vec<(SdkVersion, &String)> =
+        installed.iter().filter_map(|v| Some((v.parse().ok()?, v))).filter(|(v, _)| *v >= wanted).collect();
+    let band = |v: &SdkVersion| (v.major, v.minor, v.feature_band());
+    let same_band = |v: &SdkVersion| band(v) == band(&wanted);
+    let same_minor = |v: &SdkVersion| (v.major, v.minor) == (wanted.major, wanted.minor);
+    let same_major = |v: &SdkVersion| v.major == wanted.major;
+    let any = |_: &SdkVersion| true;
+    let newest = |within: &dyn Fn(&SdkVersion) -> bool| versions.iter().filter(|(v, _)| within(v)).max();
+    // L'ultima patch della band più bassa tra quelle ammesse
+    let lowest_band = |within: &dyn Fn(&SdkVersion) -> bool| {
+        let lowest = versions.iter().filter(|(v, _)| within(v)).map(|(v, _)| band(v)).min()?;
+        versions.iter().filter(|(v, _)| band(v) == lowest).max()
+    };
+    let exact = versions.iter().find(|(v, _)| *v == wanted);
+    let chosen = match roll_forward.unwrap_or("latestPatch") {
+        "disable" => exact,
+        "patch" => exact.or_else(|| newest(&same_band)),
+        "latestPatch" => newest(&same_band),
+        "feature" => lowest_band(&same_minor),
+        "latestFeature" => newest(&same_minor),
+        "minor" => lowest_band(&same_major),
+        "latestMinor" => newest(&same_major),
+        "major" => lowest_band(&any),
+        "latestMajor" => newest(&any),
+        _ => None,
+    };
+    chosen.map(|(_, v)| (*v).clone())
+}
+
+/// SDK che dotnet sceglierebbe per il global.json `pin` cercando nelle sue
+/// `sdk.paths` in ordine: vince la prima radice con un SDK che lo soddisfa, con la
+/// radice stessa. Senza `paths` si cerca solo in `host_root`.
+pub fn resolve_in_sdk_paths(pin: &GlobalJson, host_root: Option<&Path>) -> Result<Option<(String, PathBuf)>, DverError> {
+    for root in pin.sdk_roots(host_root) {
+        let installed: Vec<String> = scan_root_sdks(&root)?.into_iter().map(|sdk| sdk.version).collect();
+        let chosen = match pin.version.as_deref() {
+            Some(wanted) => roll_forward_sdk(&installed, wanted, pin.roll_forward.as_deref()),
+            None => resolve_sdk(&installed, None),
+        };
+        if let Some(sdk) = chosen {
+            return Ok(Some((sdk, root)));
+        }
+    }
+    Ok(None)
+}
+
+/// SDK in uso nella cartella `dir`, come lo riporterebbe `dotnet --version`: si
+/// calcola dalla radice attiva e dal global.json, avviando dotnet solo se non basta.
+pub fn current_sdk(runner: &dyn ProcessRunner, config: &Config, dir: &Path) -> Result<String, DverError> {
+    if let Some(root) = active_dotnet_root(config) {
+        let pin = find_global_json(dir)?;
+        let resolved = match pin.as_ref().filter(|p| !p.paths.is_empty()) {
+            Some(pin) => resolve_in_sdk_paths(pin, Some(&root))?.map(|(version, _)| version),
+            None => {
+                let installed: Vec<String> = scan_root_sdks(&root)?.into_iter().map(|sdk| sdk.version).collect();
+                resolve_sdk(&installed, pin.as_ref())
+            }
+        };
+        if let Some(version) = resolved {
+            return Ok(version);
+        }
+    }
+    dotnet_version(runner, config)
+}
+
+/// Un workload installato, come riportato da `dotnet workload list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledWorkload {
+    pub id: String,
+    /// Versione del manifest, es. `8.0.11/8.0.100`
+    pub manifest_version: Option<String>,
+    /// Da dove è stato installato, es. `SDK 8.0.400`
+    pub source: Option<String>,
+}
+
+/// Workload installati secondo `dotnet workload list`.
+pub fn list_installed_workloads(runner: &dyn ProcessRunner, config: &Config) -> Result<Vec<InstalledWorkload>, DverError> {
+    let stdout = dotnet_output(runner, config, &["workload", "list"]).context("Failed to list installed workloads")?;
+    Ok(parse_workload_list(&stdout))
+}
+
+/// Legge la tabella stampata da `dotnet workload list`.
+pub fn parse_workload_list(stdout: &str) -> Vec<InstalledWorkload> {
+    // La tabella inizia dopo la riga di trattini e finisce alla prima riga vuota
+    stdout
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("---"))
+        .skip(1)
+        .take_while(|l| !l.trim().is_empty())
+        .filter_map(|l| {
+            let mut columns = l.split_whitespace();
+            let id = columns.next()?.to_string();
+            let manifest_version = columns.next().map(|s| s.to_string());
+            let source = Some(columns.collect::<Vec<_>>().join(" ")).filter(|s| !s.is_empty());
+            Some(InstalledWorkload { id, manifest_version, source })
+        })
+        .collect()
+}
+
+/// Identificativi dei workload installati.
+pub fn installed_workloads(runner: &dyn ProcessRunner, config: &Config) -> Result<Vec<String>, DverError> {
+    Ok(list_installed_workloads(runner, config)?.into_iter().map(|w| w.id).collect())
+}
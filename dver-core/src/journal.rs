@@ -0,0 +1,132 @@
+//! Il giornale delle operazioni: ogni `install`, `uninstall` e `use` lascia una riga
+//! in `journal.jsonl` nella cartella di stato, con ora, argomenti, esito e percorsi
+//! toccati. `dver history` lo legge; nelle richieste di supporto dice cosa è
+//! successo sulla macchina e in che ordine.
+//!
+//! Un record per riga, aggiunto in fondo: scrivere non richiede di rileggere il file
+//! e una riga rovinata non nasconde le altre.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::provenance::current_user;
+use crate::{Config, DverError, ResultExt};
+
+/// Un'operazione che cambia gli SDK o i pin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Operation {
+    Install,
+    Uninstall,
+    Use,
+    Maintain,
+    Upgrade,
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Operation::Install => "install",
+            Operation::Uninstall => "uninstall",
+            Operation::Use => "use",
+            Operation::Maintain => "maintain",
+            Operation::Upgrade => "upgrade",
+        })
+    }
+}
+
+/// Una riga del giornale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub at: DateTime<Utc>,
+    pub operation: Operation,
+    /// Argomenti della riga di comando, senza il nome del programma
+    pub args: Vec<String>,
+    pub success: bool,
+    /// Il messaggio dell'errore, se l'operazione è fallita
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// SDK installati o rimossi e file scritti
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    pub user: Option<String>,
+    pub dver_version: String,
+}
+
+impl JournalEntry {
+    /// Record di un'operazione appena conclusa con `result`.
+    pub fn new(operation: Operation, args: Vec<String>, result: Result<(), &DverError>, paths: Vec<PathBuf>) -> JournalEntry {
+        JournalEntry {
+            at: DateTime::<Utc>::from(SystemTime::now()),
+            operation,
+            args,
+            success: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+            paths,
+            user: current_user(),
+            dver_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Quali righe mostrare.
+#[derive(Debug, Clone, Default)]
+pub struct JournalFilter {
+    pub operation: Option<Operation>,
+    /// Solo le operazioni fallite
+    pub failed: bool,
+    /// Solo da questo giorno (UTC) in poi
+    pub since: Option<NaiveDate>,
+}
+
+impl JournalFilter {
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        self.operation.is_none_or(|op| op == entry.operation)
+            && (!self.failed || !entry.success)
+            && self.since.is_none_or(|day| entry.at.date_naive() >= day)
+    }
+}
+
+/// Legge un giorno come `2024-05-31`.
+pub fn parse_day(raw: &str) -> Result<NaiveDate, DverError> {
+    raw.parse()
+        .map_err(|_| DverError::Usage(format!("Invalid date '{}'\nhint: use the YYYY-MM-DD format, e.g. 2024-05-31", raw)))
+}
+
+/// Il file del giornale.
+pub fn journal_file(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("journal.jsonl"))
+}
+
+/// Aggiunge `entry` in fondo al giornale.
+pub fn append(config: &Config, entry: &JournalEntry) -> Result<(), DverError> {
+    let path = journal_file(config)
+        .ok_or_else(|| DverError::NotFound("Cannot determine the dver state directory".to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Le righe del giornale dalla più vecchia; quelle illeggibili si saltano e un file
+/// mancante equivale a un giornale vuoto.
+pub fn load(config: &Config) -> Result<Vec<JournalEntry>, DverError> {
+    let Some(path) = journal_file(config).filter(|p| p.is_file()) else {
+        return Ok(Vec::new());
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
@@ -0,0 +1,78 @@
+//! Logica di dver utilizzabile senza passare dal binario: metadati dei rilasci .NET,
+//! installazione tramite lo script ufficiale, risoluzione delle versioni e inventario
+//! degli SDK installati.
+//!
+//! Tutte le funzioni ricevono una [`Config`], che si può costruire a mano oppure
+//! leggere come fa la CLI:
+//!
+//! ```no_run
+//! use dver_core::process::SystemRunner;
+//! use dver_core::{inventory, Config};
+//!
+//! let config = Config::load()?.with_env_overrides()?;
+//! for sdk in inventory::list_installed_sdks(&SystemRunner, &config)? {
+//!     println!("{} {}", sdk.version, sdk.path.display());
+//! }
+//! # Ok::<(), dver_core::DverError>(())
+//! ```
+//!
+//! Le funzioni di rete sono `async` (feature `async`, attiva di default). Con la
+//! feature `blocking` le stesse funzioni sono disponibili senza runtime async nei
+//! sottomoduli `http::blocking`, `metadata::blocking` e `install::blocking`.
+
+// Senza nessuna delle due API di rete restano inutilizzati gli helper condivisi
+#![cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+
+pub mod adopt;
+pub mod archive;
+pub mod audit;
+pub mod buildenv;
+pub mod caches;
+pub mod ci;
+pub mod config;
+pub mod container;
+pub mod diff;
+pub mod disk;
+pub mod distro;
+pub mod environment;
+pub mod error;
+pub mod http;
+pub mod install;
+pub mod inventory;
+pub mod journal;
+pub mod macos;
+pub mod maintain;
+pub mod manifest;
+pub mod metadata;
+pub mod outcome;
+pub mod paths;
+pub mod platform;
+pub mod process;
+pub mod project;
+pub mod provenance;
+pub mod proxy;
+pub mod registry;
+pub mod releases;
+pub mod removal;
+pub mod report;
+pub mod resolve;
+pub mod runtimeconfig;
+pub mod sandbox;
+pub mod sbom;
+pub mod segment;
+pub mod setup;
+pub mod signature;
+pub mod status;
+pub mod support;
+pub mod sync;
+pub mod templates;
+pub mod toolchain;
+pub mod tools;
+pub mod updates;
+pub mod version;
+pub mod visualstudio;
+pub mod workload;
+pub mod wsl;
+
+pub use config::{Config, OutputFormat};
+pub use error::{DverError, ResultExt};
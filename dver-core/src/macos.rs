@@ -0,0 +1,91 @@
+//! Le particolarità di macOS: gli SDK installati dal pacchetto `.pkg` di Microsoft in
+//! `/usr/local/share/dotnet`, l'attributo di quarantena che Gatekeeper mette sui file
+//! scaricati da un browser e la traduzione di Rosetta 2, per cui un processo x86_64 su
+//! un Mac Apple silicon vede `x86_64` al posto dell'architettura reale.
+//!
+//! Tutto passa per [`ProcessRunner`] (`pkgutil`, `xattr`, `sysctl`), quindi si prova
+//! anche su Linux; chi chiama controlla di essere su macOS con [`is_macos`].
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::platform::Arch;
+use crate::process::ProcessRunner;
+use crate::DverError;
+
+/// Dove il pacchetto `.pkg` installa .NET; gli SDK x64 su Apple silicon stanno in `x64/`.
+pub const PKG_ROOT: &str = "/usr/local/share/dotnet";
+
+/// Attributo esteso con cui Gatekeeper blocca i file scaricati finché l'utente non li apre.
+pub const QUARANTINE_ATTR: &str = "com.apple.quarantine";
+
+/// Prefisso delle ricevute `pkgutil` degli SDK, es. `com.microsoft.dotnet.dev.8.0.404.component.osx.arm64`.
+const SDK_RECEIPT_PREFIX: &str = "com.microsoft.dotnet.dev.";
+
+/// Vero se il processo gira su macOS.
+pub fn is_macos() -> bool {
+    std::env::consts::OS == "macos"
+}
+
+/// Un SDK installato dal pacchetto `.pkg`, dalla sua ricevuta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PkgSdk {
+    pub version: String,
+    pub arch: Option<Arch>,
+}
+
+impl PkgSdk {
+    /// La radice in cui il pacchetto lo ha installato su un host `native`.
+    pub fn root(&self, native: Option<Arch>) -> PathBuf {
+        match self.arch.filter(|arch| Some(*arch) != native) {
+            Some(arch) => Path::new(PKG_ROOT).join(arch.name()),
+            None => PathBuf::from(PKG_ROOT),
+        }
+    }
+}
+
+/// Interpreta l'output di `pkgutil --pkgs`, tenendo solo le ricevute degli SDK.
+pub fn parse_pkg_receipts(output: &str) -> Vec<PkgSdk> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(SDK_RECEIPT_PREFIX))
+        .filter_map(|rest| {
+            let (version, rid) = rest.split_once(".component.")?;
+            let arch = rid.rsplit('.').next().and_then(|name| Arch::ALL.into_iter().find(|arch| arch.name() == name));
+            Some(PkgSdk { version: version.to_string(), arch })
+        })
+        .collect()
+}
+
+/// Gli SDK che il pacchetto `.pkg` ha registrato; vuoto senza `pkgutil`.
+pub fn pkg_sdks(runner: &dyn ProcessRunner) -> Vec<PkgSdk> {
+    let mut command = Command::new("pkgutil");
+    command.arg("--pkgs");
+    match runner.output(&mut command) {
+        Ok(output) if output.status.success() => parse_pkg_receipts(&String::from_utf8_lossy(&output.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// Vero se `path` porta l'attributo di quarantena.
+pub fn is_quarantined(runner: &dyn ProcessRunner, path: &Path) -> bool {
+    let mut command = Command::new("xattr");
+    command.arg("-p").arg(QUARANTINE_ATTR).arg(path);
+    runner.output(&mut command).is_ok_and(|output| output.status.success())
+}
+
+/// Toglie la quarantena da tutto l'albero `dir`. `xattr -d` esce con errore per i file
+/// senza l'attributo, quindi chi chiama verifica il risultato con [`is_quarantined`].
+pub fn strip_quarantine(runner: &dyn ProcessRunner, dir: &Path) -> Result<(), DverError> {
+    let mut command = Command::new("xattr");
+    command.arg("-d").arg("-r").arg(QUARANTINE_ATTR).arg(dir);
+    runner.output(&mut command).map_err(|e| DverError::Process(format!("Cannot run xattr: {}", e)))?;
+    Ok(())
+}
+
+/// Vero se il processo è tradotto da Rosetta 2.
+pub fn is_translated(runner: &dyn ProcessRunner) -> bool {
+    let mut command = Command::new("sysctl");
+    command.arg("-n").arg("sysctl.proc_translated");
+    runner.output(&mut command).is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
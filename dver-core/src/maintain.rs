@@ -0,0 +1,79 @@
+//! Le scelte di `dver maintain`, il comando da pianificare con cron o l'Utilità di
+//! pianificazione per tenere aggiornati gli agent di build: quali patch installare e
+//! quali SDK superati rimuovere.
+//!
+//! Il piano dipende solo da cosa è installato e dai metadati, quindi ripetere il comando
+//! senza nuovi rilasci non cambia nulla.
+
+use std::collections::BTreeMap;
+
+use crate::releases::{ChannelReleases, ReleaseIndex, ReleaseType, SupportPhase};
+use crate::resolve::newest_matching;
+use crate::version::{SdkVersion, VersionSpec};
+
+/// Il canale `A.B` di `default_channel`: `LTS` e `STS` diventano il canale più recente
+/// di quel tipo ancora supportato, `A.B.Cxx` il suo canale.
+pub fn default_channel_version(index: &ReleaseIndex, default_channel: &str) -> Option<String> {
+    let release_type = match default_channel.to_ascii_uppercase().as_str() {
+        "LTS" => ReleaseType::Lts,
+        "STS" => ReleaseType::Sts,
+        _ => {
+            return match default_channel.parse::<VersionSpec>().ok()? {
+                VersionSpec::Minor(major, minor) | VersionSpec::Band(major, minor, _) => Some(format!("{}.{}", major, minor)),
+                _ => None,
+            }
+        }
+    };
+    index
+        .releases_index
+        .iter()
+        .filter(|c| c.release_type == Some(release_type))
+        .filter(|c| matches!(c.support_phase, Some(SupportPhase::Active | SupportPhase::Maintenance)))
+        .filter_map(|c| c.channel_version.as_ref())
+        .max_by_key(|v| format!("{}.0", v).parse::<SdkVersion>().ok())
+        .cloned()
+}
+
+/// Gli SDK da installare: la patch più recente di ogni feature band installata e, con
+/// `default_channel` (`A.B` o `A.B.Cxx`), l'SDK più recente del canale; solo quelli che mancano.
+pub fn patches_to_install(channels: &[ChannelReleases], installed: &[String], default_channel: Option<&str>) -> Vec<String> {
+    let published: Vec<String> = channels
+        .iter()
+        .flat_map(|channel| &channel.releases)
+        .flat_map(|release| release.sdk_versions())
+        .map(|v| v.to_string())
+        .collect();
+    let bands = installed
+        .iter()
+        .filter_map(|v| v.parse::<SdkVersion>().ok())
+        .filter(|v| !v.is_prerelease())
+        .map(|v| VersionSpec::Band(v.major, v.minor, v.feature_band()));
+    let default = default_channel.and_then(|c| c.parse::<VersionSpec>().ok());
+    let mut wanted: Vec<String> = Vec::new();
+    for spec in bands.chain(default) {
+        if let Some(newest) = newest_matching(&spec, &published) {
+            if !installed.contains(&newest) && !wanted.contains(&newest) {
+                wanted.push(newest);
+            }
+        }
+    }
+    wanted
+}
+
+/// Gli SDK stabili superati: per ogni feature band, quelli oltre i `keep` più recenti.
+/// Le anteprime restano.
+pub fn superseded(installed: &[String], keep: usize) -> Vec<String> {
+    let mut bands: BTreeMap<(u32, u32, u32), Vec<SdkVersion>> = BTreeMap::new();
+    for version in installed.iter().filter_map(|v| v.parse::<SdkVersion>().ok()).filter(|v| !v.is_prerelease()) {
+        bands.entry((version.major, version.minor, version.feature_band())).or_default().push(version);
+    }
+    let mut old: Vec<SdkVersion> = bands
+        .into_values()
+        .flat_map(|mut versions| {
+            versions.sort_by(|a, b| b.cmp(a));
+            versions.into_iter().skip(keep.max(1))
+        })
+        .collect();
+    old.sort();
+    old.into_iter().map(|v| v.to_string()).collect()
+}
@@ -0,0 +1,165 @@
+//! Manifest dei file di ogni SDK installato da dver, per verificarne l'integrità.
+//!
+//! I metadati dei rilasci riportano solo l'hash dell'archivio, non dei singoli file:
+//! dopo ogni installazione dver salva nella cartella di stato l'elenco dei file di
+//! `sdk/<versione>` con dimensione e SHA-256, e `dver verify` lo confronta con il disco.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Config, DverError, ResultExt};
+
+/// Un file dell'SDK come era subito dopo l'installazione.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// Percorso relativo alla cartella dell'SDK, con `/` come separatore
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Elenco dei file di un SDK installato.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub version: String,
+    /// Radice di installazione, che contiene `sdk/<versione>`
+    pub root: PathBuf,
+    pub files: Vec<FileEntry>,
+}
+
+/// Differenze tra il manifest e i file sul disco.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerifyReport {
+    /// File del manifest che non esistono più
+    pub missing: Vec<String>,
+    /// File con dimensione o contenuto diversi
+    pub modified: Vec<String>,
+    /// File presenti sul disco ma non nel manifest; non sono un errore
+    pub added: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Vero se nessun file del manifest manca o è cambiato.
+    pub fn is_intact(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// SHA-256 in esadecimale del contenuto di `path`, letto a blocchi.
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// File regolari sotto `dir`, con il percorso relativo; i link simbolici non si seguono.
+fn walk(dir: &Path, prefix: &str, out: &mut BTreeMap<String, PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&entry.path(), &relative, out)?;
+        } else if file_type.is_file() {
+            out.insert(relative, entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn files_of(sdk_dir: &Path) -> Result<BTreeMap<String, PathBuf>, DverError> {
+    let mut files = BTreeMap::new();
+    walk(sdk_dir, "", &mut files).with_context(|| format!("Failed to read {}", sdk_dir.display()))?;
+    Ok(files)
+}
+
+/// Cartella dell'SDK `version` nella radice `root`.
+pub fn sdk_dir(root: &Path, version: &str) -> PathBuf {
+    root.join("sdk").join(version)
+}
+
+/// Costruisce il manifest dell'SDK `version` installato in `root`.
+pub fn build(root: &Path, version: &str) -> Result<InstallManifest, DverError> {
+    let mut files = Vec::new();
+    for (path, full) in files_of(&sdk_dir(root, version))? {
+        let size = fs::metadata(&full)?.len();
+        let sha256 = sha256_file(&full).with_context(|| format!("Failed to hash {}", full.display()))?;
+        files.push(FileEntry { path, size, sha256 });
+    }
+    Ok(InstallManifest { version: version.to_string(), root: root.to_path_buf(), files })
+}
+
+/// Dove si salva il manifest: `<state_dir>/manifests/<radice>/<versione>.json`.
+pub fn manifest_path(config: &Config, root: &Path, version: &str) -> Option<PathBuf> {
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let key: String = root
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    config.state_dir().map(|dir| dir.join("manifests").join(key).join(format!("{}.json", version)))
+}
+
+/// Costruisce e salva il manifest dell'SDK appena installato.
+pub fn record(config: &Config, root: &Path, version: &str) -> Result<PathBuf, DverError> {
+    let path = manifest_path(config, root, version)
+        .ok_or_else(|| DverError::NotFound("Cannot determine the dver state directory".to_string()))?;
+    let manifest = build(root, version)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(&manifest)?).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Manifest salvato per l'SDK `version` in `root`, se esiste.
+pub fn load(config: &Config, root: &Path, version: &str) -> Result<Option<InstallManifest>, DverError> {
+    let Some(path) = manifest_path(config, root, version).filter(|p| p.is_file()) else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let manifest = serde_json::from_str(&content).with_context(|| format!("Invalid install manifest {}", path.display()))?;
+    Ok(Some(manifest))
+}
+
+/// Elimina il manifest di un SDK disinstallato, se esiste.
+pub fn forget(config: &Config, root: &Path, version: &str) -> Result<(), DverError> {
+    match manifest_path(config, root, version) {
+        Some(path) if path.is_file() => fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display())),
+        _ => Ok(()),
+    }
+}
+
+/// Confronta i file dell'SDK sul disco con `manifest`.
+pub fn verify(manifest: &InstallManifest) -> Result<VerifyReport, DverError> {
+    let mut on_disk = files_of(&sdk_dir(&manifest.root, &manifest.version))?;
+    let mut report = VerifyReport::default();
+    for entry in &manifest.files {
+        let Some(full) = on_disk.remove(&entry.path) else {
+            report.missing.push(entry.path.clone());
+            continue;
+        };
+        // La dimensione basta a scartare la maggior parte dei file cambiati senza leggerli
+        let changed = fs::metadata(&full)?.len() != entry.size
+            || sha256_file(&full).with_context(|| format!("Failed to hash {}", full.display()))? != entry.sha256;
+        if changed {
+            report.modified.push(entry.path.clone());
+        }
+    }
+    report.added = on_disk.into_keys().collect();
+    Ok(report)
+}
@@ -0,0 +1,269 @@
+//! Download dei metadati dei rilasci .NET pubblicati da Microsoft (`releases-index.json`
+//! e il `releases.json` di ogni canale), con cache locale per l'uso offline. I tipi
+//! dei documenti sono in [`crate::releases`].
+//!
+//! L'indice è piccolo e si scarica sempre; il `releases.json` di un canale, che pesa
+//! qualche megabyte, si riscarica solo quando `latest-release` o `latest-release-date`
+//! dell'indice non coincidono più con quelli della copia in cache.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+#[cfg(feature = "async")]
+use crate::http::HttpClient;
+use crate::releases::{ChannelReleases, Release, ReleaseChannel, ReleaseIndex};
+use crate::version::sort_versions;
+use crate::{Config, DverError, ResultExt};
+
+/// Rilasci di un canale in forma compatta, come li mostra `dver remote`.
+#[derive(Debug, Serialize)]
+pub struct RemoteChannel {
+    pub channel: String,
+    pub release_type: String,
+    pub releases: Vec<String>,
+}
+
+/// Ordine dei rilasci di `dver remote --sort`, sempre dal più recente.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum RemoteSort {
+    /// By version number
+    Version,
+    /// By release date
+    Date,
+}
+
+/// Filtri di `dver remote` sui rilasci di ogni canale.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteQuery {
+    /// Senza, i rilasci restano nell'ordine di `releases.json`
+    pub sort: Option<RemoteSort>,
+    /// Solo i rilasci usciti da questo giorno in poi
+    pub since: Option<NaiveDate>,
+    /// Al massimo tanti rilasci per canale, i primi nell'ordine scelto
+    pub limit: Option<usize>,
+}
+
+impl RemoteQuery {
+    /// Vero se il canale può avere rilasci dopo `since`, secondo la data dell'ultimo
+    /// nell'indice: gli altri non serve scaricarli.
+    pub fn may_match(&self, channel: &ReleaseChannel) -> bool {
+        match (self.since, channel.latest_release_date) {
+            (Some(since), Some(latest)) => latest >= since,
+            _ => true,
+        }
+    }
+}
+
+impl RemoteChannel {
+    /// Riassume i rilasci di `channel`; i campi mancanti diventano "unknown".
+    pub fn new(channel: &ReleaseChannel, releases: &ChannelReleases) -> RemoteChannel {
+        RemoteChannel::query(channel, releases, &RemoteQuery::default())
+    }
+
+    /// Come [`RemoteChannel::new`], con i rilasci filtrati e ordinati da `query`. Un
+    /// rilascio senza data non passa `since` e con `--sort date` va in fondo.
+    pub fn query(channel: &ReleaseChannel, releases: &ChannelReleases, query: &RemoteQuery) -> RemoteChannel {
+        let mut selected: Vec<&Release> = releases.releases
+            .iter()
+            .filter(|r| query.since.is_none_or(|since| r.release_date.is_some_and(|date| date >= since)))
+            .collect();
+        match query.sort {
+            Some(RemoteSort::Version) => selected.sort_by_key(|r| Reverse(r.version.clone())),
+            Some(RemoteSort::Date) => selected.sort_by_key(|r| Reverse(r.release_date)),
+            None => {}
+        }
+        selected.truncate(query.limit.unwrap_or(usize::MAX));
+        RemoteChannel {
+            channel: channel.channel_version.clone().unwrap_or_else(|| "unknown".to_string()),
+            release_type: channel.release_type.map_or_else(|| "unknown".to_string(), |t| t.to_string()),
+            releases: selected
+                .into_iter()
+                .map(|r| r.version.as_ref().map_or_else(|| "unknown".to_string(), |v| v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Nome del file di cache per un URL di metadati.
+fn metadata_cache_file(config: &Config, url: &str) -> Option<PathBuf> {
+    let name: String = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    config.cache_dir().map(|dir| dir.join("metadata").join(name))
+}
+
+/// Copia in cache di `url`, se presente.
+fn read_cached(config: &Config, url: &str) -> Option<String> {
+    metadata_cache_file(config, url).and_then(|f| fs::read_to_string(f).ok())
+}
+
+/// In modalità offline il documento si legge solo dalla cache.
+fn offline_copy(config: &Config, url: &str) -> Result<String, DverError> {
+    read_cached(config, url).ok_or_else(|| DverError::NotFound(format!(
+        "Offline mode: no cached copy of {}; run `dver remote` once while online to populate the cache", url)))
+}
+
+/// Salva `body` in cache dopo un download riuscito, oppure ripiega sulla copia salvata.
+fn cache_or_fallback(config: &Config, url: &str, downloaded: Result<String, DverError>) -> Result<String, DverError> {
+    match downloaded {
+        Ok(body) => {
+            if let Some(file) = metadata_cache_file(config, url) {
+                // La cache è solo un'ottimizzazione: gli errori di scrittura non sono fatali
+                let _ = file.parent().map(fs::create_dir_all);
+                let _ = fs::write(file, &body);
+            }
+            Ok(body)
+        }
+        Err(e) => match read_cached(config, url) {
+            Some(body) => {
+                eprintln!("Warning: {}; using cached copy", e);
+                Ok(body)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+fn parse_release_index(config: &Config, body: &str) -> Result<ReleaseIndex, DverError> {
+    serde_json::from_str(body).with_context(|| format!("Invalid release index at {}", config.index_url()))
+}
+
+fn parse_channel_releases(url: &str, body: &str) -> Result<ChannelReleases, DverError> {
+    serde_json::from_str(body).with_context(|| format!("Invalid channel metadata at {}", url))
+}
+
+/// Versioni SDK dei canali scaricati, ordinate e senza duplicati.
+fn sdk_versions_of<'a>(channels: impl IntoIterator<Item = &'a ChannelReleases>) -> Vec<String> {
+    let mut versions: Vec<String> = channels
+        .into_iter()
+        .flat_map(|c| &c.releases)
+        .flat_map(|release| release.sdk_versions().map(|v| v.to_string()))
+        .collect();
+    sort_versions(&mut versions);
+    versions
+}
+
+/// Vero se la copia in cache di un canale è aggiornata rispetto alla sua voce
+/// nell'indice; senza `latest-release` nell'indice non si può dire, quindi no.
+fn is_up_to_date(channel: &ReleaseChannel, cached: &ChannelReleases) -> bool {
+    channel.latest_release.is_some()
+        && cached.latest_release == channel.latest_release
+        && cached.latest_release_date == channel.latest_release_date
+}
+
+/// Copia in cache di `channel` se è ancora aggiornata (vedi [`is_up_to_date`]).
+fn fresh_cached_channel(config: &Config, channel: &ReleaseChannel) -> Option<ChannelReleases> {
+    cached_channel_releases(config, &channel.releases_json).filter(|cached| is_up_to_date(channel, cached))
+}
+
+/// Indice dei canali dalla sola cache, senza rete; `None` se manca o non è valido.
+pub fn cached_release_index(config: &Config) -> Option<ReleaseIndex> {
+    read_cached(config, config.index_url()).and_then(|body| parse_release_index(config, &body).ok())
+}
+
+/// `releases.json` di un canale dalla sola cache, senza rete.
+pub fn cached_channel_releases(config: &Config, url: &str) -> Option<ChannelReleases> {
+    read_cached(config, url).and_then(|body| parse_channel_releases(url, &body).ok())
+}
+
+/// Scarica un documento di metadati salvandone una copia in cache. In modalità
+/// offline legge solo dalla cache; se la rete fallisce ripiega sulla copia salvata.
+#[cfg(feature = "async")]
+pub async fn fetch_metadata(client: &impl HttpClient, config: &Config, url: &str) -> Result<String, DverError> {
+    if config.offline {
+        return offline_copy(config, url);
+    }
+    cache_or_fallback(config, url, client.get_text(url).await)
+}
+
+/// Scarica l'indice dei canali da `index_url` (o dal mirror configurato).
+#[cfg(feature = "async")]
+pub async fn fetch_release_index(client: &impl HttpClient, config: &Config) -> Result<ReleaseIndex, DverError> {
+    parse_release_index(config, &fetch_metadata(client, config, config.index_url()).await?)
+}
+
+/// Scarica il `releases.json` di un canale, il cui URL si trova in [`ReleaseChannel::releases_json`].
+#[cfg(feature = "async")]
+pub async fn fetch_channel_releases(client: &impl HttpClient, config: &Config, url: &str) -> Result<ChannelReleases, DverError> {
+    parse_channel_releases(url, &fetch_metadata(client, config, url).await?)
+}
+
+/// Rilasci del canale `channel` dell'indice: dalla cache se è ancora aggiornata,
+/// altrimenti scaricati con [`fetch_channel_releases`].
+#[cfg(feature = "async")]
+pub async fn fetch_channel(client: &impl HttpClient, config: &Config, channel: &ReleaseChannel) -> Result<ChannelReleases, DverError> {
+    if let Some(cached) = fresh_cached_channel(config, channel) {
+        return Ok(cached);
+    }
+    fetch_channel_releases(client, config, &channel.releases_json).await
+}
+
+/// Tutte le versioni SDK pubblicate in ogni canale, ordinate e senza duplicati.
+/// I canali i cui metadati non si possono scaricare vengono saltati.
+#[cfg(feature = "async")]
+pub async fn fetch_remote_sdk_versions(client: &impl HttpClient, config: &Config) -> Result<Vec<String>, DverError> {
+    let index = fetch_release_index(client, config).await?;
+
+    let mut channels = Vec::new();
+    for channel in &index.releases_index {
+        if let Ok(channel_releases) = fetch_channel(client, config, channel).await {
+            channels.push(channel_releases);
+        }
+    }
+    Ok(sdk_versions_of(&channels))
+}
+
+/// Le stesse funzioni di download senza runtime async, basate su [`crate::http::blocking`].
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use super::{
+        cache_or_fallback, fresh_cached_channel, offline_copy, parse_channel_releases, parse_release_index, sdk_versions_of,
+    };
+    use crate::http::blocking::HttpClient;
+    use crate::releases::{ChannelReleases, ReleaseChannel, ReleaseIndex};
+    use crate::{Config, DverError};
+
+    /// Vedi [`super::fetch_metadata`].
+    pub fn fetch_metadata(client: &impl HttpClient, config: &Config, url: &str) -> Result<String, DverError> {
+        if config.offline {
+            return offline_copy(config, url);
+        }
+        cache_or_fallback(config, url, client.get_text(url))
+    }
+
+    /// Vedi [`super::fetch_release_index`].
+    pub fn fetch_release_index(client: &impl HttpClient, config: &Config) -> Result<ReleaseIndex, DverError> {
+        parse_release_index(config, &fetch_metadata(client, config, config.index_url())?)
+    }
+
+    /// Vedi [`super::fetch_channel_releases`].
+    pub fn fetch_channel_releases(client: &impl HttpClient, config: &Config, url: &str) -> Result<ChannelReleases, DverError> {
+        parse_channel_releases(url, &fetch_metadata(client, config, url)?)
+    }
+
+    /// Vedi [`super::fetch_channel`].
+    pub fn fetch_channel(client: &impl HttpClient, config: &Config, channel: &ReleaseChannel) -> Result<ChannelReleases, DverError> {
+        if let Some(cached) = fresh_cached_channel(config, channel) {
+            return Ok(cached);
+        }
+        fetch_channel_releases(client, config, &channel.releases_json)
+    }
+
+    /// Vedi [`super::fetch_remote_sdk_versions`].
+    pub fn fetch_remote_sdk_versions(client: &impl HttpClient, config: &Config) -> Result<Vec<String>, DverError> {
+        let index = fetch_release_index(client, config)?;
+        let channels: Vec<ChannelReleases> = index.releases_index
+            .iter()
+            .filter_map(|channel| fetch_channel(client, config, channel).ok())
+            .collect();
+        Ok(sdk_versions_of(&channels))
+    }
+}
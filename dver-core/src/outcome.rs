@@ -0,0 +1,139 @@
+//! Il documento con l'esito dei comandi che cambiano gli SDK (`install`, `uninstall`, `upgrade`),
+//! stampato con `--json` (o `--output yaml`/`csv`) al posto dei messaggi, così chi
+//! avvolge dver in Ansible, Puppet o uno script può verificare cosa è cambiato.
+//!
+//! Il contratto è stabile: i campi cambiano solo aumentando [`OUTCOME_SCHEMA`]. Un
+//! comando fallito stampa comunque il documento, con `success = false` e l'errore,
+//! ed esce con l'exit code dell'errore.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::DverError;
+
+/// Versione del contratto del documento.
+pub const OUTCOME_SCHEMA: u32 = 1;
+
+/// Cosa è successo a un SDK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    Installed,
+    Removed,
+    /// Già nello stato voluto, es. un SDK già installato
+    Unchanged,
+    /// Lasciato stare di proposito, es. un SDK di Visual Studio
+    Skipped,
+    Failed,
+}
+
+/// Un errore come lo riporta l'output JSON: codice stabile, exit code e messaggio.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorInfo {
+    pub code: &'static str,
+    pub exit_code: u8,
+    pub message: String,
+}
+
+impl From<&DverError> for ErrorInfo {
+    fn from(e: &DverError) -> Self {
+        ErrorInfo { code: e.code(), exit_code: e.exit_code(), message: e.to_string() }
+    }
+}
+
+/// L'esito per un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemOutcome {
+    pub sdk: String,
+    pub status: ItemStatus,
+    pub path: Option<PathBuf>,
+    /// Spazio occupato dall'SDK installato o liberato da quello rimosso
+    pub size_bytes: Option<u64>,
+    /// Quanto è durata l'operazione su questo SDK; `None` se non ha fatto nulla
+    pub duration_ms: Option<u64>,
+    /// Perché è fallito o è stato saltato
+    pub error: Option<ErrorInfo>,
+}
+
+impl ItemOutcome {
+    pub fn new(sdk: impl Into<String>, status: ItemStatus) -> ItemOutcome {
+        ItemOutcome { sdk: sdk.into(), status, path: None, size_bytes: None, duration_ms: None, error: None }
+    }
+
+    pub fn path(mut self, path: impl Into<PathBuf>) -> ItemOutcome {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn size(mut self, size_bytes: Option<u64>) -> ItemOutcome {
+        self.size_bytes = size_bytes;
+        self
+    }
+
+    pub fn duration(mut self, elapsed: Duration) -> ItemOutcome {
+        self.duration_ms = Some(elapsed.as_millis() as u64);
+        self
+    }
+
+    pub fn error(mut self, error: &DverError) -> ItemOutcome {
+        self.error = Some(error.into());
+        self
+    }
+}
+
+/// Il documento di un comando.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandOutcome {
+    pub schema: u32,
+    /// Es. `install`
+    pub command: &'static str,
+    pub success: bool,
+    /// Vero se almeno un SDK è stato installato o rimosso
+    pub changed: bool,
+    pub duration_ms: u64,
+    pub items: Vec<ItemOutcome>,
+    /// L'errore con cui il comando è terminato
+    pub error: Option<ErrorInfo>,
+}
+
+impl CommandOutcome {
+    pub fn new(command: &'static str, items: Vec<ItemOutcome>, result: Result<(), &DverError>, elapsed: Duration) -> CommandOutcome {
+        CommandOutcome {
+            schema: OUTCOME_SCHEMA,
+            command,
+            success: result.is_ok(),
+            changed: items.iter().any(|item| matches!(item.status, ItemStatus::Installed | ItemStatus::Removed)),
+            duration_ms: elapsed.as_millis() as u64,
+            items,
+            error: result.err().map(ErrorInfo::from),
+        }
+    }
+
+    /// Una riga piatta per SDK, per l'output CSV.
+    pub fn rows(&self) -> Vec<OutcomeRow<'_>> {
+        self.items
+            .iter()
+            .map(|item| OutcomeRow {
+                sdk: &item.sdk,
+                status: item.status,
+                path: item.path.as_deref(),
+                size_bytes: item.size_bytes,
+                duration_ms: item.duration_ms,
+                error: item.error.as_ref().map(|e| e.message.as_str()),
+            })
+            .collect()
+    }
+}
+
+/// Riga CSV di [`CommandOutcome::rows`].
+#[derive(Debug, Serialize)]
+pub struct OutcomeRow<'a> {
+    pub sdk: &'a str,
+    pub status: ItemStatus,
+    pub path: Option<&'a Path>,
+    pub size_bytes: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub error: Option<&'a str>,
+}
@@ -0,0 +1,62 @@
+//! Cartelle usate da dver per configurazione, cache e stato.
+//!
+//! Su Linux e macOS seguono la specifica XDG (`XDG_CONFIG_HOME`, `XDG_CACHE_HOME`,
+//! `XDG_STATE_HOME`); su Windows le Known Folders (`RoamingAppData` per la
+//! configurazione, `LocalAppData` per cache e stato). Ognuna si può sostituire con
+//! una variabile `DVER_*_DIR`.
+
+use std::path::PathBuf;
+
+use crate::platform;
+
+const APP_DIR: &str = "dver";
+
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os(platform::current().home_var()).map(PathBuf::from)
+}
+
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// Cartella base XDG: la variabile se impostata, altrimenti `~/<fallback>`.
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    env_dir(var).or_else(|| home_dir().map(|h| h.join(fallback)))
+}
+
+/// `DVER_CONFIG_DIR`, `$XDG_CONFIG_HOME/dver` (`~/.config/dver`) o `%APPDATA%\dver`.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_dir("DVER_CONFIG_DIR") {
+        return Some(dir);
+    }
+    if platform::current().uses_xdg_dirs() {
+        xdg_dir("XDG_CONFIG_HOME", ".config").map(|d| d.join(APP_DIR))
+    } else {
+        dirs::config_dir().map(|d| d.join(APP_DIR))
+    }
+}
+
+/// `DVER_CACHE_DIR`, `$XDG_CACHE_HOME/dver` (`~/.cache/dver`) o `%LOCALAPPDATA%\dver\cache`.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_dir("DVER_CACHE_DIR") {
+        return Some(dir);
+    }
+    if platform::current().uses_xdg_dirs() {
+        xdg_dir("XDG_CACHE_HOME", ".cache").map(|d| d.join(APP_DIR))
+    } else {
+        dirs::data_local_dir().map(|d| d.join(APP_DIR).join("cache"))
+    }
+}
+
+/// `DVER_STATE_DIR`, `$XDG_STATE_HOME/dver` (`~/.local/state/dver`) o `%LOCALAPPDATA%\dver\state`.
+/// Qui finiscono dati persistenti ma non di configurazione, come log e backup.
+pub fn state_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_dir("DVER_STATE_DIR") {
+        return Some(dir);
+    }
+    if platform::current().uses_xdg_dirs() {
+        xdg_dir("XDG_STATE_HOME", ".local/state").map(|d| d.join(APP_DIR))
+    } else {
+        dirs::data_local_dir().map(|d| d.join(APP_DIR).join("state"))
+    }
+}
@@ -0,0 +1,596 @@
+//! Differenze tra sistemi operativi raccolte in un unico trait.
+//!
+//! Il resto del crate chiede a [`current`] invece di usare `cfg!(windows)`, così le
+//! scelte di ogni piattaforma si possono verificare da qualsiasi host e aggiungerne
+//! una nuova (ad esempio FreeBSD) significa implementare [`Platform`] una volta sola.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::environment::ShellKind;
+use crate::process::SystemRunner;
+
+const INSTALL_SCRIPTS_URL: &str = "https://dotnet.microsoft.com/download/dotnet/scripts/v1";
+
+/// Comportamenti che dipendono dal sistema operativo.
+pub trait Platform: Send + Sync {
+    /// Nome breve, es. "unix" o "windows".
+    fn name(&self) -> &'static str;
+
+    /// Variabile d'ambiente con la cartella home dell'utente.
+    fn home_var(&self) -> &'static str;
+
+    /// Separatore delle voci di `PATH`.
+    fn path_separator(&self) -> char;
+
+    /// Nome dell'eseguibile dotnet.
+    fn dotnet_exe(&self) -> &'static str;
+
+    /// Nome dello shim di `dotnet` installato da `dver setup`.
+    fn shim_name(&self) -> &'static str;
+
+    /// Contenuto dello shim: esegue `dotnet` con `dver exec`, cioè con l'SDK scelto da
+    /// dver. `DVER_SHIM` interrompe il ciclo se la radice gestita non ha `dotnet` e il
+    /// `PATH` riporta allo shim stesso.
+    fn shim_script(&self, dver: &Path) -> String;
+
+    /// Nome dello script `dotnet-install` adatto alla piattaforma.
+    fn install_script_name(&self) -> &'static str;
+
+    /// Comando che esegue lo script di installazione in `script`.
+    fn install_script_command(&self, script: &Path) -> Command;
+
+    /// Rende eseguibile un file scaricato; dove non serve non fa nulla.
+    fn make_executable(&self, path: &Path) -> io::Result<()>;
+
+    /// Vero se le cartelle di dver seguono la specifica XDG invece delle Known Folders.
+    fn uses_xdg_dirs(&self) -> bool;
+
+    /// Cartella in cui lo script ufficiale installa per l'utente corrente.
+    fn user_dotnet_root(&self, home: &Path) -> PathBuf;
+
+    /// Cartella dei dati di NuGet per l'utente, che contiene la cache HTTP.
+    fn nuget_data_dir(&self, home: &Path) -> PathBuf;
+
+    /// Shell da usare senza guardare `$SHELL`, se la piattaforma ne ha una sola.
+    fn default_shell(&self) -> Option<ShellKind>;
+
+    /// Editor usato da `dver config edit` quando mancano `$VISUAL` ed `$EDITOR`.
+    fn default_editor(&self) -> &'static str;
+
+    /// Radici in cui i pacchetti e gli installer di sistema mettono dotnet.
+    fn system_dotnet_roots(&self) -> Vec<PathBuf>;
+
+    /// Cartelle di sistema: cancellare qualcosa al loro interno richiede `--force`
+    /// e una conferma digitata.
+    fn system_dirs(&self) -> Vec<PathBuf>;
+
+    /// Comando che stampa `<stato>|<soggetto del certificato>` della firma Authenticode
+    /// di `file`; `None` dove Authenticode non esiste.
+    fn authenticode_command(&self, _file: &Path) -> Option<Command> {
+        None
+    }
+
+    /// Variabili con cui l'host di un processo `arch` trova la sua radice dotnet.
+    fn arch_root_vars(&self, arch: Arch) -> Vec<&'static str> {
+        vec![arch.root_var()]
+    }
+
+    /// URL ufficiale dello script di installazione.
+    fn install_script_url(&self) -> String {
+        format!("{}/{}", INSTALL_SCRIPTS_URL, self.install_script_name())
+    }
+
+    /// Comandi che elencano le installazioni registrate dagli installer MSI (Visual Studio,
+    /// winget), vedi [`crate::registry`]; nessuno dove non esistono.
+    fn installed_versions_queries(&self) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Comandi che cercano tra i programmi installati i pacchetti "from Visual Studio".
+    fn visual_studio_sdks_queries(&self) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Comando `vswhere` che elenca in JSON le istanze di Visual Studio installate.
+    fn vswhere_command(&self) -> Option<Command> {
+        None
+    }
+
+    /// Comando che elenca i processi con file aperti in `dir`, una riga `<pid> <nome>`
+    /// per processo; serve a spiegare perché una rimozione non riesce.
+    fn processes_using_command(&self, _dir: &Path) -> Option<Command> {
+        None
+    }
+
+    /// Divide un valore di `PATH` nelle sue voci, saltando quelle vuote.
+    fn split_path_list(&self, value: &str) -> Vec<PathBuf> {
+        value.split(self.path_separator()).filter(|p| !p.is_empty()).map(PathBuf::from).collect()
+    }
+}
+
+/// Linux, macOS e gli altri sistemi Unix.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unix;
+
+/// Windows.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Windows;
+
+impl Platform for Unix {
+    fn name(&self) -> &'static str {
+        "unix"
+    }
+
+    fn home_var(&self) -> &'static str {
+        "HOME"
+    }
+
+    fn path_separator(&self) -> char {
+        ':'
+    }
+
+    fn dotnet_exe(&self) -> &'static str {
+        "dotnet"
+    }
+
+    fn shim_name(&self) -> &'static str {
+        "dotnet"
+    }
+
+    fn shim_script(&self, dver: &Path) -> String {
+        let exec = format!("DVER_SHIM=1 exec '{}' exec dotnet \"$@\"", dver.display().to_string().replace('\'', r"'\''"));
+        [
+            "#!/bin/sh",
+            "# dotnet shim written by `dver setup`",
+            "if [ -n \"$DVER_SHIM\" ]; then",
+            "  echo \"dver: no dotnet in the managed root; run dver install\" >&2",
+            "  exit 127",
+            "fi",
+            &exec,
+            "",
+        ]
+        .join("\n")
+    }
+
+    fn install_script_name(&self) -> &'static str {
+        "dotnet-install.sh"
+    }
+
+    fn install_script_command(&self, script: &Path) -> Command {
+        let mut cmd = Command::new("bash");
+        cmd.arg(script);
+        cmd
+    }
+
+    #[cfg(unix)]
+    fn make_executable(&self, path: &Path) -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(path, perms)
+    }
+
+    #[cfg(not(unix))]
+    fn make_executable(&self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn uses_xdg_dirs(&self) -> bool {
+        true
+    }
+
+    fn user_dotnet_root(&self, home: &Path) -> PathBuf {
+        home.join(".dotnet")
+    }
+
+    fn nuget_data_dir(&self, home: &Path) -> PathBuf {
+        home.join(".local").join("share").join("NuGet")
+    }
+
+    fn default_shell(&self) -> Option<ShellKind> {
+        None
+    }
+
+    fn default_editor(&self) -> &'static str {
+        "vi"
+    }
+
+    fn system_dotnet_roots(&self) -> Vec<PathBuf> {
+        // Pacchetti delle distribuzioni, script Microsoft per tutto il sistema, installer per macOS
+        ["/usr/share/dotnet", "/usr/lib/dotnet", "/usr/lib64/dotnet", "/usr/local/share/dotnet"]
+            .iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    fn system_dirs(&self) -> Vec<PathBuf> {
+        ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc", "/var", "/snap", "/System", "/Library"]
+            .iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+}
+
+impl Platform for Windows {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn home_var(&self) -> &'static str {
+        "USERPROFILE"
+    }
+
+    fn path_separator(&self) -> char {
+        ';'
+    }
+
+    fn dotnet_exe(&self) -> &'static str {
+        "dotnet.exe"
+    }
+
+    fn shim_name(&self) -> &'static str {
+        "dotnet.cmd"
+    }
+
+    fn shim_script(&self, dver: &Path) -> String {
+        let exec = format!("\"{}\" exec dotnet %*", dver.display());
+        [
+            "@echo off",
+            "rem dotnet shim written by `dver setup`",
+            "setlocal",
+            "if defined DVER_SHIM (",
+            "  echo dver: no dotnet in the managed root; run dver install 1>&2",
+            "  exit /b 127",
+            ")",
+            "set DVER_SHIM=1",
+            &exec,
+            "",
+        ]
+        .join("\r\n")
+    }
+
+    fn install_script_name(&self) -> &'static str {
+        "dotnet-install.ps1"
+    }
+
+    fn install_script_command(&self, script: &Path) -> Command {
+        let mut cmd = Command::new("powershell");
+        cmd.arg("-NoLogo").arg("-NoProfile").arg("-NonInteractive");
+        cmd.arg("-ExecutionPolicy").arg("Bypass");
+        cmd.arg("-File").arg(script);
+        cmd
+    }
+
+    fn make_executable(&self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn uses_xdg_dirs(&self) -> bool {
+        false
+    }
+
+    fn user_dotnet_root(&self, home: &Path) -> PathBuf {
+        // %LOCALAPPDATA% è quasi sempre `<home>\AppData\Local`
+        home.join("AppData").join("Local").join("Microsoft").join("dotnet")
+    }
+
+    fn nuget_data_dir(&self, home: &Path) -> PathBuf {
+        home.join("AppData").join("Local").join("NuGet")
+    }
+
+    fn default_shell(&self) -> Option<ShellKind> {
+        Some(ShellKind::Powershell)
+    }
+
+    fn default_editor(&self) -> &'static str {
+        "notepad"
+    }
+
+    fn system_dotnet_roots(&self) -> Vec<PathBuf> {
+        let program_files = std::env::var_os("ProgramFiles").map(PathBuf::from).unwrap_or_else(|| PathBuf::from(r"C:\Program Files"));
+        vec![program_files.join("dotnet")]
+    }
+
+    fn system_dirs(&self) -> Vec<PathBuf> {
+        let from_env = |var: &str, fallback: &str| {
+            std::env::var_os(var).map(PathBuf::from).unwrap_or_else(|| PathBuf::from(fallback))
+        };
+        vec![
+            from_env("ProgramFiles", r"C:\Program Files"),
+            from_env("ProgramFiles(x86)", r"C:\Program Files (x86)"),
+            from_env("ProgramData", r"C:\ProgramData"),
+            from_env("SystemRoot", r"C:\Windows"),
+        ]
+    }
+
+    fn arch_root_vars(&self, arch: Arch) -> Vec<&'static str> {
+        match arch {
+            // I processi x86 su Windows a 64 bit leggono anche la variabile storica
+            Arch::X86 => vec!["DOTNET_ROOT_X86", "DOTNET_ROOT(x86)"],
+            _ => vec![arch.root_var()],
+        }
+    }
+
+    fn split_path_list(&self, value: &str) -> Vec<PathBuf> {
+        // Le voci con spazi possono essere tra virgolette, es. "C:\Program Files\dotnet"
+        value.split(';').map(|p| p.trim_matches('"')).filter(|p| !p.is_empty()).map(PathBuf::from).collect()
+    }
+
+    fn installed_versions_queries(&self) -> Vec<Command> {
+        // Le installazioni x86 su Windows a 64 bit stanno nella vista a 32 bit del registro
+        [crate::registry::INSTALLED_VERSIONS_KEY, crate::registry::INSTALLED_VERSIONS_KEY_32]
+            .iter()
+            .map(|key| {
+                let mut cmd = Command::new("reg");
+                cmd.arg("query").arg(key).arg("/s");
+                cmd
+            })
+            .collect()
+    }
+
+    fn visual_studio_sdks_queries(&self) -> Vec<Command> {
+        crate::visualstudio::UNINSTALL_KEYS
+            .iter()
+            .map(|key| {
+                let mut cmd = Command::new("reg");
+                cmd.arg("query").arg(key).arg("/s").arg("/f").arg(crate::visualstudio::FROM_VISUAL_STUDIO).arg("/d");
+                cmd
+            })
+            .collect()
+    }
+
+    fn vswhere_command(&self) -> Option<Command> {
+        // `vswhere` sta in una posizione fissa dalla versione 15.2 del Visual Studio Installer
+        let program_files = std::env::var_os("ProgramFiles(x86)").map(PathBuf::from).unwrap_or_else(|| PathBuf::from(r"C:\Program Files (x86)"));
+        let mut cmd = Command::new(program_files.join(r"Microsoft Visual Studio\Installer\vswhere.exe"));
+        cmd.args(["-all", "-prerelease", "-products", "*", "-format", "json", "-utf8"]);
+        Some(cmd)
+    }
+
+    fn processes_using_command(&self, dir: &Path) -> Option<Command> {
+        // L'eseguibile o un modulo caricato (es. `sdk\<versione>\dotnet.dll` di MSBuild) sta in `dir`
+        let dir = format!("{}\\*", dir.display().to_string().trim_end_matches('\\').replace('\'', "''"));
+        let mut cmd = Command::new("powershell");
+        cmd.arg("-NoLogo").arg("-NoProfile").arg("-NonInteractive").arg("-Command");
+        cmd.arg(format!(
+            "Get-Process | Where-Object {{ $_.Path -like '{0}' -or ($_.Modules | Where-Object {{ $_.FileName -like '{0}' }}) }} | ForEach-Object {{ \"$($_.Id) $($_.ProcessName)\" }}",
+            dir));
+        Some(cmd)
+    }
+
+    fn authenticode_command(&self, file: &Path) -> Option<Command> {
+        let path = file.display().to_string().replace('\'', "''");
+        let mut cmd = Command::new("powershell");
+        cmd.arg("-NoLogo").arg("-NoProfile").arg("-NonInteractive").arg("-Command");
+        cmd.arg(format!(
+            "$s = Get-AuthenticodeSignature -LiteralPath '{}'; \"$($s.Status)|$($s.SignerCertificate.Subject)\"", path));
+        Some(cmd)
+    }
+}
+
+/// Piattaforma su cui gira il processo.
+pub fn current() -> &'static dyn Platform {
+    #[cfg(windows)]
+    {
+        &Windows
+    }
+    #[cfg(not(windows))]
+    {
+        &Unix
+    }
+}
+
+/// Runtime identifier (RID) del sistema corrente, es. `linux-x64` o `osx-arm64`:
+/// seleziona gli archivi giusti nei metadati dei rilasci.
+pub fn rid() -> String {
+    rid_for(None)
+}
+
+/// RID del sistema corrente per l'architettura `arch`, o per quella dell'host.
+pub fn rid_for(arch: Option<Arch>) -> String {
+    let arch = match arch.or_else(Arch::native) {
+        Some(arch) => arch.name(),
+        None => rid_arch(std::env::consts::ARCH),
+    };
+    format!("{}-{}", rid_os(std::env::consts::OS), arch)
+}
+
+/// Sistema operativo nei RID, da [`std::env::consts::OS`]: `osx`, `win`, `linux`,
+/// `freebsd`, `illumos`...
+pub fn rid_os(os: &str) -> &str {
+    match os {
+        "macos" => "osx",
+        "windows" => "win",
+        other => other,
+    }
+}
+
+/// Architettura nei RID, da [`std::env::consts::ARCH`], anche per quelle che
+/// [`Arch`] non gestisce, es. `ppc64le` o `s390x`.
+pub fn rid_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "powerpc64" => "ppc64le",
+        other => other,
+    }
+}
+
+/// Chi pubblica le build di .NET per un sistema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostSupport {
+    /// Microsoft, negli archivi e con lo script dotnet-install
+    Official,
+    /// Solo la comunità o le distribuzioni (ports di FreeBSD, build di illumos, IBM
+    /// per s390x e ppc64le): dver le gestisce dopo `dver adopt` o con un `feed_url` che le ospita
+    Community,
+    /// Nessuno
+    Unsupported,
+}
+
+/// Il supporto di .NET per `os` e `arch` nella forma di [`std::env::consts`].
+pub fn host_support(os: &str, arch: &str) -> HostSupport {
+    match (os, arch) {
+        ("linux", "x86_64" | "aarch64" | "arm") | ("macos", "x86_64" | "aarch64") | ("windows", "x86_64" | "aarch64" | "x86") => {
+            HostSupport::Official
+        }
+        ("linux", "s390x" | "powerpc64" | "loongarch64" | "riscv64")
+        | ("freebsd", "x86_64" | "aarch64")
+        | ("illumos" | "solaris", "x86_64") => HostSupport::Community,
+        _ => HostSupport::Unsupported,
+    }
+}
+
+/// Il supporto di .NET per l'host corrente.
+pub fn current_support() -> HostSupport {
+    host_support(std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Perché dver non può scaricare gli SDK Microsoft per il sistema `rid`, e cosa fare;
+/// `None` se Microsoft li pubblica.
+pub fn unsupported_host_message(support: HostSupport, rid: &str) -> Option<String> {
+    let hint = match support {
+        HostSupport::Official => return None,
+        HostSupport::Community => format!(
+            "install a community build (e.g. `pkg install dotnet` on FreeBSD) and register it with `dver adopt <dir>`, or set feed_url to a mirror with {} builds",
+            rid
+        ),
+        HostSupport::Unsupported => "Microsoft and the community publish .NET for Linux, macOS, Windows, FreeBSD and illumos".to_string(),
+    };
+    Some(format!("Microsoft publishes no .NET SDK for {}\nhint: {}", rid, hint))
+}
+
+// --- Architetture ---
+
+/// Architettura di una toolchain. Sugli host ARM (Apple Silicon, Windows ARM) gli SDK
+/// x64 girano in emulazione e vivono in una radice separata, `<radice>/x64`, come
+/// con gli installer Microsoft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Arch {
+    X64,
+    Arm64,
+    X86,
+}
+
+impl Arch {
+    pub const ALL: [Arch; 3] = [Arch::X64, Arch::Arm64, Arch::X86];
+
+    /// L'architettura dell'host, se .NET la supporta; sotto Rosetta 2 quella del Mac,
+    /// non quella del processo.
+    pub fn native() -> Option<Arch> {
+        native_arch(std::env::consts::ARCH, rosetta_translated())
+    }
+
+    /// Nome nei RID e valore di `-Architecture` per lo script, es. `x64`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Arch::X64 => "x64",
+            Arch::Arm64 => "arm64",
+            Arch::X86 => "x86",
+        }
+    }
+
+    /// Vero se è l'architettura dell'host; un host sconosciuto non ne ha una.
+    pub fn is_native(self) -> bool {
+        Arch::native() == Some(self)
+    }
+
+    /// `DOTNET_ROOT_<ARCH>`, che per un processo di questa architettura ha la
+    /// precedenza su `DOTNET_ROOT`.
+    pub fn root_var(self) -> &'static str {
+        match self {
+            Arch::X64 => "DOTNET_ROOT_X64",
+            Arch::Arm64 => "DOTNET_ROOT_ARM64",
+            Arch::X86 => "DOTNET_ROOT_X86",
+        }
+    }
+}
+
+/// L'architettura dell'host per un processo `arch` (come [`std::env::consts::ARCH`]):
+/// un processo x86_64 tradotto da Rosetta 2 gira su un Mac arm64.
+pub fn native_arch(arch: &str, translated: bool) -> Option<Arch> {
+    match arch {
+        "x86_64" if translated => Some(Arch::Arm64),
+        "x86_64" => Some(Arch::X64),
+        "aarch64" => Some(Arch::Arm64),
+        "x86" => Some(Arch::X86),
+        _ => None,
+    }
+}
+
+/// Vero se dver è un processo x86_64 tradotto da Rosetta 2, ad esempio lanciato da un
+/// terminale aperto con Rosetta; chiesto a `sysctl` una sola volta.
+pub fn rosetta_translated() -> bool {
+    static TRANSLATED: OnceLock<bool> = OnceLock::new();
+    *TRANSLATED.get_or_init(|| {
+        crate::macos::is_macos() && std::env::consts::ARCH == "x86_64" && crate::macos::is_translated(&SystemRunner)
+    })
+}
+
+impl std::fmt::Display for Arch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Architettura di un eseguibile ELF, Mach-O o PE letta dalla sua intestazione;
+/// `None` per gli altri file (es. uno script) e per i Mach-O universali.
+pub fn binary_arch(path: &Path) -> Option<Arch> {
+    let mut header = Vec::new();
+    File::open(path).ok()?.take(4096).read_to_end(&mut header).ok()?;
+    let u16_le = |at: usize| Some(u16::from_le_bytes(header.get(at..at + 2)?.try_into().ok()?));
+    let u32_le = |at: usize| Some(u32::from_le_bytes(header.get(at..at + 4)?.try_into().ok()?));
+    match header.get(..4)? {
+        [0x7f, b'E', b'L', b'F'] => {
+            let machine = match header.get(5)? {
+                1 => u16_le(18)?,
+                _ => u16::from_be_bytes(header.get(18..20)?.try_into().ok()?),
+            };
+            match machine {
+                0x3e => Some(Arch::X64),
+                0xb7 => Some(Arch::Arm64),
+                0x03 => Some(Arch::X86),
+                _ => None,
+            }
+        }
+        [0xcf, 0xfa, 0xed, 0xfe] | [0xce, 0xfa, 0xed, 0xfe] => match u32_le(4)? {
+            0x0100_0007 => Some(Arch::X64),
+            0x0100_000c => Some(Arch::Arm64),
+            0x0000_0007 => Some(Arch::X86),
+            _ => None,
+        },
+        [b'M', b'Z', ..] => {
+            let pe = u32_le(0x3c)? as usize;
+            if header.get(pe..pe + 4)? != b"PE\0\0" {
+                return None;
+            }
+            match u16_le(pe + 4)? {
+                0x8664 => Some(Arch::X64),
+                0xaa64 => Some(Arch::Arm64),
+                0x014c => Some(Arch::X86),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Architettura degli SDK di una radice: quella del suo `dotnet`, altrimenti quella
+/// indicata dal nome della cartella (`.../dotnet/x64`), altrimenti quella dell'host.
+pub fn root_arch(root: &Path) -> Option<Arch> {
+    binary_arch(&root.join(current().dotnet_exe()))
+        .or_else(|| {
+            let name = root.file_name()?.to_str()?;
+            Arch::ALL.into_iter().find(|arch| arch.name().eq_ignore_ascii_case(name))
+        })
+        .or_else(Arch::native)
+}
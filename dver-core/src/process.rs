@@ -0,0 +1,97 @@
+//! Esecuzione dei processi esterni (dotnet, script di installazione) dietro un trait,
+//! così il parsing del loro output si può verificare senza un'installazione di .NET.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Output};
+use std::sync::Mutex;
+
+/// Avvia processi e ne raccoglie l'output.
+pub trait ProcessRunner: Send + Sync {
+    /// Esegue `command` attendendone la fine, come [`Command::output`].
+    fn output(&self, command: &mut Command) -> io::Result<Output>;
+}
+
+/// Esegue davvero i comandi.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl ProcessRunner for SystemRunner {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        command.output()
+    }
+}
+
+/// Riga di comando nella forma usata da [`ScriptedRunner`]: nome del programma senza
+/// cartella seguito dagli argomenti, es. `dotnet --list-sdks`.
+pub fn command_line(command: &Command) -> String {
+    let program = Path::new(command.get_program())
+        .file_name()
+        .unwrap_or(command.get_program())
+        .to_string_lossy()
+        .into_owned();
+    std::iter::once(program)
+        .chain(command.get_args().map(|a| a.to_string_lossy().into_owned()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finto esecutore che risponde con output preparati e registra i comandi ricevuti.
+/// I comandi senza risposta falliscono come se il programma non esistesse.
+#[derive(Debug, Default)]
+pub struct ScriptedRunner {
+    responses: HashMap<String, (i32, String, String)>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl ScriptedRunner {
+    pub fn new() -> ScriptedRunner {
+        ScriptedRunner::default()
+    }
+
+    /// Risposta di successo con lo stdout indicato per `command_line`, es. `"dotnet --version"`.
+    pub fn with(self, command_line: &str, stdout: &str) -> ScriptedRunner {
+        self.with_status(command_line, 0, stdout, "")
+    }
+
+    /// Risposta con exit code e stderr arbitrari.
+    pub fn with_status(mut self, command_line: &str, code: i32, stdout: &str, stderr: &str) -> ScriptedRunner {
+        self.responses.insert(command_line.to_string(), (code, stdout.to_string(), stderr.to_string()));
+        self
+    }
+
+    /// Comandi eseguiti finora, nell'ordine.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+}
+
+impl ProcessRunner for ScriptedRunner {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        let line = command_line(command);
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(line.clone());
+        }
+        let (code, stdout, stderr) = self.responses.get(&line).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("no scripted response for `{}`", line))
+        })?;
+        Ok(Output {
+            status: exit_status(*code),
+            stdout: stdout.clone().into_bytes(),
+            stderr: stderr.clone().into_bytes(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw(code << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
@@ -0,0 +1,391 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{DverError, ResultExt};
+
+pub const PROJECT_FILE: &str = ".dver.toml";
+
+/// Preferenze di installazione condivise dal team tramite il repository.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstallPreferences {
+    /// Cartella in cui installare gli SDK del progetto
+    pub install_root: Option<PathBuf>,
+}
+
+/// Proprietà MSBuild in cui `dver use` scrive anche l'SDK, per chi lo fissa in un file
+/// props oltre che in global.json.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PropsPin {
+    /// File props, relativo a `.dver.toml`
+    #[serde(default = "default_props_file")]
+    pub file: PathBuf,
+    /// Nome della proprietà, es. `DotNetSdkVersion`
+    pub property: String,
+}
+
+fn default_props_file() -> PathBuf {
+    PathBuf::from("Directory.Build.props")
+}
+
+/// Contenuto di `.dver.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// SDK fissato per il progetto (alternativa a global.json)
+    pub sdk: Option<String>,
+    /// Workload richiesti, es. `["maui", "wasm-tools"]`
+    pub workloads: Vec<String>,
+    pub install: InstallPreferences,
+    /// Facoltativo: la proprietà props da tenere allineata a global.json
+    pub props: Option<PropsPin>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// Percorso del file `.dver.toml`
+    pub path: PathBuf,
+    pub config: ProjectConfig,
+}
+
+/// Da dove proviene la versione fissata per il progetto.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinSource {
+    DverToml(PathBuf),
+    GlobalJson(PathBuf),
+}
+
+impl std::fmt::Display for PinSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinSource::DverToml(p) | PinSource::GlobalJson(p) => write!(f, "{}", p.display()),
+        }
+    }
+}
+
+/// Cerca il file `name` risalendo da `start` fino alla radice.
+fn find_upwards(start: &Path, name: &str) -> Option<PathBuf> {
+    start.ancestors().map(|dir| dir.join(name)).find(|p| p.is_file())
+}
+
+impl Project {
+    /// Carica il `.dver.toml` più vicino risalendo da `start`.
+    pub fn find(start: &Path) -> Result<Option<Project>, DverError> {
+        let Some(path) = find_upwards(start, PROJECT_FILE) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)?;
+        let mut config: ProjectConfig = toml::from_str(&content)
+            .map_err(|e| DverError::Parse(format!("Invalid project file {:?}: {}", path, e)))?;
+        // I percorsi relativi si riferiscono alla cartella del progetto, non a quella corrente
+        if let Some(dir) = path.parent() {
+            if let Some(root) = &config.install.install_root {
+                config.install.install_root = Some(dir.join(root));
+            }
+            if let Some(props) = &mut config.props {
+                props.file = dir.join(&props.file);
+            }
+        }
+        Ok(Some(Project { path, config }))
+    }
+}
+
+/// Voce di `sdk.paths` che indica la radice del `dotnet` avviato.
+pub const HOST_PATH: &str = "$host$";
+
+/// La sezione `sdk` di un global.json.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalJson {
+    pub path: PathBuf,
+    /// `sdk.version`
+    pub version: Option<String>,
+    /// `sdk.rollForward`; senza, dotnet usa `latestPatch`
+    pub roll_forward: Option<String>,
+    /// `sdk.paths`: le radici in cui cercare l'SDK, in ordine; vuoto vale `["$host$"]`
+    pub paths: Vec<String>,
+    /// `sdk.errorMessage`, che dotnet stampa quando nessun SDK soddisfa il file
+    pub error_message: Option<String>,
+}
+
+impl GlobalJson {
+    /// Le radici di `sdk.paths` in ordine: i percorsi relativi partono dalla cartella del
+    /// global.json e [`HOST_PATH`] diventa `host_root`, se noto.
+    pub fn sdk_roots(&self, host_root: Option<&Path>) -> Vec<PathBuf> {
+        if self.paths.is_empty() {
+            return host_root.map(Path::to_path_buf).into_iter().collect();
+        }
+        let dir = self.path.parent().unwrap_or(Path::new(""));
+        self.paths
+            .iter()
+            .filter_map(|entry| if entry == HOST_PATH { host_root.map(Path::to_path_buf) } else { Some(dir.join(entry)) })
+            .collect()
+    }
+}
+
+/// Carica il global.json più vicino risalendo da `start`.
+pub fn find_global_json(start: &Path) -> Result<Option<GlobalJson>, DverError> {
+    let Some(path) = find_upwards(start, "global.json") else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path)?;
+    let doc: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| DverError::Parse(format!("Invalid {:?}: {}", path, e)))?;
+    let field = |name: &str| doc.pointer(&format!("/sdk/{}", name)).and_then(|v| v.as_str()).map(|v| v.to_string());
+    let paths = doc
+        .pointer("/sdk/paths")
+        .and_then(|v| v.as_array())
+        .map(|entries| entries.iter().filter_map(|e| e.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    Ok(Some(GlobalJson {
+        version: field("version"),
+        roll_forward: field("rollForward"),
+        paths,
+        error_message: field("errorMessage"),
+        path,
+    }))
+}
+
+/// Scrive `version` in `sdk.version` del global.json `path`, creandolo se manca. Le
+/// altre proprietà (`rollForward`, `paths`, `errorMessage`, `msbuild-sdks`...) restano.
+pub fn write_global_json_version(path: &Path, version: &str) -> Result<(), DverError> {
+    let mut doc = match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str::<serde_json::Value>(&content)
+            .map_err(|e| DverError::Parse(format!("Invalid {:?}: {}", path, e)))?,
+        Err(_) => serde_json::json!({}),
+    };
+    let Some(root) = doc.as_object_mut() else {
+        return Err(DverError::Parse(format!("Invalid {:?}: not a JSON object", path)));
+    };
+    let sdk = root.entry("sdk").or_insert_with(|| serde_json::json!({}));
+    let Some(sdk) = sdk.as_object_mut() else {
+        return Err(DverError::Parse(format!("Invalid {:?}: `sdk` is not an object", path)));
+    };
+    sdk.insert("version".to_string(), version.into());
+    fs::write(path, format!("{}\n", serde_json::to_string_pretty(&doc)?)).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Valore della prima `<name>` di un file MSBuild, senza spazi ai lati.
+pub fn props_property(content: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let rest = &content[content.find(&open)? + open.len()..];
+    Some(rest[..rest.find(&close)?].trim().to_string())
+}
+
+/// Scrive `value` in ogni `<name>` del file props `path`. Se la proprietà manca si
+/// aggiunge un `<PropertyGroup>` prima di `</Project>`; se manca il file si crea.
+/// Il resto del file resta com'è.
+pub fn write_props_property(path: &Path, name: &str, value: &str) -> Result<(), DverError> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => "<Project>\n</Project>\n".to_string(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let mut updated = String::with_capacity(content.len());
+    let mut rest = content.as_str();
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else { break };
+        updated.push_str(&rest[..start + open.len()]);
+        updated.push_str(value);
+        rest = &after[end..];
+    }
+    updated.push_str(rest);
+    if updated == content && props_property(&content, name).is_none() {
+        let Some(end) = content.rfind("</Project>") else {
+            return Err(DverError::Parse(format!("Invalid {:?}: no </Project> element", path)));
+        };
+        let group = format!("  <PropertyGroup>\n    {}{}{}\n  </PropertyGroup>\n", open, value, close);
+        updated = format!("{}{}{}", &content[..end], group, &content[end..]);
+    }
+    fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Legge `sdk.version` dal global.json più vicino.
+fn global_json_version(start: &Path) -> Result<Option<(String, PathBuf)>, DverError> {
+    Ok(find_global_json(start)?.and_then(|g| g.version.map(|v| (v, g.path))))
+}
+
+/// Versione SDK fissata per la cartella `start`: `.dver.toml` ha la precedenza su global.json.
+pub fn pinned_sdk(start: &Path, project: Option<&Project>) -> Result<Option<(String, PinSource)>, DverError> {
+    if let Some(project) = project {
+        if let Some(sdk) = &project.config.sdk {
+            return Ok(Some((sdk.clone(), PinSource::DverToml(project.path.clone()))));
+        }
+    }
+    Ok(global_json_version(start)?.map(|(v, path)| (v, PinSource::GlobalJson(path))))
+}
+
+// --- Target framework dei progetti ---
+
+/// Cartelle che non contengono progetti da leggere.
+const SKIPPED_DIRS: &[&str] = &["bin", "obj", "node_modules", "packages", "artifacts"];
+
+/// Profondità massima a cui cercare i progetti sotto la radice del repository.
+const MAX_PROJECT_DEPTH: usize = 8;
+
+/// SDK richiesto dai progetti del repository, ricavato dai loro target framework.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferredSdk {
+    /// Canale più alto richiesto, es. `8.0` per `net8.0`
+    pub channel: String,
+    /// Target framework .NET trovati, es. `["net6.0", "net8.0-windows"]`
+    pub target_frameworks: Vec<String>,
+    /// File che li dichiarano
+    pub files: Vec<PathBuf>,
+}
+
+impl InferredSdk {
+    /// Primo SDK di `installed` in grado di compilare i progetti: uno del canale
+    /// richiesto o di uno successivo, perché ogni SDK compila anche i target precedenti.
+    pub fn satisfied_by<'a>(&self, installed: &'a [String]) -> Option<&'a String> {
+        let (major, minor) = self.channel.split_once('.')?;
+        let wanted: (u32, u32) = (major.parse().ok()?, minor.parse().ok()?);
+        installed
+            .iter()
+            .find(|v| v.parse::<crate::version::SdkVersion>().is_ok_and(|v| (v.major, v.minor) >= wanted))
+    }
+}
+
+/// Canale `(major, minor)` di un target framework di .NET (Core): `net8.0`,
+/// `net8.0-windows`, `netcoreapp3.1`. `None` per .NET Framework (`net48`) e
+/// .NET Standard, che qualsiasi SDK compila.
+pub fn tfm_channel(tfm: &str) -> Option<(u32, u32)> {
+    let tfm = tfm.trim().to_ascii_lowercase();
+    let version = tfm.strip_prefix("netcoreapp").or_else(|| tfm.strip_prefix("net"))?;
+    let version = version.split('-').next()?;
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Valori di `<TargetFramework>` e `<TargetFrameworks>` in un file MSBuild; quelli
+/// che usano proprietà (`$(...)`) non si possono risolvere senza MSBuild e si saltano.
+pub fn target_frameworks(content: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    for tag in ["TargetFramework", "TargetFrameworks"] {
+        let open = format!("<{}>", tag);
+        let close = format!("</{}>", tag);
+        let mut rest = content;
+        while let Some(start) = rest.find(&open) {
+            rest = &rest[start + open.len()..];
+            let Some(end) = rest.find(&close) else { break };
+            found.extend(
+                rest[..end]
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|tfm| !tfm.is_empty() && !tfm.contains("$("))
+                    .map(str::to_string),
+            );
+            rest = &rest[end + close.len()..];
+        }
+    }
+    found
+}
+
+/// Radice del repository che contiene `start` (la cartella con `.git`), oppure `start`.
+fn repository_root(start: &Path) -> &Path {
+    start.ancestors().find(|dir| dir.join(".git").exists()).unwrap_or(start)
+}
+
+/// Progetti (`*.csproj`, `*.fsproj`, `*.vbproj`) e `Directory.Build.props` sotto `dir`.
+fn collect_project_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if depth < MAX_PROJECT_DEPTH && !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_project_files(&path, depth + 1, files);
+            }
+        } else if name == "Directory.Build.props" || [".csproj", ".fsproj", ".vbproj"].iter().any(|ext| name.ends_with(ext)) {
+            files.push(path);
+        }
+    }
+}
+
+/// SDK richiesto dai progetti del repository che contiene `start`, anche senza
+/// global.json; `None` se nessun progetto dichiara un target framework di .NET (Core).
+pub fn infer_sdk(start: &Path) -> Result<Option<InferredSdk>, DverError> {
+    let mut files = Vec::new();
+    collect_project_files(repository_root(start), 0, &mut files);
+
+    let mut inferred = InferredSdk { channel: String::new(), target_frameworks: Vec::new(), files: Vec::new() };
+    let mut highest: Option<(u32, u32)> = None;
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        let tfms: Vec<String> = target_frameworks(&content).into_iter().filter(|t| tfm_channel(t).is_some()).collect();
+        if tfms.is_empty() {
+            continue;
+        }
+        for tfm in tfms {
+            highest = highest.max(tfm_channel(&tfm));
+            if !inferred.target_frameworks.contains(&tfm) {
+                inferred.target_frameworks.push(tfm);
+            }
+        }
+        inferred.files.push(file);
+    }
+    Ok(highest.map(|(major, minor)| InferredSdk { channel: format!("{}.{}", major, minor), ..inferred }))
+}
+
+// --- Workload dei progetti ---
+
+/// Vero se il file MSBuild imposta la proprietà `name` a `true`.
+fn property_is_true(content: &str, name: &str) -> bool {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    content.match_indices(&open).any(|(start, _)| {
+        let rest = &content[start + open.len()..];
+        rest.find(&close).is_some_and(|end| rest[..end].trim().eq_ignore_ascii_case("true"))
+    })
+}
+
+/// Workload richiesti da un file MSBuild: `maui` con `<UseMaui>`, i workload delle
+/// piattaforme per i target framework `-android`, `-ios` e `-maccatalyst` senza MAUI,
+/// `wasm-tools` per la compilazione AOT o nativa di WebAssembly e `aspire` per gli
+/// AppHost di .NET 8 (da .NET 9 Aspire è un pacchetto, non un workload).
+pub fn project_workloads(content: &str) -> Vec<String> {
+    let mut workloads = Vec::new();
+    if property_is_true(content, "UseMaui") {
+        workloads.push("maui".to_string());
+    } else {
+        for tfm in target_frameworks(content) {
+            // La piattaforma può avere una versione, es. `net8.0-android34.0`
+            let platform = tfm
+                .split_once('-')
+                .map(|(_, p)| p.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.').to_ascii_lowercase());
+            if let Some(platform) = platform.filter(|p| ["android", "ios", "maccatalyst"].contains(&p.as_str())) {
+                workloads.push(platform);
+            }
+        }
+    }
+    if property_is_true(content, "RunAOTCompilation") || property_is_true(content, "WasmBuildNative") {
+        workloads.push("wasm-tools".to_string());
+    }
+    if property_is_true(content, "IsAspireHost") && !content.contains("Aspire.AppHost.Sdk") {
+        workloads.push("aspire".to_string());
+    }
+    workloads.sort();
+    workloads.dedup();
+    workloads
+}
+
+/// Workload richiesti dai progetti del repository che contiene `start`, ordinati.
+pub fn required_workloads(start: &Path) -> Result<Vec<String>, DverError> {
+    let mut files = Vec::new();
+    collect_project_files(repository_root(start), 0, &mut files);
+    let mut workloads = Vec::new();
+    for file in files {
+        workloads.extend(project_workloads(&fs::read_to_string(&file)?));
+    }
+    workloads.sort();
+    workloads.dedup();
+    Ok(workloads)
+}
@@ -0,0 +1,96 @@
+//! Provenienza di ogni SDK installato da dver: da dove viene, con quale script,
+//! quando e da chi. I record stanno in `installs.json` nella cartella di stato.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Config, DverError, ResultExt};
+
+/// Feed da cui lo script scarica gli SDK quando `feed_url` non è configurato.
+pub const DEFAULT_FEED: &str = "https://builds.dotnet.microsoft.com/dotnet";
+
+/// Feed delle build giornaliere, da cui lo script scarica con `-Quality daily`.
+pub const DAILY_FEED: &str = "https://ci.dot.net/public";
+
+/// Come e da chi è stato installato un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub version: String,
+    /// Radice di installazione, che contiene `sdk/<versione>`
+    pub root: PathBuf,
+    pub installed_at: DateTime<Utc>,
+    /// Utente che ha lanciato l'installazione, se noto
+    pub installed_by: Option<String>,
+    /// Feed passato allo script (`-AzureFeed`)
+    pub feed_url: String,
+    /// URL da cui è stato scaricato lo script di installazione, o l'archivio con
+    /// `install_method = "archive"`
+    pub script_url: String,
+    /// SHA-256 dello script eseguito (ne identifica la versione) o dell'archivio
+    pub script_sha256: String,
+    /// Versione di dver che ha eseguito l'installazione
+    pub dver_version: String,
+    /// Build giornaliera (`quality = "daily"`), non supportata da Microsoft
+    #[serde(default)]
+    pub daily: bool,
+}
+
+/// Il file di stato con i record di provenienza.
+pub fn state_file(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("installs.json"))
+}
+
+/// Utente corrente secondo l'ambiente (`USER` o `USERNAME`).
+pub fn current_user() -> Option<String> {
+    ["USER", "USERNAME"].iter().find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+}
+
+/// Tutti i record salvati; un file mancante equivale a nessun record.
+pub fn load_all(config: &Config) -> Result<Vec<Provenance>, DverError> {
+    let Some(path) = state_file(config).filter(|p| p.is_file()) else {
+        return Ok(Vec::new());
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid install records in {}", path.display()))
+}
+
+fn save_all(config: &Config, records: &[Provenance]) -> Result<(), DverError> {
+    let path = state_file(config)
+        .ok_or_else(|| DverError::NotFound("Cannot determine the dver state directory".to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(records)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Stessa radice, anche se scritta in modo diverso (link, `..`).
+pub fn same_root(a: &Path, b: &Path) -> bool {
+    a == b || matches!((fs::canonicalize(a), fs::canonicalize(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+/// Record dell'SDK `version` nella radice `root`, se dver lo ha installato.
+pub fn find(config: &Config, root: &Path, version: &str) -> Result<Option<Provenance>, DverError> {
+    Ok(load_all(config)?.into_iter().find(|p| p.version == version && same_root(&p.root, root)))
+}
+
+/// Salva `record`, sostituendo quello di una precedente installazione della stessa versione.
+pub fn record(config: &Config, record: Provenance) -> Result<(), DverError> {
+    let mut records = load_all(config)?;
+    records.retain(|p| !(p.version == record.version && same_root(&p.root, &record.root)));
+    records.push(record);
+    save_all(config, &records)
+}
+
+/// Elimina il record di un SDK disinstallato.
+pub fn forget(config: &Config, root: &Path, version: &str) -> Result<(), DverError> {
+    let mut records = load_all(config)?;
+    let before = records.len();
+    records.retain(|p| !(p.version == version && same_root(&p.root, root)));
+    if records.len() == before {
+        return Ok(());
+    }
+    save_all(config, &records)
+}
@@ -0,0 +1,185 @@
+//! Il proxy configurato nel sistema, per chi non sa quale sia: le impostazioni Internet
+//! dell'utente e quelle di WinHTTP su Windows, i proxy di sistema di macOS (`scutil
+//! --proxy`) e quelli di GNOME (`gsettings`).
+//!
+//! Vale solo quando né la configurazione (`proxy`) né le variabili `HTTPS_PROXY`,
+//! `HTTP_PROXY` e `ALL_PROXY` ne indicano uno. Gli script PAC non si possono
+//! valutare: si riportano, così l'utente sa quale URL chiedere.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::process::{ProcessRunner, SystemRunner};
+use crate::Config;
+
+/// Variabili d'ambiente con cui l'utente sceglie il proxy; reqwest le legge da sé.
+pub const PROXY_VARS: [&str; 6] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"];
+
+/// Chiave delle impostazioni Internet dell'utente su Windows.
+pub const INTERNET_SETTINGS_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings";
+
+/// Un proxy letto dalle impostazioni del sistema.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SystemProxy {
+    /// URL del proxy, es. `http://proxy.corp:8080`
+    pub url: Option<String>,
+    /// Script PAC configurato, che dver non valuta
+    pub pac_url: Option<String>,
+    /// Host da raggiungere senza proxy, nella forma di `NO_PROXY`, es. `.corp`
+    pub no_proxy: Vec<String>,
+    /// Da dove viene, es. `WinHTTP settings`
+    pub source: &'static str,
+}
+
+impl SystemProxy {
+    fn is_empty(&self) -> bool {
+        self.url.is_none() && self.pac_url.is_none()
+    }
+}
+
+/// Aggiunge `http://` a un indirizzo `host:porta`.
+fn with_scheme(address: &str) -> String {
+    if address.contains("://") { address.to_string() } else { format!("http://{}", address) }
+}
+
+/// Converte un'eccezione in stile Windows o macOS (`*.corp`, `<local>`) in una voce di
+/// `NO_PROXY`; `<local>` (i nomi senza punto) non ha equivalente e si scarta.
+fn no_proxy_entry(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim().trim_matches(['\'', '"']);
+    if pattern.is_empty() || pattern.eq_ignore_ascii_case("<local>") {
+        return None;
+    }
+    Some(pattern.strip_prefix('*').filter(|rest| rest.starts_with('.')).unwrap_or(pattern).to_string())
+}
+
+/// Sceglie il proxy HTTPS da un `ProxyServer` di Windows, che è `host:porta` per tutti i
+/// protocolli o `http=host:porta;https=host:porta`.
+fn windows_server(value: &str) -> Option<String> {
+    if !value.contains('=') {
+        return Some(with_scheme(value.trim())).filter(|s| s != "http://");
+    }
+    let by_scheme = |scheme: &str| {
+        value.split(';').find_map(|part| part.trim().strip_prefix(scheme).and_then(|rest| rest.strip_prefix('='))).map(str::trim)
+    };
+    by_scheme("https").or_else(|| by_scheme("http")).filter(|s| !s.is_empty()).map(with_scheme)
+}
+
+/// Interpreta `reg query` sulle [`INTERNET_SETTINGS_KEY`].
+pub fn parse_internet_settings(output: &str) -> Option<SystemProxy> {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let mut parts = line.trim().splitn(3, "    ");
+            (parts.next()? == name).then(|| parts.nth(1).unwrap_or("").trim().to_string())
+        })
+    };
+    let enabled = value("ProxyEnable").is_some_and(|v| v != "0x0");
+    let proxy = SystemProxy {
+        url: value("ProxyServer").filter(|_| enabled).and_then(|v| windows_server(&v)),
+        pac_url: value("AutoConfigURL").filter(|v| !v.is_empty()),
+        no_proxy: value("ProxyOverride").map(|v| v.split(';').filter_map(no_proxy_entry).collect()).unwrap_or_default(),
+        source: "Windows Internet settings",
+    };
+    (!proxy.is_empty()).then_some(proxy)
+}
+
+/// Interpreta `netsh winhttp show proxy`. Il testo è tradotto, quindi si leggono solo
+/// le righe `etichetta : valore`: la prima è il server, la seconda le eccezioni.
+pub fn parse_winhttp(output: &str) -> Option<SystemProxy> {
+    let mut values = output.lines().filter_map(|line| line.split_once(" : ")).map(|(_, value)| value.trim());
+    let url = values.next().and_then(windows_server)?;
+    let no_proxy = values.next().map(|v| v.split(';').filter_map(no_proxy_entry).collect()).unwrap_or_default();
+    Some(SystemProxy { url: Some(url), pac_url: None, no_proxy, source: "WinHTTP settings" })
+}
+
+/// Interpreta `scutil --proxy` su macOS.
+pub fn parse_scutil(output: &str) -> Option<SystemProxy> {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.trim().split_once(" : ")?;
+            (key == name).then(|| value.trim().to_string())
+        })
+    };
+    let server = |prefix: &str| {
+        let host = value(&format!("{}Proxy", prefix)).filter(|_| value(&format!("{}Enable", prefix)).as_deref() == Some("1"))?;
+        Some(match value(&format!("{}Port", prefix)) {
+            Some(port) => format!("http://{}:{}", host, port),
+            None => format!("http://{}", host),
+        })
+    };
+    // Le eccezioni sono le righe `<indice> : <host>` dentro `ExceptionsList`
+    let no_proxy = output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("ExceptionsList"))
+        .skip(1)
+        .take_while(|line| !line.trim().starts_with('}'))
+        .filter_map(|line| line.split_once(" : ").and_then(|(_, host)| no_proxy_entry(host)))
+        .collect();
+    let pac_url = value("ProxyAutoConfigURLString").filter(|_| value("ProxyAutoConfigEnable").as_deref() == Some("1"));
+    let proxy = SystemProxy { url: server("HTTPS").or_else(|| server("HTTP")), pac_url, no_proxy, source: "macOS network settings" };
+    (!proxy.is_empty()).then_some(proxy)
+}
+
+/// Interpreta `gsettings list-recursively org.gnome.system.proxy`.
+pub fn parse_gsettings(output: &str) -> Option<SystemProxy> {
+    let value = |schema: &str, key: &str| {
+        output.lines().find_map(|line| {
+            let rest = line.trim().strip_prefix(schema)?.strip_prefix(' ')?.strip_prefix(key)?.strip_prefix(' ')?;
+            Some(rest.trim().trim_matches('\'').to_string())
+        })
+    };
+    let base = "org.gnome.system.proxy";
+    let mode = value(base, "mode")?;
+    let server = |protocol: &str| {
+        let schema = format!("{}.{}", base, protocol);
+        let host = value(&schema, "host").filter(|h| !h.is_empty())?;
+        let port = value(&schema, "port").filter(|p| p != "0");
+        Some(match port {
+            Some(port) => format!("http://{}:{}", host, port),
+            None => format!("http://{}", host),
+        })
+    };
+    let no_proxy = value(base, "ignore-hosts")
+        .map(|list| list.trim_start_matches('[').trim_end_matches(']').split(',').filter_map(no_proxy_entry).collect())
+        .unwrap_or_default();
+    let proxy = match mode.as_str() {
+        "manual" => SystemProxy { url: server("https").or_else(|| server("http")), no_proxy, source: "GNOME proxy settings", ..SystemProxy::default() },
+        "auto" => SystemProxy { pac_url: value(base, "autoconfig-url").filter(|u| !u.is_empty()), no_proxy, source: "GNOME proxy settings", ..SystemProxy::default() },
+        _ => return None,
+    };
+    (!proxy.is_empty()).then_some(proxy)
+}
+
+fn query(runner: &dyn ProcessRunner, program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    runner.output(&mut command).ok().filter(|output| output.status.success()).map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Il proxy configurato nel sistema `os` (come `std::env::consts::OS`), se c'è.
+pub fn detect(runner: &dyn ProcessRunner, os: &str) -> Option<SystemProxy> {
+    match os {
+        // Le impostazioni dell'utente prima di quelle di WinHTTP, che valgono per i servizi
+        "windows" => query(runner, "reg", &["query", INTERNET_SETTINGS_KEY])
+            .and_then(|out| parse_internet_settings(&out))
+            .or_else(|| query(runner, "netsh", &["winhttp", "show", "proxy"]).and_then(|out| parse_winhttp(&out))),
+        "macos" => query(runner, "scutil", &["--proxy"]).and_then(|out| parse_scutil(&out)),
+        _ => query(runner, "gsettings", &["list-recursively", "org.gnome.system.proxy"]).and_then(|out| parse_gsettings(&out)),
+    }
+}
+
+/// Vero se una delle [`PROXY_VARS`] è impostata.
+pub fn env_proxy_set() -> bool {
+    PROXY_VARS.iter().any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// Il proxy di sistema da usare con `config`: nessuno se la configurazione o l'ambiente
+/// ne indicano uno, o con `ignore_system_proxy`. Si legge una volta per processo.
+pub fn system_proxy(config: &Config) -> Option<&'static SystemProxy> {
+    static DETECTED: OnceLock<Option<SystemProxy>> = OnceLock::new();
+    if config.proxy.is_some() || config.ignore_system_proxy || env_proxy_set() {
+        return None;
+    }
+    DETECTED.get_or_init(|| detect(&SystemRunner, std::env::consts::OS)).as_ref()
+}
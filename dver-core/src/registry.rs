@@ -0,0 +1,103 @@
+//! Le installazioni di .NET registrate dagli installer MSI su Windows (Visual Studio,
+//! winget, il pacchetto scaricato dal sito) in
+//! `HKLM\SOFTWARE\dotnet\Setup\InstalledVersions`, lette con `reg query`.
+//!
+//! Servono a `list`, `doctor` e `uninstall` per riconoscere gli SDK che appartengono
+//! a un pacchetto MSI: cancellarne i file lascerebbe il pacchetto registrato ma rotto,
+//! quindi vanno rimossi dalle impostazioni di Windows o con winget.
+
+use std::path::{Path, PathBuf};
+
+use crate::platform::Platform;
+use crate::process::ProcessRunner;
+use crate::provenance::same_root;
+
+/// Chiave con le installazioni native (x64 e arm64).
+pub const INSTALLED_VERSIONS_KEY: &str = r"HKLM\SOFTWARE\dotnet\Setup\InstalledVersions";
+/// La stessa chiave nella vista a 32 bit, con le installazioni x86.
+pub const INSTALLED_VERSIONS_KEY_32: &str = r"HKLM\SOFTWARE\WOW6432Node\dotnet\Setup\InstalledVersions";
+
+/// Le versioni registrate per un'architettura.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryInstall {
+    /// Sottochiave dell'architettura, es. `x64`
+    pub arch: String,
+    /// Valore `InstallLocation`, es. `C:\Program Files\dotnet\`
+    pub install_location: Option<PathBuf>,
+    pub sdks: Vec<String>,
+    /// Coppie (nome del framework, versione), es. (`Microsoft.NETCore.App`, `8.0.11`)
+    pub runtimes: Vec<(String, String)>,
+}
+
+impl RegistryInstall {
+    /// Vero se l'SDK `version` nella radice `root` appartiene a questa installazione.
+    pub fn owns_sdk(&self, root: &Path, version: &str) -> bool {
+        self.sdks.iter().any(|v| v == version) && self.install_location.as_deref().is_some_and(|loc| same_root(loc, root))
+    }
+
+    /// SDK registrati la cui cartella non esiste più, es. dopo una cancellazione a mano.
+    pub fn missing_sdks(&self) -> Vec<&str> {
+        let Some(location) = &self.install_location else {
+            return Vec::new();
+        };
+        self.sdks.iter().filter(|v| !location.join("sdk").join(v).is_dir()).map(String::as_str).collect()
+    }
+}
+
+/// Interpreta l'output di `reg query <chiave> /s` su [`INSTALLED_VERSIONS_KEY`].
+pub fn parse_installed_versions(output: &str) -> Vec<RegistryInstall> {
+    let mut installs: Vec<RegistryInstall> = Vec::new();
+    // Percorso della chiave corrente sotto `InstalledVersions`, es. ["x64", "sdk"]
+    let mut key: Vec<String> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with("HKEY_") {
+            key = match line.trim().split_once(r"\InstalledVersions") {
+                Some((_, rest)) => rest.split('\\').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+                None => Vec::new(),
+            };
+            continue;
+        }
+        let mut parts = line.trim().splitn(3, "    ");
+        let (Some(name), Some(_kind)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let data = parts.next().unwrap_or("").trim();
+        let Some(arch) = key.first() else {
+            continue;
+        };
+        let install = match installs.iter_mut().position(|i| &i.arch == arch) {
+            Some(found) => &mut installs[found],
+            None => {
+                installs.push(RegistryInstall { arch: arch.clone(), ..RegistryInstall::default() });
+                installs.last_mut().expect("just pushed")
+            }
+        };
+        match key.iter().skip(1).map(String::as_str).collect::<Vec<_>>().as_slice() {
+            // Senza la barra finale, che su Unix farebbe parte dell'ultimo componente
+            [] if name == "InstallLocation" && !data.is_empty() => {
+                install.install_location = Some(PathBuf::from(data.trim_end_matches(['\\', '/'])))
+            }
+            ["sdk"] => install.sdks.push(name.to_string()),
+            ["sharedfx", framework] => install.runtimes.push((framework.to_string(), name.to_string())),
+            _ => {}
+        }
+    }
+    installs
+}
+
+/// Le installazioni registrate sull'host; vuoto dove il registro non esiste o la
+/// chiave manca.
+pub fn installed_versions(runner: &dyn ProcessRunner, platform: &dyn Platform) -> Vec<RegistryInstall> {
+    platform
+        .installed_versions_queries()
+        .iter_mut()
+        .filter_map(|command| runner.output(command).ok())
+        .filter(|output| output.status.success())
+        .flat_map(|output| parse_installed_versions(&String::from_utf8_lossy(&output.stdout)))
+        .collect()
+}
+
+/// L'installazione MSI a cui appartiene l'SDK `version` in `root`, se c'è.
+pub fn msi_owner<'a>(installs: &'a [RegistryInstall], root: &Path, version: &str) -> Option<&'a RegistryInstall> {
+    installs.iter().find(|install| install.owns_sdk(root, version))
+}
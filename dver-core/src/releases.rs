@@ -0,0 +1,328 @@
+//! Tipi dei metadati dei rilasci .NET pubblicati da Microsoft: l'indice dei canali
+//! (`releases-index.json`) e il `releases.json` di ogni canale.
+//!
+//! Il modulo non fa I/O: si può usare per leggere i metadati ottenuti in qualsiasi
+//! modo. Per scaricarli con la cache di dver vedi [`crate::metadata`].
+//!
+//! ```
+//! use dver_core::releases::{ReleaseIndex, ReleaseType};
+//!
+//! let index: ReleaseIndex = serde_json::from_str(r#"{"releases-index": [{
+//!     "channel-version": "8.0", "release-type": "lts", "eol-date": "2026-11-10",
+//!     "releases.json": "https://example.com/8.0/releases.json"
+//! }]}"#)?;
+//! let channel = &index.releases_index[0];
+//! assert_eq!(channel.release_type, Some(ReleaseType::Lts));
+//! assert_eq!(channel.eol_date.unwrap().to_string(), "2026-11-10");
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::de::{Deserializer, Error as _};
+use serde::{Deserialize, Serialize};
+
+use crate::version::SdkVersion;
+
+// --- Enumerazioni ---
+
+/// Politica di supporto di un canale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseType {
+    /// Long Term Support, tre anni di aggiornamenti.
+    Lts,
+    /// Standard Term Support.
+    Sts,
+    /// Valore non ancora noto a questa versione di dver.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Fase del ciclo di vita in cui si trova un canale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SupportPhase {
+    Preview,
+    GoLive,
+    Active,
+    Maintenance,
+    Eol,
+    /// Valore non ancora noto a questa versione di dver.
+    #[serde(other)]
+    Unknown,
+}
+
+impl fmt::Display for ReleaseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReleaseType::Lts => "lts",
+            ReleaseType::Sts => "sts",
+            ReleaseType::Unknown => "unknown",
+        })
+    }
+}
+
+impl fmt::Display for SupportPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SupportPhase::Preview => "preview",
+            SupportPhase::GoLive => "go-live",
+            SupportPhase::Active => "active",
+            SupportPhase::Maintenance => "maintenance",
+            SupportPhase::Eol => "eol",
+            SupportPhase::Unknown => "unknown",
+        })
+    }
+}
+
+// --- releases-index.json ---
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseIndex {
+    #[serde(rename = "releases-index")]
+    pub releases_index: Vec<ReleaseChannel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReleaseChannel {
+    /// Versione del canale, ad esempio "8.0" (non è una versione SDK completa).
+    #[serde(default)]
+    pub channel_version: Option<String>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_release: Option<SdkVersion>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_release_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    pub security: Option<bool>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_runtime: Option<SdkVersion>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_sdk: Option<SdkVersion>,
+
+    #[serde(default)]
+    pub product: Option<String>,
+
+    #[serde(default)]
+    pub release_type: Option<ReleaseType>,
+
+    #[serde(default)]
+    pub support_phase: Option<SupportPhase>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub eol_date: Option<NaiveDate>,
+
+    #[serde(rename = "releases.json")]
+    pub releases_json: String,
+}
+
+// --- releases.json di un canale ---
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChannelReleases {
+    #[serde(default)]
+    pub channel_version: Option<String>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_release: Option<SdkVersion>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_release_date: Option<NaiveDate>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_runtime: Option<SdkVersion>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub latest_sdk: Option<SdkVersion>,
+
+    #[serde(default)]
+    pub release_type: Option<ReleaseType>,
+
+    #[serde(default)]
+    pub support_phase: Option<SupportPhase>,
+
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub eol_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    pub lifecycle_policy: Option<String>,
+
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub releases: Vec<Release>, // sempre un vettore anche se null nel JSON
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Release {
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub release_date: Option<NaiveDate>,
+
+    #[serde(rename = "release-version", default, deserialize_with = "parsed_or_none")]
+    pub version: Option<SdkVersion>,
+
+    #[serde(default)]
+    pub security: Option<bool>,
+
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub cve_list: Vec<CVE>,
+
+    #[serde(default)]
+    pub release_notes: Option<String>,
+
+    #[serde(default)]
+    pub runtime: Option<DotnetRuntime>,
+
+    #[serde(default)]
+    pub sdk: Option<DotnetSdk>,
+
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub sdks: Vec<DotnetSdk>, // può essere vuoto se null nel JSON
+
+    #[serde(default)]
+    pub aspnetcore_runtime: Option<AspNetCoreRuntime>,
+
+    #[serde(default)]
+    pub windowsdesktop: Option<WindowsDesktop>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CVE {
+    pub cve_id: String,
+    pub cve_url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub name: String,
+    #[serde(default)]
+    pub rid: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub akams: Option<String>,
+    /// Dimensione in byte, se il feed la riporta (quello ufficiale no)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DotnetRuntime {
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub version: Option<SdkVersion>,
+    #[serde(default)]
+    pub version_display: Option<String>,
+    #[serde(default)]
+    pub vs_version: Option<String>,
+    #[serde(default)]
+    pub vs_mac_version: Option<String>,
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub files: Vec<FileInfo>, // può essere vuoto se null
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DotnetSdk {
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub version: Option<SdkVersion>,
+    #[serde(default)]
+    pub version_display: Option<String>,
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub runtime_version: Option<SdkVersion>,
+    #[serde(default)]
+    pub vs_version: Option<String>,
+    #[serde(default)]
+    pub vs_mac_version: Option<String>,
+    #[serde(default)]
+    pub vs_support: Option<String>,
+    #[serde(default)]
+    pub vs_mac_support: Option<String>,
+    #[serde(default)]
+    pub csharp_version: Option<String>,
+    #[serde(default)]
+    pub fsharp_version: Option<String>,
+    #[serde(default)]
+    pub vb_version: Option<String>,
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub files: Vec<FileInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AspNetCoreRuntime {
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub version: Option<SdkVersion>,
+    #[serde(default)]
+    pub version_display: Option<String>,
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub version_aspnetcoremodule: Vec<String>, // può essere vuoto se null
+    #[serde(default)]
+    pub vs_version: Option<String>,
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub files: Vec<FileInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WindowsDesktop {
+    #[serde(default, deserialize_with = "parsed_or_none")]
+    pub version: Option<SdkVersion>,
+    #[serde(default)]
+    pub version_display: Option<String>,
+    #[serde(default, deserialize_with = "null_to_vec")]
+    pub files: Vec<FileInfo>,
+}
+
+// --- Deserializzatori di supporto ---
+
+fn null_to_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Legge una stringa tipizzata; `null` e la stringa vuota (presente in alcuni
+/// rilasci storici) diventano `None`, un valore non valido è un errore.
+fn parsed_or_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(s) if !s.trim().is_empty() => s.trim().parse().map(Some).map_err(D::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+// --- Metodi ---
+
+impl ReleaseChannel {
+    /// Vero per i canali con supporto a lungo termine.
+    pub fn is_lts(&self) -> bool {
+        self.release_type == Some(ReleaseType::Lts)
+    }
+}
+
+impl Release {
+    /// Versioni di tutti gli SDK pubblicati con questo rilascio.
+    pub fn sdk_versions(&self) -> impl Iterator<Item = &SdkVersion> {
+        self.sdks
+            .iter()
+            .chain(self.sdk.as_ref())
+            .filter_map(|sdk| sdk.version.as_ref())
+    }
+}
@@ -0,0 +1,176 @@
+//! Rimozione degli alberi di file degli SDK. Su Windows usa i percorsi estesi `\\?\`
+//! (i file più annidati di un SDK superano i 260 caratteri), toglie l'attributo di sola
+//! lettura e riconosce i file tenuti aperti da un `dotnet` o da un nodo MSBuild ancora
+//! in esecuzione, dicendo quale processo li tiene.
+//!
+//! La cartella viene prima rinominata accanto a sé: se un file è bloccato il rename
+//! fallisce e l'SDK resta intero, invece di restare a metà; dopo il rename `dotnet` non
+//! lo vede più e quello che resta è solo da pulire.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::archive::long_path;
+use crate::platform::Platform;
+use crate::process::ProcessRunner;
+use crate::DverError;
+
+/// Suffisso della copia rinominata di una cartella in corso di rimozione.
+const REMOVING_SUFFIX: &str = ".dver-removing";
+
+/// Pausa tra due tentativi su file bloccati.
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Il nome che `path` prende durante la rimozione, nella stessa cartella, es.
+/// `sdk/.8.0.404.dver-removing`.
+pub fn removing_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!(".{}{}", name, REMOVING_SUFFIX))
+}
+
+/// Vero se `e` viene da un file aperto da un altro processo: su Windows
+/// `ERROR_ACCESS_DENIED` (un eseguibile in esecuzione), `ERROR_SHARING_VIOLATION` e
+/// `ERROR_LOCK_VIOLATION`. Altrove i file aperti si possono sempre rimuovere.
+pub fn is_lock_error(e: &io::Error) -> bool {
+    cfg!(windows) && matches!(e.raw_os_error(), Some(5 | 32 | 33))
+}
+
+/// Interpreta le righe `<pid> <nome>` del comando di [`Platform::processes_using_command`].
+pub fn parse_holders(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, name) = line.trim().split_once(' ')?;
+            pid.parse::<u32>().ok().map(|pid| format!("{} ({})", name.trim(), pid))
+        })
+        .collect()
+}
+
+/// I processi che usano file in `dir`, es. `dotnet (4242)`; vuoto dove non si sa chiederlo.
+pub fn holders(runner: &dyn ProcessRunner, platform: &dyn Platform, dir: &Path) -> Vec<String> {
+    let Some(mut command) = platform.processes_using_command(dir) else {
+        return Vec::new();
+    };
+    match runner.output(&mut command) {
+        Ok(output) if output.status.success() => parse_holders(&String::from_utf8_lossy(&output.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// Il percorso come lo scrive l'utente, senza il prefisso `\\?\`.
+fn shown(path: &Path) -> String {
+    let text = path.display().to_string();
+    match text.strip_prefix(r"\\?\UNC\") {
+        Some(unc) => format!(r"\\{}", unc),
+        None => text.strip_prefix(r"\\?\").map(str::to_string).unwrap_or(text),
+    }
+}
+
+/// Un elemento che non si è potuto rimuovere.
+struct Failure {
+    path: PathBuf,
+    error: io::Error,
+}
+
+fn remove_entry(path: &Path) -> Result<(), Failure> {
+    let fail = |error| Failure { path: path.to_path_buf(), error };
+    let file_type = fs::symlink_metadata(path).map_err(fail)?.file_type();
+    if file_type.is_dir() {
+        for entry in fs::read_dir(path).map_err(fail)? {
+            remove_entry(&entry.map_err(fail)?.path())?;
+        }
+        retry_writable(path, |p| fs::remove_dir(p))
+    } else if file_type.is_symlink() {
+        // Su Windows i link a cartelle si rimuovono come cartelle
+        fs::remove_file(path).or_else(|_| fs::remove_dir(path)).map_err(fail)
+    } else {
+        retry_writable(path, |p| fs::remove_file(p))
+    }
+}
+
+/// Riprova `remove` una volta dopo aver tolto l'attributo di sola lettura di Windows.
+fn retry_writable(path: &Path, remove: fn(&Path) -> io::Result<()>) -> Result<(), Failure> {
+    let retried = match remove(path) {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied && make_writable(path) => remove(path),
+        result => result,
+    };
+    retried.map_err(|error| Failure { path: path.to_path_buf(), error })
+}
+
+/// Toglie la sola lettura a `path`; vero se c'era.
+#[cfg(windows)]
+fn make_writable(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else { return false };
+    let mut permissions = metadata.permissions();
+    if !permissions.readonly() {
+        return false;
+    }
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions).is_ok()
+}
+
+#[cfg(not(windows))]
+fn make_writable(_path: &Path) -> bool {
+    false
+}
+
+fn locked(runner: &dyn ProcessRunner, platform: &dyn Platform, dir: &Path, failure: &Failure) -> DverError {
+    let holders = holders(runner, platform, dir);
+    let who = if holders.is_empty() { "another process".to_string() } else { holders.join(", ") };
+    DverError::Permission(format!(
+        "{} is held by {} ({})\nhint: close Visual Studio and other IDEs, run `dotnet build-server shutdown`, then retry or pass --wait <seconds>",
+        shown(&failure.path),
+        who,
+        failure.error
+    ))
+}
+
+/// Rimuove l'albero `path`. Con file bloccati riprova per al massimo `wait`, poi spiega
+/// quale processo li tiene; se il blocco arriva dopo il rename, i resti stanno in
+/// [`removing_path`] e la rimozione successiva li pulisce.
+pub fn remove_tree(runner: &dyn ProcessRunner, platform: &dyn Platform, path: &Path, wait: Duration) -> Result<(), DverError> {
+    let deadline = Instant::now() + wait;
+    let source = long_path(path);
+    let staging = long_path(&removing_path(path));
+    // Resti di una rimozione interrotta
+    if staging.symlink_metadata().is_ok() {
+        let _ = remove_entry(&staging);
+    }
+    loop {
+        match fs::rename(&source, &staging) {
+            Ok(()) => break,
+            Err(error) if is_lock_error(&error) => {
+                let failure = Failure { path: path.to_path_buf(), error };
+                if Instant::now() >= deadline {
+                    return Err(locked(runner, platform, path, &failure));
+                }
+                thread::sleep(RETRY_INTERVAL);
+            }
+            Err(error) => {
+                let context = format!("Failed to remove {}", path.display());
+                return Err(DverError::Context { context, source: Box::new(error.into()) });
+            }
+        }
+    }
+    loop {
+        match remove_entry(&staging) {
+            Ok(()) => return Ok(()),
+            Err(failure) if is_lock_error(&failure.error) => {
+                if Instant::now() >= deadline {
+                    let e = locked(runner, platform, path, &failure);
+                    let context = format!("Files are left in {}", shown(&staging));
+                    return Err(DverError::Context { context, source: Box::new(e) });
+                }
+                thread::sleep(RETRY_INTERVAL);
+            }
+            Err(failure) => {
+                let context = format!("Failed to remove {}", shown(&failure.path));
+                return Err(DverError::Context { context, source: Box::new(failure.error.into()) });
+            }
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Istantanea di una macchina per `dver report`: host, SDK, runtime e workload con
+//! origine e fine del supporto, il pin attivo e l'esito di `doctor`.
+//!
+//! Il documento è pensato per essere raccolto da molte macchine e aggregato altrove:
+//! è autosufficiente e i suoi campi cambiano solo aumentando [`REPORT_SCHEMA`].
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::adopt::Adoption;
+use crate::audit::is_end_of_support;
+use crate::caches::CacheLocation;
+use crate::disk::format_size;
+use crate::inventory::{InstalledRuntime, InstalledSdk, InstalledWorkload};
+use crate::platform::{self, HostSupport};
+use crate::provenance::{current_user, same_root, Provenance};
+use crate::registry::{msi_owner, RegistryInstall};
+use crate::releases::ReleaseIndex;
+use crate::version::SdkVersion;
+
+/// Versione del formato di `dver report`.
+pub const REPORT_SCHEMA: u32 = 1;
+
+/// La macchina su cui è stato prodotto il report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostInfo {
+    pub hostname: Option<String>,
+    pub os: String,
+    pub arch: String,
+    /// RID usato per scegliere gli archivi, es. `linux-x64`
+    pub rid: String,
+    /// Se Microsoft pubblica gli SDK per questo sistema
+    pub support: HostSupport,
+    pub user: Option<String>,
+}
+
+impl HostInfo {
+    pub fn current() -> HostInfo {
+        HostInfo {
+            hostname: hostname(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            rid: platform::rid(),
+            support: platform::current_support(),
+            user: current_user(),
+        }
+    }
+}
+
+/// Nome della macchina dalle variabili d'ambiente o, su Unix, da `/etc/hostname`.
+fn hostname() -> Option<String> {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Chi ha messo un SDK sulla macchina.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    /// Installato da dver, con un record di provenienza
+    Dver,
+    /// Di un pacchetto MSI registrato su Windows (Visual Studio, winget, installer)
+    Msi,
+    /// Installato in altro modo e registrato con `dver adopt`
+    Adopted,
+    /// Nessun record
+    Unknown,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Origin::Dver => "dver",
+            Origin::Msi => "msi",
+            Origin::Adopted => "adopted",
+            Origin::Unknown => "unknown",
+        })
+    }
+}
+
+/// Un SDK installato nel report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportSdk {
+    pub version: String,
+    pub path: PathBuf,
+    pub origin: Origin,
+    /// Canale, es. `8.0`
+    pub channel: Option<String>,
+    /// Canale fuori supporto; `None` se i metadati non lo conoscono
+    pub is_eol: Option<bool>,
+}
+
+/// Un runtime installato nel report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportRuntime {
+    pub name: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub is_eol: Option<bool>,
+}
+
+/// L'SDK fissato e quello risolto nella cartella da cui è stato lanciato il report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivePin {
+    pub directory: PathBuf,
+    /// Versione fissata da `.dver.toml` o global.json
+    pub pinned: Option<String>,
+    /// Il file che la fissa
+    pub source: Option<PathBuf>,
+    /// SDK che dotnet userebbe nella cartella
+    pub resolved: Option<String>,
+}
+
+/// Esito di un controllo di `doctor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Un controllo di `doctor` con il messaggio mostrato all'utente.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorCheck {
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl DoctorCheck {
+    pub fn ok(message: impl Into<String>) -> DoctorCheck {
+        DoctorCheck { status: CheckStatus::Ok, message: message.into() }
+    }
+
+    pub fn warning(message: impl Into<String>) -> DoctorCheck {
+        DoctorCheck { status: CheckStatus::Warning, message: message.into() }
+    }
+
+    pub fn error(message: impl Into<String>) -> DoctorCheck {
+        DoctorCheck { status: CheckStatus::Error, message: message.into() }
+    }
+}
+
+/// Conteggi di `doctor`, con i controlli che non sono andati a buon fine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorSummary {
+    pub ok: usize,
+    pub warnings: usize,
+    pub errors: usize,
+    pub problems: Vec<DoctorCheck>,
+}
+
+impl DoctorSummary {
+    pub fn new(checks: &[DoctorCheck]) -> DoctorSummary {
+        let count = |status: CheckStatus| checks.iter().filter(|c| c.status == status).count();
+        DoctorSummary {
+            ok: count(CheckStatus::Ok),
+            warnings: count(CheckStatus::Warning),
+            errors: count(CheckStatus::Error),
+            problems: checks.iter().filter(|c| c.status != CheckStatus::Ok).cloned().collect(),
+        }
+    }
+}
+
+/// Il report completo di una macchina.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FleetReport {
+    pub schema: u32,
+    pub generated_at: DateTime<Utc>,
+    pub dver_version: String,
+    pub host: HostInfo,
+    pub sdks: Vec<ReportSdk>,
+    pub runtimes: Vec<ReportRuntime>,
+    pub workloads: Vec<InstalledWorkload>,
+    /// Cache di dver e di NuGet, con lo spazio occupato
+    pub caches: Vec<CacheLocation>,
+    pub pin: ActivePin,
+    pub doctor: DoctorSummary,
+}
+
+impl FleetReport {
+    /// Report vuoto per questa macchina, prodotto adesso.
+    pub fn new(dver_version: &str, pin: ActivePin) -> FleetReport {
+        FleetReport {
+            schema: REPORT_SCHEMA,
+            generated_at: DateTime::<Utc>::from(SystemTime::now()),
+            dver_version: dver_version.to_string(),
+            host: HostInfo::current(),
+            sdks: Vec::new(),
+            runtimes: Vec::new(),
+            workloads: Vec::new(),
+            caches: Vec::new(),
+            pin,
+            doctor: DoctorSummary::new(&[]),
+        }
+    }
+
+    /// Lo stesso report in Markdown, da leggere o da allegare a un ticket.
+    pub fn markdown(&self) -> String {
+        let eol = |e: Option<bool>| match e {
+            Some(true) => "**yes**",
+            Some(false) => "no",
+            None => "unknown",
+        };
+        let mut out = format!(
+            "# dver report: {}\n\nGenerated {} by dver {} (schema {}).\n\n",
+            self.host.hostname.as_deref().unwrap_or("unknown host"),
+            self.generated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.dver_version,
+            self.schema,
+        );
+        out.push_str(&format!(
+            "| Host | OS | Arch | RID | User |\n|---|---|---|---|---|\n| {} | {} | {} | {} | {} |\n\n",
+            self.host.hostname.as_deref().unwrap_or("-"),
+            self.host.os,
+            self.host.arch,
+            self.host.rid,
+            self.host.user.as_deref().unwrap_or("-"),
+        ));
+
+        out.push_str("## SDKs\n\n| Version | Channel | Origin | EOL | Path |\n|---|---|---|---|---|\n");
+        for sdk in &self.sdks {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                sdk.version,
+                sdk.channel.as_deref().unwrap_or("-"),
+                sdk.origin,
+                eol(sdk.is_eol),
+                sdk.path.display(),
+            ));
+        }
+        out.push_str("\n## Runtimes\n\n| Name | Version | EOL |\n|---|---|---|\n");
+        for runtime in &self.runtimes {
+            out.push_str(&format!("| {} | {} | {} |\n", runtime.name, runtime.version, eol(runtime.is_eol)));
+        }
+        out.push_str("\n## Workloads\n\n");
+        if self.workloads.is_empty() {
+            out.push_str("None.\n");
+        }
+        for workload in &self.workloads {
+            out.push_str(&format!("- {}", workload.id));
+            if let Some(version) = &workload.manifest_version {
+                out.push_str(&format!(" ({})", version));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("\n## Caches\n\n| Cache | Path | Source | Size |\n|---|---|---|---|\n");
+        for cache in &self.caches {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                cache.kind,
+                cache.path.display(),
+                cache.source,
+                cache.size_bytes.map(format_size).unwrap_or_else(|| "-".to_string()),
+            ));
+        }
+
+        out.push_str(&format!("\n## Active SDK\n\nIn `{}`: ", self.pin.directory.display()));
+        match (&self.pin.pinned, &self.pin.source) {
+            (Some(pinned), Some(source)) => out.push_str(&format!("SDK {} pinned in `{}`", pinned, source.display())),
+            _ => out.push_str("no pin"),
+        }
+        out.push_str(&format!(", resolves to {}.\n", self.pin.resolved.as_deref().unwrap_or("no installed SDK")));
+
+        out.push_str(&format!(
+            "\n## Doctor\n\n{} ok, {} warnings, {} errors.\n",
+            self.doctor.ok, self.doctor.warnings, self.doctor.errors,
+        ));
+        for problem in &self.doctor.problems {
+            let status = if problem.status == CheckStatus::Error { "error" } else { "warning" };
+            out.push_str(&format!("\n- **{}**: {}", status, problem.message));
+        }
+        if !self.doctor.problems.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Canale `A.B` di una versione.
+fn channel_of(version: &str) -> Option<String> {
+    version.parse::<SdkVersion>().ok().map(|v| format!("{}.{}", v.major, v.minor))
+}
+
+/// Fine del supporto del canale di `version` secondo `index`, alla data `today`.
+fn eol_of(index: Option<&ReleaseIndex>, version: &str, today: NaiveDate) -> Option<bool> {
+    is_end_of_support(index?, &channel_of(version)?, today)
+}
+
+/// Origine dell'SDK `sdk` secondo i record di provenienza, il registro di Windows e
+/// i record di adozione.
+pub fn origin_of(sdk: &InstalledSdk, records: &[Provenance], msi: &[RegistryInstall], adoptions: &[Adoption]) -> Origin {
+    let root = sdk.path.parent().and_then(Path::parent).unwrap_or(Path::new(""));
+    if records.iter().any(|p| p.version == sdk.version && same_root(&p.root, root)) {
+        Origin::Dver
+    } else if msi_owner(msi, root, &sdk.version).is_some() {
+        Origin::Msi
+    } else if adoptions.iter().any(|a| a.version == sdk.version && same_root(&a.root, root)) {
+        Origin::Adopted
+    } else {
+        Origin::Unknown
+    }
+}
+
+/// Voci del report per gli SDK installati; senza `index` la fine del supporto resta ignota.
+pub fn report_sdks(
+    sdks: &[InstalledSdk],
+    records: &[Provenance],
+    msi: &[RegistryInstall],
+    adoptions: &[Adoption],
+    index: Option<&ReleaseIndex>,
+    today: NaiveDate,
+) -> Vec<ReportSdk> {
+    sdks.iter()
+        .map(|sdk| ReportSdk {
+            version: sdk.version.clone(),
+            path: sdk.path.clone(),
+            origin: origin_of(sdk, records, msi, adoptions),
+            channel: channel_of(&sdk.version),
+            is_eol: eol_of(index, &sdk.version, today),
+        })
+        .collect()
+}
+
+/// Voci del report per i runtime installati.
+pub fn report_runtimes(runtimes: &[InstalledRuntime], index: Option<&ReleaseIndex>, today: NaiveDate) -> Vec<ReportRuntime> {
+    runtimes
+        .iter()
+        .map(|runtime| ReportRuntime {
+            name: runtime.name.clone(),
+            version: runtime.version.clone(),
+            path: runtime.path.clone(),
+            is_eol: eol_of(index, &runtime.version, today),
+        })
+        .collect()
+}
@@ -0,0 +1,39 @@
+//! Confronto tra la versione richiesta e quelle note: risoluzione delle versioni
+//! parziali e suggerimenti per quelle sconosciute.
+
+use crate::version::{SdkVersion, VersionSpec};
+
+/// La versione più recente tra `candidates` che soddisfa `spec`. Per le versioni
+/// parziali le anteprime si usano solo se non c'è una release stabile.
+pub fn newest_matching(spec: &VersionSpec, candidates: &[String]) -> Option<String> {
+    let matching: Vec<SdkVersion> = candidates
+        .iter()
+        .filter_map(|c| c.parse::<SdkVersion>().ok())
+        .filter(|v| spec.matches(v))
+        .collect();
+    let stable = matching.iter().filter(|v| !v.is_prerelease()).max();
+    stable.or_else(|| matching.iter().max()).map(|v| v.to_string())
+}
+
+/// Restituisce fino a tre versioni note vicine a `target` (distanza di edit), le più vicine prima.
+pub fn closest_versions<'a>(target: &str, candidates: &'a [String]) -> Vec<&'a str> {
+    let max_distance = (target.len() / 3).max(2);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|c| (strsim::levenshtein(target, c), c.as_str()))
+        .filter(|(d, c)| *d > 0 && *d <= max_distance && c.split('.').next() == target.split('.').next())
+        .collect();
+    scored.sort();
+    scored.dedup();
+    scored.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
+/// Costruisce un messaggio "X not found — did you mean Y?" per una versione sconosciuta.
+pub fn not_found_message(what: &str, target: &str, candidates: &[String]) -> String {
+    let suggestions = closest_versions(target, candidates);
+    match suggestions.as_slice() {
+        [] => format!("{} {} not found", what, target),
+        [only] => format!("{} {} not found — did you mean {}?", what, target, only),
+        [rest @ .., last] => format!("{} {} not found — did you mean {} or {}?", what, target, rest.join(", "), last),
+    }
+}
@@ -0,0 +1,220 @@
+//! I file `*.runtimeconfig.json` delle applicazioni pubblicate, per `dver runtime`.
+//!
+//! Il file dice con quali framework condivisi parte l'app e con quale `rollForward`
+//! l'host sceglie tra le versioni installate. Qui si leggono i framework richiesti,
+//! si fissa l'app a un runtime e si simula la scelta dell'host.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::version::SdkVersion;
+use crate::{DverError, ResultExt};
+
+/// Un runtime condiviso installabile da solo, per le macchine che eseguono le app
+/// senza compilarle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RuntimeKind {
+    /// `Microsoft.NETCore.App`
+    Dotnet,
+    /// `Microsoft.AspNetCore.App`
+    AspNetCore,
+    /// `Microsoft.WindowsDesktop.App`
+    WindowsDesktop,
+}
+
+impl RuntimeKind {
+    /// Il runtime che fornisce il framework `name`, se è uno di quelli noti.
+    pub fn from_framework(name: &str) -> Option<RuntimeKind> {
+        [RuntimeKind::Dotnet, RuntimeKind::AspNetCore, RuntimeKind::WindowsDesktop]
+            .into_iter()
+            .find(|kind| kind.framework_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Il nome del framework, come in `shared/<nome>`.
+    pub fn framework_name(self) -> &'static str {
+        match self {
+            RuntimeKind::Dotnet => "Microsoft.NETCore.App",
+            RuntimeKind::AspNetCore => "Microsoft.AspNetCore.App",
+            RuntimeKind::WindowsDesktop => "Microsoft.WindowsDesktop.App",
+        }
+    }
+
+    /// Il valore di `-Runtime` per lo script di installazione.
+    pub fn as_script_arg(self) -> &'static str {
+        match self {
+            RuntimeKind::Dotnet => "dotnet",
+            RuntimeKind::AspNetCore => "aspnetcore",
+            RuntimeKind::WindowsDesktop => "windowsdesktop",
+        }
+    }
+}
+
+/// Come l'host sceglie il runtime tra quelli installati; vedi `rollForward` nella
+/// documentazione di .NET.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum RollForward {
+    /// Solo la versione esatta
+    Disable,
+    /// L'ultima patch della stessa minor
+    LatestPatch,
+    /// La minor richiesta o la più bassa superiore (predefinito di .NET)
+    #[default]
+    Minor,
+    /// L'ultima minor della stessa major
+    LatestMinor,
+    /// Come `Minor`, poi la major più bassa superiore
+    Major,
+    /// L'ultima versione installata
+    LatestMajor,
+}
+
+impl RollForward {
+    /// Il valore scritto nel runtimeconfig, es. `LatestPatch`.
+    pub fn as_config_value(self) -> &'static str {
+        match self {
+            RollForward::Disable => "Disable",
+            RollForward::LatestPatch => "LatestPatch",
+            RollForward::Minor => "Minor",
+            RollForward::LatestMinor => "LatestMinor",
+            RollForward::Major => "Major",
+            RollForward::LatestMajor => "LatestMajor",
+        }
+    }
+
+    /// Legge un valore del runtimeconfig; l'host non distingue le maiuscole.
+    pub fn from_config_value(value: &str) -> Option<RollForward> {
+        [
+            RollForward::Disable,
+            RollForward::LatestPatch,
+            RollForward::Minor,
+            RollForward::LatestMinor,
+            RollForward::Major,
+            RollForward::LatestMajor,
+        ]
+        .into_iter()
+        .find(|policy| policy.as_config_value().eq_ignore_ascii_case(value))
+    }
+}
+
+/// Un framework richiesto dall'app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FrameworkReference {
+    pub name: String,
+    pub version: String,
+}
+
+/// Quello che conta di un runtimeconfig per scegliere i runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuntimeConfig {
+    pub path: PathBuf,
+    pub frameworks: Vec<FrameworkReference>,
+    /// `rollForward` scritto nel file; senza, l'host usa [`RollForward::Minor`]
+    pub roll_forward: Option<RollForward>,
+}
+
+impl RuntimeConfig {
+    /// La politica con cui l'host sceglierà il runtime.
+    pub fn effective_roll_forward(&self) -> RollForward {
+        self.roll_forward.unwrap_or_default()
+    }
+}
+
+/// I `*.runtimeconfig.json` nella cartella `dir`, esclusi i `.dev.json` dei build
+/// locali, in ordine di nome.
+pub fn find_runtime_configs(dir: &Path) -> Result<Vec<PathBuf>, DverError> {
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file() && p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.ends_with(".runtimeconfig.json"))
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Legge `runtimeOptions` dal contenuto di un runtimeconfig: `framework` (un solo
+/// framework) o `frameworks`.
+pub fn parse_runtime_config(path: &Path, content: &str) -> Result<RuntimeConfig, DverError> {
+    let value: Value = serde_json::from_str(content).with_context(|| format!("Invalid runtimeconfig {}", path.display()))?;
+    let options = &value["runtimeOptions"];
+    let frameworks = options["framework"]
+        .as_object()
+        .map(|f| vec![Value::Object(f.clone())])
+        .or_else(|| options["frameworks"].as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|f| {
+            Some(FrameworkReference { name: f["name"].as_str()?.to_string(), version: f["version"].as_str()?.to_string() })
+        })
+        .collect();
+    let roll_forward = options["rollForward"].as_str().and_then(RollForward::from_config_value);
+    Ok(RuntimeConfig { path: path.to_path_buf(), frameworks, roll_forward })
+}
+
+/// Legge il runtimeconfig in `path`.
+pub fn read_runtime_config(path: &Path) -> Result<RuntimeConfig, DverError> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_runtime_config(path, &content)
+}
+
+/// Il contenuto di un runtimeconfig con tutti i framework alla versione `version` e
+/// `rollForward` a `roll_forward`; il resto del file resta com'è.
+pub fn pin_runtime_config(content: &str, version: &str, roll_forward: RollForward) -> Result<String, DverError> {
+    let mut value: Value = serde_json::from_str(content).context("Invalid runtimeconfig")?;
+    let Some(options) = value.get_mut("runtimeOptions").and_then(Value::as_object_mut) else {
+        return Err(DverError::Parse("The runtimeconfig has no runtimeOptions".to_string()));
+    };
+    if let Some(framework) = options.get_mut("framework").and_then(Value::as_object_mut) {
+        framework.insert("version".to_string(), json!(version));
+    }
+    if let Some(frameworks) = options.get_mut("frameworks").and_then(Value::as_array_mut) {
+        for framework in frameworks.iter_mut().filter_map(Value::as_object_mut) {
+            framework.insert("version".to_string(), json!(version));
+        }
+    }
+    options.insert("rollForward".to_string(), json!(roll_forward.as_config_value()));
+    let mut pinned = serde_json::to_string_pretty(&value)?;
+    pinned.push('\n');
+    Ok(pinned)
+}
+
+/// La versione tra `installed` che l'host sceglierebbe per `wanted` con
+/// `roll_forward`; sempre l'ultima patch, tranne con [`RollForward::Disable`]. Le
+/// anteprime contano solo se ne è richiesta una.
+pub fn resolve_framework(installed: &[String], wanted: &str, roll_forward: RollForward) -> Option<String> {
+    let want: SdkVersion = wanted.parse().ok()?;
+    let candidates: Vec<(SdkVersion, &String)> = installed
+        .iter()
+        .filter_map(|v| Some((v.parse::<SdkVersion>().ok()?, v)))
+        .filter(|(v, _)| *v >= want && (!v.is_prerelease() || want.is_prerelease()))
+        .collect();
+    let latest_patch = |major: u32, minor: u32| {
+        candidates
+            .iter()
+            .filter(|(v, _)| v.major == major && v.minor == minor)
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, s)| s.to_string())
+    };
+    let lowest_minor = |major: u32| {
+        candidates.iter().filter(|(v, _)| v.major == major).map(|(v, _)| v.minor).min()
+    };
+    match roll_forward {
+        RollForward::Disable => candidates.iter().find(|(v, _)| *v == want).map(|(_, s)| s.to_string()),
+        RollForward::LatestPatch => latest_patch(want.major, want.minor),
+        RollForward::Minor => latest_patch(want.major, lowest_minor(want.major)?),
+        RollForward::LatestMinor => {
+            candidates.iter().filter(|(v, _)| v.major == want.major).max_by(|(a, _), (b, _)| a.cmp(b)).map(|(_, s)| s.to_string())
+        }
+        RollForward::Major => {
+            let major = candidates.iter().map(|(v, _)| v.major).filter(|m| *m >= want.major).min()?;
+            latest_patch(major, lowest_minor(major)?)
+        }
+        RollForward::LatestMajor => candidates.iter().max_by(|(a, _), (b, _)| a.cmp(b)).map(|(_, s)| s.to_string()),
+    }
+}
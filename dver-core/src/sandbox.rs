@@ -0,0 +1,68 @@
+//! Controlli che precedono ogni cancellazione sul disco.
+//!
+//! Un percorso si può rimuovere solo se, una volta risolti `..` e link simbolici,
+//! resta dentro una delle radici configurate esplicitamente: un SDK che è un link
+//! verso un'altra cartella, o una radice riportata da `dotnet --list-sdks` ma mai
+//! configurata, non vengono toccati.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::platform::Platform;
+use crate::DverError;
+
+/// Esito del controllo su un percorso da rimuovere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Removal {
+    /// Si può rimuovere; il percorso è quello canonico
+    Allowed(PathBuf),
+    /// Dentro una radice gestita ma sotto una cartella di sistema: serve `--force`
+    /// e una conferma digitata
+    System(PathBuf),
+}
+
+impl Removal {
+    /// Percorso canonico da rimuovere.
+    pub fn path(&self) -> &Path {
+        match self {
+            Removal::Allowed(path) | Removal::System(path) => path,
+        }
+    }
+}
+
+/// Vero se `path` (già canonico) è sotto una delle cartelle di sistema di `platform`.
+pub fn is_system_path(platform: &dyn Platform, path: &Path) -> bool {
+    platform.system_dirs().iter().any(|dir| {
+        path.starts_with(dir) || fs::canonicalize(dir).is_ok_and(|canonical| path.starts_with(canonical))
+    })
+}
+
+/// Verifica che `path` si possa cancellare: deve esistere e il suo percorso canonico
+/// deve stare strettamente dentro una delle `roots` canoniche.
+pub fn check_removal(platform: &dyn Platform, path: &Path, roots: &[PathBuf]) -> Result<Removal, DverError> {
+    let refuse = |reason: &str| {
+        DverError::Permission(format!(
+            "Refusing to remove {}: {}\nhint: dver only deletes inside install_root and the directories listed in [roots]",
+            path.display(), reason))
+    };
+    if roots.is_empty() {
+        return Err(refuse("no managed roots are configured"));
+    }
+    let canonical = fs::canonicalize(path).map_err(|e| refuse(&format!("cannot resolve the path ({})", e)))?;
+    let inside = roots
+        .iter()
+        .filter_map(|root| fs::canonicalize(root).ok())
+        .any(|root| canonical != root && canonical.starts_with(&root));
+    if !inside {
+        return Err(if canonical.as_path() != path {
+            refuse(&format!("it resolves to {}, outside the managed roots", canonical.display()))
+        } else {
+            refuse("it is outside the managed roots")
+        });
+    }
+    if is_system_path(platform, &canonical) {
+        Ok(Removal::System(canonical))
+    } else {
+        Ok(Removal::Allowed(canonical))
+    }
+}
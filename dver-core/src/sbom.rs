@@ -0,0 +1,324 @@
+//! Distinta dei componenti (SBOM) della toolchain .NET installata, nei formati
+//! CycloneDX 1.5 e SPDX 2.3 in JSON.
+//!
+//! Versioni e percorsi vengono dall'inventario locale; URL e SHA-512 sono quelli
+//! dell'archivio ufficiale per il RID corrente riportati nei metadati dei rilasci.
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::inventory::{InstalledRuntime, InstalledSdk, InstalledWorkload};
+use crate::releases::{ChannelReleases, FileInfo, Release};
+use crate::version::SdkVersion;
+
+/// Formato del documento prodotto da `dver sbom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 (JSON)
+    Cyclonedx,
+    /// SPDX 2.3 (JSON)
+    Spdx,
+}
+
+/// Tipo di componente della toolchain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentType {
+    Sdk,
+    Runtime,
+    Workload,
+}
+
+/// Un elemento della distinta.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Component {
+    pub kind: ComponentType,
+    /// `dotnet-sdk`, il nome del framework o l'id del workload
+    pub name: String,
+    pub version: String,
+    /// Cartella sul disco, se nota
+    pub path: Option<PathBuf>,
+    /// URL dell'archivio ufficiale per il RID corrente
+    pub download_url: Option<String>,
+    /// SHA-512 (esadecimale) dell'archivio ufficiale
+    pub sha512: Option<String>,
+    /// Radice in `[roots]` per gli SDK, sorgente di installazione per i workload
+    pub source: Option<String>,
+}
+
+const SUPPLIER: &str = "Microsoft Corporation";
+
+/// Archivio (`.tar.gz` o `.zip`) per `rid` tra i file di un rilascio.
+fn archive<'a>(files: &'a [FileInfo], rid: &str) -> Option<&'a FileInfo> {
+    files
+        .iter()
+        .find(|f| f.rid.as_deref() == Some(rid) && (f.name.ends_with(".tar.gz") || f.name.ends_with(".zip")))
+}
+
+/// Primo archivio trovato nei rilasci di `channels` con `files_of`.
+fn find_archive<'a>(
+    channels: &'a [ChannelReleases],
+    rid: &str,
+    files_of: impl Fn(&'a Release) -> Vec<&'a [FileInfo]>,
+) -> Option<&'a FileInfo> {
+    channels
+        .iter()
+        .flat_map(|channel| &channel.releases)
+        .find_map(|release| files_of(release).into_iter().find_map(|files| archive(files, rid)))
+}
+
+/// Archivio dell'SDK `version` per `rid`; usato anche da `install_method = "archive"`.
+pub(crate) fn sdk_archive<'a>(channels: &'a [ChannelReleases], rid: &str, version: &SdkVersion) -> Option<&'a FileInfo> {
+    find_archive(channels, rid, |release| {
+        release
+            .sdks
+            .iter()
+            .chain(&release.sdk)
+            .filter(|sdk| sdk.version.as_ref() == Some(version))
+            .map(|sdk| sdk.files.as_slice())
+            .collect()
+    })
+}
+
+fn runtime_archive<'a>(channels: &'a [ChannelReleases], rid: &str, name: &str, version: &SdkVersion) -> Option<&'a FileInfo> {
+    let matches = |v: &Option<SdkVersion>| v.as_ref() == Some(version);
+    find_archive(channels, rid, |release| {
+        let files = match name {
+            "Microsoft.NETCore.App" => release.runtime.as_ref().filter(|r| matches(&r.version)).map(|r| &r.files),
+            "Microsoft.AspNetCore.App" => {
+                release.aspnetcore_runtime.as_ref().filter(|r| matches(&r.version)).map(|r| &r.files)
+            }
+            "Microsoft.WindowsDesktop.App" => {
+                release.windowsdesktop.as_ref().filter(|r| matches(&r.version)).map(|r| &r.files)
+            }
+            _ => None,
+        };
+        files.map(|f| f.as_slice()).into_iter().collect()
+    })
+}
+
+/// Componenti della distinta: gli SDK, i runtime e i workload installati, con URL e
+/// hash dell'archivio ufficiale per `rid` quando compaiono in `channels`.
+pub fn components(
+    sdks: &[InstalledSdk],
+    runtimes: &[InstalledRuntime],
+    workloads: &[InstalledWorkload],
+    channels: &[ChannelReleases],
+    rid: &str,
+) -> Vec<Component> {
+    let with_archive = |mut component: Component, file: Option<&FileInfo>| {
+        if let Some(file) = file {
+            component.download_url = Some(file.url.clone());
+            component.sha512 = file.hash.clone().filter(|h| !h.is_empty());
+        }
+        component
+    };
+
+    let mut components = Vec::new();
+    for sdk in sdks {
+        let file = sdk.version.parse().ok().and_then(|v| sdk_archive(channels, rid, &v));
+        components.push(with_archive(
+            Component {
+                kind: ComponentType::Sdk,
+                name: "dotnet-sdk".to_string(),
+                version: sdk.version.clone(),
+                path: Some(sdk.path.clone()),
+                download_url: None,
+                sha512: None,
+                source: sdk.root.clone(),
+            },
+            file,
+        ));
+    }
+    for runtime in runtimes {
+        let file = runtime.version.parse().ok().and_then(|v| runtime_archive(channels, rid, &runtime.name, &v));
+        components.push(with_archive(
+            Component {
+                kind: ComponentType::Runtime,
+                name: runtime.name.clone(),
+                version: runtime.version.clone(),
+                path: Some(runtime.path.clone()),
+                download_url: None,
+                sha512: None,
+                source: None,
+            },
+            file,
+        ));
+    }
+    for workload in workloads {
+        // La versione del manifest è `<workload>/<feature band>`
+        let version = workload.manifest_version.as_deref().unwrap_or("").split('/').next().unwrap_or("");
+        components.push(Component {
+            kind: ComponentType::Workload,
+            name: workload.id.clone(),
+            version: version.to_string(),
+            path: None,
+            download_url: None,
+            sha512: None,
+            source: workload.source.clone(),
+        });
+    }
+    components
+}
+
+/// Una distinta pronta da serializzare.
+#[derive(Debug, Clone)]
+pub struct Sbom {
+    pub components: Vec<Component>,
+    /// Momento della generazione
+    pub created: SystemTime,
+    /// Versione di dver, riportata come strumento che ha prodotto il documento
+    pub tool_version: String,
+}
+
+impl Sbom {
+    /// Documento nel formato richiesto.
+    pub fn render(&self, format: SbomFormat) -> Value {
+        match format {
+            SbomFormat::Cyclonedx => self.cyclonedx(),
+            SbomFormat::Spdx => self.spdx(),
+        }
+    }
+
+    fn timestamp(&self) -> String {
+        DateTime::<Utc>::from(self.created).to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Documento CycloneDX 1.5.
+    pub fn cyclonedx(&self) -> Value {
+        let components: Vec<Value> = self
+            .components
+            .iter()
+            .map(|c| {
+                let mut component = json!({
+                    "type": match c.kind {
+                        ComponentType::Sdk => "application",
+                        ComponentType::Runtime => "framework",
+                        ComponentType::Workload => "library",
+                    },
+                    "bom-ref": reference(c),
+                    "supplier": { "name": SUPPLIER },
+                    "name": c.name,
+                    "version": c.version,
+                    "purl": purl(c),
+                });
+                if let Some(hash) = &c.sha512 {
+                    component["hashes"] = json!([{ "alg": "SHA-512", "content": hash }]);
+                }
+                if let Some(url) = &c.download_url {
+                    component["externalReferences"] = json!([{ "type": "distribution", "url": url }]);
+                }
+                let properties = properties(c);
+                if !properties.is_empty() {
+                    component["properties"] = Value::Array(
+                        properties.into_iter().map(|(name, value)| json!({ "name": name, "value": value })).collect(),
+                    );
+                }
+                component
+            })
+            .collect();
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "metadata": {
+                "timestamp": self.timestamp(),
+                "tools": { "components": [{ "type": "application", "name": "dver", "version": self.tool_version }] },
+            },
+            "components": components,
+        })
+    }
+
+    /// Documento SPDX 2.3.
+    pub fn spdx(&self) -> Value {
+        let packages: Vec<Value> = self
+            .components
+            .iter()
+            .map(|c| {
+                let mut package = json!({
+                    "SPDXID": format!("SPDXRef-{}", reference(c)),
+                    "name": c.name,
+                    "versionInfo": c.version,
+                    "supplier": format!("Organization: {}", SUPPLIER),
+                    "downloadLocation": c.download_url.as_deref().unwrap_or("NOASSERTION"),
+                    "filesAnalyzed": false,
+                    "licenseConcluded": "NOASSERTION",
+                    "licenseDeclared": "NOASSERTION",
+                    "copyrightText": "NOASSERTION",
+                    "externalRefs": [{
+                        "referenceCategory": "PACKAGE-MANAGER",
+                        "referenceType": "purl",
+                        "referenceLocator": purl(c),
+                    }],
+                });
+                if let Some(hash) = &c.sha512 {
+                    package["checksums"] = json!([{ "algorithm": "SHA512", "checksumValue": hash }]);
+                }
+                let properties = properties(c);
+                if !properties.is_empty() {
+                    let comment: Vec<String> = properties.into_iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+                    package["comment"] = json!(comment.join("\n"));
+                }
+                package
+            })
+            .collect();
+        let relationships: Vec<Value> = self
+            .components
+            .iter()
+            .map(|c| json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": format!("SPDXRef-{}", reference(c)),
+            }))
+            .collect();
+        let seconds = self.created.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "dotnet-toolchain",
+            "documentNamespace": format!("https://github.com/stescobedo92/dotnet-version-manager/sbom/{}", seconds),
+            "creationInfo": {
+                "created": self.timestamp(),
+                "creators": [format!("Tool: dver-{}", self.tool_version)],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        })
+    }
+}
+
+/// Identificativo del componente nel documento: solo lettere, cifre, `.` e `-`
+/// come richiesto da SPDX.
+fn reference(c: &Component) -> String {
+    let kind = match c.kind {
+        ComponentType::Sdk => "sdk",
+        ComponentType::Runtime => "runtime",
+        ComponentType::Workload => "workload",
+    };
+    format!("{}-{}-{}", kind, c.name, c.version)
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' { ch } else { '-' })
+        .collect()
+}
+
+fn purl(c: &Component) -> String {
+    format!("pkg:generic/microsoft/{}@{}", c.name, c.version)
+}
+
+/// Dati locali senza un campo standard: percorso e sorgente di installazione.
+fn properties(c: &Component) -> Vec<(&'static str, String)> {
+    let mut properties = Vec::new();
+    if let Some(path) = &c.path {
+        properties.push(("dver:path", path.display().to_string()));
+    }
+    if let Some(source) = &c.source {
+        properties.push(("dver:source", source.clone()));
+    }
+    properties
+}
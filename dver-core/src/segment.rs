@@ -0,0 +1,112 @@
+//! Dati per i segmenti del prompt (starship, oh-my-posh), per `dver prompt`.
+//!
+//! Il contratto è stabile: i campi di [`PromptSegment`] e le variabili di
+//! [`PromptSegment::render_env`] cambiano solo aumentando [`SEGMENT_SCHEMA`]. Il
+//! calcolo legge solo il disco e la cache dei metadati, senza avviare dotnet né usare
+//! la rete, perché il prompt lo ripete a ogni comando.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::audit::is_end_of_support;
+use crate::inventory::{active_dotnet_root, resolve_in_sdk_paths, resolve_sdk, scan_root_sdks};
+use crate::metadata::cached_release_index;
+use crate::project::find_global_json;
+use crate::version::SdkVersion;
+use crate::{Config, DverError};
+
+/// Versione del contratto di `dver prompt`.
+pub const SEGMENT_SCHEMA: u32 = 1;
+
+/// Cosa decide l'SDK della cartella.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SegmentSource {
+    /// `sdk.version` del global.json più vicino
+    GlobalJson,
+    /// Nessun pin: l'SDK installato più recente
+    Latest,
+}
+
+impl SegmentSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SegmentSource::GlobalJson => "global-json",
+            SegmentSource::Latest => "latest",
+        }
+    }
+}
+
+/// SDK in uso nella cartella, come lo mostra un prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptSegment {
+    pub schema: u32,
+    pub version: String,
+    /// Canale, es. `8.0`
+    pub channel: String,
+    pub source: SegmentSource,
+    /// Il global.json, per `source = "global-json"`
+    pub source_path: Option<PathBuf>,
+    pub is_preview: bool,
+    /// Canale fuori supporto; `None` se la cache dei metadati non lo conosce
+    pub is_eol: Option<bool>,
+}
+
+impl PromptSegment {
+    /// Segmento per la cartella `dir` alla data di oggi; vedi [`PromptSegment::collect`].
+    pub fn current(config: &Config, dir: &Path) -> Result<Option<PromptSegment>, DverError> {
+        let today = DateTime::<Utc>::from(SystemTime::now()).date_naive();
+        PromptSegment::collect(config, dir, today)
+    }
+
+    /// Segmento per la cartella `dir` alla data `today`; `None` se nessun SDK è installato.
+    pub fn collect(config: &Config, dir: &Path, today: NaiveDate) -> Result<Option<PromptSegment>, DverError> {
+        let Some(root) = active_dotnet_root(config) else {
+            return Ok(None);
+        };
+        let pin = find_global_json(dir)?.filter(|g| g.version.is_some());
+        let version = match pin.as_ref().filter(|p| !p.paths.is_empty()) {
+            Some(pin) => resolve_in_sdk_paths(pin, Some(&root))?.map(|(version, _)| version),
+            None => {
+                let installed: Vec<String> = scan_root_sdks(&root)?.into_iter().map(|sdk| sdk.version).collect();
+                resolve_sdk(&installed, pin.as_ref())
+            }
+        };
+        let Some(version) = version else {
+            return Ok(None);
+        };
+        let Ok(parsed) = version.parse::<SdkVersion>() else {
+            return Ok(None);
+        };
+        let channel = format!("{}.{}", parsed.major, parsed.minor);
+        let is_eol = cached_release_index(config).and_then(|index| is_end_of_support(&index, &channel, today));
+        Ok(Some(PromptSegment {
+            schema: SEGMENT_SCHEMA,
+            is_preview: parsed.is_prerelease(),
+            version,
+            channel,
+            source: if pin.is_some() { SegmentSource::GlobalJson } else { SegmentSource::Latest },
+            source_path: pin.map(|g| g.path),
+            is_eol,
+        }))
+    }
+
+    /// Righe `NOME=valore` con prefisso `DVER_PROMPT_`; i booleani sono `1` o `0`,
+    /// vuoti se non noti.
+    pub fn render_env(&self) -> String {
+        let flag = |b: bool| if b { "1" } else { "0" };
+        let lines = [
+            ("SCHEMA", self.schema.to_string()),
+            ("VERSION", self.version.clone()),
+            ("CHANNEL", self.channel.clone()),
+            ("SOURCE", self.source.as_str().to_string()),
+            ("SOURCE_PATH", self.source_path.as_ref().map(|p| p.display().to_string()).unwrap_or_default()),
+            ("PREVIEW", flag(self.is_preview).to_string()),
+            ("EOL", self.is_eol.map(flag).unwrap_or_default().to_string()),
+        ];
+        lines.iter().map(|(name, value)| format!("DVER_PROMPT_{}={}\n", name, value)).collect()
+    }
+}
@@ -0,0 +1,274 @@
+//! Quello che `dver setup` aggiunge alla macchina oltre alla configurazione (lo shim
+//! di `dotnet` e il blocco che attiva dver nel file di avvio della shell) e l'elenco di
+//! tutto ciò che dver ha creato, che `dver self-uninstall` rimuove.
+//!
+//! Il blocco sta tra due righe di commento fisse, così rilanciare il setup lo
+//! sostituisce invece di duplicarlo e si può togliere senza toccare il resto del file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::environment::{posix_quote, powershell_quote, DotnetEnvironment, ShellKind};
+use crate::provenance::{self, same_root};
+use crate::sandbox::is_system_path;
+use crate::{adopt, platform, Config, DverError, ResultExt};
+
+/// Prima riga del blocco di dver nei file di avvio della shell.
+pub const HOOK_START: &str = "# >>> dver >>>";
+/// Ultima riga del blocco di dver.
+pub const HOOK_END: &str = "# <<< dver <<<";
+
+/// Cartella degli shim, `shims` nella cartella dello stato.
+pub fn shims_dir(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("shims"))
+}
+
+/// Scrive in `dir` lo shim di `dotnet` che passa da `dver`, l'eseguibile indicato.
+pub fn write_shim(dir: &Path, dver: &Path) -> Result<PathBuf, DverError> {
+    let platform = platform::current();
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let shim = dir.join(platform.shim_name());
+    fs::write(&shim, platform.shim_script(dver)).with_context(|| format!("Failed to write {}", shim.display()))?;
+    platform.make_executable(&shim).with_context(|| format!("Failed to make {} executable", shim.display()))?;
+    Ok(shim)
+}
+
+/// File di avvio di `shell` nella home `home`; `None` per cmd, che non ne ha uno.
+pub fn rc_file(shell: ShellKind, home: &Path) -> Option<PathBuf> {
+    let xdg_config = || {
+        std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()).map(PathBuf::from).unwrap_or_else(|| home.join(".config"))
+    };
+    match shell {
+        ShellKind::Bash => Some(home.join(".bashrc")),
+        ShellKind::Zsh => {
+            let dir = std::env::var_os("ZDOTDIR").filter(|v| !v.is_empty()).map(PathBuf::from);
+            Some(dir.unwrap_or_else(|| home.to_path_buf()).join(".zshrc"))
+        }
+        ShellKind::Fish => Some(xdg_config().join("fish").join("config.fish")),
+        ShellKind::Powershell if platform::current().uses_xdg_dirs() => {
+            Some(xdg_config().join("powershell").join("Microsoft.PowerShell_profile.ps1"))
+        }
+        ShellKind::Powershell => Some(home.join("Documents").join("PowerShell").join("Microsoft.PowerShell_profile.ps1")),
+        ShellKind::Cmd => None,
+    }
+}
+
+/// Il blocco per `shell`: mette gli shim in `PATH` e valuta `dver env` a ogni avvio,
+/// che poi antepone la radice gestita.
+pub fn hook_block(shell: ShellKind, dver: &Path, shims: &Path) -> String {
+    let shims = DotnetEnvironment { path_prepend: Some(shims.to_path_buf()), ..Default::default() }.render(shell);
+    let dver = dver.display().to_string();
+    let activate = match shell {
+        ShellKind::Bash => format!("eval \"$({} env --shell bash)\"\n", posix_quote(&dver)),
+        ShellKind::Zsh => format!("eval \"$({} env --shell zsh)\"\n", posix_quote(&dver)),
+        ShellKind::Fish => format!("{} env --shell fish | source\n", posix_quote(&dver)),
+        ShellKind::Powershell => {
+            format!("& {} env --shell powershell | Out-String | Invoke-Expression\n", powershell_quote(&dver))
+        }
+        ShellKind::Cmd => String::new(),
+    };
+    format!("{}\n{}{}{}\n", HOOK_START, shims, activate, HOOK_END)
+}
+
+/// `content` con il blocco di dver sostituito da `block`, o con `block` in fondo se
+/// non c'era.
+pub fn upsert_hook(content: &str, block: &str) -> String {
+    if let Some((before, after)) = split_hook(content) {
+        return format!("{}{}{}", before, block, after);
+    }
+    let mut updated = content.to_string();
+    if !updated.is_empty() {
+        if !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push('\n');
+    }
+    updated.push_str(block);
+    updated
+}
+
+/// `content` senza il blocco di dver e senza la riga vuota che `upsert_hook` gli
+/// mette davanti; `None` se il blocco non c'è.
+pub fn remove_hook(content: &str) -> Option<String> {
+    let (before, after) = split_hook(content)?;
+    let before = before.strip_suffix("\n\n").map(|b| format!("{}\n", b)).unwrap_or_else(|| before.to_string());
+    Some(format!("{}{}", before, after))
+}
+
+/// Il testo prima e dopo il blocco di dver, se `content` ne contiene uno completo.
+fn split_hook(content: &str) -> Option<(&str, &str)> {
+    let start = content.find(HOOK_START)?;
+    let end = start + content[start..].find(HOOK_END)? + HOOK_END.len();
+    let end = if content[end..].starts_with('\n') { end + 1 } else { end };
+    Some((&content[..start], &content[end..]))
+}
+
+/// Scrive o aggiorna il blocco di dver nel file `rc`, creandolo se manca.
+pub fn install_hook(rc: &Path, block: &str) -> Result<(), DverError> {
+    let content = match fs::read_to_string(rc) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", rc.display())),
+    };
+    if let Some(parent) = rc.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(rc, upsert_hook(&content, block)).with_context(|| format!("Failed to write {}", rc.display()))
+}
+
+/// Toglie il blocco di dver dal file `rc`; falso se il file non lo contiene.
+pub fn uninstall_hook(rc: &Path) -> Result<bool, DverError> {
+    let Ok(content) = fs::read_to_string(rc) else {
+        return Ok(false);
+    };
+    let Some(cleaned) = remove_hook(&content) else {
+        return Ok(false);
+    };
+    fs::write(rc, cleaned).with_context(|| format!("Failed to write {}", rc.display()))?;
+    Ok(true)
+}
+
+// --- Disinstallazione ---
+
+/// Una cosa creata da dver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FootprintKind {
+    /// Una radice gestita che contiene solo ciò che dver vi ha installato
+    InstallRoot,
+    /// La cartella di un SDK installato da dver in una radice che contiene anche altro
+    Sdk,
+    /// La cartella degli shim
+    Shims,
+    /// Il blocco di dver in un file di avvio della shell
+    ShellHook,
+    /// La cache di dver
+    Cache,
+    /// La cartella dello stato: provenienza, manifest, log
+    State,
+    /// Il file di configurazione
+    Config,
+}
+
+impl std::fmt::Display for FootprintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FootprintKind::InstallRoot => "install root",
+            FootprintKind::Sdk => "sdk",
+            FootprintKind::Shims => "shims",
+            FootprintKind::ShellHook => "shell hook",
+            FootprintKind::Cache => "cache",
+            FootprintKind::State => "state",
+            FootprintKind::Config => "config",
+        })
+    }
+}
+
+/// Un percorso da rimuovere o da ripulire.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FootprintItem {
+    pub kind: FootprintKind,
+    pub path: PathBuf,
+}
+
+/// Voci di una radice .NET che le installazioni creano accanto a `sdk`.
+const ROOT_LAYOUT: &[&str] = &[
+    "dotnet", "dotnet.exe", "host", "shared", "packs", "sdk", "sdk-manifests", "templates", "metadata", "library-packs",
+    "LICENSE.txt", "ThirdPartyNotices.txt",
+];
+
+/// Tutto ciò che dver ha creato e che esiste ancora, nell'ordine in cui va rimosso.
+/// Degli SDK si toccano solo quelli con un record di provenienza: una radice gestita
+/// va via per intero solo se, tolti quelli, non resta altro che la struttura di .NET,
+/// e mai se contiene SDK adottati. Con `keep_sdks` gli SDK restano; le radici sotto
+/// le cartelle di sistema o che coincidono con la home restano sempre.
+pub fn footprint(config: &Config, home: &Path, keep_sdks: bool) -> Vec<FootprintItem> {
+    let mut items: Vec<FootprintItem> = if keep_sdks { Vec::new() } else { sdk_footprint(config, home) };
+    let mut push = |kind, path: Option<PathBuf>| {
+        if let Some(path) = path.filter(|p| p.exists()) {
+            items.push(FootprintItem { kind, path });
+        }
+    };
+    push(FootprintKind::Shims, shims_dir(config));
+    let shells = [ShellKind::Bash, ShellKind::Zsh, ShellKind::Fish, ShellKind::Powershell];
+    for rc in shells.into_iter().filter_map(|shell| rc_file(shell, home)) {
+        let hooked = fs::read_to_string(&rc).is_ok_and(|content| split_hook(&content).is_some());
+        if hooked {
+            push(FootprintKind::ShellHook, Some(rc));
+        }
+    }
+    push(FootprintKind::Cache, config.cache_dir());
+    push(FootprintKind::State, config.state_dir());
+    push(FootprintKind::Config, Config::path());
+    items
+}
+
+/// Le radici da togliere per intero e, nelle altre, le cartelle degli SDK installati da dver.
+fn sdk_footprint(config: &Config, home: &Path) -> Vec<FootprintItem> {
+    // Senza i record non si sa cosa ha installato dver: non si tocca nessun SDK
+    let (Ok(installed), Ok(adopted)) = (provenance::load_all(config), adopt::load_all(config)) else {
+        return Vec::new();
+    };
+    let home = fs::canonicalize(home).ok();
+    let removable = |path: &Path| {
+        fs::canonicalize(path).is_ok_and(|canonical| {
+            canonical.parent().is_some() && Some(&canonical) != home.as_ref() && !is_system_path(platform::current(), &canonical)
+        })
+    };
+    let recorded = |root: &Path, version: &str| installed.iter().any(|p| p.version == version && same_root(&p.root, root));
+
+    // `managed_roots` è ordinato: al contrario, una radice dentro un'altra (es.
+    // `<radice>/x64`) si valuta prima di quella che la contiene
+    let managed = config.managed_roots();
+    let mut whole: Vec<PathBuf> = Vec::new();
+    for root in managed.iter().rev().cloned() {
+        let from_adopt = adopted.iter().any(|a| same_root(&a.root, &root) || a.moved_from.as_deref().is_some_and(|m| same_root(m, &root)));
+        if from_adopt || !removable(&root) {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&root) else { continue };
+        let only_dver = entries.flatten().all(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            ROOT_LAYOUT.contains(&name.as_str()) || whole.iter().any(|r| same_root(r, &entry.path()))
+        });
+        let sdks: Vec<String> = fs::read_dir(root.join("sdk"))
+            .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+            .unwrap_or_default();
+        if only_dver && sdks.iter().all(|version| recorded(&root, version)) {
+            whole.push(root);
+        }
+    }
+    whole.reverse();
+    let whole: Vec<PathBuf> = whole.iter().filter(|root| !whole.iter().any(|r| r != *root && root.starts_with(r))).cloned().collect();
+
+    let mut items: Vec<FootprintItem> = Vec::new();
+    for record in &installed {
+        let path = record.root.join("sdk").join(&record.version);
+        let in_managed = managed.iter().any(|r| same_root(r, &record.root));
+        if in_managed && path.is_dir() && removable(&path) && !whole.iter().any(|r| path.starts_with(r)) {
+            items.push(FootprintItem { kind: FootprintKind::Sdk, path });
+        }
+    }
+    items.extend(whole.into_iter().map(|path| FootprintItem { kind: FootprintKind::InstallRoot, path }));
+    items
+}
+
+/// Rimuove `item`: il blocco dal file di avvio, il file di configurazione con la sua
+/// cartella se resta vuota, le altre cartelle per intero.
+pub fn remove_footprint(item: &FootprintItem) -> Result<(), DverError> {
+    let path = &item.path;
+    match item.kind {
+        FootprintKind::ShellHook => uninstall_hook(path).map(|_| ()),
+        FootprintKind::Config => {
+            fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+            if let Some(dir) = path.parent() {
+                // Fallisce se la cartella contiene altro, e allora resta
+                let _ = fs::remove_dir(dir);
+            }
+            Ok(())
+        }
+        _ => fs::remove_dir_all(path).with_context(|| format!("Failed to remove {}", path.display())),
+    }
+}
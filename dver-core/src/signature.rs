@@ -0,0 +1,96 @@
+//! Verifica delle firme di ciò che dver scarica ed esegue: Authenticode per gli
+//! script e gli eseguibili Windows, firme PGP staccate per lo script `dotnet-install.sh`.
+//!
+//! Quanto essere severi lo decide la [`SignaturePolicy`] della configurazione.
+
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform;
+use crate::process::ProcessRunner;
+use crate::DverError;
+
+/// Cosa fare quando una firma manca o non è valida.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignaturePolicy {
+    /// Interrompe l'operazione
+    Require,
+    /// Stampa un avviso e prosegue
+    Warn,
+    /// Non verifica nulla
+    #[default]
+    Off,
+}
+
+/// Esito di una verifica.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// Firma valida; la stringa descrive il firmatario
+    Verified(String),
+    /// Non c'è materiale con cui verificare (firma non pubblicata, strumento mancante)
+    Unavailable(String),
+    /// La firma esiste ma non è valida o non è di Microsoft
+    Invalid(String),
+}
+
+/// Soggetto che deve comparire nel certificato Authenticode degli artefatti ufficiali.
+const MICROSOFT_SUBJECT: &str = "O=Microsoft Corporation";
+
+/// Controlla la firma Authenticode di `file` con `Get-AuthenticodeSignature`.
+/// Sulle piattaforme senza Authenticode la verifica non è disponibile.
+pub fn verify_authenticode(runner: &dyn ProcessRunner, file: &Path) -> Verification {
+    let Some(mut command) = platform::current().authenticode_command(file) else {
+        return Verification::Unavailable(format!("Authenticode signatures can only be checked on Windows ({})", file.display()));
+    };
+    let output = match runner.output(&mut command) {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => return Verification::Unavailable(format!(
+            "Get-AuthenticodeSignature failed for {}: {}", file.display(), String::from_utf8_lossy(&output.stderr).trim())),
+        Err(e) => return Verification::Unavailable(format!("Cannot run PowerShell to check {}: {}", file.display(), e)),
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (status, subject) = stdout.trim().split_once('|').unwrap_or((stdout.trim(), ""));
+    match status {
+        "Valid" if subject.contains(MICROSOFT_SUBJECT) => Verification::Verified(subject.to_string()),
+        "Valid" => Verification::Invalid(format!("{} is signed by {:?}, not by Microsoft", file.display(), subject)),
+        "NotSigned" => Verification::Unavailable(format!("{} is not signed", file.display())),
+        other => Verification::Invalid(format!("Authenticode signature of {} is {}", file.display(), other)),
+    }
+}
+
+/// Controlla la firma PGP staccata `signature` di `data` con `gpgv` e le chiavi di `keyring`.
+pub fn verify_pgp(runner: &dyn ProcessRunner, keyring: &Path, data: &Path, signature: &Path) -> Verification {
+    let mut command = Command::new("gpgv");
+    command.arg("--keyring").arg(keyring).arg(signature).arg(data);
+    match runner.output(&mut command) {
+        Ok(output) if output.status.success() => Verification::Verified(format!("a key in {}", keyring.display())),
+        Ok(output) => Verification::Invalid(format!(
+            "PGP signature of {} does not verify: {}", data.display(), String::from_utf8_lossy(&output.stderr).trim())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Verification::Unavailable("gpgv is not installed; install GnuPG to check PGP signatures".to_string())
+        }
+        Err(e) => Verification::Unavailable(format!("Cannot run gpgv: {}", e)),
+    }
+}
+
+/// Applica `policy` all'esito della verifica di `subject`: con `require` ogni esito
+/// diverso da [`Verification::Verified`] è un errore, con `warn` un avviso su stderr.
+pub fn enforce(policy: SignaturePolicy, subject: &str, verification: Verification) -> Result<(), DverError> {
+    let problem = match verification {
+        Verification::Verified(_) => return Ok(()),
+        Verification::Unavailable(reason) => format!("Cannot verify the signature of {}: {}", subject, reason),
+        Verification::Invalid(reason) => format!("Invalid signature on {}: {}", subject, reason),
+    };
+    match policy {
+        SignaturePolicy::Require => Err(DverError::Signature(format!(
+            "{}\nhint: set signature_policy to `warn` or `off` to continue without a verified signature", problem))),
+        SignaturePolicy::Warn => {
+            eprintln!("Warning: {}", problem);
+            Ok(())
+        }
+        SignaturePolicy::Off => Ok(()),
+    }
+}
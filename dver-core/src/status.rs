@@ -0,0 +1,121 @@
+//! Il riepilogo di `dver status`: radice in uso, SDK attivo e pin, quanto è installato,
+//! aggiornamenti noti, stato di shim e blocco della shell.
+//!
+//! Legge solo il disco e la cache dei metadati, senza avviare dotnet né usare la rete:
+//! è il comando da lanciare per primo quando qualcosa non torna.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::disk;
+use crate::environment::{path_entries, path_position, ShellKind};
+use crate::inventory::{active_dotnet_root, scan_root_runtimes, scan_root_sdks};
+use crate::paths;
+use crate::platform;
+use crate::project::{pinned_sdk, PinSource, Project};
+use crate::segment::PromptSegment;
+use crate::setup::{rc_file, shims_dir, HOOK_START};
+use crate::updates::{cached_updates, SdkUpdate, UpdateStatus};
+use crate::{Config, DverError};
+
+/// Stato di un pezzo installato da `dver setup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Health {
+    Ok,
+    /// Lo shim c'è ma la sua cartella non è nel `PATH`
+    NotOnPath,
+    Missing,
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Health::Ok => "ok",
+            Health::NotOnPath => "not on PATH",
+            Health::Missing => "missing",
+        })
+    }
+}
+
+/// La versione fissata per la cartella e il file che la fissa.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusPin {
+    pub version: String,
+    pub source: PathBuf,
+}
+
+/// Tutto quello che mostra `dver status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Status {
+    /// La radice gestita, o quella trovata se nessuna è configurata
+    pub install_root: Option<PathBuf>,
+    /// L'SDK che dotnet sceglierebbe nella cartella
+    pub active_sdk: Option<String>,
+    pub pin: Option<StatusPin>,
+    pub sdks: usize,
+    pub runtimes: usize,
+    /// Spazio occupato da SDK e runtime
+    pub size_bytes: u64,
+    /// Aggiornamenti noti alla cache; `None` se la cache dei metadati è vuota
+    pub updates: Option<Vec<SdkUpdate>>,
+    pub shim: Health,
+    /// Il file di avvio della shell, se la shell ne ha uno
+    pub hook_file: Option<PathBuf>,
+    pub hook: Health,
+}
+
+impl Status {
+    /// Riepilogo per la cartella `dir` e la shell `shell`.
+    pub fn collect(config: &Config, dir: &Path, shell: ShellKind) -> Result<Status, DverError> {
+        let install_root = active_dotnet_root(config);
+        let (sdks, runtimes) = match &install_root {
+            Some(root) => (scan_root_sdks(root)?, scan_root_runtimes(root)?),
+            None => (Vec::new(), Vec::new()),
+        };
+        let dirs: Vec<PathBuf> = sdks.iter().map(|s| s.path.clone()).chain(runtimes.iter().map(|r| r.path.clone())).collect();
+        let size_bytes = disk::sizes(config, &dirs)?.into_iter().sum();
+
+        let project = Project::find(dir)?;
+        let pin = pinned_sdk(dir, project.as_ref())?.map(|(version, source)| {
+            let (PinSource::DverToml(source) | PinSource::GlobalJson(source)) = source;
+            StatusPin { version, source }
+        });
+        let mut checked: Vec<(String, String)> = sdks.iter().map(|s| (s.version.clone(), "installed".to_string())).collect();
+        if let Some(pin) = pin.as_ref().filter(|p| !checked.iter().any(|(v, _)| *v == p.version)) {
+            checked.push((pin.version.clone(), pin.source.display().to_string()));
+        }
+        let updates = cached_updates(config, &checked)
+            .map(|updates| updates.into_iter().filter(|u| u.status != UpdateStatus::Current).collect());
+
+        let hook_file = paths::home_dir().and_then(|home| rc_file(shell, &home));
+        let hooked = hook_file.as_ref().and_then(|rc| fs::read_to_string(rc).ok()).is_some_and(|c| c.contains(HOOK_START));
+        Ok(Status {
+            active_sdk: PromptSegment::current(config, dir)?.map(|segment| segment.version),
+            install_root,
+            pin,
+            sdks: sdks.len(),
+            runtimes: runtimes.len(),
+            size_bytes,
+            updates,
+            shim: shim_health(config),
+            hook_file,
+            hook: if hooked { Health::Ok } else { Health::Missing },
+        })
+    }
+}
+
+/// Lo shim di `dotnet` esiste e la sua cartella è nel `PATH`?
+fn shim_health(config: &Config) -> Health {
+    let platform = platform::current();
+    let Some(dir) = shims_dir(config).filter(|d| d.join(platform.shim_name()).is_file()) else {
+        return Health::Missing;
+    };
+    if path_position(&path_entries(), &dir).is_some() {
+        Health::Ok
+    } else {
+        Health::NotOnPath
+    }
+}
@@ -0,0 +1,140 @@
+//! Il pacchetto di supporto di `dver doctor --bundle`: uno zip con tutto quello che
+//! serve per capire un problema segnalato, senza i segreti.
+//!
+//! Dalla configurazione e dall'ambiente si tolgono i valori delle chiavi che sembrano
+//! segreti (token, password, chiavi) e, negli URL, le credenziali e la query string,
+//! dove i mirror tengono spesso i token SAS.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use toml::{Table, Value};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::{DverError, ResultExt};
+
+/// Ciò che prende il posto di un valore rimosso.
+pub const REDACTED: &str = "<redacted>";
+
+/// Parti del nome che indicano un segreto, in maiuscolo.
+const SECRET_MARKERS: &[&str] = &["TOKEN", "SECRET", "PASSWORD", "PASSWD", "APIKEY", "API_KEY", "ACCESS_KEY", "CREDENTIAL", "AUTH"];
+
+/// Variabili d'ambiente che contano per dver e dotnet, per prefisso.
+const ENV_PREFIXES: &[&str] = &["DOTNET_", "DVER_", "NUGET_", "MSBUILD", "XDG_"];
+
+/// Altre variabili d'ambiente utili, per nome esatto (senza distinguere le maiuscole).
+const ENV_NAMES: &[&str] = &[
+    "PATH", "HOME", "USERPROFILE", "SHELL", "ZDOTDIR", "TERM", "CI", "TF_BUILD", "GITHUB_ACTIONS", "GITLAB_CI",
+    "JENKINS_URL", "TEAMCITY_VERSION", "HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY", "SSL_CERT_FILE",
+    "SSL_CERT_DIR",
+];
+
+/// Un file del pacchetto.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleFile {
+    /// Percorso nello zip, con `/` come separatore
+    pub name: String,
+    pub contents: Vec<u8>,
+    /// Cosa contiene, per il riepilogo stampato
+    pub description: String,
+}
+
+impl BundleFile {
+    pub fn new(name: impl Into<String>, contents: impl Into<Vec<u8>>, description: impl Into<String>) -> BundleFile {
+        BundleFile { name: name.into(), contents: contents.into(), description: description.into() }
+    }
+}
+
+/// Vero se il nome di una chiave o di una variabile fa pensare a un segreto.
+pub fn is_secret_name(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+/// Toglie credenziali e query string da un URL; gli altri valori restano com'erano.
+pub fn scrub_value(value: &str) -> String {
+    let Some(scheme_end) = value.find("://").map(|i| i + 3) else {
+        return value.to_string();
+    };
+    let (head, rest) = value.split_at(scheme_end);
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(authority_end);
+    let authority = match authority.rfind('@') {
+        Some(at) => format!("{}@{}", REDACTED, &authority[at + 1..]),
+        None => authority.to_string(),
+    };
+    let tail = match tail.find('?') {
+        Some(query) => format!("{}?{}", &tail[..query], REDACTED),
+        None => tail.to_string(),
+    };
+    format!("{}{}{}", head, authority, tail)
+}
+
+/// Applica [`scrub_value`] a ogni URL di un testo libero, come un file JSON di stato.
+pub fn scrub_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(found) = rest.find("://") {
+        let start = rest[..found]
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')))
+            .map_or(0, |i| i + 1);
+        let end = rest[found..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`'))
+            .map_or(rest.len(), |i| found + i);
+        out.push_str(&rest[..start]);
+        out.push_str(&scrub_value(&rest[start..end]));
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn scrub_toml(key: &str, value: &mut Value) {
+    match value {
+        Value::String(s) if is_secret_name(key) => *s = REDACTED.to_string(),
+        Value::String(s) => *s = scrub_value(s),
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub_toml(key, item)),
+        Value::Table(table) => table.iter_mut().for_each(|(key, value)| scrub_toml(key, value)),
+        _ => {}
+    }
+}
+
+/// Il `config.toml` `content` senza segreti, riscritto da capo.
+pub fn scrub_config(content: &str) -> Result<String, DverError> {
+    let mut table: Table = toml::from_str(content).map_err(|e| DverError::Parse(format!("Invalid config file: {}", e)))?;
+    table.iter_mut().for_each(|(key, value)| scrub_toml(key, value));
+    toml::to_string(&table).map_err(|e| DverError::Parse(e.to_string()))
+}
+
+/// Le variabili di `vars` che contano per dver e dotnet, ordinate e senza segreti.
+pub fn scrub_environment(vars: impl IntoIterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let mut kept: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| {
+            let upper = name.to_ascii_uppercase();
+            ENV_PREFIXES.iter().any(|prefix| upper.starts_with(prefix)) || ENV_NAMES.contains(&upper.as_str())
+        })
+        .map(|(name, value)| {
+            let value = if is_secret_name(&name) { REDACTED.to_string() } else { scrub_value(&value) };
+            (name, value)
+        })
+        .collect();
+    kept.sort();
+    kept
+}
+
+/// Scrive `files` nello zip `path`, sostituendolo se esiste.
+pub fn write_bundle(path: &Path, files: &[BundleFile]) -> Result<(), DverError> {
+    let failed = || format!("Failed to write {}", path.display());
+    let file = File::create(path).with_context(failed)?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for entry in files {
+        zip.start_file(entry.name.as_str(), options).map_err(std::io::Error::other).with_context(failed)?;
+        zip.write_all(&entry.contents).with_context(failed)?;
+    }
+    zip.finish().map_err(std::io::Error::other).with_context(failed)?;
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Il profilo condiviso tra più macchine di `dver sync push|pull`: il manifest della
+//! toolchain (come `dver export`) e la parte portabile della configurazione, salvati in
+//! un repository git o in una cartella HTTP(S) che accetta `PUT` (WebDAV, un container
+//! di blob con token SAS nella query string).
+//!
+//! Le chiavi legate alla macchina (percorsi, architettura, proxy) e quelle che sembrano
+//! segreti non escono mai dalla macchina, e `pull` non le sovrascrive.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use toml::{Table, Value};
+
+use crate::process::ProcessRunner;
+use crate::support::{is_secret_name, scrub_text};
+use crate::{Config, DverError};
+
+/// Nome del manifest della toolchain nel profilo.
+pub const TOOLCHAIN_FILE: &str = "toolchain.json";
+/// Nome della configurazione portabile nel profilo.
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Chiavi della configurazione che descrivono la macchina e non si sincronizzano.
+const MACHINE_KEYS: &[&str] = &[
+    "install_root", "roots", "arch", "proxy", "ignore_system_proxy", "ca_bundle", "signing_keyring", "cache_dir", "state_dir", "nuget_packages",
+    "nuget_http_cache", "sync_remote",
+];
+
+/// Dove sta il profilo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncBackend {
+    /// Un repository git, clonato e aggiornato con il comando `git`
+    Git(String),
+    /// Una cartella HTTP(S) letta con `GET` e scritta con `PUT`
+    Http(String),
+}
+
+impl SyncBackend {
+    /// Interpreta `sync_remote`: `git+<url>`, gli URL che finiscono in `.git`, quelli
+    /// `ssh://`, `git://` e `file://` e la forma `utente@host:percorso` sono repository
+    /// git; gli altri URL `http(s)://` sono cartelle.
+    pub fn parse(remote: &str) -> Result<SyncBackend, DverError> {
+        let remote = remote.trim();
+        if let Some(url) = remote.strip_prefix("git+") {
+            return Ok(SyncBackend::Git(url.to_string()));
+        }
+        let is_http = remote.starts_with("http://") || remote.starts_with("https://");
+        let scp_like = !remote.contains("://") && remote.split_once(':').is_some_and(|(host, _)| host.contains('@'));
+        if remote.trim_end_matches('/').ends_with(".git")
+            || ["ssh://", "git://", "file://"].iter().any(|scheme| remote.starts_with(scheme))
+            || scp_like
+        {
+            Ok(SyncBackend::Git(remote.to_string()))
+        } else if is_http {
+            Ok(SyncBackend::Http(remote.to_string()))
+        } else {
+            Err(DverError::Usage(format!(
+                "Unsupported sync_remote {:?}\nhint: use a git repository (ending in .git, ssh://, or git+<url>) or an http(s):// folder",
+                remote
+            )))
+        }
+    }
+}
+
+/// URL del file `name` nella cartella `base`, lasciando in fondo l'eventuale query string.
+pub fn file_url(base: &str, name: &str) -> String {
+    let (path, query) = match base.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (base, None),
+    };
+    let url = format!("{}/{}", path.trim_end_matches('/'), name);
+    match query {
+        Some(query) => format!("{}?{}", url, query),
+        None => url,
+    }
+}
+
+fn is_local(key: &str) -> bool {
+    MACHINE_KEYS.contains(&key) || is_secret_name(key)
+}
+
+/// Divide `table` in parte portabile e parte locale; delle tabelle (`env`, `tools`)
+/// si guardano le singole voci.
+fn split(table: Table) -> (Table, Table) {
+    let (mut portable, mut local) = (Table::new(), Table::new());
+    for (key, value) in table {
+        match value {
+            _ if is_local(&key) => {
+                local.insert(key, value);
+            }
+            Value::Table(entries) => {
+                let (shared, own): (Table, Table) = entries.into_iter().partition(|(name, _)| !is_secret_name(name));
+                if !shared.is_empty() {
+                    portable.insert(key.clone(), Value::Table(shared));
+                }
+                if !own.is_empty() {
+                    local.insert(key, Value::Table(own));
+                }
+            }
+            value => {
+                portable.insert(key, value);
+            }
+        }
+    }
+    (portable, local)
+}
+
+fn parse_table(content: &str, what: &str) -> Result<Table, DverError> {
+    content.parse::<Table>().map_err(|e| DverError::Parse(format!("Invalid {}: {}", what, e)))
+}
+
+/// La parte di `config.toml` che segue l'utente su ogni macchina.
+pub fn portable_config(content: &str) -> Result<String, DverError> {
+    let (portable, _) = split(parse_table(content, "config file")?);
+    toml::to_string_pretty(&portable).map_err(|e| DverError::Parse(e.to_string()))
+}
+
+/// La configurazione locale con la parte portabile sostituita da quella di `remote`.
+pub fn merge_config(local: &str, remote: &str) -> Result<Table, DverError> {
+    let (mut merged, _) = split(parse_table(remote, "synced config")?);
+    let (_, own) = split(parse_table(local, "config file")?);
+    for (key, value) in own {
+        match (merged.get_mut(&key), value) {
+            (Some(Value::Table(shared)), Value::Table(entries)) => shared.extend(entries),
+            (_, value) => {
+                merged.insert(key, value);
+            }
+        }
+    }
+    Ok(merged)
+}
+
+fn git(runner: &dyn ProcessRunner, dir: Option<&Path>, args: &[&str]) -> Result<Output, DverError> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    command.args(args);
+    runner.output(&mut command).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => DverError::NotFound(
+            "git was not found\nhint: install git, or set sync_remote to an http(s):// folder".to_string(),
+        ),
+        _ => DverError::Process(format!("Cannot run git: {}", e)),
+    })
+}
+
+fn git_checked(runner: &dyn ProcessRunner, dir: Option<&Path>, args: &[&str]) -> Result<Output, DverError> {
+    let output = git(runner, dir, args)?;
+    if !output.status.success() {
+        // `sync_remote` può contenere credenziali, sia negli argomenti sia nei messaggi di git
+        let stderr = scrub_text(&String::from_utf8_lossy(&output.stderr)).into_bytes();
+        let command = scrub_text(&format!("git {}", args.join(" ")));
+        return Err(DverError::process(&command, &Output { stderr, ..output }, None));
+    }
+    Ok(output)
+}
+
+/// Dove `sync` clona il repository git del profilo, ogni volta da capo.
+pub fn checkout_dir(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("sync"))
+}
+
+/// Clona `remote` in `dir`, che non deve esistere; un repository vuoto va bene.
+pub fn git_clone(runner: &dyn ProcessRunner, remote: &str, dir: &Path) -> Result<(), DverError> {
+    let dir = dir.to_string_lossy();
+    git_checked(runner, None, &["clone", "--quiet", "--depth", "1", "--", remote, &dir])?;
+    Ok(())
+}
+
+/// Registra e invia i file cambiati nella copia `dir`; `false` se non è cambiato nulla.
+/// Senza un'identità configurata in git, i commit sono firmati `dver`.
+pub fn git_commit_and_push(runner: &dyn ProcessRunner, dir: &Path, message: &str) -> Result<bool, DverError> {
+    git_checked(runner, Some(dir), &["add", "--all"])?;
+    if git(runner, Some(dir), &["diff", "--cached", "--quiet"])?.status.success() {
+        return Ok(false);
+    }
+    let has_identity = git(runner, Some(dir), &["config", "user.email"])?.status.success();
+    let mut commit = if has_identity { Vec::new() } else { vec!["-c", "user.name=dver", "-c", "user.email=dver@localhost"] };
+    commit.extend(["commit", "--quiet", "-m", message]);
+    git_checked(runner, Some(dir), &commit)?;
+    git_checked(runner, Some(dir), &["push", "--quiet", "origin", "HEAD"])?;
+    Ok(true)
+}
+
+/// Legge il file `name` della copia `dir`; `None` se manca.
+pub fn read_checkout_file(dir: &Path, name: &str) -> Result<Option<String>, DverError> {
+    match fs::read_to_string(dir.join(name)) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,98 @@
+//! Le cache del motore dei template di `dotnet new` e i pacchetti di template
+//! distribuiti con ogni SDK.
+//!
+//! Ogni SDK tiene la sua cache in `<DOTNET_CLI_HOME o home>/.templateengine/dotnetcli/v<versione>`;
+//! dopo un cambio di versione una cache rimasta indietro fa elencare a `dotnet new`
+//! template che non ci sono più o nasconde quelli nuovi. Svuotarla basta: `dotnet new`
+//! la ricostruisce al primo uso. I pacchetti installati con `dotnet new install` stanno
+//! in `.templateengine/packages`, condivisi da tutti gli SDK, e non si toccano.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::runtimeconfig::read_runtime_config;
+use crate::version::compare_version_strings;
+use crate::{disk, paths, Config, DverError, ResultExt};
+
+/// Cartella del motore dei template sotto la home della CLI.
+pub const ENGINE_DIR: &str = ".templateengine";
+
+/// La cartella `.templateengine` usata da dotnet: sotto `DOTNET_CLI_HOME`, dalla
+/// tabella `[env]` o dall'ambiente, altrimenti sotto la home.
+pub fn engine_dir(config: &Config) -> Option<PathBuf> {
+    let cli_home = config.env.get("DOTNET_CLI_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("DOTNET_CLI_HOME").filter(|v| !v.is_empty()).map(PathBuf::from));
+    cli_home.or_else(paths::home_dir).map(|home| home.join(ENGINE_DIR))
+}
+
+/// La cache dei template di un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateCache {
+    /// Versione dell'SDK, es. `8.0.404`
+    pub sdk: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// Falso se l'SDK non è più installato
+    pub installed: bool,
+}
+
+/// Le cache per SDK in `<engine>/dotnetcli`, dalla versione più vecchia; `installed`
+/// sono le versioni degli SDK presenti.
+pub fn sdk_caches(engine: &Path, installed: &[String]) -> Vec<TemplateCache> {
+    let mut caches: Vec<TemplateCache> = fs::read_dir(engine.join("dotnetcli"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let sdk = name.strip_prefix('v')?.to_string();
+            let path = entry.path();
+            let size_bytes = disk::dir_size(&path).unwrap_or(0);
+            Some(TemplateCache { installed: installed.contains(&sdk), sdk, path, size_bytes })
+        })
+        .collect();
+    caches.sort_by(|a, b| compare_version_strings(&a.sdk, &b.sdk));
+    caches
+}
+
+/// Svuota e rimuove la cache `cache`.
+pub fn clear(cache: &TemplateCache) -> Result<(), DverError> {
+    fs::remove_dir_all(&cache.path).with_context(|| format!("Failed to remove {}", cache.path.display()))
+}
+
+/// I pacchetti di template distribuiti con un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplatePacks {
+    /// Versione dell'SDK, es. `8.0.404`
+    pub sdk: String,
+    /// Cartella `<root>/templates/<versione del runtime>`
+    pub path: PathBuf,
+    /// Identificativi dei pacchetti, es. `microsoft.dotnet.common.projecttemplates.8.0`
+    pub packs: Vec<String>,
+}
+
+/// I pacchetti di template dell'SDK in `sdk_dir` (`<root>/sdk/<versione>`): stanno in
+/// `<root>/templates/<versione>`, con la versione del runtime che l'SDK usa, letta dal
+/// suo `dotnet.runtimeconfig.json`. `None` se l'SDK non la dichiara.
+pub fn bundled_packs(sdk_dir: &Path) -> Option<TemplatePacks> {
+    let sdk = sdk_dir.file_name()?.to_string_lossy().into_owned();
+    let root = sdk_dir.parent()?.parent()?;
+    let runtime = read_runtime_config(&sdk_dir.join("dotnet.runtimeconfig.json")).ok()?.frameworks.into_iter().next()?.version;
+    let path = root.join("templates").join(&runtime);
+    let suffix = format!(".{}.nupkg", runtime);
+    let mut packs: Vec<String> = fs::read_dir(&path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
+            let id = name.strip_suffix(&suffix).or_else(|| name.strip_suffix(".nupkg"))?;
+            Some(id.to_string())
+        })
+        .collect();
+    packs.sort();
+    Some(TemplatePacks { sdk, path, packs })
+}
@@ -0,0 +1,115 @@
+//! Manifest della toolchain installata: SDK, runtime, workload e canale predefinito.
+//!
+//! `dver export` lo scrive e `dver import` lo usa per riprodurre lo stesso stato su
+//! un'altra macchina, installando quello che manca e, a richiesta, rimuovendo gli SDK
+//! in più. I runtime non si installano da soli: arrivano con gli SDK, e il manifest
+//! serve a controllare che ci siano tutti.
+
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::{InstalledRuntime, InstalledSdk};
+use crate::version::{sort_versions, VersionSpec};
+use crate::{Config, DverError};
+
+/// Versione del formato del manifest; quelli più recenti vengono rifiutati.
+pub const TOOLCHAIN_FORMAT: u32 = 1;
+
+/// Un runtime condiviso, es. `Microsoft.NETCore.App 8.0.11`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ToolchainRuntime {
+    pub name: String,
+    pub version: String,
+}
+
+/// Contenuto di un manifest della toolchain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    pub format: u32,
+    /// Versioni esatte degli SDK, dalla più vecchia
+    pub sdks: Vec<String>,
+    #[serde(default)]
+    pub runtimes: Vec<ToolchainRuntime>,
+    /// Identificativi dei workload, es. `wasm-tools`
+    #[serde(default)]
+    pub workloads: Vec<String>,
+    /// `default_channel` della configurazione
+    #[serde(default)]
+    pub default_channel: Option<String>,
+}
+
+/// Cosa serve perché la macchina corrisponda al manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportPlan {
+    /// SDK del manifest non installati
+    pub install: Vec<String>,
+    /// SDK installati che il manifest non elenca
+    pub extra: Vec<String>,
+    /// Workload del manifest non installati
+    pub workloads: Vec<String>,
+}
+
+impl ImportPlan {
+    /// Vero se la macchina ha già tutto quello che il manifest chiede.
+    pub fn is_satisfied(&self) -> bool {
+        self.install.is_empty() && self.workloads.is_empty()
+    }
+}
+
+impl Toolchain {
+    /// Manifest dello stato corrente, senza duplicati e in ordine di versione.
+    pub fn capture(sdks: &[InstalledSdk], runtimes: &[InstalledRuntime], workloads: &[String], config: &Config) -> Toolchain {
+        let mut versions: Vec<String> = sdks.iter().map(|sdk| sdk.version.clone()).collect();
+        sort_versions(&mut versions);
+        let mut runtimes: Vec<ToolchainRuntime> = runtimes
+            .iter()
+            .map(|r| ToolchainRuntime { name: r.name.clone(), version: r.version.clone() })
+            .collect();
+        runtimes.dedup();
+        let mut workloads = workloads.to_vec();
+        workloads.sort();
+        workloads.dedup();
+        Toolchain {
+            format: TOOLCHAIN_FORMAT,
+            sdks: versions,
+            runtimes,
+            workloads,
+            default_channel: config.default_channel.clone(),
+        }
+    }
+
+    /// Legge un manifest, controllando il formato e che gli SDK siano versioni esatte.
+    pub fn parse(content: &str) -> Result<Toolchain, DverError> {
+        let toolchain: Toolchain = serde_json::from_str(content)
+            .map_err(|e| DverError::Parse(format!("Invalid toolchain manifest: {}", e)))?;
+        if toolchain.format > TOOLCHAIN_FORMAT {
+            return Err(DverError::Parse(format!(
+                "Toolchain manifest format {} is newer than this dver supports ({}); upgrade dver",
+                toolchain.format, TOOLCHAIN_FORMAT
+            )));
+        }
+        if let Some(sdk) = toolchain.sdks.iter().find(|v| !v.parse::<VersionSpec>().is_ok_and(|s| s.is_exact())) {
+            return Err(DverError::Parse(format!("Invalid SDK version {:?} in the toolchain manifest", sdk)));
+        }
+        Ok(toolchain)
+    }
+
+    /// Differenze tra il manifest e gli SDK e workload installati.
+    pub fn plan(&self, installed_sdks: &[String], installed_workloads: &[String]) -> ImportPlan {
+        let mut extra: Vec<String> = installed_sdks.iter().filter(|v| !self.sdks.contains(v)).cloned().collect();
+        sort_versions(&mut extra);
+        ImportPlan {
+            install: self.sdks.iter().filter(|v| !installed_sdks.contains(v)).cloned().collect(),
+            extra,
+            workloads: self.workloads.iter().filter(|w| !installed_workloads.contains(w)).cloned().collect(),
+        }
+    }
+
+    /// Runtime del manifest che non risultano tra gli `installed`.
+    pub fn missing_runtimes(&self, installed: &[InstalledRuntime]) -> Vec<ToolchainRuntime> {
+        self.runtimes
+            .iter()
+            .filter(|r| !installed.iter().any(|i| i.name == r.name && i.version == r.version))
+            .cloned()
+            .collect()
+    }
+}
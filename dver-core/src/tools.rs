@@ -0,0 +1,150 @@
+//! Strumenti .NET dichiarati e installati, per `dver tool sync`.
+//!
+//! Gli strumenti globali si dichiarano nella tabella `[tools]` della configurazione,
+//! quelli locali nel manifest `.config/dotnet-tools.json` del repository. Un'installazione
+//! si lega all'SDK attivo in quel momento: confrontare il dichiarato con l'installato
+//! mostra gli strumenti rimasti indietro o mai installati.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::version::compare_version_strings;
+use crate::{DverError, ResultExt};
+
+/// Versione che accetta qualsiasi strumento installato.
+pub const ANY_VERSION: &str = "*";
+
+/// Dove vive uno strumento.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolScope {
+    /// `dotnet tool install --global`
+    Global,
+    /// Il manifest `.config/dotnet-tools.json`, ripristinato con `dotnet tool restore`
+    Local,
+}
+
+/// Confronto tra la versione dichiarata e quella installata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolStatus {
+    Current,
+    /// Installato con un'altra versione
+    Drift,
+    Missing,
+}
+
+/// Uno strumento installato secondo `dotnet tool list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledTool {
+    pub id: String,
+    pub version: String,
+    pub commands: Vec<String>,
+}
+
+/// Esito per uno strumento dichiarato.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolDrift {
+    pub id: String,
+    pub scope: ToolScope,
+    /// Versione dichiarata; `None` per [`ANY_VERSION`]
+    pub wanted: Option<String>,
+    pub installed: Option<String>,
+    pub status: ToolStatus,
+}
+
+/// Legge la tabella stampata da `dotnet tool list --global` (o `--local`, che ha in
+/// più la colonna del manifest).
+pub fn parse_tool_list(stdout: &str) -> Vec<InstalledTool> {
+    stdout
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|l| {
+            let mut columns = l.split_whitespace();
+            let id = columns.next()?.to_string();
+            let version = columns.next()?.to_string();
+            let commands = columns.next().map(|c| c.split(',').map(str::to_string).collect()).unwrap_or_default();
+            Some(InstalledTool { id, version, commands })
+        })
+        .collect()
+}
+
+/// Confronta gli strumenti globali `wanted` (id e versione) con quelli `installed`.
+/// Gli id di NuGet non distinguono le maiuscole.
+pub fn global_drift(wanted: &BTreeMap<String, String>, installed: &[InstalledTool]) -> Vec<ToolDrift> {
+    wanted
+        .iter()
+        .map(|(id, version)| {
+            let wanted = Some(version.trim()).filter(|v| !v.is_empty() && *v != ANY_VERSION).map(str::to_string);
+            let installed = installed.iter().find(|t| t.id.eq_ignore_ascii_case(id)).map(|t| t.version.clone());
+            let status = match (&installed, &wanted) {
+                (None, _) => ToolStatus::Missing,
+                (Some(have), Some(want)) if have != want => ToolStatus::Drift,
+                _ => ToolStatus::Current,
+            };
+            ToolDrift { id: id.clone(), scope: ToolScope::Global, wanted, installed, status }
+        })
+        .collect()
+}
+
+/// Il manifest degli strumenti locali, come in `dotnet-tools.json`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ToolManifest {
+    #[serde(default)]
+    pub tools: BTreeMap<String, ManifestTool>,
+}
+
+/// Una voce del manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestTool {
+    pub version: String,
+}
+
+/// Il manifest che dotnet userebbe da `start`: `.config/dotnet-tools.json` o
+/// `dotnet-tools.json` nella cartella o in una delle superiori.
+pub fn find_tool_manifest(start: &Path) -> Result<Option<(PathBuf, ToolManifest)>, DverError> {
+    let Some(path) = start
+        .ancestors()
+        .flat_map(|dir| [dir.join(".config").join("dotnet-tools.json"), dir.join("dotnet-tools.json")])
+        .find(|p| p.is_file())
+    else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let manifest = serde_json::from_str(&content).with_context(|| format!("Invalid tool manifest {}", path.display()))?;
+    Ok(Some((path, manifest)))
+}
+
+/// Confronta gli strumenti del manifest con quelli già ripristinati in `packages`
+/// (`<id minuscolo>/<versione>`); uno strumento non ripristinato può averne in cache
+/// un'altra versione.
+pub fn local_drift(manifest: &ToolManifest, packages: &Path) -> Vec<ToolDrift> {
+    manifest
+        .tools
+        .iter()
+        .map(|(id, tool)| {
+            let dir = packages.join(id.to_ascii_lowercase());
+            let restored = dir.join(tool.version.to_ascii_lowercase()).is_dir();
+            let installed = if restored {
+                Some(tool.version.clone())
+            } else {
+                fs::read_dir(&dir).ok().and_then(|entries| {
+                    entries
+                        .filter_map(Result::ok)
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .max_by(|a, b| compare_version_strings(a, b))
+                })
+            };
+            let status = match (&installed, restored) {
+                (_, true) => ToolStatus::Current,
+                (Some(_), false) => ToolStatus::Drift,
+                (None, false) => ToolStatus::Missing,
+            };
+            ToolDrift { id: id.clone(), scope: ToolScope::Local, wanted: Some(tool.version.clone()), installed, status }
+        })
+        .collect()
+}
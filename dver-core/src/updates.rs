@@ -0,0 +1,284 @@
+//! Aggiornamenti disponibili per gli SDK installati e per quello fissato dal progetto,
+//! per `dver check-updates`, per `dver upgrade` e, dalla sola cache, per `dver status`.
+//!
+//! Si propone l'SDK più recente della stessa feature band, cioè l'aggiornamento che
+//! `rollForward: latestPatch` accetterebbe; se la versione attuale è esposta a CVE
+//! corrette da rilasci successivi l'aggiornamento è di sicurezza.
+//!
+//! [`update_notice`] ne ricava la riga che dver stampa alla fine di un comando: legge
+//! solo i metadati che i comandi in rete hanno già lasciato in cache, senza processi
+//! in background, e si ripete al più una volta ogni `update_notice_interval_hours`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{audit, channels_of, AuditStatus};
+use crate::config::DEFAULT_UPDATE_NOTICE_INTERVAL_HOURS;
+use crate::inventory::{active_dotnet_root, scan_root_sdks};
+use crate::metadata::{cached_channel_releases, cached_release_index};
+use crate::releases::ChannelReleases;
+use crate::sbom::sdk_archive;
+use crate::version::SdkVersion;
+use crate::Config;
+
+/// Quali aggiornamenti annunciare alla fine dei comandi.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateNotifications {
+    /// Tutti gli aggiornamenti
+    #[default]
+    All,
+    /// Solo quelli che correggono CVE
+    Security,
+    /// Nessun avviso
+    Off,
+}
+
+/// Esito per un SDK, dal meno al più urgente.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateStatus {
+    /// Nessun SDK più recente nella feature band
+    Current,
+    /// Un SDK più recente nella feature band
+    Update,
+    /// Un SDK più recente che corregge CVE note
+    Security,
+}
+
+impl UpdateStatus {
+    /// Exit code di `check-updates`: 0 aggiornato, 1 aggiornamenti, 2 di sicurezza.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            UpdateStatus::Current => 0,
+            UpdateStatus::Update => 1,
+            UpdateStatus::Security => 2,
+        }
+    }
+}
+
+/// Aggiornamento disponibile per un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SdkUpdate {
+    pub version: String,
+    /// `installed`, oppure il file che fissa la versione (`global.json`, `.dver.toml`)
+    pub source: String,
+    pub status: UpdateStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_to: Option<String>,
+    /// CVE corrette dall'aggiornamento
+    pub cves: Vec<String>,
+}
+
+/// SDK più recente della feature band di `version` nei rilasci di `channels`; le
+/// anteprime contano solo se lo è anche `version`.
+fn newest_in_band(channels: &[ChannelReleases], version: &SdkVersion) -> Option<SdkVersion> {
+    channels
+        .iter()
+        .flat_map(|channel| &channel.releases)
+        .flat_map(|release| release.sdk_versions())
+        .filter(|v| v.major == version.major && v.minor == version.minor && v.feature_band() == version.feature_band())
+        .filter(|v| !v.is_prerelease() || version.is_prerelease())
+        .max()
+        .cloned()
+}
+
+/// Confronta gli SDK `sdks` (coppie versione e provenienza) con i rilasci di `channels`.
+pub fn check_updates(channels: &[ChannelReleases], sdks: &[(String, String)]) -> Vec<SdkUpdate> {
+    sdks.iter()
+        .map(|(version, source)| {
+            let mut update = SdkUpdate {
+                version: version.clone(),
+                source: source.clone(),
+                status: UpdateStatus::Current,
+                update_to: None,
+                cves: Vec::new(),
+            };
+            let Ok(parsed) = version.parse::<SdkVersion>() else {
+                return update;
+            };
+            let newest = newest_in_band(channels, &parsed).filter(|v| *v > parsed).map(|v| v.to_string());
+            let finding = audit(channels, std::slice::from_ref(version), &[]).remove(0);
+            if finding.status == AuditStatus::Affected {
+                update.status = UpdateStatus::Security;
+                update.update_to = finding.update_to.or(newest);
+                update.cves = finding.advisories.into_iter().map(|a| a.cve_id).collect();
+            } else if newest.is_some() {
+                update.status = UpdateStatus::Update;
+                update.update_to = newest;
+            }
+            update
+        })
+        .collect()
+}
+
+/// Un aggiornamento da proporre con `dver upgrade`: la patch più recente di una feature
+/// band installata, con quanto serve per decidere se accettarla.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PendingUpgrade {
+    /// Es. `8.0.4xx`
+    pub band: String,
+    /// L'SDK più recente installato nella band
+    pub from: String,
+    pub to: String,
+    pub release_date: Option<NaiveDate>,
+    /// CVE note di `from` corrette dall'aggiornamento
+    pub cves: Vec<String>,
+    /// Dimensione dell'archivio di `to` secondo i metadati, se la riportano
+    pub download_bytes: Option<u64>,
+}
+
+impl PendingUpgrade {
+    pub fn is_security(&self) -> bool {
+        !self.cves.is_empty()
+    }
+}
+
+/// Gli aggiornamenti per feature band degli SDK stabili `installed`, dalla band più
+/// vecchia; le dimensioni sono quelle degli archivi per `rid`.
+pub fn pending_upgrades(channels: &[ChannelReleases], installed: &[String], rid: &str) -> Vec<PendingUpgrade> {
+    let mut newest: Vec<SdkVersion> = Vec::new();
+    for version in installed.iter().filter_map(|v| v.parse::<SdkVersion>().ok()).filter(|v| !v.is_prerelease()) {
+        let same_band = |v: &SdkVersion| (v.major, v.minor, v.feature_band()) == (version.major, version.minor, version.feature_band());
+        match newest.iter_mut().find(|v| same_band(v)) {
+            Some(current) if *current < version => *current = version,
+            Some(_) => {}
+            None => newest.push(version),
+        }
+    }
+    newest.sort();
+    newest
+        .into_iter()
+        .filter_map(|from| {
+            let to = newest_in_band(channels, &from).filter(|v| *v > from)?;
+            let update = check_updates(channels, &[(from.to_string(), String::new())]).remove(0);
+            let release_date = channels
+                .iter()
+                .flat_map(|channel| &channel.releases)
+                .find(|release| release.sdk_versions().any(|v| *v == to))
+                .and_then(|release| release.release_date);
+            Some(PendingUpgrade {
+                band: format!("{}.{}.{}xx", from.major, from.minor, from.feature_band() / 100),
+                from: from.to_string(),
+                to: to.to_string(),
+                release_date,
+                cves: update.cves,
+                download_bytes: sdk_archive(channels, rid, &to).and_then(|file| file.size),
+            })
+        })
+        .collect()
+}
+
+/// Come [`check_updates`], ma con i soli metadati già in cache e senza rete; `None`
+/// se la cache non ha l'indice dei canali. I canali mai scaricati non contano.
+pub fn cached_updates(config: &Config, sdks: &[(String, String)]) -> Option<Vec<SdkUpdate>> {
+    let index = cached_release_index(config)?;
+    let wanted = channels_of(sdks.iter().map(|(version, _)| version.as_str()));
+    let channels: Vec<ChannelReleases> = index
+        .releases_index
+        .iter()
+        .filter(|c| c.channel_version.as_ref().is_some_and(|v| wanted.contains(v)))
+        .filter_map(|c| cached_channel_releases(config, &c.releases_json))
+        .collect();
+    Some(check_updates(&channels, sdks))
+}
+
+/// Esito complessivo: quello più urgente.
+pub fn overall(updates: &[SdkUpdate]) -> UpdateStatus {
+    updates.iter().map(|u| u.status).max().unwrap_or(UpdateStatus::Current)
+}
+
+/// Report in Markdown, da incollare nella descrizione di una pull request.
+pub fn markdown(updates: &[SdkUpdate]) -> String {
+    let mut out = String::from("| SDK | Source | Status | Update to | CVEs |\n|---|---|---|---|---|\n");
+    for u in updates {
+        let status = match u.status {
+            UpdateStatus::Current => "up to date",
+            UpdateStatus::Update => "update available",
+            UpdateStatus::Security => "**security update**",
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            u.version,
+            u.source,
+            status,
+            u.update_to.as_deref().unwrap_or("-"),
+            if u.cves.is_empty() { "-".to_string() } else { u.cves.join(", ") },
+        ));
+    }
+    out
+}
+
+/// Quando è stato stampato l'ultimo avviso.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct NoticeState {
+    last_notice: Option<DateTime<Utc>>,
+}
+
+fn notice_file(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("update-notice.json"))
+}
+
+/// La riga di avviso per `updates` secondo `level`; gli aggiornamenti verso un SDK
+/// già tra gli `installed` non contano.
+pub fn notice_line(updates: &[SdkUpdate], installed: &[String], level: UpdateNotifications) -> Option<String> {
+    let pending: Vec<&SdkUpdate> = updates
+        .iter()
+        .filter(|u| match level {
+            UpdateNotifications::All => u.status != UpdateStatus::Current,
+            UpdateNotifications::Security => u.status == UpdateStatus::Security,
+            UpdateNotifications::Off => false,
+        })
+        .filter(|u| u.update_to.as_ref().is_some_and(|to| !installed.contains(to)))
+        .collect();
+    let security = pending.iter().filter(|u| u.status == UpdateStatus::Security).count();
+    match pending.as_slice() {
+        [] => None,
+        [update] => {
+            let to = update.update_to.as_deref().unwrap_or_default();
+            let kind = if security == 1 { " (security)" } else { "" };
+            Some(format!("SDK {} is available for {}{}; run `dver install {}`", to, update.version, kind, to))
+        }
+        many => {
+            let kind = if security > 0 { format!(" ({} security)", security) } else { String::new() };
+            Some(format!("{} SDK updates are available{}; run `dver check-updates`", many.len(), kind))
+        }
+    }
+}
+
+/// L'avviso sugli aggiornamenti degli SDK installati nella radice attiva, se
+/// `update_notifications` lo consente e l'ultimo è più vecchio dell'intervallo.
+/// Usa solo la cache; ricorda `now` quando restituisce un avviso.
+pub fn update_notice(config: &Config, now: SystemTime) -> Option<String> {
+    if config.update_notifications == UpdateNotifications::Off {
+        return None;
+    }
+    let now = DateTime::<Utc>::from(now);
+    let file = notice_file(config)?;
+    let state: NoticeState = fs::read_to_string(&file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let interval = Duration::hours(config.update_notice_interval_hours.unwrap_or(DEFAULT_UPDATE_NOTICE_INTERVAL_HOURS).into());
+    if state.last_notice.is_some_and(|last| now - last < interval) {
+        return None;
+    }
+    let installed: Vec<String> = scan_root_sdks(&active_dotnet_root(config)?)
+        .ok()?
+        .into_iter()
+        .map(|s| s.version)
+        .filter(|version| config.follows_sdk(version))
+        .collect();
+    let sdks: Vec<(String, String)> = installed.iter().map(|v| (v.clone(), "installed".to_string())).collect();
+    let line = notice_line(&cached_updates(config, &sdks)?, &installed, config.update_notifications)?;
+    // Un file di stato non scrivibile non deve far fallire il comando
+    let state = NoticeState { last_notice: Some(now) };
+    if let (Some(parent), Ok(content)) = (file.parent(), serde_json::to_string(&state)) {
+        let _ = fs::create_dir_all(parent).and_then(|_| fs::write(&file, content));
+    }
+    Some(line)
+}
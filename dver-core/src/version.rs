@@ -0,0 +1,182 @@
+//! Versioni degli SDK .NET: `major.minor.patch[-prerelease]`, dove le centinaia della
+//! patch sono la feature band (8.0.404 → band 8.0.4xx, patch 4).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::DverError;
+
+/// Versione di un SDK o runtime, es. `8.0.404` o `9.0.100-rc.2.24474.11`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SdkVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Etichetta di anteprima senza il `-`, es. `rc.2.24474.11`
+    pub prerelease: Option<String>,
+}
+
+impl SdkVersion {
+    /// Feature band, es. 400 per 8.0.404.
+    pub fn feature_band(&self) -> u32 {
+        self.patch / 100 * 100
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+}
+
+impl FromStr for SdkVersion {
+    type Err = DverError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DverError::Parse(format!("Invalid SDK version {:?}: expected MAJOR.MINOR.PATCH[-LABEL]", s));
+        let (core, prerelease) = match s.split_once('-') {
+            Some((core, label)) if !label.is_empty() => (core, Some(label.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (s, None),
+        };
+        let parts: Vec<u32> = core
+            .split('.')
+            .map(|p| p.parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [major, minor, patch] = parts[..] else {
+            return Err(invalid());
+        };
+        Ok(SdkVersion { major, minor, patch, prerelease })
+    }
+}
+
+impl fmt::Display for SdkVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(label) = &self.prerelease {
+            write!(f, "-{}", label)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for SdkVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                // Una release viene dopo tutte le sue anteprime
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_labels(a, b),
+            })
+    }
+}
+
+impl PartialOrd for SdkVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Confronta le etichette come SemVer: identificatori numerici per valore, prima di quelli testuali.
+fn compare_labels(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        return match (left.next(), right.next()) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ord == Ordering::Equal {
+                    continue;
+                }
+                ord
+            }
+        };
+    }
+}
+
+impl Serialize for SdkVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SdkVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Ordina stringhe di versione per versione; quelle non interpretabili vanno in fondo, in ordine alfabetico.
+pub fn compare_version_strings(a: &str, b: &str) -> Ordering {
+    match (a.parse::<SdkVersion>(), b.parse::<SdkVersion>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Ordina e rimuove i duplicati, dalla versione più vecchia alla più recente.
+pub fn sort_versions(versions: &mut Vec<String>) {
+    versions.sort_by(|a, b| compare_version_strings(a, b));
+    versions.dedup();
+}
+
+/// Versione richiesta dall'utente: esatta (`8.0.404`) o parziale (`8`, `8.0`, `8.0.4xx`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSpec {
+    Exact(SdkVersion),
+    Major(u32),
+    Minor(u32, u32),
+    /// Feature band, es. `8.0.4xx`
+    Band(u32, u32, u32),
+}
+
+impl VersionSpec {
+    pub fn is_exact(&self) -> bool {
+        matches!(self, VersionSpec::Exact(_))
+    }
+
+    pub fn matches(&self, version: &SdkVersion) -> bool {
+        match self {
+            VersionSpec::Exact(v) => v == version,
+            VersionSpec::Major(major) => version.major == *major,
+            VersionSpec::Minor(major, minor) => (version.major, version.minor) == (*major, *minor),
+            VersionSpec::Band(major, minor, band) => {
+                (version.major, version.minor, version.feature_band()) == (*major, *minor, *band)
+            }
+        }
+    }
+}
+
+impl FromStr for VersionSpec {
+    type Err = DverError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DverError::Usage(format!(
+            "Invalid version {:?}: expected e.g. 8, 8.0, 8.0.4xx or 8.0.404", s));
+        let number = |p: &str| p.parse::<u32>().map_err(|_| invalid());
+        let parts: Vec<&str> = s.split('.').collect();
+        match parts[..] {
+            [major] => Ok(VersionSpec::Major(number(major)?)),
+            [major, minor] => Ok(VersionSpec::Minor(number(major)?, number(minor)?)),
+            [major, minor, band] if band.len() == 3 && band.ends_with("xx") => {
+                Ok(VersionSpec::Band(number(major)?, number(minor)?, number(&band[..1])? * 100))
+            }
+            _ => s.parse().map(VersionSpec::Exact).map_err(|_| invalid()),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Visual Studio su Windows: gli SDK che installa con i suoi pacchetti MSI (tra i
+//! programmi installati come "Microsoft .NET SDK 8.0.404 (x64) from Visual Studio") e le
+//! sue istanze, trovate con `vswhere`.
+//!
+//! Quegli SDK li aggiorna e li rimuove il Visual Studio Installer, quindi `uninstall` li
+//! lascia stare; la versione di un'istanza è quella del suo MSBuild, che deve essere
+//! almeno il `minimumMSBuildVersion` dell'SDK scelto da `global.json`.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::platform::Platform;
+use crate::process::ProcessRunner;
+use crate::registry::{msi_owner, RegistryInstall};
+
+/// Chiavi dei programmi installati, nella vista nativa e in quella a 32 bit.
+pub const UNINSTALL_KEYS: [&str; 2] = [
+    r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+    r"HKLM\SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+];
+
+/// Il testo che Visual Studio aggiunge al nome dei suoi pacchetti.
+pub const FROM_VISUAL_STUDIO: &str = "from Visual Studio";
+
+/// Un SDK installato da Visual Studio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VsSdk {
+    pub version: String,
+    /// Architettura come nel nome del pacchetto, es. `x64`
+    pub arch: String,
+}
+
+/// Interpreta l'output di `reg query <chiave> /s /f "from Visual Studio" /d`.
+pub fn parse_uninstall_entries(output: &str) -> Vec<VsSdk> {
+    let mut sdks: Vec<VsSdk> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().splitn(3, "    ");
+            if parts.next()? != "DisplayName" {
+                return None;
+            }
+            let name = parts.nth(1)?.trim();
+            let rest = name.strip_prefix("Microsoft .NET SDK ")?.strip_suffix(FROM_VISUAL_STUDIO)?.trim();
+            let (version, arch) = rest.split_once(' ')?;
+            let arch = arch.trim().strip_prefix('(')?.strip_suffix(')')?;
+            Some(VsSdk { version: version.to_string(), arch: arch.to_string() })
+        })
+        .collect();
+    sdks.dedup();
+    sdks
+}
+
+/// Gli SDK installati da Visual Studio; vuoto dove il registro non c'è.
+pub fn bundled_sdks(runner: &dyn ProcessRunner, platform: &dyn Platform) -> Vec<VsSdk> {
+    let mut sdks: Vec<VsSdk> = Vec::new();
+    for mut command in platform.visual_studio_sdks_queries() {
+        if let Ok(output) = runner.output(&mut command) {
+            for sdk in parse_uninstall_entries(&String::from_utf8_lossy(&output.stdout)) {
+                if !sdks.contains(&sdk) {
+                    sdks.push(sdk);
+                }
+            }
+        }
+    }
+    sdks
+}
+
+/// Vero se l'SDK `version` nella radice `root` è di Visual Studio: un pacchetto MSI
+/// l'ha registrato lì, e Visual Studio ha installato quella versione per quella architettura.
+pub fn is_bundled(sdks: &[VsSdk], installs: &[RegistryInstall], root: &Path, version: &str) -> bool {
+    msi_owner(installs, root, version).is_some_and(|install| {
+        sdks.iter().any(|sdk| sdk.version == version && sdk.arch.eq_ignore_ascii_case(&install.arch))
+    })
+}
+
+/// Un'istanza di Visual Studio riportata da `vswhere`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VsInstance {
+    pub display_name: String,
+    /// Versione dell'istanza e del suo MSBuild, es. `17.10.35004.147`
+    pub installation_version: String,
+    pub installation_path: PathBuf,
+}
+
+/// Interpreta l'output di `vswhere -format json`; un output illeggibile non ha istanze.
+pub fn parse_vswhere(output: &str) -> Vec<VsInstance> {
+    serde_json::from_str(output).unwrap_or_default()
+}
+
+/// Le istanze di Visual Studio installate; vuoto senza `vswhere`.
+pub fn instances(runner: &dyn ProcessRunner, platform: &dyn Platform) -> Vec<VsInstance> {
+    let Some(mut command) = platform.vswhere_command() else {
+        return Vec::new();
+    };
+    match runner.output(&mut command) {
+        Ok(output) if output.status.success() => parse_vswhere(&String::from_utf8_lossy(&output.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// La versione minima di MSBuild (e quindi di Visual Studio) che l'SDK in `sdk_dir`
+/// richiede, dal file `minimumMSBuildVersion`.
+pub fn minimum_msbuild_version(sdk_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(sdk_dir.join("minimumMSBuildVersion")).ok()?;
+    content.lines().next().map(|line| line.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Confronta due versioni puntate componente per componente, es. `17.8.3` < `17.10`.
+pub fn compare_dotted(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| v.split('.').map(|p| p.parse::<u64>().unwrap_or(0)).collect::<Vec<_>>();
+    let (a, b) = (parts(a), parts(b));
+    let len = a.len().max(b.len());
+    let at = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len).map(|i| at(&a, i).cmp(&at(&b, i))).find(|o| o.is_ne()).unwrap_or(Ordering::Equal)
+}
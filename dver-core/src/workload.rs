@@ -0,0 +1,125 @@
+//! Workload installati con `dver workload`, per SDK. I record stanno in
+//! `workloads.json` nella cartella di stato.
+//!
+//! I manifest dei workload dipendono dalla feature band: un SDK di una band nuova
+//! (es. 8.0.400 dopo 8.0.300) parte senza workload. Con i record, `install` li
+//! reinstalla sull'SDK nuovo con [`replay_source`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::provenance::same_root;
+use crate::version::SdkVersion;
+use crate::{Config, DverError, ResultExt};
+
+/// I workload installati da dver su un SDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SdkWorkloads {
+    pub sdk: String,
+    /// Radice che contiene `sdk/<versione>`
+    pub root: PathBuf,
+    pub workloads: Vec<String>,
+}
+
+/// Il file di stato con i workload per SDK.
+pub fn state_file(config: &Config) -> Option<PathBuf> {
+    config.state_dir().map(|dir| dir.join("workloads.json"))
+}
+
+/// Tutti i record salvati; un file mancante equivale a nessun record.
+pub fn load_all(config: &Config) -> Result<Vec<SdkWorkloads>, DverError> {
+    let Some(path) = state_file(config).filter(|p| p.is_file()) else {
+        return Ok(Vec::new());
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid workload records in {}", path.display()))
+}
+
+fn save_all(config: &Config, records: &[SdkWorkloads]) -> Result<(), DverError> {
+    let path = state_file(config)
+        .ok_or_else(|| DverError::NotFound("Cannot determine the dver state directory".to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(records)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Workload registrati per l'SDK `sdk` in `root`.
+pub fn tracked(config: &Config, root: &Path, sdk: &str) -> Result<Vec<String>, DverError> {
+    Ok(load_all(config)?
+        .into_iter()
+        .find(|r| r.sdk == sdk && same_root(&r.root, root))
+        .map(|r| r.workloads)
+        .unwrap_or_default())
+}
+
+/// Cambia i workload registrati per `sdk` in `root` con `update`; un SDK che resta
+/// senza workload perde il record.
+fn update(config: &Config, root: &Path, sdk: &str, update: impl FnOnce(&mut Vec<String>)) -> Result<(), DverError> {
+    let mut records = load_all(config)?;
+    let position = records.iter().position(|r| r.sdk == sdk && same_root(&r.root, root));
+    let mut workloads = position.map(|i| records.remove(i).workloads).unwrap_or_default();
+    update(&mut workloads);
+    workloads.sort();
+    workloads.dedup();
+    if !workloads.is_empty() {
+        records.push(SdkWorkloads { sdk: sdk.to_string(), root: root.to_path_buf(), workloads });
+    }
+    save_all(config, &records)
+}
+
+/// Registra `ids` tra i workload dell'SDK `sdk` in `root`.
+pub fn add(config: &Config, root: &Path, sdk: &str, ids: &[String]) -> Result<(), DverError> {
+    update(config, root, sdk, |workloads| workloads.extend_from_slice(ids))
+}
+
+/// Toglie `ids` dai workload dell'SDK `sdk` in `root`.
+pub fn remove(config: &Config, root: &Path, sdk: &str, ids: &[String]) -> Result<(), DverError> {
+    update(config, root, sdk, |workloads| workloads.retain(|w| !ids.contains(w)))
+}
+
+/// Dimentica l'SDK `sdk` in `root`, es. dopo la disinstallazione.
+pub fn forget(config: &Config, root: &Path, sdk: &str) -> Result<(), DverError> {
+    let mut records = load_all(config)?;
+    let before = records.len();
+    records.retain(|r| !(r.sdk == sdk && same_root(&r.root, root)));
+    if records.len() == before {
+        return Ok(());
+    }
+    save_all(config, &records)
+}
+
+/// Il record da cui ripristinare i workload sul nuovo SDK `sdk` in `root`: quello
+/// dell'SDK precedente più recente dello stesso canale, se è di un'altra feature band.
+/// `None` se `sdk` ha già un record o nessun SDK precedente ne ha.
+pub fn replay_source<'a>(records: &'a [SdkWorkloads], root: &Path, sdk: &str) -> Option<&'a SdkWorkloads> {
+    let new: SdkVersion = sdk.parse().ok()?;
+    let in_root = || records.iter().filter(|r| same_root(&r.root, root));
+    if in_root().any(|r| r.sdk == sdk) {
+        return None;
+    }
+    // Nella stessa band i workload sono già installati: i manifest sono condivisi
+    in_root()
+        .filter_map(|r| Some((r.sdk.parse::<SdkVersion>().ok()?, r)))
+        .filter(|(v, r)| v.major == new.major && v.minor == new.minor && *v < new && !r.workloads.is_empty())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .filter(|(v, _)| v.feature_band() != new.feature_band())
+        .map(|(_, r)| r)
+}
+
+/// Cartella con un global.json che fissa esattamente l'SDK `sdk`: lanciato lì,
+/// `dotnet workload` agisce su quell'SDK e non su quello della cartella corrente.
+pub fn sdk_pin_dir(config: &Config, sdk: &str) -> Result<PathBuf, DverError> {
+    let dir = config
+        .cache_dir()
+        .ok_or_else(|| DverError::NotFound("Cannot determine the dver cache directory".to_string()))?
+        .join("sdk-pins")
+        .join(sdk);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let global_json = serde_json::json!({ "sdk": { "version": sdk, "rollForward": "disable" } });
+    let path = dir.join("global.json");
+    fs::write(&path, serde_json::to_vec_pretty(&global_json)?).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(dir)
+}
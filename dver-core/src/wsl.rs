@@ -0,0 +1,74 @@
+//! Windows Subsystem for Linux. Dentro WSL convivono due mondi: il `PATH` di Linux
+//! riceve dall'interop anche le cartelle di Windows (es. `/mnt/c/Program Files/dotnet`),
+//! il cui `dotnet.exe` vede solo gli SDK di Windows, e i dischi di Windows sono montati
+//! in `/mnt/<lettera>`, dove un SDK per Linux funziona male: i permessi di esecuzione e
+//! i link simbolici non sono quelli di Linux e ogni accesso passa per il file system 9P.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::DverError;
+
+/// File con la release del kernel; quello di WSL contiene `microsoft`.
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// L'eseguibile di Windows che l'interop rende avviabile da Linux.
+pub const WINDOWS_DOTNET: &str = "dotnet.exe";
+
+/// Vero se `osrelease` è di un kernel WSL, es. `5.15.153.1-microsoft-standard-WSL2`.
+pub fn is_wsl_release(osrelease: &str) -> bool {
+    osrelease.to_ascii_lowercase().contains("microsoft")
+}
+
+/// Vero se il processo gira dentro WSL.
+pub fn is_wsl() -> bool {
+    std::env::consts::OS == "linux"
+        && (std::env::var_os("WSL_DISTRO_NAME").is_some()
+            || fs::read_to_string(OSRELEASE).is_ok_and(|release| is_wsl_release(&release)))
+}
+
+/// Il nome della distribuzione WSL, es. `Ubuntu-24.04`.
+pub fn distro_name() -> Option<String> {
+    std::env::var("WSL_DISTRO_NAME").ok().filter(|name| !name.is_empty())
+}
+
+/// La lettera del disco di Windows su cui sta `path`, se è sotto `/mnt/<lettera>`.
+pub fn windows_drive(path: &Path) -> Option<char> {
+    let mut components = path.components();
+    if components.next() != Some(Component::RootDir) || components.next()?.as_os_str() != "mnt" {
+        return None;
+    }
+    let drive = components.next()?.as_os_str().to_str()?;
+    let mut chars = drive.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_alphabetic() => Some(letter.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// La prima voce di `entries` con il `dotnet.exe` di Windows, con la sua posizione.
+pub fn windows_dotnet_entry(entries: &[PathBuf]) -> Option<(usize, &PathBuf)> {
+    entries
+        .iter()
+        .enumerate()
+        .find(|(_, entry)| windows_drive(entry).is_some() && entry.join(WINDOWS_DOTNET).is_file())
+}
+
+/// L'errore per un'installazione in `root` dentro WSL, se `root` è su un disco di Windows.
+pub fn install_root_error(root: &Path) -> Option<DverError> {
+    windows_drive(root).map(|drive| {
+        DverError::Usage(format!(
+            "Refusing to install into {}: it is on the Windows drive {}: mounted by WSL, where Linux SDKs break\nhint: pick a path in the Linux file system, e.g. `dver config set install_root ~/.dotnet`",
+            root.display(),
+            drive
+        ))
+    })
+}
+
+/// Dentro WSL rifiuta le installazioni su un disco di Windows; fuori da WSL non controlla nulla.
+pub fn check_install_root(root: Option<&Path>) -> Result<(), DverError> {
+    match root.filter(|_| is_wsl()).and_then(install_root_error) {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
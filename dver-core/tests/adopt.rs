@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dver_core::adopt::{find, forget, load_all, record, relocate, root_name, Adoption, Ownership};
+use dver_core::Config;
+use tempfile::TempDir;
+
+#[test]
+fn root_names_come_from_the_path_and_stay_unique() {
+    let mut roots = BTreeMap::new();
+    assert_eq!(root_name(Path::new("/usr/share/dotnet"), &roots), "usr-share-dotnet");
+    roots.insert("usr-share-dotnet".to_string(), PathBuf::from("/opt/other"));
+    assert_eq!(root_name(Path::new("/usr/share/dotnet"), &roots), "usr-share-dotnet-2");
+    // Una cartella già in `[roots]` tiene il suo nome
+    roots.insert("system".to_string(), PathBuf::from("/usr/share/dotnet"));
+    assert_eq!(root_name(Path::new("/usr/share/dotnet"), &roots), "system");
+}
+
+#[test]
+fn adoptions_are_recorded_replaced_and_forgotten() {
+    let state = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let config = Config { state_dir: Some(state.path().to_path_buf()), ..Default::default() };
+    record(&config, &[Adoption::new("8.0.404".to_string(), root.path().to_path_buf(), Ownership::External, None)]).unwrap();
+    record(&config, &[Adoption::new("8.0.404".to_string(), root.path().to_path_buf(), Ownership::Managed, None)]).unwrap();
+    assert_eq!(load_all(&config).unwrap().len(), 1);
+    assert_eq!(find(&config, root.path(), "8.0.404").unwrap().unwrap().ownership, Ownership::Managed);
+
+    forget(&config, root.path(), "8.0.404").unwrap();
+    assert!(load_all(&config).unwrap().is_empty());
+}
+
+#[test]
+fn relocating_merges_into_the_managed_root() {
+    let dir = TempDir::new().unwrap();
+    let src = dir.path().join("usr-dotnet");
+    let dst = dir.path().join("managed");
+    fs::create_dir_all(src.join("sdk/8.0.307")).unwrap();
+    fs::create_dir_all(dst.join("sdk/9.0.100")).unwrap();
+    fs::write(src.join("dotnet"), "old host").unwrap();
+    fs::write(dst.join("dotnet"), "new host").unwrap();
+
+    relocate(&src, &dst).unwrap();
+    assert!(dst.join("sdk/8.0.307").is_dir());
+    assert!(dst.join("sdk/9.0.100").is_dir());
+    // I file già presenti nella radice gestita restano quelli
+    assert_eq!(fs::read_to_string(dst.join("dotnet")).unwrap(), "new host");
+    #[cfg(unix)]
+    assert_eq!(fs::read_link(&src).unwrap(), dst);
+}
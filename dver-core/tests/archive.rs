@@ -0,0 +1,178 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use dver_core::archive::{extract, sha512_file, ArchiveKind};
+use sha2::{Digest, Sha512};
+use tempfile::TempDir;
+
+/// Un `.tar.gz` con la struttura di un SDK: l'eseguibile, un file annidato e un link.
+fn sdk_tar_gz(path: &Path) {
+    let file = fs::File::create(path).unwrap();
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::fast()));
+    let mut add = |name: &str, mode: u32, body: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        builder.append_data(&mut header, name, body).unwrap();
+    };
+    add("dotnet", 0o755, b"#!/bin/sh\n");
+    add("sdk/8.0.404/dotnet.dll", 0o644, &[7u8; 1000]);
+    let mut link = tar::Header::new_gnu();
+    link.set_entry_type(tar::EntryType::Symlink);
+    link.set_size(0);
+    link.set_mode(0o777);
+    builder.append_link(&mut link, "sdk/8.0.404/current.dll", "dotnet.dll").unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+#[test]
+fn archive_kind_follows_the_file_name() {
+    assert_eq!(ArchiveKind::of("dotnet-sdk-8.0.404-linux-x64.tar.gz"), Some(ArchiveKind::TarGz));
+    assert_eq!(ArchiveKind::of("dotnet-sdk-8.0.404-win-x64.zip"), Some(ArchiveKind::Zip));
+    assert_eq!(ArchiveKind::of("dotnet-sdk-8.0.404-win-x64.exe"), None);
+}
+
+#[test]
+fn tar_gz_is_extracted_with_modes_links_and_progress() {
+    let dir = TempDir::new().unwrap();
+    let archive = dir.path().join("sdk.tar.gz");
+    sdk_tar_gz(&archive);
+    let dest = dir.path().join("root");
+    let seen = Mutex::new(Vec::new());
+
+    let extracted = extract(&archive, &dest, &|read, total| seen.lock().unwrap().push((read, total))).unwrap();
+    assert_eq!(extracted.files, 3);
+    assert_eq!(extracted.bytes, 1010);
+    assert_eq!(fs::read(dest.join("sdk/8.0.404/dotnet.dll")).unwrap(), vec![7u8; 1000]);
+    let size = fs::metadata(&archive).unwrap().len();
+    assert_eq!(seen.lock().unwrap().last(), Some(&(size, Some(size))));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(fs::metadata(dest.join("dotnet")).unwrap().permissions().mode() & 0o777, 0o755);
+        assert_eq!(fs::read_link(dest.join("sdk/8.0.404/current.dll")).unwrap(), Path::new("dotnet.dll"));
+    }
+}
+
+#[test]
+fn tar_entries_outside_the_destination_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let archive = dir.path().join("evil.tar.gz");
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(fs::File::create(&archive).unwrap(), flate2::Compression::fast()));
+    let mut header = tar::Header::new_gnu();
+    header.as_old_mut().name[..9].copy_from_slice(b"../escape");
+    header.set_size(1);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append(&header, &b"x"[..]).unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let err = extract(&archive, &dir.path().join("root"), &|_, _| {}).unwrap_err();
+    assert!(err.to_string().contains("outside the destination"), "{}", err);
+    assert!(!dir.path().join("escape").exists());
+}
+
+#[test]
+fn zip_is_extracted_with_unix_modes() {
+    let dir = TempDir::new().unwrap();
+    let archive = dir.path().join("sdk.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    writer.start_file("dotnet", options.unix_permissions(0o755)).unwrap();
+    writer.write_all(b"#!/bin/sh\n").unwrap();
+    writer.add_directory("sdk/8.0.404/", options).unwrap();
+    writer.start_file("sdk/8.0.404/dotnet.dll", options.unix_permissions(0o644)).unwrap();
+    writer.write_all(&[1u8; 500]).unwrap();
+    writer.finish().unwrap();
+
+    let dest = dir.path().join("root");
+    let extracted = extract(&archive, &dest, &|_, _| {}).unwrap();
+    assert_eq!(extracted.files, 2);
+    assert_eq!(extracted.bytes, 510);
+    assert_eq!(fs::read(dest.join("sdk/8.0.404/dotnet.dll")).unwrap().len(), 500);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(fs::metadata(dest.join("dotnet")).unwrap().permissions().mode() & 0o777, 0o755);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn zip_links_pointing_outside_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let archive = dir.path().join("evil.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+    writer.add_symlink("sdk/passwd", "../../../etc/passwd", zip::write::SimpleFileOptions::default()).unwrap();
+    writer.finish().unwrap();
+
+    let err = extract(&archive, &dir.path().join("root"), &|_, _| {}).unwrap_err();
+    assert!(err.to_string().contains("outside the destination"), "{}", err);
+}
+
+#[cfg(unix)]
+#[test]
+fn zip_links_chained_outside_are_rejected() {
+    // `a/b` punta alla destinazione, quindi `a/b/..` è la cartella che la contiene:
+    // nei nomi tutto resta dentro, seguendo i link no. Il link `x` prima o dopo `a/b`
+    for (name, links) in [("after.zip", [("a/b", ".."), ("x", "a/b/..")]), ("before.zip", [("x", "a/b/.."), ("a/b", "..")])] {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join(name);
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        for (link, target) in links {
+            writer.add_symlink(link, target, zip::write::SimpleFileOptions::default()).unwrap();
+        }
+        writer.start_file("x/escaped", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"outside").unwrap();
+        writer.finish().unwrap();
+
+        let err = extract(&archive, &dir.path().join("root"), &|_, _| {}).unwrap_err();
+        assert!(err.to_string().contains("outside the destination"), "{}: {}", name, err);
+        assert!(!dir.path().join("escaped").exists(), "{}", name);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn zip_files_are_not_written_through_links() {
+    // `f` punta a `b/../outside.txt` quando `b` non esiste ancora; poi `b -> .` la fa
+    // uscire dalla destinazione, e la voce `./f` scriverebbe attraverso il link
+    let dir = TempDir::new().unwrap();
+    let archive = dir.path().join("through.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+    writer.add_symlink("f", "b/../outside.txt", zip::write::SimpleFileOptions::default()).unwrap();
+    writer.add_symlink("b", ".", zip::write::SimpleFileOptions::default()).unwrap();
+    writer.start_file("./f", zip::write::SimpleFileOptions::default()).unwrap();
+    writer.write_all(b"outside").unwrap();
+    writer.finish().unwrap();
+
+    let dest = dir.path().join("root");
+    extract(&archive, &dest, &|_, _| {}).unwrap();
+    assert!(!dir.path().join("outside.txt").exists());
+    // Il file sostituisce il link
+    assert!(!dest.join("f").symlink_metadata().unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(dest.join("f")).unwrap(), "outside");
+
+    // Un link lasciato a puntare fuori è rifiutato a estrazione finita
+    let archive = dir.path().join("dangling.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+    writer.add_symlink("f", "b/../outside.txt", zip::write::SimpleFileOptions::default()).unwrap();
+    writer.add_symlink("b", ".", zip::write::SimpleFileOptions::default()).unwrap();
+    writer.finish().unwrap();
+    fs::write(dir.path().join("outside.txt"), "mine").unwrap();
+    let err = extract(&archive, &dir.path().join("other"), &|_, _| {}).unwrap_err();
+    assert!(err.to_string().contains("outside the destination"), "{}", err);
+}
+
+#[test]
+fn sha512_matches_the_release_metadata_format() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("data");
+    fs::write(&file, b"abc").unwrap();
+    let expected: String = Sha512::digest(b"abc").iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(sha512_file(&file).unwrap(), expected);
+}
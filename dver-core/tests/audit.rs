@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+use dver_core::audit::{is_end_of_support, outdated_reasons};
+use dver_core::releases::{ChannelReleases, ReleaseIndex};
+
+const INDEX: &str = include_str!("fixtures/releases-index.json");
+const CHANNEL_8_0: &str = include_str!("fixtures/8.0/releases.json");
+
+fn fixtures() -> (ReleaseIndex, Vec<ChannelReleases>) {
+    (serde_json::from_str(INDEX).unwrap(), vec![serde_json::from_str(CHANNEL_8_0).unwrap()])
+}
+
+fn day(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+#[test]
+fn patched_sdks_of_supported_channels_are_fine() {
+    let (index, channels) = fixtures();
+    assert!(outdated_reasons(&index, &channels, "8.0.406", day("2025-03-01")).is_empty());
+}
+
+#[test]
+fn sdks_with_later_security_fixes_are_reported() {
+    let (index, channels) = fixtures();
+    assert_eq!(
+        outdated_reasons(&index, &channels, "8.0.100", day("2025-03-01")),
+        ["1 known CVE is fixed in later releases, update to 8.0.113"]
+    );
+}
+
+#[test]
+fn end_of_support_comes_from_the_phase_or_the_date() {
+    let (index, channels) = fixtures();
+    assert_eq!(
+        outdated_reasons(&index, &channels, "3.1.426", day("2025-03-01")),
+        [".NET 3.1 reached end of support on 2022-12-13"]
+    );
+    // 8.0 è ancora "active" nell'indice, ma la data di fine supporto è passata
+    assert_eq!(
+        outdated_reasons(&index, &channels, "8.0.406", day("2026-11-10")),
+        [".NET 8.0 reached end of support on 2026-11-10"]
+    );
+}
+
+#[test]
+fn unknown_versions_have_no_reasons() {
+    let (index, channels) = fixtures();
+    assert!(outdated_reasons(&index, &channels, "not-a-version", day("2025-03-01")).is_empty());
+    assert!(outdated_reasons(&index, &channels, "7.0.100", day("2025-03-01")).is_empty());
+}
+
+#[test]
+fn end_of_support_is_unknown_for_channels_missing_from_the_index() {
+    let (index, _) = fixtures();
+    assert_eq!(is_end_of_support(&index, "3.1", day("2025-03-01")), Some(true));
+    assert_eq!(is_end_of_support(&index, "8.0", day("2025-03-01")), Some(false));
+    assert_eq!(is_end_of_support(&index, "4.0", day("2025-03-01")), None);
+}
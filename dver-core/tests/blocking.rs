@@ -0,0 +1,213 @@
+#![cfg(feature = "blocking")]
+
+use std::sync::Mutex;
+
+use dver_core::config::InstallMethod;
+use dver_core::http::blocking::{http_client, HttpClient};
+use dver_core::http::StaticClient;
+use dver_core::install::blocking::{estimate_install, install_sdk};
+use dver_core::install::{InstallRequest, EXTRACTION_RATIO};
+use dver_core::metadata::blocking::{fetch_channel, fetch_release_index, fetch_remote_sdk_versions};
+use dver_core::process::SystemRunner;
+use dver_core::{platform, provenance, Config};
+use sha2::{Digest, Sha512};
+
+const INDEX_URL: &str = "https://example.com/releases-index.json";
+
+fn client() -> StaticClient {
+    StaticClient::new()
+        .with(INDEX_URL, r#"{"releases-index": [
+            {"channel-version": "9.0", "release-type": "sts", "releases.json": "https://example.com/9.0.json"},
+            {"channel-version": "8.0", "release-type": "lts", "releases.json": "https://example.com/8.0.json"},
+            {"channel-version": "7.0", "release-type": "sts", "releases.json": "https://example.com/missing.json"}
+        ]}"#)
+        .with("https://example.com/9.0.json", r#"{"releases": [{"release-version": "9.0.0", "sdk": {"version": "9.0.100"}}]}"#)
+        .with("https://example.com/8.0.json", r#"{"releases": [{"release-version": "8.0.11", "sdks": [{"version": "8.0.404"}, {"version": "8.0.307"}]}]}"#)
+}
+
+fn config(cache: &tempfile::TempDir) -> Config {
+    Config {
+        index_url: Some(INDEX_URL.to_string()),
+        cache_dir: Some(cache.path().to_path_buf()),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn fetches_remote_versions_without_a_runtime() {
+    let cache = tempfile::tempdir().unwrap();
+    let versions = fetch_remote_sdk_versions(&client(), &config(&cache)).unwrap();
+    assert_eq!(versions, ["8.0.307", "8.0.404", "9.0.100"]);
+}
+
+#[test]
+fn offline_mode_reads_the_cache_filled_by_a_previous_fetch() {
+    let cache = tempfile::tempdir().unwrap();
+    let mut config = config(&cache);
+    fetch_release_index(&client(), &config).unwrap();
+
+    config.offline = true;
+    let index = fetch_release_index(&StaticClient::new(), &config).unwrap();
+    assert_eq!(index.releases_index.len(), 3);
+}
+
+#[test]
+fn no_network_fails_before_building_a_client() {
+    let config = Config { no_network: true, ..Config::default() };
+    let err = http_client(&config, INDEX_URL).unwrap_err();
+    assert_eq!(err.code(), "network-disabled");
+}
+
+#[test]
+fn downloads_are_hashed_while_written_and_leave_nothing_behind_on_failure() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = StaticClient::new().with("https://example.com/abc", "abc");
+    let dest = dir.path().join("abc.txt");
+    let seen = Mutex::new(Vec::new());
+    let download = client
+        .download("https://example.com/abc", &dest, &|written, total| seen.lock().unwrap().push((written, total)))
+        .unwrap();
+    assert_eq!(download.size, 3);
+    assert_eq!(download.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(std::fs::read_to_string(&dest).unwrap(), "abc");
+    assert_eq!(*seen.lock().unwrap(), [(3, Some(3))]);
+
+    let missing = dir.path().join("missing.txt");
+    client.download("https://example.com/missing", &missing, &|_, _| {}).unwrap_err();
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+fn channel_index(latest: &str) -> String {
+    format!(r#"{{"releases-index": [{{"channel-version": "8.0", "latest-release": "{}", "latest-release-date": "2024-11-12",
+        "releases.json": "https://example.com/8.0.json"}}]}}"#, latest)
+}
+
+fn channel_json(releases: &[&str]) -> String {
+    let entries: Vec<String> = releases.iter().map(|r| format!(r#"{{"release-version": "{}"}}"#, r)).collect();
+    format!(r#"{{"latest-release": "{}", "latest-release-date": "2024-11-12", "releases": [{}]}}"#, releases[0], entries.join(","))
+}
+
+#[test]
+fn channel_metadata_is_only_downloaded_again_when_the_index_moves() {
+    let cache = tempfile::tempdir().unwrap();
+    let config = config(&cache);
+    let first = StaticClient::new()
+        .with(INDEX_URL, channel_index("8.0.11"))
+        .with("https://example.com/8.0.json", channel_json(&["8.0.11"]));
+    let index = fetch_release_index(&first, &config).unwrap();
+    assert_eq!(fetch_channel(&first, &config, &index.releases_index[0]).unwrap().releases.len(), 1);
+
+    // Stesso `latest-release` nell'indice: il releases.json cambiato sul server non si scarica
+    let unchanged = StaticClient::new()
+        .with(INDEX_URL, channel_index("8.0.11"))
+        .with("https://example.com/8.0.json", channel_json(&["8.0.11", "8.0.10"]));
+    let index = fetch_release_index(&unchanged, &config).unwrap();
+    assert_eq!(fetch_channel(&unchanged, &config, &index.releases_index[0]).unwrap().releases.len(), 1);
+
+    let moved = StaticClient::new()
+        .with(INDEX_URL, channel_index("8.0.12"))
+        .with("https://example.com/8.0.json", channel_json(&["8.0.12", "8.0.11"]));
+    let index = fetch_release_index(&moved, &config).unwrap();
+    assert_eq!(fetch_channel(&moved, &config, &index.releases_index[0]).unwrap().releases.len(), 2);
+}
+
+fn sdk_archive() -> Vec<u8> {
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast()));
+    for (name, body) in [("dotnet", &b"#!/bin/sh\n"[..]), ("sdk/8.0.404/dotnet.dll", &b"dll"[..])] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, name, body).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+fn archive_client(archive: &[u8], hash: &str) -> (StaticClient, String) {
+    let url = format!("https://example.com/dotnet-sdk-8.0.404-{}.tar.gz", platform::rid());
+    let channel = format!(
+        r#"{{"releases": [{{"release-version": "8.0.11", "sdks": [{{"version": "8.0.404", "files": [
+            {{"name": "dotnet-sdk-{rid}.tar.gz", "rid": "{rid}", "url": "{url}", "hash": "{hash}"}}]}}]}}]}}"#,
+        rid = platform::rid(), url = url, hash = hash);
+    let client = StaticClient::new()
+        .with(INDEX_URL, r#"{"releases-index": [{"channel-version": "8.0", "releases.json": "https://example.com/8.0.json"}]}"#)
+        .with("https://example.com/8.0.json", channel)
+        .with(url.clone(), archive.to_vec());
+    (client, url)
+}
+
+#[test]
+fn archive_installs_are_verified_extracted_and_recorded() {
+    let cache = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let config = Config {
+        install_method: InstallMethod::Archive,
+        install_root: Some(root.path().to_path_buf()),
+        state_dir: Some(cache.path().join("state")),
+        ..config(&cache)
+    };
+    let archive = sdk_archive();
+    let request = InstallRequest { version: Some("8.0.404".to_string()), ..InstallRequest::default() };
+
+    let (tampered, _) = archive_client(&archive, &"0".repeat(128));
+    let err = install_sdk(&tampered, &SystemRunner, &request, &config).unwrap_err();
+    assert_eq!(err.code(), "hash-mismatch");
+    assert!(!root.path().join("sdk").exists());
+
+    let hash: String = Sha512::digest(&archive).iter().map(|b| format!("{:02x}", b)).collect();
+    let (client, url) = archive_client(&archive, &hash);
+    let output = install_sdk(&client, &SystemRunner, &request, &config).unwrap();
+    assert!(output.contains("Extracted 2 files"), "{}", output);
+    assert_eq!(std::fs::read(root.path().join("sdk/8.0.404/dotnet.dll")).unwrap(), b"dll");
+    assert!(!cache.path().join("archives").read_dir().unwrap().any(|_| true));
+    let record = provenance::find(&config, root.path(), "8.0.404").unwrap().unwrap();
+    assert_eq!(record.script_url, url);
+}
+
+#[test]
+fn archive_installs_reuse_a_verified_archive_already_in_the_cache() {
+    let cache = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let config = Config {
+        install_method: InstallMethod::Archive,
+        install_root: Some(root.path().to_path_buf()),
+        state_dir: Some(cache.path().join("state")),
+        ..config(&cache)
+    };
+    let archive = sdk_archive();
+    let hash: String = Sha512::digest(&archive).iter().map(|b| format!("{:02x}", b)).collect();
+    let (client, url) = archive_client(&archive, &hash);
+    // Il client risponde ancora ai metadati, ma l'archivio c'è solo nella cache, come in un bundle offline
+    let offline_client = StaticClient::new()
+        .with(INDEX_URL, client.get(INDEX_URL).unwrap())
+        .with("https://example.com/8.0.json", client.get("https://example.com/8.0.json").unwrap());
+    std::fs::create_dir_all(cache.path().join("archives")).unwrap();
+    std::fs::write(cache.path().join("archives").join(format!("dotnet-sdk-{}.tar.gz", platform::rid())), &archive).unwrap();
+
+    let request = InstallRequest { version: Some("8.0.404".to_string()), ..InstallRequest::default() };
+    let output = install_sdk(&offline_client, &SystemRunner, &request, &config).unwrap();
+    assert!(output.contains("Extracted 2 files"), "{}", output);
+    assert_eq!(provenance::find(&config, root.path(), "8.0.404").unwrap().unwrap().script_url, url);
+}
+
+#[test]
+fn install_estimates_come_from_the_metadata_a_head_request_or_the_cache() {
+    let cache = tempfile::tempdir().unwrap();
+    let config = config(&cache);
+    let archive = vec![0u8; 1000];
+    let (client, _) = archive_client(&archive, "");
+    // Senza `size` nei metadati la dimensione viene dal server
+    let estimate = estimate_install(&client, &config, "8.0.404").unwrap().unwrap();
+    assert_eq!((estimate.download_bytes, estimate.extracted_bytes), (1000, 1000 * EXTRACTION_RATIO));
+
+    let channel = String::from_utf8(client.get("https://example.com/8.0.json").unwrap()).unwrap();
+    let sized = StaticClient::new()
+        .with(INDEX_URL, client.get(INDEX_URL).unwrap())
+        .with("https://example.com/8.0.json", channel.replace(r#""hash": """#, r#""hash": "", "size": 2048"#));
+    assert_eq!(estimate_install(&sized, &config, "8.0.404").unwrap().unwrap().download_bytes, 2048);
+
+    std::fs::create_dir_all(cache.path().join("archives")).unwrap();
+    std::fs::write(cache.path().join("archives").join(format!("dotnet-sdk-{}.tar.gz", platform::rid())), &archive).unwrap();
+    let cached = estimate_install(&sized, &config, "8.0.404").unwrap().unwrap();
+    assert_eq!((cached.download_bytes, cached.extracted_bytes), (0, 1000 * EXTRACTION_RATIO));
+}
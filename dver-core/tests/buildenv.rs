@@ -0,0 +1,49 @@
+use std::fs;
+
+use dver_core::buildenv::{check_overrides, nuget_configs, sdk_tooling};
+use tempfile::TempDir;
+
+#[test]
+fn tooling_is_read_from_the_sdk_directory() {
+    let sdk = TempDir::new().unwrap();
+    fs::create_dir_all(sdk.path().join("SdkResolvers/Microsoft.DotNet.MSBuildSdkResolver")).unwrap();
+    fs::create_dir_all(sdk.path().join("SdkResolvers/Microsoft.Build.NuGetSdkResolver")).unwrap();
+    fs::write(sdk.path().join("dotnet.deps.json"), r#"{"libraries":{"Microsoft.Build/17.12.6":{},"NuGet.Commands/6.12.1":{}}}"#).unwrap();
+
+    let tooling = sdk_tooling(sdk.path());
+    assert_eq!(tooling.msbuild_version.as_deref(), Some("17.12.6"));
+    assert_eq!(tooling.nuget_version.as_deref(), Some("6.12.1"));
+    assert_eq!(tooling.sdk_resolvers, ["Microsoft.Build.NuGetSdkResolver", "Microsoft.DotNet.MSBuildSdkResolver"]);
+    assert!(sdk_tooling(&sdk.path().join("missing")).msbuild_version.is_none());
+}
+
+#[test]
+fn the_closest_nuget_config_comes_first_and_the_user_one_last() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path().join("repo/src/App");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(dir.path().join("repo/NuGet.Config"), "<configuration />").unwrap();
+    fs::write(project.join("nuget.config"), "<configuration />").unwrap();
+    let user = dir.path().join("user.config");
+    fs::write(&user, "<configuration />").unwrap();
+
+    let configs = nuget_configs(&project, Some(user.clone()));
+    assert_eq!(configs, [project.join("nuget.config"), dir.path().join("repo/NuGet.Config"), user]);
+}
+
+#[test]
+fn overrides_outside_the_resolved_sdk_are_flagged() {
+    let root = TempDir::new().unwrap();
+    let sdk = root.path().join("sdk/8.0.404");
+    fs::create_dir_all(sdk.join("Sdks")).unwrap();
+    let sdks_path = sdk.join("Sdks").display().to_string();
+    let var = |name: &str| match name {
+        "MSBuildSDKsPath" => Some(sdks_path.clone()),
+        "MSBUILD_EXE_PATH" => Some("/usr/share/dotnet/sdk/6.0.428/MSBuild.dll".to_string()),
+        _ => None,
+    };
+    let overrides = check_overrides(var, root.path(), &sdk);
+    assert_eq!(overrides.len(), 2);
+    assert!(overrides.iter().find(|o| o.name == "MSBuildSDKsPath").unwrap().matches);
+    assert!(!overrides.iter().find(|o| o.name == "MSBUILD_EXE_PATH").unwrap().matches);
+}
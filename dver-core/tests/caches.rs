@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use dver_core::caches::{cache_locations, clear_dir, nuget_http_cache_dir, nuget_packages_dir, CacheKind, CacheSource};
+use dver_core::Config;
+
+#[test]
+fn configured_nuget_caches_win_and_reach_dotnet() {
+    let config = Config {
+        nuget_packages: Some(PathBuf::from("/mnt/ci/nuget/packages")),
+        nuget_http_cache: Some(PathBuf::from("/mnt/ci/nuget/http")),
+        cache_dir: Some(PathBuf::from("/mnt/ci/dver")),
+        ..Config::default()
+    };
+    assert_eq!(nuget_packages_dir(&config), Some((PathBuf::from("/mnt/ci/nuget/packages"), CacheSource::Config)));
+    assert_eq!(nuget_http_cache_dir(&config).unwrap().0, PathBuf::from("/mnt/ci/nuget/http"));
+
+    let env = config.dotnet_env();
+    assert_eq!(env["NUGET_PACKAGES"], "/mnt/ci/nuget/packages");
+    assert_eq!(env["NUGET_HTTP_CACHE_PATH"], "/mnt/ci/nuget/http");
+
+    let kinds: Vec<(CacheKind, CacheSource)> = cache_locations(&config).iter().map(|l| (l.kind, l.source)).collect();
+    assert_eq!(
+        kinds,
+        [
+            (CacheKind::Dver, CacheSource::Config),
+            (CacheKind::NugetPackages, CacheSource::Config),
+            (CacheKind::NugetHttpCache, CacheSource::Config),
+        ]
+    );
+}
+
+#[test]
+fn clearing_a_cache_keeps_its_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("newtonsoft.json/13.0.3")).unwrap();
+    std::fs::write(dir.path().join("index.dat"), "x").unwrap();
+    clear_dir(dir.path()).unwrap();
+    assert!(dir.path().is_dir());
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    clear_dir(&dir.path().join("missing")).unwrap();
+}
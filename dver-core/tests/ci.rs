@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use dver_core::ci::{render_env, CiProvider};
+use dver_core::environment::DotnetEnvironment;
+
+fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: BTreeMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    move |name| map.get(name).cloned()
+}
+
+fn environment() -> DotnetEnvironment {
+    DotnetEnvironment {
+        vars: BTreeMap::from([("DOTNET_ROOT".to_string(), "/opt/dotnet".to_string())]),
+        path_prepend: Some(PathBuf::from("/opt/dotnet")),
+    }
+}
+
+#[test]
+fn providers_are_detected_from_their_variables() {
+    assert_eq!(CiProvider::detect_from(vars(&[("TF_BUILD", "True"), ("CI", "true")])), Some(CiProvider::AzurePipelines));
+    assert_eq!(CiProvider::detect_from(vars(&[("GITLAB_CI", "true")])), Some(CiProvider::Gitlab));
+    assert_eq!(CiProvider::detect_from(vars(&[("JENKINS_URL", "https://ci.example.com/")])), Some(CiProvider::Jenkins));
+    assert_eq!(CiProvider::detect_from(vars(&[("TEAMCITY_VERSION", "2024.07")])), Some(CiProvider::Teamcity));
+    assert_eq!(CiProvider::detect_from(vars(&[("CI", "1")])), Some(CiProvider::Other));
+    assert_eq!(CiProvider::detect_from(vars(&[("CI", "false"), ("TF_BUILD", "")])), None);
+}
+
+#[test]
+fn azure_pipelines_gets_logging_commands() {
+    let rendered = render_env(CiProvider::AzurePipelines, &environment(), vars(&[])).unwrap();
+    assert_eq!(rendered.commands, [
+        "##vso[task.setvariable variable=DOTNET_ROOT]/opt/dotnet",
+        "##vso[task.prependpath]/opt/dotnet",
+    ]);
+    assert!(rendered.files.is_empty());
+}
+
+#[test]
+fn github_actions_appends_to_the_job_files() {
+    let github = vars(&[("GITHUB_ENV", "/tmp/env"), ("GITHUB_PATH", "/tmp/path")]);
+    let rendered = render_env(CiProvider::GithubActions, &environment(), github).unwrap();
+    assert!(rendered.commands.is_empty());
+    assert_eq!(rendered.files[&PathBuf::from("/tmp/env")], ["DOTNET_ROOT=/opt/dotnet"]);
+    assert_eq!(rendered.files[&PathBuf::from("/tmp/path")], ["/opt/dotnet"]);
+    assert!(render_env(CiProvider::GithubActions, &environment(), vars(&[])).is_none());
+}
+
+#[test]
+fn teamcity_sets_env_parameters_with_escaping() {
+    let env = DotnetEnvironment {
+        vars: BTreeMap::from([("NAME".to_string(), "it's [x]|y".to_string())]),
+        path_prepend: None,
+    };
+    let rendered = render_env(CiProvider::Teamcity, &env, vars(&[])).unwrap();
+    assert_eq!(rendered.commands, ["##teamcity[setParameter name='env.NAME' value='it|'s |[x|]||y']"]);
+}
+
+#[test]
+fn systems_without_agent_commands_render_nothing() {
+    assert!(render_env(CiProvider::Gitlab, &environment(), vars(&[])).is_none());
+    assert!(render_env(CiProvider::Jenkins, &environment(), vars(&[])).is_none());
+}
+
+#[test]
+fn errors_become_build_annotations() {
+    let message = "SDK 7.0.999 not found\nhint: 100% sure";
+    assert_eq!(
+        CiProvider::AzurePipelines.error_annotation(message).unwrap(),
+        "##vso[task.logissue type=error]SDK 7.0.999 not found%0Ahint: 100%AZP25 sure"
+    );
+    assert_eq!(
+        CiProvider::GithubActions.error_annotation(message).unwrap(),
+        "::error::SDK 7.0.999 not found%0Ahint: 100%25 sure"
+    );
+    assert!(CiProvider::Gitlab.error_annotation(message).is_none());
+}
@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use dver_core::container::{
+    container_rid, devcontainer_block, devcontainer_feature, devcontainer_install_script, dockerfile, images_for,
+    BUNDLE_MOUNT, DEVCONTAINER_DOTNET_ROOT,
+};
+use dver_core::releases::ChannelReleases;
+use dver_core::DverError;
+
+fn channel() -> ChannelReleases {
+    serde_json::from_str(include_str!("fixtures/8.0/releases.json")).unwrap()
+}
+
+#[test]
+fn images_match_the_runtime_shipped_with_the_sdk() {
+    let images = images_for(&[channel()], &"8.0.406".parse().unwrap()).unwrap();
+    assert_eq!(images.sdk_image, "mcr.microsoft.com/dotnet/sdk:8.0.406");
+    assert_eq!(images.runtime_version, "8.0.13");
+    assert_eq!(images.aspnet_version.as_deref(), Some("8.0.13"));
+    assert_eq!(images.runtime_image, "mcr.microsoft.com/dotnet/aspnet:8.0.13");
+    assert_eq!(images.runtime_deps_image, "mcr.microsoft.com/dotnet/runtime-deps:8.0.13");
+
+    let err = images_for(&[channel()], &"8.0.999".parse().unwrap()).unwrap_err();
+    assert!(matches!(err, DverError::NotFound(_)));
+}
+
+#[test]
+fn dockerfile_builds_on_the_sdk_image_or_installs_from_the_bundle() {
+    let images = images_for(&[channel()], &"8.0.406".parse().unwrap()).unwrap();
+    let plain = dockerfile(&images, None);
+    assert!(plain.contains("FROM mcr.microsoft.com/dotnet/sdk:8.0.406 AS build\n"), "{}", plain);
+    assert!(plain.contains("FROM mcr.microsoft.com/dotnet/aspnet:8.0.13\n"), "{}", plain);
+    assert!(!plain.contains("DVER_"), "{}", plain);
+
+    let offline = dockerfile(&images, Some(Path::new("dver-bundle")));
+    assert!(offline.contains("FROM mcr.microsoft.com/dotnet/runtime-deps:8.0.13 AS build\n"), "{}", offline);
+    assert!(offline.contains(&format!("COPY dver-bundle {}\n", BUNDLE_MOUNT)), "{}", offline);
+    assert!(offline.contains("DVER_OFFLINE=true"), "{}", offline);
+    assert!(offline.contains("install --from-project"), "{}", offline);
+    assert!(container_rid().starts_with("linux-"));
+}
+
+#[test]
+fn devcontainer_feature_installs_the_pinned_sdk_with_dver() {
+    let feature = devcontainer_feature("dver", "8.0.404");
+    assert_eq!(feature["id"], "dver");
+    assert_eq!(feature["options"]["version"]["default"], "8.0.404");
+    assert_eq!(feature["containerEnv"]["DOTNET_ROOT"], DEVCONTAINER_DOTNET_ROOT);
+
+    let script = devcontainer_install_script();
+    assert!(script.starts_with("#!/bin/sh\n"));
+    assert!(script.contains(&format!("install --version \"$VERSION\" --install-path {}", DEVCONTAINER_DOTNET_ROOT)), "{}", script);
+
+    let block = devcontainer_block("./dver", "8.0.404");
+    assert_eq!(block["features"]["./dver"]["version"], "8.0.404");
+    assert!(block["postCreateCommand"].as_str().unwrap().contains("install --from-project"));
+}
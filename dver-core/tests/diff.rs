@@ -0,0 +1,53 @@
+use dver_core::diff::{diff_files, diff_sdks};
+use dver_core::manifest::{FileEntry, InstallManifest};
+use dver_core::releases::ChannelReleases;
+use dver_core::DverError;
+
+const CHANNEL_8_0: &str = include_str!("fixtures/8.0/releases.json");
+
+fn channels() -> Vec<ChannelReleases> {
+    vec![serde_json::from_str(CHANNEL_8_0).unwrap()]
+}
+
+#[test]
+fn newer_sdks_list_their_components_and_the_cves_fixed_on_the_way() {
+    let diff = diff_sdks(&channels(), "8.0.100", "8.0.406").unwrap();
+    assert_eq!(diff.from.runtime.as_deref(), Some("8.0.0"));
+    assert_eq!(diff.to.runtime.as_deref(), Some("8.0.13"));
+    assert_eq!(diff.from.aspnetcore.as_deref(), Some("8.0.0"));
+    assert_eq!((diff.from.csharp.as_deref(), diff.to.csharp.as_deref()), (Some("12.0"), Some("12.0")));
+    assert_eq!(diff.to.release_date.map(|d| d.to_string()).as_deref(), Some("2025-02-11"));
+    let cves: Vec<&str> = diff.fixed_cves.iter().map(|c| c.cve_id.as_str()).collect();
+    assert_eq!(cves, ["CVE-2025-21172"]);
+
+    // Le voci di `sdks` prendono le versioni dei linguaggi dall'SDK principale del rilascio
+    let band = diff_sdks(&channels(), "8.0.406", "8.0.113").unwrap();
+    assert_eq!(band.to.csharp.as_deref(), Some("12.0"));
+    assert!(band.fixed_cves.is_empty());
+}
+
+#[test]
+fn going_back_fixes_nothing_and_unknown_sdks_are_not_found() {
+    let diff = diff_sdks(&channels(), "8.0.406", "8.0.100").unwrap();
+    assert!(diff.fixed_cves.is_empty());
+    assert!(matches!(diff_sdks(&channels(), "8.0.100", "8.0.999"), Err(DverError::NotFound(_))));
+}
+
+#[test]
+fn file_diffs_split_added_removed_and_changed() {
+    let manifest = |version: &str, files: &[(&str, &str)]| InstallManifest {
+        version: version.to_string(),
+        root: "/dotnet".into(),
+        files: files
+            .iter()
+            .map(|(path, sha256)| FileEntry { path: path.to_string(), size: 1, sha256: sha256.to_string() })
+            .collect(),
+    };
+    let from = manifest("8.0.403", &[("dotnet.dll", "a"), ("old.dll", "b"), ("same.json", "c")]);
+    let to = manifest("8.0.404", &[("dotnet.dll", "z"), ("new.dll", "d"), ("same.json", "c")]);
+    let diff = diff_files(&from, &to);
+    assert_eq!(diff.added, ["new.dll"]);
+    assert_eq!(diff.removed, ["old.dll"]);
+    assert_eq!(diff.changed, ["dotnet.dll"]);
+    assert_eq!(diff.unchanged, 1);
+}
@@ -0,0 +1,56 @@
+use std::fs;
+
+use dver_core::disk::{dir_size, format_size, sizes};
+use dver_core::Config;
+use tempfile::TempDir;
+
+#[test]
+fn dir_size_adds_up_files_in_nested_directories() {
+    let dir = TempDir::new().unwrap();
+    for i in 0..20 {
+        let sub = dir.path().join(format!("pack{}", i)).join("lib");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(sub.join("a.dll"), vec![0u8; 100]).unwrap();
+        fs::write(dir.path().join(format!("pack{}", i)).join("b.xml"), vec![0u8; 10]).unwrap();
+    }
+    fs::write(dir.path().join("dotnet.dll"), vec![0u8; 7]).unwrap();
+    assert_eq!(dir_size(dir.path()).unwrap(), 20 * 110 + 7);
+    assert!(dir_size(&dir.path().join("missing")).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn dir_size_does_not_follow_symlinks() {
+    let dir = TempDir::new().unwrap();
+    let outside = TempDir::new().unwrap();
+    fs::write(outside.path().join("big.bin"), vec![0u8; 10_000]).unwrap();
+    std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+    assert!(dir_size(dir.path()).unwrap() < 10_000);
+}
+
+#[test]
+fn sizes_are_cached_until_the_directory_changes() {
+    let state = TempDir::new().unwrap();
+    let sdk = TempDir::new().unwrap();
+    let config = Config { state_dir: Some(state.path().to_path_buf()), ..Default::default() };
+    fs::write(sdk.path().join("a.dll"), vec![0u8; 50]).unwrap();
+    let dirs = vec![sdk.path().to_path_buf()];
+    assert_eq!(sizes(&config, &dirs).unwrap(), vec![50]);
+    assert!(state.path().join("sizes.json").is_file());
+
+    // Un file cambiato in una sottocartella non tocca la data della cartella: resta la cache
+    fs::write(sdk.path().join("a.dll"), vec![0u8; 80]).unwrap();
+    assert_eq!(sizes(&config, &dirs).unwrap(), vec![50]);
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    fs::write(sdk.path().join("b.dll"), vec![0u8; 20]).unwrap();
+    assert_eq!(sizes(&config, &dirs).unwrap(), vec![100]);
+}
+
+#[test]
+fn sizes_are_formatted_in_binary_units() {
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(212 * 1024 * 1024 + 400 * 1024), "212.4 MiB");
+    assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+}
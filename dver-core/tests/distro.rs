@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use dver_core::distro::{
+    conflicts, parse_dpkg_query, parse_snap_list, replacement_commands, restore_commands, PackageSource, SystemPackage,
+};
+use dver_core::process::command_line;
+
+const SNAP_LIST: &str = "Name        Version          Rev    Tracking       Publisher   Notes
+core22      20241001         1663   latest/stable  canonical✓  base
+dotnet-sdk  8.0.404          253    latest/stable  dotnetcore✓ classic
+";
+
+const DPKG_QUERY: &str = "dotnet-host-8.0\t8.0.11-0ubuntu1~24.04.1\tii 
+dotnet-sdk-8.0\t8.0.111-0ubuntu1~24.04.1\tii 
+dotnet-host\t9.0.0-1\tii 
+dotnet-sdk-7.0\t7.0.410-1\trc 
+";
+
+#[test]
+fn snaps_and_apt_packages_are_told_apart() {
+    let snaps = parse_snap_list(SNAP_LIST);
+    assert_eq!(snaps, [SystemPackage { name: "dotnet-sdk".to_string(), version: "8.0.404".to_string(), source: PackageSource::Snap }]);
+    assert_eq!(snaps[0].root(), PathBuf::from("/snap/dotnet-sdk/current"));
+
+    // I pacchetti rimossi ma non purgati (`rc`) non contano
+    let apt = parse_dpkg_query(DPKG_QUERY);
+    let sources: Vec<(&str, PackageSource)> = apt.iter().map(|p| (p.name.as_str(), p.source)).collect();
+    assert_eq!(
+        sources,
+        [("dotnet-host-8.0", PackageSource::Ubuntu), ("dotnet-sdk-8.0", PackageSource::Ubuntu), ("dotnet-host", PackageSource::Microsoft)]
+    );
+    assert_eq!(apt[0].root(), PathBuf::from("/usr/lib/dotnet"));
+    assert_eq!(apt[2].root(), PathBuf::from("/usr/share/dotnet"));
+}
+
+#[test]
+fn conflicts_and_commands_follow_the_packages() {
+    let mut packages = parse_snap_list(SNAP_LIST);
+    packages.extend(parse_dpkg_query(DPKG_QUERY));
+    let messages = conflicts(&packages);
+    assert_eq!(messages.len(), 3, "{:?}", messages);
+    assert!(messages[1].contains("host/fxr"), "{}", messages[1]);
+    assert!(conflicts(&packages[..1]).len() == 1);
+
+    let lines = |commands: Vec<std::process::Command>| commands.iter().map(command_line).collect::<Vec<_>>();
+    assert_eq!(
+        lines(replacement_commands(&packages, true, true)),
+        [
+            "sudo snap disable dotnet-sdk",
+            "sudo dpkg-divert --local --rename --divert /usr/bin/dotnet.distrib --add /usr/bin/dotnet",
+        ]
+    );
+    // Come root e senza il link resta solo lo snap
+    assert_eq!(lines(replacement_commands(&packages, false, false)), ["snap disable dotnet-sdk"]);
+    assert_eq!(
+        lines(restore_commands(&packages, true)),
+        ["sudo snap enable dotnet-sdk", "sudo dpkg-divert --rename --remove /usr/bin/dotnet"]
+    );
+    assert!(replacement_commands(&[], true, true).is_empty());
+}
@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+
+use dver_core::environment::{
+    merge_vscode_settings, msys_path, path_position, vscode_terminal_env_key, DotnetEnvironment, ShellKind,
+};
+use dver_core::platform::{self, Arch, Windows};
+use dver_core::Config;
+use serde_json::{json, Value};
+
+#[test]
+fn vscode_settings_point_the_extension_and_terminal_at_the_root() {
+    let env = DotnetEnvironment::default();
+    let settings = env.vscode_settings(Path::new("/opt/dotnet"));
+    assert_eq!(settings["dotnet.dotnetPath"], "/opt/dotnet");
+    assert_eq!(settings["omnisharp.dotnetPath"], "/opt/dotnet");
+    let terminal = &settings[vscode_terminal_env_key()];
+    assert_eq!(terminal["DOTNET_ROOT"], "/opt/dotnet");
+    assert!(terminal["PATH"].as_str().unwrap().starts_with("/opt/dotnet"));
+    assert!(terminal["PATH"].as_str().unwrap().ends_with("${env:PATH}"));
+}
+
+#[test]
+fn merging_keeps_other_settings_and_terminal_variables() {
+    let key = vscode_terminal_env_key();
+    let existing = json!({
+        "editor.formatOnSave": true,
+        "dotnet.dotnetPath": "/usr/share/dotnet",
+        key: { "ASPNETCORE_ENVIRONMENT": "Development" },
+    });
+    let settings = DotnetEnvironment::default().vscode_settings(Path::new("/opt/dotnet"));
+    let merged: Value = serde_json::from_str(&merge_vscode_settings(Some(&existing.to_string()), settings).unwrap()).unwrap();
+    assert_eq!(merged["editor.formatOnSave"], true);
+    assert_eq!(merged["dotnet.dotnetPath"], "/opt/dotnet");
+    assert_eq!(merged[key]["ASPNETCORE_ENVIRONMENT"], "Development");
+    assert_eq!(merged[key]["DOTNET_ROOT"], "/opt/dotnet");
+}
+
+#[test]
+fn settings_with_comments_are_not_rewritten() {
+    let settings = DotnetEnvironment::default().vscode_settings(Path::new("/opt/dotnet"));
+    let err = merge_vscode_settings(Some("{\n  // tabs\n  \"editor.tabSize\": 4\n}"), settings).unwrap_err();
+    assert_eq!(err.code(), "parse");
+}
+
+#[test]
+fn foreign_architecture_roots_are_exported_next_to_the_native_one() {
+    let root = tempfile::tempdir().unwrap();
+    let foreign = if Arch::native() == Some(Arch::Arm64) { Arch::X64 } else { Arch::Arm64 };
+    let config = Config { install_root: Some(root.path().to_path_buf()), ..Config::default() };
+    let var = platform::current().arch_root_vars(foreign)[0];
+    assert!(!DotnetEnvironment::from_config(&config).vars.contains_key(var));
+
+    let foreign_root = root.path().join(foreign.name());
+    std::fs::create_dir_all(&foreign_root).unwrap();
+    let env = DotnetEnvironment::from_config(&config);
+    assert_eq!(env.vars[var], foreign_root.display().to_string());
+
+    // Scegliendo l'altra architettura cambia il `dotnet` nel PATH, non DOTNET_ROOT
+    let env = DotnetEnvironment::from_config(&Config { arch: Some(foreign), ..config });
+    assert_eq!(env.path_prepend, Some(foreign_root));
+    assert_eq!(env.vars["DOTNET_ROOT"], root.path().display().to_string());
+}
+
+#[test]
+fn posix_shells_on_windows_get_msys_paths_and_the_rest_keep_windows_ones() {
+    let env = DotnetEnvironment {
+        vars: [("DOTNET_ROOT".to_string(), r"C:\Users\ada\.dotnet".to_string())].into_iter().collect(),
+        path_prepend: Some(r"C:\Users\ada\.dotnet".into()),
+    };
+    let bash = env.render_for(ShellKind::Bash, &Windows);
+    // `dotnet.exe` legge DOTNET_ROOT come percorso Windows
+    assert_eq!(bash, "export DOTNET_ROOT='C:\\Users\\ada\\.dotnet'\nexport PATH='/c/Users/ada/.dotnet':\"$PATH\"\n");
+    let cmd = env.render_for(ShellKind::Cmd, &Windows);
+    assert!(cmd.ends_with("set \"PATH=C:\\Users\\ada\\.dotnet;%PATH%\"\n"), "{}", cmd);
+    let pwsh = env.render_for(ShellKind::Powershell, &Windows);
+    assert!(pwsh.contains("[IO.Path]::PathSeparator"), "{}", pwsh);
+
+    assert_eq!(msys_path(r"D:\tools\dotnet"), "/d/tools/dotnet");
+    assert_eq!(msys_path("/home/ada/.dotnet"), "/home/ada/.dotnet");
+}
+
+#[test]
+fn path_entries_are_matched_by_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let entries = [PathBuf::from("/nowhere"), dir.path().join(".")];
+    assert_eq!(path_position(&entries, dir.path()), Some(1));
+    assert_eq!(path_position(&entries, Path::new("/elsewhere")), None);
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::PathBuf;
+
+use dver_core::inventory::{
+    list_installed_runtimes, list_installed_sdks, parse_list_runtimes, parse_list_sdks, parse_version_output, read_list_runtimes, read_list_sdks,
+    resolve_sdk, roll_forward_sdk,
+};
+use dver_core::process::ScriptedRunner;
+use dver_core::project::GlobalJson;
+use dver_core::{Config, DverError};
+use tempfile::TempDir;
+
+fn installed() -> Vec<String> {
+    ["8.0.403", "8.0.404", "8.0.307", "9.0.100-rc.2.24474.11"].iter().map(|v| v.to_string()).collect()
+}
+
+fn pin(version: &str, roll_forward: Option<&str>) -> GlobalJson {
+    GlobalJson {
+        path: PathBuf::from("global.json"),
+        version: Some(version.to_string()),
+        roll_forward: roll_forward.map(|r| r.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn without_global_json_the_newest_sdk_wins() {
+    assert_eq!(resolve_sdk(&installed(), None).as_deref(), Some("9.0.100-rc.2.24474.11"));
+    assert_eq!(resolve_sdk(&[], None), None);
+}
+
+#[test]
+fn global_json_rolls_forward_within_the_feature_band() {
+    let installed = installed();
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.400", None))).as_deref(), Some("8.0.404"));
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.403", Some("patch")))).as_deref(), Some("8.0.403"));
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.300", Some("disable")))), None);
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.307", Some("disable")))).as_deref(), Some("8.0.307"));
+    // Nessun SDK della band o politica sconosciuta: decide dotnet
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.405", None))), None);
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.100", Some("sometimes")))), None);
+    // Le politiche oltre la band seguono roll_forward_sdk
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.300", Some("feature")))).as_deref(), Some("8.0.307"));
+    assert_eq!(resolve_sdk(&installed, Some(&pin("8.0.100", Some("latestMajor")))).as_deref(), Some("9.0.100-rc.2.24474.11"));
+}
+
+#[test]
+fn every_roll_forward_policy_picks_what_dotnet_would() {
+    let installed: Vec<String> = ["8.0.303", "8.0.307", "8.0.404", "9.0.100", "9.0.102"].iter().map(|v| v.to_string()).collect();
+    let pick = |wanted: &str, policy: &str| roll_forward_sdk(&installed, wanted, Some(policy));
+    assert_eq!(pick("8.0.300", "disable"), None);
+    assert_eq!(pick("8.0.303", "patch").as_deref(), Some("8.0.303"));
+    assert_eq!(pick("8.0.300", "patch").as_deref(), Some("8.0.307"));
+    assert_eq!(pick("8.0.200", "latestPatch"), None);
+    assert_eq!(pick("8.0.200", "feature").as_deref(), Some("8.0.307"));
+    assert_eq!(pick("8.0.200", "latestFeature").as_deref(), Some("8.0.404"));
+    assert_eq!(pick("8.0.405", "minor"), None);
+    assert_eq!(pick("8.0.405", "major").as_deref(), Some("9.0.102"));
+    assert_eq!(pick("8.0.100", "latestMajor").as_deref(), Some("9.0.102"));
+    assert_eq!(pick("8.0.100", "sometimes"), None);
+    assert_eq!(roll_forward_sdk(&installed, "8.0.400", None).as_deref(), Some("8.0.404"));
+}
+
+#[test]
+fn dotnet_root_is_scanned_without_running_dotnet() {
+    let root = TempDir::new().unwrap();
+    fs::create_dir_all(root.path().join("sdk/8.0.404")).unwrap();
+    fs::create_dir_all(root.path().join("shared/Microsoft.NETCore.App/8.0.11")).unwrap();
+    let config = Config {
+        env: [("DOTNET_ROOT".to_string(), root.path().display().to_string())].into_iter().collect(),
+        ..Config::default()
+    };
+    // ScriptedRunner fallisce ogni comando: se dotnet venisse avviato, l'elenco fallirebbe
+    let runner = ScriptedRunner::new();
+    let sdks = list_installed_sdks(&runner, &config).unwrap();
+    assert_eq!(sdks.len(), 1);
+    assert_eq!(sdks[0].path, root.path().join("sdk/8.0.404"));
+    let runtimes = list_installed_runtimes(&runner, &config).unwrap();
+    assert_eq!((runtimes[0].name.as_str(), runtimes[0].version.as_str()), ("Microsoft.NETCore.App", "8.0.11"));
+}
+
+// Output di `dotnet` catturati su macchine reali
+const SDKS_LINUX: &str = include_str!("fixtures/dotnet-list/sdks-linux.txt");
+const SDKS_WINDOWS: &str = include_str!("fixtures/dotnet-list/sdks-windows.txt");
+const SDKS_NONE_DE: &str = include_str!("fixtures/dotnet-list/sdks-none-de.txt");
+const RUNTIMES_WINDOWS: &str = include_str!("fixtures/dotnet-list/runtimes-windows.txt");
+const SDKS_MACOS_3_1: &str = include_str!("fixtures/dotnet-list/sdks-macos-3.1.txt");
+const RUNTIMES_LINUX_6_0: &str = include_str!("fixtures/dotnet-list/runtimes-linux-6.0.txt");
+const VERSION_FIRST_RUN_3_1: &str = include_str!("fixtures/dotnet-list/version-first-run-3.1.txt");
+
+#[test]
+fn list_sdks_output_is_parsed_line_by_line() {
+    let sdks = parse_list_sdks(SDKS_LINUX);
+    let versions: Vec<&str> = sdks.iter().map(|sdk| sdk.version.as_str()).collect();
+    assert_eq!(versions, ["6.0.428", "8.0.404", "9.0.100-rc.2.24474.11"]);
+    assert_eq!(sdks[1].path, PathBuf::from("/usr/share/dotnet/sdk/8.0.404"));
+}
+
+#[test]
+fn windows_paths_with_spaces_non_ascii_and_brackets_are_kept_whole() {
+    // Con BOM e fine riga CRLF, come da una console Windows reindirizzata
+    let sdks = parse_list_sdks(SDKS_WINDOWS);
+    let bases: Vec<String> = sdks.iter().map(|sdk| sdk.path.parent().unwrap().display().to_string()).collect();
+    assert_eq!(bases, [r"C:\Program Files\dotnet\sdk", r"C:\Users\José Muñoz\.dotnet\sdk", r"D:\tools [x64]\dotnet\sdk"]);
+    assert_eq!(sdks[0].version, "8.0.404");
+
+    let runtimes = parse_list_runtimes(RUNTIMES_WINDOWS);
+    assert_eq!(runtimes.len(), 3);
+    assert_eq!((runtimes[1].name.as_str(), runtimes[1].version.as_str()), ("Microsoft.NETCore.App", "8.0.11"));
+    assert!(runtimes[1].path.display().to_string().starts_with(r"C:\Program Files\dotnet\shared\Microsoft.NETCore.App"));
+}
+
+#[test]
+fn localized_messages_and_stray_lines_are_ignored() {
+    assert!(parse_list_sdks(SDKS_NONE_DE).is_empty());
+    assert!(parse_list_sdks("8.0.404 /usr/share/dotnet/sdk\n[/usr/share/dotnet/sdk]\nwarning: [x] []\n").is_empty());
+    assert!(parse_list_runtimes("Microsoft.NETCore.App [/usr/share/dotnet/shared/Microsoft.NETCore.App]\n").is_empty());
+}
+
+#[test]
+fn output_of_older_sdks_and_unknown_annotations_is_still_read() {
+    let versions: Vec<String> = parse_list_sdks(SDKS_MACOS_3_1).into_iter().map(|sdk| sdk.version).collect();
+    assert_eq!(versions, ["3.1.426", "5.0.408", "6.0.428"]);
+    let runtimes = parse_list_runtimes(RUNTIMES_LINUX_6_0);
+    assert_eq!(runtimes[1].path, PathBuf::from("/usr/lib/dotnet/shared/Microsoft.NETCore.App/6.0.36"));
+    // Parole in più prima della cartella, come potrebbe aggiungerle un SDK futuro
+    let sdks = parse_list_sdks("10.0.100 (preview) [/usr/share/dotnet/sdk]\n");
+    assert_eq!(sdks[0].path, PathBuf::from("/usr/share/dotnet/sdk/10.0.100"));
+
+    // Il benvenuto del primo avvio di .NET Core 3.1 precede la versione
+    assert_eq!(parse_version_output(VERSION_FIRST_RUN_3_1).as_deref(), Some("3.1.426"));
+    assert_eq!(parse_version_output("\u{feff}8.0.404\r\n").as_deref(), Some("8.0.404"));
+    assert_eq!(parse_version_output("Welcome to .NET!\n"), None);
+}
+
+#[test]
+fn unknown_listings_fall_back_to_their_folders_or_fail_loudly() {
+    let root = TempDir::new().unwrap();
+    fs::create_dir_all(root.path().join("sdk/8.0.404")).unwrap();
+    fs::create_dir_all(root.path().join("shared/Microsoft.NETCore.App/8.0.11")).unwrap();
+    let config = Config::default();
+
+    let sdks = read_list_sdks(&config, &format!("sdk: 8.0.404 [{}]\n", root.path().join("sdk").display())).unwrap();
+    assert_eq!(sdks[0].path, root.path().join("sdk/8.0.404"));
+    let listing = format!("runtime: Microsoft.NETCore.App@8.0.11 [{}]\n", root.path().join("shared/Microsoft.NETCore.App").display());
+    assert_eq!(read_list_runtimes(&config, &listing).unwrap()[0].version, "8.0.11");
+
+    // Niente da leggere nemmeno dalle cartelle: un errore e non un elenco vuoto
+    let err = read_list_sdks(&config, "SDK 8.0.404 at /nowhere/sdk\n").unwrap_err();
+    assert!(matches!(&err, DverError::Parse(msg) if msg.contains("--debug-parse")), "{}", err);
+    // Un messaggio senza versioni resta un elenco vuoto
+    assert!(read_list_sdks(&config, SDKS_NONE_DE).unwrap().is_empty());
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use dver_core::journal::{append, journal_file, load, parse_day, JournalEntry, JournalFilter, Operation};
+use dver_core::{Config, DverError};
+use tempfile::TempDir;
+
+fn entry(operation: Operation, day: u32, error: Option<&str>) -> JournalEntry {
+    let failure = error.map(|e| DverError::NotFound(e.to_string()));
+    JournalEntry {
+        at: Utc.with_ymd_and_hms(2025, 11, day, 9, 30, 0).unwrap(),
+        ..JournalEntry::new(operation, vec!["install".to_string()], failure.as_ref().map_or(Ok(()), Err), vec![PathBuf::from("/r/sdk/8.0.404")])
+    }
+}
+
+#[test]
+fn entries_are_appended_and_unreadable_lines_skipped() {
+    let state = TempDir::new().unwrap();
+    let config = Config { state_dir: Some(state.path().to_path_buf()), ..Config::default() };
+    assert!(load(&config).unwrap().is_empty());
+
+    let first = entry(Operation::Install, 10, None);
+    let second = entry(Operation::Uninstall, 12, Some("SDK 8.0.404 is not installed"));
+    append(&config, &first).unwrap();
+    let path = journal_file(&config).unwrap();
+    std::fs::write(&path, format!("{}{{truncated\n", std::fs::read_to_string(&path).unwrap())).unwrap();
+    append(&config, &second).unwrap();
+
+    let entries = load(&config).unwrap();
+    assert_eq!(entries, [first, second.clone()]);
+    assert!(!second.success);
+    assert_eq!(second.error.as_deref(), Some("SDK 8.0.404 is not installed"));
+}
+
+#[test]
+fn filters_combine_operation_outcome_and_day() {
+    let entries = [
+        entry(Operation::Install, 10, None),
+        entry(Operation::Use, 11, None),
+        entry(Operation::Install, 12, Some("no such version")),
+    ];
+    let days = |filter: JournalFilter| -> Vec<u32> {
+        use chrono::Datelike;
+        entries.iter().filter(|e| filter.matches(e)).map(|e| e.at.day()).collect()
+    };
+    assert_eq!(days(JournalFilter::default()), [10, 11, 12]);
+    assert_eq!(days(JournalFilter { operation: Some(Operation::Install), ..Default::default() }), [10, 12]);
+    assert_eq!(days(JournalFilter { failed: true, ..Default::default() }), [12]);
+    assert_eq!(days(JournalFilter { since: Some(parse_day("2025-11-11").unwrap()), ..Default::default() }), [11, 12]);
+
+    assert_eq!(parse_day("2025-11-11").unwrap(), NaiveDate::from_ymd_opt(2025, 11, 11).unwrap());
+    assert!(matches!(parse_day("11/11/2025"), Err(DverError::Usage(_))));
+}
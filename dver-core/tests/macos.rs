@@ -0,0 +1,50 @@
+use std::path::{Path, PathBuf};
+
+use dver_core::macos::{is_quarantined, is_translated, parse_pkg_receipts, strip_quarantine, PkgSdk};
+use dver_core::platform::Arch;
+use dver_core::process::ScriptedRunner;
+
+const PKGUTIL: &str = "com.apple.pkg.CLTools_Executables
+com.microsoft.dotnet.dev.8.0.404.component.osx.arm64
+com.microsoft.dotnet.dev.9.0.100-rc.2.24474.11.component.osx.x64
+com.microsoft.dotnet.sharedframework.Microsoft.NETCore.App.8.0.11.component.osx.arm64
+com.microsoft.dotnet.sharedhost.component.osx.arm64
+";
+
+#[test]
+fn pkg_receipts_name_the_sdks_and_their_arch() {
+    let sdks = parse_pkg_receipts(PKGUTIL);
+    assert_eq!(
+        sdks,
+        [
+            PkgSdk { version: "8.0.404".to_string(), arch: Some(Arch::Arm64) },
+            PkgSdk { version: "9.0.100-rc.2.24474.11".to_string(), arch: Some(Arch::X64) },
+        ]
+    );
+    // Su Apple silicon gli SDK x64 del pacchetto stanno in una sottocartella
+    assert_eq!(sdks[0].root(Some(Arch::Arm64)), PathBuf::from("/usr/local/share/dotnet"));
+    assert_eq!(sdks[1].root(Some(Arch::Arm64)), PathBuf::from("/usr/local/share/dotnet/x64"));
+    assert_eq!(sdks[1].root(Some(Arch::X64)), PathBuf::from("/usr/local/share/dotnet"));
+}
+
+#[test]
+fn quarantine_is_read_and_stripped_with_xattr() {
+    let dotnet = Path::new("/Users/ada/.dotnet/dotnet");
+    let runner = ScriptedRunner::new().with("xattr -p com.apple.quarantine /Users/ada/.dotnet/dotnet", "0083;6731f0e2;Safari;");
+    assert!(is_quarantined(&runner, dotnet));
+    // `xattr -p` esce con 1 quando l'attributo manca
+    let runner = ScriptedRunner::new().with_status("xattr -p com.apple.quarantine /Users/ada/.dotnet/dotnet", 1, "", "No such xattr");
+    assert!(!is_quarantined(&runner, dotnet));
+
+    let runner = ScriptedRunner::new().with_status("xattr -d -r com.apple.quarantine /Users/ada/.dotnet", 1, "", "No such xattr");
+    strip_quarantine(&runner, Path::new("/Users/ada/.dotnet")).unwrap();
+    assert!(strip_quarantine(&ScriptedRunner::new(), Path::new("/Users/ada/.dotnet")).is_err());
+}
+
+#[test]
+fn rosetta_translation_comes_from_sysctl() {
+    assert!(is_translated(&ScriptedRunner::new().with("sysctl -n sysctl.proc_translated", "1\n")));
+    assert!(!is_translated(&ScriptedRunner::new().with("sysctl -n sysctl.proc_translated", "0\n")));
+    // Sui Mac Intel la chiave non esiste
+    assert!(!is_translated(&ScriptedRunner::new()));
+}
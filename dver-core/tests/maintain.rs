@@ -0,0 +1,40 @@
+use dver_core::maintain::{default_channel_version, patches_to_install, superseded};
+use dver_core::releases::{ChannelReleases, ReleaseIndex};
+
+const INDEX: &str = include_str!("fixtures/releases-index.json");
+const CHANNEL_8_0: &str = include_str!("fixtures/8.0/releases.json");
+
+fn channels() -> Vec<ChannelReleases> {
+    vec![serde_json::from_str(CHANNEL_8_0).unwrap()]
+}
+
+fn versions(list: &[&str]) -> Vec<String> {
+    list.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn default_channels_resolve_to_a_supported_channel() {
+    let index: ReleaseIndex = serde_json::from_str(INDEX).unwrap();
+    // 10.0 è ancora un'anteprima, 3.1 è fuori supporto
+    assert_eq!(default_channel_version(&index, "LTS").as_deref(), Some("8.0"));
+    assert_eq!(default_channel_version(&index, "sts").as_deref(), Some("9.0"));
+    assert_eq!(default_channel_version(&index, "8.0.4xx").as_deref(), Some("8.0"));
+    assert_eq!(default_channel_version(&index, "latest"), None);
+}
+
+#[test]
+fn missing_patches_follow_installed_bands_and_the_default_channel() {
+    let installed = versions(&["8.0.100", "8.0.403"]);
+    assert_eq!(patches_to_install(&channels(), &installed, None), ["8.0.113", "8.0.406"]);
+    assert_eq!(patches_to_install(&channels(), &versions(&["8.0.406"]), Some("8.0.3xx")), ["8.0.310"]);
+    // Tutto aggiornato: ripetere non installa nulla
+    assert!(patches_to_install(&channels(), &versions(&["8.0.113", "8.0.406"]), Some("8.0")).is_empty());
+}
+
+#[test]
+fn superseded_patches_keep_the_newest_per_band() {
+    let installed = versions(&["8.0.404", "8.0.100", "8.0.403", "8.0.406", "9.0.100-rc.2.24474.11"]);
+    assert_eq!(superseded(&installed, 1), ["8.0.403", "8.0.404"]);
+    assert_eq!(superseded(&installed, 2), ["8.0.403"]);
+    assert_eq!(superseded(&installed, 0), superseded(&installed, 1));
+}
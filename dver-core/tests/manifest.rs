@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+use dver_core::manifest::{build, sha256_file, verify};
+use tempfile::TempDir;
+
+fn install_fake_sdk(root: &Path) {
+    let sdk = root.join("sdk/8.0.404");
+    fs::create_dir_all(sdk.join("Sdks")).unwrap();
+    fs::write(sdk.join("dotnet.dll"), "sdk 8.0.404").unwrap();
+    fs::write(sdk.join("Sdks/Microsoft.NET.Sdk.props"), "<Project />").unwrap();
+}
+
+#[test]
+fn manifest_lists_files_with_relative_paths_and_hashes() {
+    let dir = TempDir::new().unwrap();
+    install_fake_sdk(dir.path());
+    let manifest = build(dir.path(), "8.0.404").unwrap();
+    let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, ["Sdks/Microsoft.NET.Sdk.props", "dotnet.dll"]);
+    assert_eq!(manifest.files[1].size, 11);
+    assert_eq!(manifest.files[1].sha256, sha256_file(&dir.path().join("sdk/8.0.404/dotnet.dll")).unwrap());
+    assert!(verify(&manifest).unwrap().is_intact());
+}
+
+#[test]
+fn verification_reports_missing_modified_and_added_files() {
+    let dir = TempDir::new().unwrap();
+    install_fake_sdk(dir.path());
+    let manifest = build(dir.path(), "8.0.404").unwrap();
+
+    let sdk = dir.path().join("sdk/8.0.404");
+    fs::remove_file(sdk.join("Sdks/Microsoft.NET.Sdk.props")).unwrap();
+    // Stessa dimensione, contenuto diverso
+    fs::write(sdk.join("dotnet.dll"), "sdk 8.0.405").unwrap();
+    fs::write(sdk.join("extra.txt"), "").unwrap();
+
+    let report = verify(&manifest).unwrap();
+    assert!(!report.is_intact());
+    assert_eq!(report.missing, ["Sdks/Microsoft.NET.Sdk.props"]);
+    assert_eq!(report.modified, ["dotnet.dll"]);
+    assert_eq!(report.added, ["extra.txt"]);
+}
+
+#[test]
+fn sha256_of_a_known_file() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("abc");
+    fs::write(&file, "abc").unwrap();
+    assert_eq!(sha256_file(&file).unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+}
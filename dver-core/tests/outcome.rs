@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use dver_core::outcome::{CommandOutcome, ItemOutcome, ItemStatus, OUTCOME_SCHEMA};
+use dver_core::DverError;
+
+#[test]
+fn the_outcome_document_carries_per_item_and_command_errors() {
+    let error = DverError::Permission("Refusing to remove SDK 8.0.100".to_string());
+    let items = vec![
+        ItemOutcome::new("8.0.404", ItemStatus::Unchanged),
+        ItemOutcome::new("8.0.100", ItemStatus::Failed).path("/usr/share/dotnet/sdk/8.0.100").error(&error),
+    ];
+    let outcome = CommandOutcome::new("uninstall", items, Err(&error), Duration::from_millis(1500));
+    assert!(!outcome.changed && !outcome.success);
+    let doc = serde_json::to_value(&outcome).unwrap();
+    assert_eq!(doc["schema"], OUTCOME_SCHEMA);
+    assert_eq!(doc["duration_ms"], 1500);
+    assert_eq!(doc["error"], serde_json::json!({ "code": "permission", "exit_code": 7, "message": "Refusing to remove SDK 8.0.100" }));
+    assert_eq!(doc["items"][1]["error"]["code"], "permission");
+
+    let rows = outcome.rows();
+    assert_eq!((rows[1].sdk, rows[1].status, rows[1].error), ("8.0.100", ItemStatus::Failed, Some("Refusing to remove SDK 8.0.100")));
+
+    let removed = vec![ItemOutcome::new("8.0.404", ItemStatus::Removed).size(Some(1024)).duration(Duration::from_millis(20))];
+    assert!(CommandOutcome::new("uninstall", removed, Ok(()), Duration::ZERO).changed);
+}
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use dver_core::environment::ShellKind;
+use dver_core::platform::{
+    binary_arch, host_support, native_arch, rid_arch, rid_os, root_arch, unsupported_host_message, Arch, HostSupport,
+    Platform, Unix, Windows,
+};
+use dver_core::process::command_line;
+use dver_core::Config;
+
+#[test]
+fn unix_uses_bash_and_xdg_dirs() {
+    let unix = Unix;
+    assert_eq!(unix.home_var(), "HOME");
+    assert_eq!(unix.dotnet_exe(), "dotnet");
+    assert!(unix.uses_xdg_dirs());
+    assert_eq!(unix.default_shell(), None);
+    assert_eq!(unix.install_script_url(), "https://dotnet.microsoft.com/download/dotnet/scripts/v1/dotnet-install.sh");
+    assert_eq!(command_line(&unix.install_script_command(Path::new("/tmp/dotnet-install.sh"))), "bash /tmp/dotnet-install.sh");
+    assert_eq!(unix.user_dotnet_root(Path::new("/home/ada")), PathBuf::from("/home/ada/.dotnet"));
+}
+
+#[test]
+fn windows_uses_powershell_and_known_folders() {
+    let windows = Windows;
+    assert_eq!(windows.home_var(), "USERPROFILE");
+    assert_eq!(windows.dotnet_exe(), "dotnet.exe");
+    assert!(!windows.uses_xdg_dirs());
+    assert_eq!(windows.default_shell(), Some(ShellKind::Powershell));
+    assert_eq!(windows.default_editor(), "notepad");
+    assert!(windows.install_script_url().ends_with("/dotnet-install.ps1"));
+    let command = command_line(&windows.install_script_command(Path::new("install.ps1")));
+    assert_eq!(command, "powershell -NoLogo -NoProfile -NonInteractive -ExecutionPolicy Bypass -File install.ps1");
+}
+
+#[test]
+fn path_lists_split_on_the_platform_separator() {
+    assert_eq!(Unix.split_path_list("/usr/bin::/home/ada/.dotnet"), [PathBuf::from("/usr/bin"), PathBuf::from("/home/ada/.dotnet")]);
+    assert_eq!(Windows.split_path_list(r"C:\Windows;C:\Users\ada\.dotnet;"), [PathBuf::from(r"C:\Windows"), PathBuf::from(r"C:\Users\ada\.dotnet")]);
+    assert_eq!(
+        Windows.split_path_list(r#"C:\Windows;"C:\Program Files\dotnet";"#),
+        [PathBuf::from(r"C:\Windows"), PathBuf::from(r"C:\Program Files\dotnet")]
+    );
+}
+
+/// Un'architettura diversa da quella dell'host su cui girano i test.
+fn foreign_arch() -> Arch {
+    if Arch::native() == Some(Arch::Arm64) { Arch::X64 } else { Arch::Arm64 }
+}
+
+#[test]
+fn executable_headers_give_the_architecture() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, bytes: &[u8]| {
+        let path = dir.path().join(name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    };
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1];
+    elf.resize(18, 0);
+    elf.extend_from_slice(&0xb7u16.to_le_bytes());
+    assert_eq!(binary_arch(&write("elf", &elf)), Some(Arch::Arm64));
+
+    let mut macho = vec![0xcf, 0xfa, 0xed, 0xfe];
+    macho.extend_from_slice(&0x0100_0007u32.to_le_bytes());
+    assert_eq!(binary_arch(&write("macho", &macho)), Some(Arch::X64));
+
+    let mut pe = vec![b'M', b'Z'];
+    pe.resize(0x3c, 0);
+    pe.extend_from_slice(&0x40u32.to_le_bytes());
+    pe.extend_from_slice(b"PE\0\0");
+    pe.extend_from_slice(&0x014cu16.to_le_bytes());
+    assert_eq!(binary_arch(&write("pe", &pe)), Some(Arch::X86));
+
+    assert_eq!(binary_arch(&write("script", b"#!/usr/bin/env bash\n")), None);
+    assert_eq!(root_arch(&dir.path().join("x64")), Some(Arch::X64));
+}
+
+#[test]
+fn other_architectures_get_their_own_root_and_variables() {
+    let config = Config { install_root: Some(PathBuf::from("/opt/dotnet")), ..Config::default() };
+    let foreign = foreign_arch();
+    assert_eq!(config.arch_root(None), Some(PathBuf::from("/opt/dotnet")));
+    assert_eq!(config.arch_root(Arch::native()), Some(PathBuf::from("/opt/dotnet")));
+    assert_eq!(config.arch_root(Some(foreign)), Some(PathBuf::from("/opt/dotnet").join(foreign.name())));
+    assert_eq!(Config { arch: Some(foreign), ..config.clone() }.managed_root(), config.arch_root(Some(foreign)));
+
+    assert_eq!(Unix.arch_root_vars(Arch::X64), ["DOTNET_ROOT_X64"]);
+    assert_eq!(Windows.arch_root_vars(Arch::X86), ["DOTNET_ROOT_X86", "DOTNET_ROOT(x86)"]);
+}
+
+#[test]
+fn rids_and_support_cover_the_bsds_and_illumos() {
+    assert_eq!((rid_os("macos"), rid_os("windows"), rid_os("freebsd"), rid_os("illumos")), ("osx", "win", "freebsd", "illumos"));
+    assert_eq!((rid_arch("x86_64"), rid_arch("powerpc64"), rid_arch("s390x")), ("x64", "ppc64le", "s390x"));
+
+    assert_eq!(host_support("linux", "aarch64"), HostSupport::Official);
+    assert_eq!(host_support("windows", "x86"), HostSupport::Official);
+    assert_eq!(host_support("freebsd", "x86_64"), HostSupport::Community);
+    assert_eq!(host_support("illumos", "x86_64"), HostSupport::Community);
+    assert_eq!(host_support("openbsd", "x86_64"), HostSupport::Unsupported);
+
+    assert_eq!(unsupported_host_message(HostSupport::Official, "linux-x64"), None);
+    let community = unsupported_host_message(HostSupport::Community, "freebsd-x64").unwrap();
+    assert!(community.starts_with("Microsoft publishes no .NET SDK for freebsd-x64\nhint: "), "{}", community);
+    assert!(community.contains("dver adopt"), "{}", community);
+    assert!(unsupported_host_message(HostSupport::Unsupported, "openbsd-x64").unwrap().contains("FreeBSD and illumos"));
+}
+
+#[test]
+fn rosetta_makes_an_x86_64_process_an_arm64_host() {
+    assert_eq!(native_arch("x86_64", false), Some(Arch::X64));
+    assert_eq!(native_arch("x86_64", true), Some(Arch::Arm64));
+    assert_eq!(native_arch("aarch64", false), Some(Arch::Arm64));
+    assert_eq!(native_arch("s390x", false), None);
+}
@@ -0,0 +1,175 @@
+use std::fs;
+
+use dver_core::inventory::resolve_in_sdk_paths;
+use dver_core::project::{
+    find_global_json, infer_sdk, project_workloads, props_property, required_workloads, target_frameworks, tfm_channel,
+    write_global_json_version, write_props_property, Project,
+};
+use tempfile::TempDir;
+
+#[test]
+fn target_frameworks_map_to_sdk_channels() {
+    assert_eq!(tfm_channel("net8.0"), Some((8, 0)));
+    assert_eq!(tfm_channel("net10.0-windows10.0.19041"), Some((10, 0)));
+    assert_eq!(tfm_channel("netcoreapp3.1"), Some((3, 1)));
+    assert_eq!(tfm_channel("net48"), None);
+    assert_eq!(tfm_channel("netstandard2.0"), None);
+}
+
+#[test]
+fn both_target_framework_properties_are_read() {
+    let project = r#"<Project Sdk="Microsoft.NET.Sdk">
+  <PropertyGroup>
+    <TargetFrameworks>net6.0; net8.0-android;$(ExtraTargets)</TargetFrameworks>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Legacy)' == 'true'">
+    <TargetFramework>net48</TargetFramework>
+  </PropertyGroup>
+</Project>"#;
+    assert_eq!(target_frameworks(project), ["net48", "net6.0", "net8.0-android"]);
+}
+
+#[test]
+fn the_highest_target_of_the_repository_decides_the_sdk() {
+    let repo = TempDir::new().unwrap();
+    fs::create_dir_all(repo.path().join(".git")).unwrap();
+    fs::create_dir_all(repo.path().join("src/App")).unwrap();
+    fs::create_dir_all(repo.path().join("src/App/bin/Debug")).unwrap();
+    fs::write(repo.path().join("src/App/App.csproj"), "<Project><PropertyGroup><TargetFramework>net8.0</TargetFramework></PropertyGroup></Project>").unwrap();
+    fs::write(repo.path().join("Directory.Build.props"), "<Project><PropertyGroup><TargetFrameworks>net9.0;net48</TargetFrameworks></PropertyGroup></Project>").unwrap();
+    // Le cartelle di build non contano
+    fs::write(repo.path().join("src/App/bin/Debug/Old.csproj"), "<TargetFramework>net10.0</TargetFramework>").unwrap();
+
+    let inferred = infer_sdk(&repo.path().join("src/App")).unwrap().unwrap();
+    assert_eq!(inferred.channel, "9.0");
+    assert_eq!(inferred.target_frameworks, ["net9.0", "net8.0"]);
+    assert_eq!(inferred.files.len(), 2);
+    let installed = ["10.0.100".to_string(), "8.0.404".to_string()];
+    assert_eq!(inferred.satisfied_by(&installed).map(String::as_str), Some("10.0.100"));
+    assert!(inferred.satisfied_by(&installed[1..]).is_none());
+
+    let empty = TempDir::new().unwrap();
+    assert!(infer_sdk(empty.path()).unwrap().is_none());
+}
+
+#[test]
+fn workloads_are_detected_from_project_properties() {
+    let maui = r#"<Project Sdk="Microsoft.NET.Sdk">
+  <PropertyGroup>
+    <TargetFrameworks>net8.0-android;net8.0-ios</TargetFrameworks>
+    <UseMaui>true</UseMaui>
+  </PropertyGroup>
+</Project>"#;
+    assert_eq!(project_workloads(maui), ["maui"]);
+
+    let android = r#"<Project><PropertyGroup><TargetFrameworks>net9.0-android35.0;net9.0</TargetFrameworks></PropertyGroup></Project>"#;
+    assert_eq!(project_workloads(android), ["android"]);
+
+    let wasm = r#"<Project Sdk="Microsoft.NET.Sdk.BlazorWebAssembly">
+  <PropertyGroup><TargetFramework>net8.0</TargetFramework><RunAOTCompilation> True </RunAOTCompilation></PropertyGroup>
+</Project>"#;
+    assert_eq!(project_workloads(wasm), ["wasm-tools"]);
+
+    let aspire8 = r#"<Project><PropertyGroup><IsAspireHost>true</IsAspireHost></PropertyGroup></Project>"#;
+    assert_eq!(project_workloads(aspire8), ["aspire"]);
+    let aspire9 = r#"<Project Sdk="Microsoft.NET.Sdk"><Sdk Name="Aspire.AppHost.Sdk" Version="9.0.0" />
+  <PropertyGroup><IsAspireHost>true</IsAspireHost></PropertyGroup></Project>"#;
+    assert!(project_workloads(aspire9).is_empty());
+    assert!(project_workloads("<Project><PropertyGroup><UseMaui>false</UseMaui></PropertyGroup></Project>").is_empty());
+}
+
+#[test]
+fn required_workloads_cover_every_project_of_the_repository() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    let app = dir.path().join("src").join("App");
+    fs::create_dir_all(&app).unwrap();
+    fs::write(app.join("App.csproj"), "<Project><PropertyGroup><UseMaui>true</UseMaui></PropertyGroup></Project>").unwrap();
+    let web = dir.path().join("src").join("Web");
+    fs::create_dir_all(&web).unwrap();
+    fs::write(web.join("Web.csproj"), "<Project><PropertyGroup><WasmBuildNative>true</WasmBuildNative></PropertyGroup></Project>").unwrap();
+    assert_eq!(required_workloads(&web).unwrap(), ["maui", "wasm-tools"]);
+}
+
+const GLOBAL_JSON: &str = r#"{
+  "sdk": {
+    "version": "8.0.400",
+    "paths": [".dotnet", "$host$"],
+    "errorMessage": "Run ./eng/install-sdk.sh first."
+  },
+  "msbuild-sdks": { "Microsoft.Build.Traversal": "4.1.0" }
+}"#;
+
+#[test]
+fn sdk_paths_are_searched_in_order_from_the_global_json_folder() {
+    let repo = TempDir::new().unwrap();
+    let host = TempDir::new().unwrap();
+    fs::write(repo.path().join("global.json"), GLOBAL_JSON).unwrap();
+    fs::create_dir_all(repo.path().join("src")).unwrap();
+    let pin = find_global_json(&repo.path().join("src")).unwrap().unwrap();
+    assert_eq!(pin.error_message.as_deref(), Some("Run ./eng/install-sdk.sh first."));
+    assert_eq!(pin.sdk_roots(Some(host.path())), [repo.path().join(".dotnet"), host.path().to_path_buf()]);
+    assert_eq!(pin.sdk_roots(None), [repo.path().join(".dotnet")]);
+
+    fs::create_dir_all(host.path().join("sdk/8.0.404")).unwrap();
+    assert_eq!(resolve_in_sdk_paths(&pin, Some(host.path())).unwrap(), Some(("8.0.404".to_string(), host.path().to_path_buf())));
+    // Un SDK nella cartella del repository vince anche se è più vecchio
+    fs::create_dir_all(repo.path().join(".dotnet/sdk/8.0.401")).unwrap();
+    assert_eq!(resolve_in_sdk_paths(&pin, Some(host.path())).unwrap().map(|(sdk, _)| sdk).as_deref(), Some("8.0.401"));
+    assert_eq!(resolve_in_sdk_paths(&pin, None).unwrap().map(|(_, root)| root), Some(repo.path().join(".dotnet")));
+}
+
+#[test]
+fn writing_the_version_keeps_the_other_properties() {
+    let repo = TempDir::new().unwrap();
+    let path = repo.path().join("global.json");
+    fs::write(&path, GLOBAL_JSON).unwrap();
+    write_global_json_version(&path, "9.0.100").unwrap();
+    let doc: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(doc["sdk"]["version"], "9.0.100");
+    assert_eq!(doc["sdk"]["paths"], serde_json::json!([".dotnet", "$host$"]));
+    assert_eq!(doc["sdk"]["errorMessage"], "Run ./eng/install-sdk.sh first.");
+    assert_eq!(doc["msbuild-sdks"]["Microsoft.Build.Traversal"], "4.1.0");
+
+    let fresh = repo.path().join("new.json");
+    write_global_json_version(&fresh, "8.0.404").unwrap();
+    assert_eq!(fs::read_to_string(&fresh).unwrap(), "{\n  \"sdk\": {\n    \"version\": \"8.0.404\"\n  }\n}\n");
+    fs::write(&path, "[]").unwrap();
+    assert!(write_global_json_version(&path, "8.0.404").is_err());
+}
+
+#[test]
+fn the_props_property_is_rewritten_added_or_created() {
+    let repo = TempDir::new().unwrap();
+    fs::write(repo.path().join(".dver.toml"), "[props]\nproperty = \"DotNetSdkVersion\"\n").unwrap();
+    let props = Project::find(repo.path()).unwrap().unwrap().config.props.unwrap();
+    assert_eq!(props.file, repo.path().join("Directory.Build.props"));
+
+    // Il file manca: si crea
+    write_props_property(&props.file, "DotNetSdkVersion", "8.0.404").unwrap();
+    let content = fs::read_to_string(&props.file).unwrap();
+    assert_eq!(content, "<Project>\n  <PropertyGroup>\n    <DotNetSdkVersion>8.0.404</DotNetSdkVersion>\n  </PropertyGroup>\n</Project>\n");
+
+    // Le altre proprietà e i commenti restano
+    let existing = "<Project>\n  <!-- toolset -->\n  <PropertyGroup>\n    <LangVersion>latest</LangVersion>\n    <DotNetSdkVersion> 8.0.404 </DotNetSdkVersion>\n  </PropertyGroup>\n</Project>\n";
+    fs::write(&props.file, existing).unwrap();
+    write_props_property(&props.file, "DotNetSdkVersion", "9.0.100").unwrap();
+    let content = fs::read_to_string(&props.file).unwrap();
+    assert_eq!(content, existing.replace(" 8.0.404 ", "9.0.100"));
+    assert_eq!(props_property(&content, "DotNetSdkVersion").as_deref(), Some("9.0.100"));
+
+    write_props_property(&props.file, "ToolsetVersion", "9.0.100").unwrap();
+    let content = fs::read_to_string(&props.file).unwrap();
+    assert!(content.ends_with("  <PropertyGroup>\n    <ToolsetVersion>9.0.100</ToolsetVersion>\n  </PropertyGroup>\n</Project>\n"), "{}", content);
+    assert!(content.contains("<LangVersion>latest</LangVersion>"));
+
+    fs::write(&props.file, "<Import Project=\"x.props\" />").unwrap();
+    assert!(write_props_property(&props.file, "DotNetSdkVersion", "9.0.100").is_err());
+
+    // Un file che c'è ma non si legge non va sostituito con uno vuoto
+    let unreadable = repo.path().join("Unreadable.props");
+    fs::create_dir(&unreadable).unwrap();
+    let err = write_props_property(&unreadable, "DotNetSdkVersion", "9.0.100").unwrap_err();
+    assert!(err.to_string().contains("Failed to read"), "{}", err);
+    assert!(unreadable.is_dir());
+}
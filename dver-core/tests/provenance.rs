@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use chrono::{TimeZone, Utc};
+use dver_core::provenance::{find, forget, load_all, record, Provenance};
+use dver_core::Config;
+use tempfile::TempDir;
+
+fn provenance(root: &Path, version: &str, script_sha256: &str) -> Provenance {
+    Provenance {
+        version: version.to_string(),
+        root: root.to_path_buf(),
+        installed_at: Utc.with_ymd_and_hms(2025, 11, 12, 9, 30, 0).unwrap(),
+        installed_by: Some("ada".to_string()),
+        feed_url: "https://builds.dotnet.microsoft.com/dotnet".to_string(),
+        script_url: "https://dot.net/v1/dotnet-install.sh".to_string(),
+        script_sha256: script_sha256.to_string(),
+        dver_version: "0.1.0".to_string(),
+        daily: false,
+    }
+}
+
+fn config(state: &TempDir) -> Config {
+    Config { state_dir: Some(state.path().to_path_buf()), ..Default::default() }
+}
+
+#[test]
+fn records_are_found_by_version_and_root() {
+    let state = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let config = config(&state);
+    assert!(find(&config, root.path(), "8.0.404").unwrap().is_none());
+
+    record(&config, provenance(root.path(), "8.0.404", "aa")).unwrap();
+    record(&config, provenance(root.path(), "9.0.100", "bb")).unwrap();
+    let found = find(&config, root.path(), "8.0.404").unwrap().unwrap();
+    assert_eq!(found, provenance(root.path(), "8.0.404", "aa"));
+    assert!(find(&config, Path::new("/elsewhere"), "8.0.404").unwrap().is_none());
+}
+
+#[test]
+fn reinstalling_replaces_the_record_and_uninstalling_forgets_it() {
+    let state = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let config = config(&state);
+    record(&config, provenance(root.path(), "8.0.404", "aa")).unwrap();
+    record(&config, provenance(root.path(), "8.0.404", "cc")).unwrap();
+    let all = load_all(&config).unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].script_sha256, "cc");
+
+    forget(&config, root.path(), "8.0.404").unwrap();
+    assert!(load_all(&config).unwrap().is_empty());
+    // Dimenticare un SDK senza record non è un errore
+    forget(&config, root.path(), "8.0.404").unwrap();
+}
@@ -0,0 +1,85 @@
+use dver_core::process::ScriptedRunner;
+use dver_core::proxy::{detect, parse_gsettings, parse_internet_settings, parse_scutil, parse_winhttp, INTERNET_SETTINGS_KEY};
+
+const INTERNET_SETTINGS: &str = r"
+HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Internet Settings
+    ProxyEnable    REG_DWORD    0x1
+    ProxyServer    REG_SZ    http=proxy.corp:8080;https=secure.corp:8443
+    ProxyOverride    REG_SZ    *.corp.local;10.*;<local>
+";
+
+const WINHTTP: &str = "
+Current WinHTTP proxy settings:
+
+    Proxy Server(s) :  proxy.corp:3128
+    Bypass List     :  *.corp;<local>
+";
+
+const SCUTIL: &str = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  HTTPEnable : 1
+  HTTPPort : 8080
+  HTTPProxy : proxy.corp
+  HTTPSEnable : 0
+  ProxyAutoConfigEnable : 0
+}
+";
+
+const GSETTINGS: &str = "org.gnome.system.proxy use-same-proxy true
+org.gnome.system.proxy mode 'manual'
+org.gnome.system.proxy ignore-hosts ['localhost', '127.0.0.0/8', '::1']
+org.gnome.system.proxy autoconfig-url ''
+org.gnome.system.proxy.http host 'proxy.corp'
+org.gnome.system.proxy.http port 3128
+org.gnome.system.proxy.https host ''
+org.gnome.system.proxy.https port 0
+";
+
+#[test]
+fn windows_user_settings_prefer_the_https_proxy() {
+    let proxy = parse_internet_settings(INTERNET_SETTINGS).unwrap();
+    assert_eq!(proxy.url.as_deref(), Some("http://secure.corp:8443"));
+    assert_eq!(proxy.no_proxy, [".corp.local", "10.*"]);
+
+    // Proxy disattivato e nessuno script PAC: niente da usare
+    let disabled = INTERNET_SETTINGS.replace("0x1", "0x0");
+    assert_eq!(parse_internet_settings(&disabled), None);
+    let pac = format!("{}    AutoConfigURL    REG_SZ    http://wpad.corp/proxy.pac\n", disabled);
+    let proxy = parse_internet_settings(&pac).unwrap();
+    assert_eq!((proxy.url, proxy.pac_url.as_deref()), (None, Some("http://wpad.corp/proxy.pac")));
+}
+
+#[test]
+fn winhttp_is_read_from_its_label_value_lines() {
+    let proxy = parse_winhttp(WINHTTP).unwrap();
+    assert_eq!(proxy.url.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(proxy.no_proxy, [".corp"]);
+    // Accesso diretto, anche con il testo tradotto
+    assert_eq!(parse_winhttp("\nImpostazioni proxy WinHTTP correnti:\n\n    Accesso diretto (nessun server proxy).\n"), None);
+}
+
+#[test]
+fn macos_and_gnome_manual_proxies_are_found() {
+    let proxy = parse_scutil(SCUTIL).unwrap();
+    assert_eq!(proxy.url.as_deref(), Some("http://proxy.corp:8080"));
+    assert_eq!(proxy.no_proxy, [".local", "169.254/16"]);
+
+    let proxy = parse_gsettings(GSETTINGS).unwrap();
+    assert_eq!(proxy.url.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(proxy.no_proxy, ["localhost", "127.0.0.0/8", "::1"]);
+    assert_eq!(parse_gsettings(&GSETTINGS.replace("'manual'", "'none'")), None);
+}
+
+#[test]
+fn windows_falls_back_to_winhttp() {
+    let user = format!("reg query {}", INTERNET_SETTINGS_KEY);
+    let runner = ScriptedRunner::new()
+        .with(&user, "\nHKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings\n    ProxyEnable    REG_DWORD    0x0\n")
+        .with("netsh winhttp show proxy", WINHTTP);
+    assert_eq!(detect(&runner, "windows").unwrap().source, "WinHTTP settings");
+    // Senza gsettings (es. un server senza GNOME) non c'è proxy di sistema
+    assert_eq!(detect(&ScriptedRunner::new(), "linux"), None);
+}
@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use dver_core::platform::{Unix, Windows};
+use dver_core::process::ScriptedRunner;
+use dver_core::registry::{
+    installed_versions, msi_owner, parse_installed_versions, INSTALLED_VERSIONS_KEY, INSTALLED_VERSIONS_KEY_32,
+};
+
+const X64: &str = r"
+HKEY_LOCAL_MACHINE\SOFTWARE\dotnet\Setup\InstalledVersions\x64
+    InstallLocation    REG_SZ    C:\Program Files\dotnet\
+
+HKEY_LOCAL_MACHINE\SOFTWARE\dotnet\Setup\InstalledVersions\x64\sdk
+    8.0.404    REG_DWORD    0x1
+
+HKEY_LOCAL_MACHINE\SOFTWARE\dotnet\Setup\InstalledVersions\x64\sharedfx\Microsoft.NETCore.App
+    8.0.11    REG_DWORD    0x1
+";
+
+const X86: &str = r"
+HKEY_LOCAL_MACHINE\SOFTWARE\WOW6432Node\dotnet\Setup\InstalledVersions\x86
+    InstallLocation    REG_SZ    C:\Program Files (x86)\dotnet\
+
+HKEY_LOCAL_MACHINE\SOFTWARE\WOW6432Node\dotnet\Setup\InstalledVersions\x86\sdk
+    6.0.428    REG_DWORD    0x1
+";
+
+#[test]
+fn reg_query_output_lists_sdks_and_runtimes_per_arch() {
+    let installs = parse_installed_versions(X64);
+    assert_eq!(installs.len(), 1);
+    assert_eq!(installs[0].arch, "x64");
+    assert_eq!(installs[0].install_location.as_deref(), Some(Path::new(r"C:\Program Files\dotnet")));
+    assert_eq!(installs[0].sdks, ["8.0.404"]);
+    assert_eq!(installs[0].runtimes, [("Microsoft.NETCore.App".to_string(), "8.0.11".to_string())]);
+
+    let root = Path::new(r"C:\Program Files\dotnet");
+    assert!(installs[0].owns_sdk(root, "8.0.404"));
+    assert!(!installs[0].owns_sdk(root, "9.0.100"));
+    assert!(!installs[0].owns_sdk(Path::new(r"C:\Users\ada\.dotnet"), "8.0.404"));
+    // La cartella non esiste su questa macchina
+    assert_eq!(installs[0].missing_sdks(), ["8.0.404"]);
+}
+
+#[test]
+fn both_registry_views_are_read_and_missing_keys_are_skipped() {
+    let x64 = format!("reg query {} /s", INSTALLED_VERSIONS_KEY);
+    let x86 = format!("reg query {} /s", INSTALLED_VERSIONS_KEY_32);
+    let runner = ScriptedRunner::new().with(&x64, X64).with(&x86, X86);
+    let installs = installed_versions(&runner, &Windows);
+    assert_eq!(installs.iter().map(|i| i.arch.as_str()).collect::<Vec<_>>(), ["x64", "x86"]);
+    let owner = msi_owner(&installs, Path::new(r"C:\Program Files (x86)\dotnet"), "6.0.428").unwrap();
+    assert_eq!(owner.arch, "x86");
+
+    // Senza .NET installato dagli MSI `reg query` esce con 1
+    let runner = ScriptedRunner::new().with_status(&x64, 1, "", "ERROR: The system was unable to find the specified registry key or value.");
+    assert!(installed_versions(&runner, &Windows).is_empty());
+    // Su Unix non c'è nessun registro da leggere
+    let runner = ScriptedRunner::new();
+    assert!(installed_versions(&runner, &Unix).is_empty());
+    assert!(runner.calls().is_empty());
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use dver_core::releases::{ChannelReleases, ReleaseIndex, ReleaseType, SupportPhase};
+use dver_core::version::SdkVersion;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// Estratti ridotti di releases-index.json e 8.0/releases.json nel formato pubblicato
+// su builds.dotnet.microsoft.com, con i campi null e i valori vuoti dei documenti reali.
+const INDEX: &str = include_str!("fixtures/releases-index.json");
+const CHANNEL_8_0: &str = include_str!("fixtures/8.0/releases.json");
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn version(s: &str) -> SdkVersion {
+    s.parse().unwrap()
+}
+
+/// Deserializza, riserializza e deserializza di nuovo: i due valori devono coincidere.
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(json: &str) -> T {
+    let parsed: T = serde_json::from_str(json).unwrap();
+    let reparsed: T = serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+    assert_eq!(parsed, reparsed);
+    parsed
+}
+
+#[test]
+fn release_index_round_trips_with_typed_fields() {
+    let index: ReleaseIndex = round_trip(INDEX);
+    let channels = &index.releases_index;
+    assert_eq!(channels.len(), 4);
+
+    let preview = &channels[0];
+    assert_eq!(preview.support_phase, Some(SupportPhase::Preview));
+    assert_eq!(preview.eol_date, None);
+    assert!(preview.latest_sdk.as_ref().unwrap().is_prerelease());
+
+    let net8 = &channels[2];
+    assert!(net8.is_lts());
+    assert_eq!(net8.latest_sdk, Some(version("8.0.406")));
+    assert_eq!(net8.latest_release_date, Some(date("2025-02-11")));
+    assert_eq!(net8.eol_date, Some(date("2026-11-10")));
+
+    assert_eq!(channels[1].release_type, Some(ReleaseType::Sts));
+    assert_eq!(channels[3].support_phase, Some(SupportPhase::Eol));
+}
+
+#[test]
+fn channel_releases_round_trip_with_typed_fields() {
+    let channel: ChannelReleases = round_trip(CHANNEL_8_0);
+    assert_eq!(channel.release_type, Some(ReleaseType::Lts));
+    assert_eq!(channel.releases.len(), 3);
+
+    let latest = &channel.releases[0];
+    assert_eq!(latest.release_date, Some(date("2025-02-11")));
+    assert_eq!(latest.version, Some(version("8.0.13")));
+    assert_eq!(latest.cve_list[0].cve_id, "CVE-2025-21172");
+    let sdks: Vec<String> = latest.sdk_versions().map(|v| v.to_string()).collect();
+    assert_eq!(sdks, ["8.0.406", "8.0.310", "8.0.113", "8.0.406"]);
+    let aspnet = latest.aspnetcore_runtime.as_ref().unwrap();
+    assert_eq!(aspnet.version_aspnetcoremodule, ["18.0.25015.13"]);
+    assert_eq!(latest.sdk.as_ref().unwrap().files[1].akams.as_deref(), Some("https://aka.ms/dotnet/8.0/dotnet-sdk-win-x64.exe"));
+
+    // null diventa un vettore vuoto
+    let ga = &channel.releases[1];
+    assert!(ga.sdks.is_empty());
+    assert!(ga.aspnetcore_runtime.as_ref().unwrap().version_aspnetcoremodule.is_empty());
+
+    let rc = &channel.releases[2];
+    assert!(rc.cve_list.is_empty());
+    assert_eq!(rc.version, Some(version("8.0.0-rc.2")));
+    assert_eq!(rc.sdk.as_ref().unwrap().runtime_version, Some(version("8.0.0-rc.2.23479.6")));
+}
+
+#[test]
+fn cli_fixtures_parse() {
+    round_trip::<ReleaseIndex>(include_str!("../../tests/fixtures/releases-index.json"));
+    round_trip::<ChannelReleases>(include_str!("../../tests/fixtures/8.0/releases.json"));
+    round_trip::<ChannelReleases>(include_str!("../../tests/fixtures/9.0/releases.json"));
+}
+
+#[test]
+fn unknown_enum_values_and_empty_strings_are_tolerated() {
+    let channel: ChannelReleases = serde_json::from_str(
+        r#"{ "release-type": "ets", "support-phase": "sunset", "eol-date": "", "latest-sdk": "", "releases": null }"#,
+    )
+    .unwrap();
+    assert_eq!(channel.release_type, Some(ReleaseType::Unknown));
+    assert_eq!(channel.support_phase, Some(SupportPhase::Unknown));
+    assert_eq!(channel.eol_date, None);
+    assert_eq!(channel.latest_sdk, None);
+    assert!(channel.releases.is_empty());
+}
+
+#[test]
+fn malformed_dates_and_versions_are_errors() {
+    for json in [r#"{ "eol-date": "10/11/2026" }"#, r#"{ "latest-sdk": "eight" }"#] {
+        assert!(serde_json::from_str::<ChannelReleases>(json).is_err(), "{}", json);
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+use std::time::Duration;
+
+use dver_core::platform::{Unix, Windows};
+use dver_core::process::ScriptedRunner;
+use dver_core::removal::{holders, parse_holders, remove_tree, removing_path};
+
+#[test]
+fn removal_goes_through_a_hidden_sibling() {
+    assert_eq!(removing_path(Path::new("/opt/dotnet/sdk/8.0.404")), Path::new("/opt/dotnet/sdk/.8.0.404.dver-removing"));
+
+    let root = tempfile::tempdir().unwrap();
+    let sdk = root.path().join("sdk/8.0.404");
+    let deep = sdk.join("Sdks/Microsoft.NET.Sdk.BlazorWebAssembly/targets/Microsoft.NET.Sdk.BlazorWebAssembly.6_0.targets.d");
+    std::fs::create_dir_all(&deep).unwrap();
+    std::fs::write(deep.join("a.props"), "x").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(root.path().join("elsewhere"), sdk.join("link")).unwrap();
+    // Resti di una rimozione interrotta
+    let leftover = removing_path(&sdk);
+    std::fs::create_dir_all(leftover.join("old")).unwrap();
+
+    remove_tree(&ScriptedRunner::new(), &Unix, &sdk, Duration::ZERO).unwrap();
+    assert!(!sdk.exists() && !leftover.exists());
+    assert!(root.path().join("sdk").is_dir());
+
+    let missing = remove_tree(&ScriptedRunner::new(), &Unix, &sdk, Duration::ZERO).unwrap_err();
+    assert!(missing.to_string().starts_with("Failed to remove"), "{}", missing);
+}
+
+#[test]
+fn holders_name_the_processes() {
+    assert_eq!(parse_holders("4242 dotnet\r\n977 MSBuild\r\n\r\n"), ["dotnet (4242)", "MSBuild (977)"]);
+    // Su Unix nessun comando: i file aperti non bloccano la rimozione
+    let runner = ScriptedRunner::new();
+    assert!(holders(&runner, &Unix, Path::new("/opt/dotnet/sdk/8.0.404")).is_empty());
+    assert!(runner.calls().is_empty());
+    // Su Windows si chiede a PowerShell, e senza risposta non si sa
+    assert!(holders(&runner, &Windows, Path::new(r"C:\Program Files\dotnet\sdk\8.0.404")).is_empty());
+    assert!(runner.calls()[0].starts_with("powershell -NoLogo -NoProfile -NonInteractive -Command Get-Process"), "{:?}", runner.calls());
+}
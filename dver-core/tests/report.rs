@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use dver_core::adopt::{Adoption, Ownership};
+use dver_core::inventory::{InstalledRuntime, InstalledSdk};
+use dver_core::provenance::Provenance;
+use dver_core::releases::ReleaseIndex;
+use dver_core::report::{
+    report_runtimes, report_sdks, ActivePin, CheckStatus, DoctorCheck, DoctorSummary, FleetReport, Origin,
+};
+
+const INDEX: &str = include_str!("fixtures/releases-index.json");
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+}
+
+fn sdk(root: &str, version: &str) -> InstalledSdk {
+    InstalledSdk { version: version.to_string(), path: PathBuf::from(root).join("sdk").join(version), root: None, arch: None }
+}
+
+#[test]
+fn sdks_carry_their_origin_and_end_of_support() {
+    let index: ReleaseIndex = serde_json::from_str(INDEX).unwrap();
+    let sdks = [sdk("/opt/dotnet", "8.0.404"), sdk("/usr/share/dotnet", "3.1.426"), sdk("/opt/dotnet", "7.0.410")];
+    let records = [Provenance {
+        version: "8.0.404".to_string(),
+        root: PathBuf::from("/opt/dotnet"),
+        installed_at: "2025-01-10T12:00:00Z".parse().unwrap(),
+        installed_by: None,
+        feed_url: "https://builds.dotnet.microsoft.com/dotnet".to_string(),
+        script_url: "https://dot.net/v1/dotnet-install.sh".to_string(),
+        script_sha256: "abc".to_string(),
+        dver_version: "0.1.0".to_string(),
+        daily: false,
+    }];
+    let adoptions = [Adoption {
+        version: "3.1.426".to_string(),
+        root: PathBuf::from("/usr/share/dotnet"),
+        ownership: Ownership::External,
+        adopted_at: "2025-01-11T12:00:00Z".parse().unwrap(),
+        moved_from: None,
+    }];
+
+    let entries = report_sdks(&sdks, &records, &[], &adoptions, Some(&index), today());
+    let summary: Vec<(&str, Origin, Option<bool>)> =
+        entries.iter().map(|e| (e.version.as_str(), e.origin, e.is_eol)).collect();
+    assert_eq!(
+        summary,
+        [("8.0.404", Origin::Dver, Some(false)), ("3.1.426", Origin::Adopted, Some(true)), ("7.0.410", Origin::Unknown, None)]
+    );
+    assert_eq!(entries[0].channel.as_deref(), Some("8.0"));
+
+    // Senza indice la fine del supporto resta ignota
+    assert_eq!(report_sdks(&sdks, &[], &[], &[], None, today())[1].is_eol, None);
+}
+
+#[test]
+fn runtimes_use_their_channel_for_end_of_support() {
+    let index: ReleaseIndex = serde_json::from_str(INDEX).unwrap();
+    let runtimes = [InstalledRuntime {
+        name: "Microsoft.NETCore.App".to_string(),
+        version: "3.1.32".to_string(),
+        path: PathBuf::from("/opt/dotnet/shared/Microsoft.NETCore.App/3.1.32"),
+    }];
+    assert_eq!(report_runtimes(&runtimes, Some(&index), today())[0].is_eol, Some(true));
+}
+
+#[test]
+fn the_doctor_summary_keeps_only_the_problems() {
+    let checks = [
+        DoctorCheck::ok("dotnet command is available in your PATH."),
+        DoctorCheck::warning("PATH might be wrong."),
+        DoctorCheck::error("SDK 8.0.404 is not installed."),
+    ];
+    let summary = DoctorSummary::new(&checks);
+    assert_eq!((summary.ok, summary.warnings, summary.errors), (1, 1, 1));
+    assert_eq!(summary.problems.iter().map(|c| c.status).collect::<Vec<_>>(), [CheckStatus::Warning, CheckStatus::Error]);
+}
+
+#[test]
+fn the_markdown_report_has_a_section_per_area() {
+    let pin = ActivePin {
+        directory: PathBuf::from("/work"),
+        pinned: Some("8.0.404".to_string()),
+        source: Some(PathBuf::from("/work/global.json")),
+        resolved: Some("8.0.404".to_string()),
+    };
+    let mut report = FleetReport::new("1.2.3", pin);
+    report.sdks = report_sdks(&[sdk("/opt/dotnet", "8.0.404")], &[], &[], &[], None, today());
+    report.doctor = DoctorSummary::new(&[DoctorCheck::error("SDK 9.0.100 is not installed.")]);
+
+    let markdown = report.markdown();
+    let sdk_row = format!("| 8.0.404 | 8.0 | unknown | unknown | {} |", report.sdks[0].path.display());
+    for expected in [
+        "## SDKs",
+        sdk_row.as_str(),
+        "## Runtimes",
+        "## Workloads\n\nNone.",
+        "## Caches\n\n| Cache | Path | Source | Size |",
+        "SDK 8.0.404 pinned in `/work/global.json`, resolves to 8.0.404.",
+        "0 ok, 0 warnings, 1 errors.",
+        "- **error**: SDK 9.0.100 is not installed.",
+    ] {
+        assert!(markdown.contains(expected), "missing {:?} in:\n{}", expected, markdown);
+    }
+    assert!(markdown.contains("by dver 1.2.3 (schema 1)"));
+}
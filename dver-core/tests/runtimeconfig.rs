@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use dver_core::runtimeconfig::{
+    find_runtime_configs, parse_runtime_config, pin_runtime_config, resolve_framework, RollForward, RuntimeKind,
+};
+
+fn versions(list: &[&str]) -> Vec<String> {
+    list.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn reads_a_single_framework_or_a_list() {
+    let path = Path::new("App.runtimeconfig.json");
+    let single = r#"{"runtimeOptions":{"framework":{"name":"Microsoft.NETCore.App","version":"8.0.0"},"rollForward":"latestPatch"}}"#;
+    let config = parse_runtime_config(path, single).unwrap();
+    assert_eq!(config.frameworks.len(), 1);
+    assert_eq!(config.roll_forward, Some(RollForward::LatestPatch));
+
+    let many = r#"{"runtimeOptions":{"frameworks":[
+        {"name":"Microsoft.NETCore.App","version":"8.0.0"},{"name":"Microsoft.AspNetCore.App","version":"8.0.0"}]}}"#;
+    let config = parse_runtime_config(path, many).unwrap();
+    let names: Vec<&str> = config.frameworks.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["Microsoft.NETCore.App", "Microsoft.AspNetCore.App"]);
+    assert_eq!(config.effective_roll_forward(), RollForward::Minor);
+    assert_eq!(RuntimeKind::from_framework(names[1]), Some(RuntimeKind::AspNetCore));
+}
+
+#[test]
+fn pinning_sets_every_framework_and_keeps_the_rest() {
+    let content = r#"{"runtimeOptions":{"tfm":"net8.0","framework":{"name":"Microsoft.NETCore.App","version":"8.0.0"},
+        "configProperties":{"System.GC.Server":true}}}"#;
+    let pinned = pin_runtime_config(content, "8.0.11", RollForward::Disable).unwrap();
+    let config = parse_runtime_config(Path::new("a"), &pinned).unwrap();
+    assert_eq!(config.frameworks[0].version, "8.0.11");
+    assert_eq!(config.roll_forward, Some(RollForward::Disable));
+    assert!(pinned.contains("\"System.GC.Server\": true"));
+
+    assert!(pin_runtime_config("{}", "8.0.11", RollForward::Minor).is_err());
+}
+
+#[test]
+fn resolution_follows_the_roll_forward_policy() {
+    let installed = versions(&["6.0.36", "8.0.10", "8.0.11", "8.1.2", "8.2.0", "9.0.0", "9.0.1-rc.1"]);
+    let resolve = |wanted, policy| resolve_framework(&installed, wanted, policy);
+    assert_eq!(resolve("8.0.10", RollForward::Disable).as_deref(), Some("8.0.10"));
+    assert_eq!(resolve("8.0.9", RollForward::Disable), None);
+    assert_eq!(resolve("8.0.0", RollForward::LatestPatch).as_deref(), Some("8.0.11"));
+    assert_eq!(resolve("8.0.12", RollForward::LatestPatch), None);
+    assert_eq!(resolve("8.0.12", RollForward::Minor).as_deref(), Some("8.1.2"));
+    assert_eq!(resolve("8.0.0", RollForward::LatestMinor).as_deref(), Some("8.2.0"));
+    assert_eq!(resolve("7.0.0", RollForward::Minor), None);
+    assert_eq!(resolve("7.0.0", RollForward::Major).as_deref(), Some("8.0.11"));
+    assert_eq!(resolve("6.0.0", RollForward::LatestMajor).as_deref(), Some("9.0.0"));
+}
+
+#[test]
+fn only_published_runtimeconfigs_are_found() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["App.runtimeconfig.json", "App.runtimeconfig.dev.json", "App.deps.json"] {
+        std::fs::write(dir.path().join(name), "{}").unwrap();
+    }
+    assert_eq!(find_runtime_configs(dir.path()).unwrap(), [dir.path().join("App.runtimeconfig.json")]);
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::Path;
+
+use dver_core::platform::{Unix, Windows};
+use dver_core::sandbox::{check_removal, is_system_path, Removal};
+use tempfile::TempDir;
+
+fn sdk_root() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("dotnet");
+    fs::create_dir_all(root.join("sdk/8.0.404")).unwrap();
+    (dir, root)
+}
+
+#[test]
+fn paths_inside_a_managed_root_are_allowed() {
+    let (_dir, root) = sdk_root();
+    let removal = check_removal(&Unix, &root.join("sdk/8.0.404"), std::slice::from_ref(&root)).unwrap();
+    assert_eq!(removal, Removal::Allowed(fs::canonicalize(root.join("sdk/8.0.404")).unwrap()));
+}
+
+#[test]
+fn the_root_itself_and_paths_outside_it_are_refused() {
+    let (dir, root) = sdk_root();
+    fs::create_dir_all(dir.path().join("other")).unwrap();
+    for path in [root.clone(), dir.path().join("other"), root.join("sdk/../../other")] {
+        let err = check_removal(&Unix, &path, std::slice::from_ref(&root)).unwrap_err();
+        assert_eq!(err.code(), "permission", "{}", path.display());
+    }
+}
+
+#[test]
+fn nothing_is_removed_without_configured_roots() {
+    let (_dir, root) = sdk_root();
+    let err = check_removal(&Unix, &root.join("sdk/8.0.404"), &[]).unwrap_err();
+    assert!(err.to_string().contains("no managed roots are configured"), "{}", err);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_out_of_the_root_are_refused() {
+    let (dir, root) = sdk_root();
+    let outside = dir.path().join("precious");
+    fs::create_dir_all(&outside).unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("sdk/9.0.100")).unwrap();
+
+    let err = check_removal(&Unix, &root.join("sdk/9.0.100"), std::slice::from_ref(&root)).unwrap_err();
+    assert!(err.to_string().contains("outside the managed roots"), "{}", err);
+}
+
+#[test]
+fn system_directories_are_recognised() {
+    assert!(is_system_path(&Unix, Path::new("/usr/share/dotnet/sdk/8.0.404")));
+    assert!(!is_system_path(&Unix, Path::new("/home/ada/.dotnet/sdk/8.0.404")));
+    assert!(!is_system_path(&Windows, Path::new("/home/ada/.dotnet/sdk/8.0.404")));
+}
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use dver_core::inventory::{InstalledRuntime, InstalledSdk, InstalledWorkload};
+use dver_core::releases::ChannelReleases;
+use dver_core::sbom::{components, ComponentType, Sbom};
+use serde_json::json;
+
+const CHANNEL_8_0: &str = include_str!("fixtures/8.0/releases.json");
+
+fn sbom(rid: &str) -> Sbom {
+    let channel: ChannelReleases = serde_json::from_str(CHANNEL_8_0).unwrap();
+    let sdks = vec![
+        InstalledSdk { version: "8.0.406".into(), path: PathBuf::from("/opt/dotnet/sdk/8.0.406"), root: Some("work".into()), arch: None },
+        InstalledSdk { version: "7.0.100".into(), path: PathBuf::from("/opt/dotnet/sdk/7.0.100"), root: None, arch: None },
+    ];
+    let runtimes = vec![InstalledRuntime {
+        name: "Microsoft.AspNetCore.App".into(),
+        version: "8.0.13".into(),
+        path: PathBuf::from("/opt/dotnet/shared/Microsoft.AspNetCore.App/8.0.13"),
+    }];
+    let workloads = vec![InstalledWorkload {
+        id: "wasm-tools".into(),
+        manifest_version: Some("8.0.13/8.0.100".into()),
+        source: Some("SDK 8.0.400".into()),
+    }];
+    Sbom {
+        components: components(&sdks, &runtimes, &workloads, &[channel], rid),
+        created: SystemTime::UNIX_EPOCH + Duration::from_secs(1_739_232_000),
+        tool_version: "0.2.0".into(),
+    }
+}
+
+#[test]
+fn components_take_archive_url_and_hash_for_the_rid() {
+    let sbom = sbom("linux-x64");
+    let sdk = &sbom.components[0];
+    assert_eq!(sdk.kind, ComponentType::Sdk);
+    assert_eq!(sdk.download_url.as_deref(),
+        Some("https://builds.dotnet.microsoft.com/dotnet/Sdk/8.0.406/dotnet-sdk-8.0.406-linux-x64.tar.gz"));
+    assert_eq!(sdk.sha512.as_deref(), Some("0a4c6a0a3d1c0e3b1ff5b6a3cfa4d0d6e4e6c3a6b7e12a4f9c5f0b8a2d7e1c3f"));
+
+    // Versione assente dai metadati: resta senza origine né hash
+    assert_eq!((sbom.components[1].download_url.as_ref(), sbom.components[1].sha512.as_ref()), (None, None));
+
+    let aspnet = &sbom.components[2];
+    assert_eq!(aspnet.kind, ComponentType::Runtime);
+    assert!(aspnet.download_url.as_deref().unwrap().ends_with("aspnetcore-runtime-8.0.13-linux-x64.tar.gz"));
+
+    let workload = &sbom.components[3];
+    assert_eq!((workload.kind, workload.version.as_str()), (ComponentType::Workload, "8.0.13"));
+}
+
+#[test]
+fn installers_are_not_archives() {
+    // Per win-x64 il fixture pubblica solo l'installer .exe dell'SDK
+    let sbom = sbom("win-x64");
+    assert_eq!(sbom.components[0].sha512, None);
+}
+
+#[test]
+fn cyclonedx_document_lists_every_component() {
+    let doc = sbom("linux-x64").cyclonedx();
+    assert_eq!(doc["bomFormat"], "CycloneDX");
+    assert_eq!(doc["specVersion"], "1.5");
+    assert_eq!(doc["metadata"]["timestamp"], "2025-02-11T00:00:00Z");
+    assert_eq!(doc["components"].as_array().unwrap().len(), 4);
+    assert_eq!(
+        doc["components"][0],
+        json!({
+            "type": "application",
+            "bom-ref": "sdk-dotnet-sdk-8.0.406",
+            "supplier": { "name": "Microsoft Corporation" },
+            "name": "dotnet-sdk",
+            "version": "8.0.406",
+            "purl": "pkg:generic/microsoft/dotnet-sdk@8.0.406",
+            "hashes": [{ "alg": "SHA-512", "content": "0a4c6a0a3d1c0e3b1ff5b6a3cfa4d0d6e4e6c3a6b7e12a4f9c5f0b8a2d7e1c3f" }],
+            "externalReferences": [{
+                "type": "distribution",
+                "url": "https://builds.dotnet.microsoft.com/dotnet/Sdk/8.0.406/dotnet-sdk-8.0.406-linux-x64.tar.gz",
+            }],
+            "properties": [
+                { "name": "dver:path", "value": "/opt/dotnet/sdk/8.0.406" },
+                { "name": "dver:source", "value": "work" },
+            ],
+        })
+    );
+}
+
+#[test]
+fn spdx_document_describes_every_package() {
+    let doc = sbom("linux-x64").spdx();
+    assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+    assert_eq!(doc["creationInfo"]["creators"], json!(["Tool: dver-0.2.0"]));
+    let packages = doc["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 4);
+    assert_eq!(packages[1]["downloadLocation"], "NOASSERTION");
+    assert_eq!(packages[1].get("checksums"), None);
+    assert_eq!(packages[2]["SPDXID"], "SPDXRef-runtime-Microsoft.AspNetCore.App-8.0.13");
+    assert_eq!(doc["relationships"].as_array().unwrap().len(), 4);
+    assert_eq!(doc["relationships"][3]["relatedSpdxElement"], "SPDXRef-workload-wasm-tools-8.0.13");
+}
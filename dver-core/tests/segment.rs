@@ -0,0 +1,54 @@
+use std::fs;
+
+use chrono::NaiveDate;
+use dver_core::segment::{PromptSegment, SegmentSource, SEGMENT_SCHEMA};
+use dver_core::Config;
+use tempfile::TempDir;
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+}
+
+#[test]
+fn the_segment_follows_global_json_and_renders_stable_variables() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("dotnet");
+    for version in ["8.0.404", "9.0.100", "10.0.100-preview.1.25080.5"] {
+        fs::create_dir_all(root.join("sdk").join(version)).unwrap();
+    }
+    let work = dir.path().join("work");
+    fs::create_dir_all(&work).unwrap();
+    let config = Config {
+        install_root: Some(root),
+        cache_dir: Some(dir.path().join("cache")),
+        ..Default::default()
+    };
+
+    let latest = PromptSegment::collect(&config, &work, today()).unwrap().unwrap();
+    assert_eq!(latest.version, "10.0.100-preview.1.25080.5");
+    assert_eq!(latest.source, SegmentSource::Latest);
+    assert!(latest.is_preview);
+    // Senza metadati in cache la fine del supporto non è nota
+    assert_eq!(latest.is_eol, None);
+
+    fs::write(work.join("global.json"), r#"{ "sdk": { "version": "8.0.404" } }"#).unwrap();
+    let pinned = PromptSegment::collect(&config, &work, today()).unwrap().unwrap();
+    assert_eq!(pinned.source, SegmentSource::GlobalJson);
+    assert_eq!(pinned.source_path, Some(work.join("global.json")));
+    assert_eq!(
+        pinned.render_env(),
+        format!(
+            "DVER_PROMPT_SCHEMA={}\nDVER_PROMPT_VERSION=8.0.404\nDVER_PROMPT_CHANNEL=8.0\nDVER_PROMPT_SOURCE=global-json\n\
+             DVER_PROMPT_SOURCE_PATH={}\nDVER_PROMPT_PREVIEW=0\nDVER_PROMPT_EOL=\n",
+            SEGMENT_SCHEMA,
+            work.join("global.json").display(),
+        )
+    );
+}
+
+#[test]
+fn no_installed_sdk_means_no_segment() {
+    let dir = TempDir::new().unwrap();
+    let config = Config { install_root: Some(dir.path().join("empty")), ..Default::default() };
+    assert!(PromptSegment::collect(&config, dir.path(), today()).unwrap().is_none());
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use dver_core::environment::ShellKind;
+use dver_core::setup::{hook_block, remove_hook, upsert_hook, HOOK_END, HOOK_START};
+
+#[test]
+fn the_hook_is_appended_once_and_replaced_in_place() {
+    let block = hook_block(ShellKind::Bash, Path::new("/opt/dver/bin/dver"), Path::new("/home/u/.local/state/dver/shims"));
+    assert_eq!(
+        block,
+        format!(
+            "{}\nexport PATH='/home/u/.local/state/dver/shims':\"$PATH\"\neval \"$('/opt/dver/bin/dver' env --shell bash)\"\n{}\n",
+            HOOK_START, HOOK_END
+        )
+    );
+
+    let rc = upsert_hook("alias ll='ls -l'", &block);
+    assert_eq!(rc, format!("alias ll='ls -l'\n\n{}", block));
+    assert_eq!(upsert_hook(&rc, &block), rc);
+
+    let moved = hook_block(ShellKind::Bash, Path::new("/usr/local/bin/dver"), Path::new("/shims"));
+    let edited = format!("{}export EDITOR=vim\n", rc);
+    assert_eq!(upsert_hook(&edited, &moved), format!("alias ll='ls -l'\n\n{}export EDITOR=vim\n", moved));
+    assert_eq!(upsert_hook("", &block), block);
+}
+
+#[test]
+fn fish_and_powershell_hooks_source_dver_env() {
+    let fish = hook_block(ShellKind::Fish, Path::new("/bin/dver"), Path::new("/shims"));
+    assert!(fish.contains("set -gx PATH '/shims' $PATH\n'/bin/dver' env --shell fish | source\n"), "{}", fish);
+    let pwsh = hook_block(ShellKind::Powershell, Path::new("/bin/dver"), Path::new("/shims"));
+    assert!(pwsh.contains("& '/bin/dver' env --shell powershell | Out-String | Invoke-Expression"), "{}", pwsh);
+}
+
+#[test]
+fn removing_the_hook_restores_the_original_file() {
+    let block = hook_block(ShellKind::Zsh, Path::new("/bin/dver"), Path::new("/shims"));
+    let original = "export EDITOR=vim\n";
+    let hooked = upsert_hook(original, &block);
+    assert_eq!(remove_hook(&hooked).as_deref(), Some(original));
+    assert_eq!(remove_hook(&format!("{}alias g=git\n", hooked)).as_deref(), Some("export EDITOR=vim\nalias g=git\n"));
+    assert_eq!(remove_hook(&block).as_deref(), Some(""));
+    assert_eq!(remove_hook(original), None);
+}
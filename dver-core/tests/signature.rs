@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use dver_core::process::ScriptedRunner;
+use dver_core::signature::{enforce, verify_authenticode, verify_pgp, SignaturePolicy, Verification};
+
+#[test]
+fn pgp_signatures_are_checked_with_gpgv() {
+    let (keyring, data, sig) = (Path::new("/keys/microsoft.gpg"), Path::new("/tmp/install.sh"), Path::new("/tmp/install.sh.sig"));
+    let runner = ScriptedRunner::new().with("gpgv --keyring /keys/microsoft.gpg /tmp/install.sh.sig /tmp/install.sh", "");
+    assert!(matches!(verify_pgp(&runner, keyring, data, sig), Verification::Verified(_)));
+
+    let runner = ScriptedRunner::new().with_status(
+        "gpgv --keyring /keys/microsoft.gpg /tmp/install.sh.sig /tmp/install.sh", 1, "", "gpgv: BAD signature");
+    match verify_pgp(&runner, keyring, data, sig) {
+        Verification::Invalid(reason) => assert!(reason.contains("BAD signature"), "{}", reason),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // ScriptedRunner risponde NotFound ai comandi sconosciuti, come un gpgv assente
+    assert!(matches!(verify_pgp(&ScriptedRunner::new(), keyring, data, sig), Verification::Unavailable(_)));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn authenticode_is_unavailable_outside_windows() {
+    let verification = verify_authenticode(&ScriptedRunner::new(), Path::new("dotnet.exe"));
+    assert!(matches!(verification, Verification::Unavailable(_)));
+}
+
+#[test]
+fn policy_decides_what_a_failed_check_means() {
+    let missing = || Verification::Unavailable("no signature".to_string());
+    let bad = || Verification::Invalid("tampered".to_string());
+
+    let err = enforce(SignaturePolicy::Require, "the script", missing()).unwrap_err();
+    assert_eq!(err.code(), "signature");
+    assert_eq!(err.exit_code(), 12);
+    assert!(enforce(SignaturePolicy::Require, "the script", bad()).is_err());
+    assert!(enforce(SignaturePolicy::Require, "the script", Verification::Verified("Microsoft".to_string())).is_ok());
+
+    assert!(enforce(SignaturePolicy::Warn, "the script", bad()).is_ok());
+    assert!(enforce(SignaturePolicy::Off, "the script", bad()).is_ok());
+}
@@ -0,0 +1,33 @@
+use std::fs;
+
+use dver_core::environment::ShellKind;
+use dver_core::status::{Health, Status};
+use dver_core::Config;
+use tempfile::TempDir;
+
+#[test]
+fn status_counts_the_root_and_reports_an_empty_cache_as_unknown() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().join("dotnet");
+    fs::create_dir_all(root.join("sdk/8.0.404")).unwrap();
+    fs::write(root.join("sdk/8.0.404/dotnet.dll"), "sdk").unwrap();
+    fs::create_dir_all(root.join("shared/Microsoft.NETCore.App/8.0.11")).unwrap();
+    fs::create_dir_all(root.join("shared/Microsoft.AspNetCore.App/8.0.11")).unwrap();
+    let work = temp.path().join("work");
+    fs::create_dir_all(&work).unwrap();
+    fs::write(work.join("global.json"), r#"{"sdk":{"version":"8.0.404"}}"#).unwrap();
+    let config = Config {
+        install_root: Some(root.clone()),
+        cache_dir: Some(temp.path().join("cache")),
+        state_dir: Some(temp.path().join("state")),
+        ..Config::default()
+    };
+
+    let status = Status::collect(&config, &work, ShellKind::Cmd).unwrap();
+    assert_eq!(status.install_root, Some(root));
+    assert_eq!(status.active_sdk.as_deref(), Some("8.0.404"));
+    assert_eq!(status.pin.map(|p| p.source), Some(work.join("global.json")));
+    assert_eq!((status.sdks, status.runtimes, status.size_bytes), (1, 2, 3));
+    assert_eq!(status.updates, None);
+    assert_eq!((status.shim, status.hook_file, status.hook), (Health::Missing, None, Health::Missing));
+}
@@ -0,0 +1,51 @@
+use dver_core::sync::{file_url, merge_config, portable_config, SyncBackend};
+use dver_core::DverError;
+
+#[test]
+fn remotes_are_git_repositories_or_http_folders() {
+    let git = |url: &str| SyncBackend::Git(url.to_string());
+    assert_eq!(SyncBackend::parse("git@github.com:me/dotnet-profile.git").unwrap(), git("git@github.com:me/dotnet-profile.git"));
+    assert_eq!(SyncBackend::parse("https://github.com/me/profile.git").unwrap(), git("https://github.com/me/profile.git"));
+    assert_eq!(SyncBackend::parse("git+https://git.corp/me/profile").unwrap(), git("https://git.corp/me/profile"));
+    assert_eq!(SyncBackend::parse("/srv/profile.git").unwrap(), git("/srv/profile.git"));
+    assert_eq!(
+        SyncBackend::parse("https://dav.example.com/dver/").unwrap(),
+        SyncBackend::Http("https://dav.example.com/dver/".to_string())
+    );
+    assert!(matches!(SyncBackend::parse("~/profile"), Err(DverError::Usage(_))));
+}
+
+#[test]
+fn file_urls_keep_the_query_string_last() {
+    assert_eq!(file_url("https://dav.example.com/dver/", "config.toml"), "https://dav.example.com/dver/config.toml");
+    assert_eq!(
+        file_url("https://acct.blob.core.windows.net/profiles/me?sv=2024&sig=abc", "toolchain.json"),
+        "https://acct.blob.core.windows.net/profiles/me/toolchain.json?sv=2024&sig=abc"
+    );
+}
+
+#[test]
+fn machine_keys_and_secrets_stay_local() {
+    let laptop = r#"
+install_root = "/Users/me/.dotnet"
+default_channel = "8.0"
+proxy = "http://proxy.home:3128"
+[env]
+DOTNET_NOLOGO = "1"
+NUGET_API_KEY = "s3cret"
+"#;
+    let portable = portable_config(laptop).unwrap();
+    assert!(portable.contains("default_channel = \"8.0\""), "{}", portable);
+    assert!(portable.contains("DOTNET_NOLOGO"), "{}", portable);
+    for local in ["install_root", "proxy", "NUGET_API_KEY"] {
+        assert!(!portable.contains(local), "{} leaked into {}", local, portable);
+    }
+
+    let desktop = "install_root = \"/opt/dotnet\"\ndefault_channel = \"9.0\"\nquality = \"preview\"\n[env]\nNUGET_API_KEY = \"mine\"\n";
+    let merged = merge_config(desktop, &portable).unwrap();
+    assert_eq!(merged["install_root"].as_str(), Some("/opt/dotnet"));
+    assert_eq!(merged["default_channel"].as_str(), Some("8.0"));
+    assert!(!merged.contains_key("quality"), "portable keys follow the synced profile");
+    assert_eq!(merged["env"]["NUGET_API_KEY"].as_str(), Some("mine"));
+    assert_eq!(merged["env"]["DOTNET_NOLOGO"].as_str(), Some("1"));
+}
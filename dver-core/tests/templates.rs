@@ -0,0 +1,42 @@
+use std::fs;
+
+use dver_core::templates::{bundled_packs, sdk_caches};
+use tempfile::TempDir;
+
+#[test]
+fn caches_are_listed_per_sdk_and_flagged_once_the_sdk_is_gone() {
+    let engine = TempDir::new().unwrap();
+    for sdk in ["v9.0.100", "v8.0.404", "v10.0.100"] {
+        fs::create_dir_all(engine.path().join("dotnetcli").join(sdk)).unwrap();
+    }
+    fs::write(engine.path().join("dotnetcli/v8.0.404/templatecache.json"), "{}").unwrap();
+    // I pacchetti installati con `dotnet new install` non sono una cache per SDK
+    fs::create_dir_all(engine.path().join("packages")).unwrap();
+
+    let caches = sdk_caches(engine.path(), &["8.0.404".to_string(), "10.0.100".to_string()]);
+    let summary: Vec<(&str, bool, u64)> = caches.iter().map(|c| (c.sdk.as_str(), c.installed, c.size_bytes)).collect();
+    assert_eq!(summary, [("8.0.404", true, 2), ("9.0.100", false, 0), ("10.0.100", true, 0)]);
+    assert!(sdk_caches(&engine.path().join("missing"), &[]).is_empty());
+}
+
+#[test]
+fn template_packs_follow_the_runtime_the_sdk_runs_on() {
+    let root = TempDir::new().unwrap();
+    let sdk = root.path().join("sdk/8.0.404");
+    fs::create_dir_all(&sdk).unwrap();
+    fs::write(
+        sdk.join("dotnet.runtimeconfig.json"),
+        r#"{"runtimeOptions":{"tfm":"net8.0","framework":{"name":"Microsoft.NETCore.App","version":"8.0.11"}}}"#,
+    )
+    .unwrap();
+    let templates = root.path().join("templates/8.0.11");
+    fs::create_dir_all(&templates).unwrap();
+    for pack in ["microsoft.dotnet.common.itemtemplates.8.0.11.nupkg", "Microsoft.DotNet.Web.ProjectTemplates.8.0.8.0.11.nupkg"] {
+        fs::write(templates.join(pack), "").unwrap();
+    }
+
+    let packs = bundled_packs(&sdk).unwrap();
+    assert_eq!((packs.sdk.as_str(), packs.path.as_path()), ("8.0.404", templates.as_path()));
+    assert_eq!(packs.packs, ["microsoft.dotnet.common.itemtemplates", "microsoft.dotnet.web.projecttemplates.8.0"]);
+    assert_eq!(bundled_packs(&root.path().join("sdk/9.0.100")), None);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "async")]
+
+use std::path::PathBuf;
+
+use dver_core::config::{TlsRoots, TlsVersion};
+use dver_core::http::ReqwestClient;
+use dver_core::Config;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+fn config_with_bundle(bundle: PathBuf) -> Config {
+    Config { ca_bundle: Some(bundle), ..Config::default() }
+}
+
+#[test]
+fn extra_roots_and_tls_options_build_a_client() {
+    for tls_roots in [TlsRoots::Native, TlsRoots::Webpki] {
+        let config = Config {
+            tls_roots,
+            min_tls_version: Some(TlsVersion::Tls12),
+            ..config_with_bundle(PathBuf::from(FIXTURES).join("corp-root.pem"))
+        };
+        ReqwestClient::new(&config).unwrap();
+    }
+}
+
+#[test]
+fn rustls_accepts_a_tls_1_3_minimum() {
+    let config = Config { tls_roots: TlsRoots::Webpki, min_tls_version: Some(TlsVersion::Tls13), ..Config::default() };
+    ReqwestClient::new(&config).unwrap();
+}
+
+#[test]
+fn a_missing_bundle_is_reported_with_its_path() {
+    let err = ReqwestClient::new(&config_with_bundle(PathBuf::from("/nonexistent/ca.pem"))).unwrap_err();
+    assert_eq!(err.code(), "not-found");
+    assert!(err.to_string().contains("/nonexistent/ca.pem"), "{}", err);
+}
+
+#[test]
+fn a_bundle_without_certificates_is_a_parse_error() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let bundle = dir.path().join("empty.pem");
+    std::fs::write(&bundle, "not a certificate\n").unwrap();
+    let err = ReqwestClient::new(&config_with_bundle(bundle)).unwrap_err();
+    assert_eq!(err.code(), "parse");
+}
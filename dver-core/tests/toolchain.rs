@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use dver_core::inventory::{InstalledRuntime, InstalledSdk};
+use dver_core::toolchain::{Toolchain, ToolchainRuntime, TOOLCHAIN_FORMAT};
+use dver_core::{Config, DverError};
+
+fn sdk(version: &str) -> InstalledSdk {
+    InstalledSdk { version: version.to_string(), path: PathBuf::from("/dotnet/sdk").join(version), root: None, arch: None }
+}
+
+fn runtime(version: &str) -> InstalledRuntime {
+    InstalledRuntime {
+        name: "Microsoft.NETCore.App".to_string(),
+        version: version.to_string(),
+        path: PathBuf::from("/dotnet/shared/Microsoft.NETCore.App").join(version),
+    }
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn capture_sorts_and_deduplicates_the_installed_state() {
+    let config = Config { default_channel: Some("8.0".to_string()), ..Default::default() };
+    let toolchain = Toolchain::capture(
+        &[sdk("9.0.100"), sdk("8.0.404"), sdk("8.0.404")],
+        &[runtime("8.0.11"), runtime("9.0.0")],
+        &strings(&["wasm-tools", "maui", "wasm-tools"]),
+        &config,
+    );
+    assert_eq!(toolchain.format, TOOLCHAIN_FORMAT);
+    assert_eq!(toolchain.sdks, ["8.0.404", "9.0.100"]);
+    assert_eq!(toolchain.runtimes.len(), 2);
+    assert_eq!(toolchain.workloads, ["maui", "wasm-tools"]);
+    assert_eq!(toolchain.default_channel.as_deref(), Some("8.0"));
+
+    let json = serde_json::to_string(&toolchain).unwrap();
+    assert_eq!(Toolchain::parse(&json).unwrap(), toolchain);
+}
+
+#[test]
+fn parse_rejects_newer_formats_and_partial_versions() {
+    let newer = format!(r#"{{ "format": {}, "sdks": [] }}"#, TOOLCHAIN_FORMAT + 1);
+    assert!(matches!(Toolchain::parse(&newer), Err(DverError::Parse(m)) if m.contains("upgrade dver")));
+    assert!(matches!(Toolchain::parse(r#"{ "format": 1, "sdks": ["8.0"] }"#), Err(DverError::Parse(_))));
+    assert!(matches!(Toolchain::parse(r#"{ "format": 1, "sdks": ["8.0.404; rm -rf /"] }"#), Err(DverError::Parse(_))));
+    assert!(matches!(Toolchain::parse("not json"), Err(DverError::Parse(_))));
+
+    let minimal = Toolchain::parse(r#"{ "format": 1, "sdks": ["8.0.404"] }"#).unwrap();
+    assert!(minimal.runtimes.is_empty() && minimal.workloads.is_empty() && minimal.default_channel.is_none());
+}
+
+#[test]
+fn plan_lists_missing_and_extra_sdks_and_missing_workloads() {
+    let toolchain = Toolchain::parse(r#"{ "format": 1, "sdks": ["8.0.404", "9.0.100"], "workloads": ["wasm-tools", "maui"] }"#).unwrap();
+    let plan = toolchain.plan(&strings(&["9.0.100", "8.0.403", "7.0.410"]), &strings(&["wasm-tools"]));
+    assert_eq!(plan.install, ["8.0.404"]);
+    assert_eq!(plan.extra, ["7.0.410", "8.0.403"]);
+    assert_eq!(plan.workloads, ["maui"]);
+    assert!(!plan.is_satisfied());
+
+    let done = toolchain.plan(&strings(&["8.0.404", "9.0.100"]), &strings(&["maui", "wasm-tools"]));
+    assert!(done.is_satisfied() && done.extra.is_empty());
+}
+
+#[test]
+fn missing_runtimes_compare_name_and_version() {
+    let mut toolchain = Toolchain::capture(&[], &[runtime("8.0.11"), runtime("9.0.0")], &[], &Config::default());
+    toolchain.runtimes.push(ToolchainRuntime { name: "Microsoft.AspNetCore.App".to_string(), version: "8.0.11".to_string() });
+    let missing = toolchain.missing_runtimes(&[runtime("8.0.11")]);
+    assert_eq!(missing.iter().map(|r| (r.name.as_str(), r.version.as_str())).collect::<Vec<_>>(), [
+        ("Microsoft.NETCore.App", "9.0.0"),
+        ("Microsoft.AspNetCore.App", "8.0.11"),
+    ]);
+}
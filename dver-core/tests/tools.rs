@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use dver_core::tools::{find_tool_manifest, global_drift, local_drift, parse_tool_list, ToolScope, ToolStatus};
+use tempfile::TempDir;
+
+const GLOBAL_LIST: &str = "\
+Package Id          Version      Commands
+------------------------------------------------
+dotnet-ef           8.0.10       dotnet-ef
+dotnet-reportgenerator-globaltool      5.3.0        reportgenerator
+";
+
+#[test]
+fn global_tools_are_compared_with_the_declared_versions() {
+    let installed = parse_tool_list(GLOBAL_LIST);
+    assert_eq!(installed.len(), 2);
+    assert_eq!(installed[1].commands, ["reportgenerator"]);
+
+    let wanted: BTreeMap<String, String> = [
+        ("Dotnet-EF", "8.0.11"),
+        ("dotnet-reportgenerator-globaltool", "*"),
+        ("dotnet-format", "8.0.0"),
+    ]
+    .into_iter()
+    .map(|(id, v)| (id.to_string(), v.to_string()))
+    .collect();
+    let drifts = global_drift(&wanted, &installed);
+    let summary: Vec<(&str, ToolStatus)> = drifts.iter().map(|d| (d.id.as_str(), d.status)).collect();
+    assert_eq!(
+        summary,
+        [
+            ("Dotnet-EF", ToolStatus::Drift),
+            ("dotnet-format", ToolStatus::Missing),
+            ("dotnet-reportgenerator-globaltool", ToolStatus::Current),
+        ]
+    );
+    assert_eq!(drifts[0].installed.as_deref(), Some("8.0.10"));
+    assert_eq!(drifts[2].wanted, None);
+    assert!(drifts.iter().all(|d| d.scope == ToolScope::Global));
+}
+
+#[test]
+fn local_tools_are_compared_with_the_restored_packages() {
+    let dir = TempDir::new().unwrap();
+    let repo = dir.path().join("repo");
+    fs::create_dir_all(repo.join(".config")).unwrap();
+    fs::create_dir_all(repo.join("src")).unwrap();
+    fs::write(
+        repo.join(".config").join("dotnet-tools.json"),
+        r#"{ "version": 1, "isRoot": true, "tools": {
+            "dotnet-ef": { "version": "8.0.11", "commands": ["dotnet-ef"] },
+            "csharpier": { "version": "0.30.1", "commands": ["dotnet-csharpier"] },
+            "Cake.Tool": { "version": "4.0.0", "commands": ["dotnet-cake"] } } }"#,
+    )
+    .unwrap();
+    let packages = dir.path().join("packages");
+    fs::create_dir_all(packages.join("dotnet-ef").join("8.0.11")).unwrap();
+    fs::create_dir_all(packages.join("csharpier").join("0.29.0")).unwrap();
+
+    let (path, manifest) = find_tool_manifest(&repo.join("src")).unwrap().unwrap();
+    assert_eq!(path, repo.join(".config").join("dotnet-tools.json"));
+    let drifts = local_drift(&manifest, &packages);
+    let summary: Vec<(&str, ToolStatus, Option<&str>)> =
+        drifts.iter().map(|d| (d.id.as_str(), d.status, d.installed.as_deref())).collect();
+    assert_eq!(
+        summary,
+        [
+            ("Cake.Tool", ToolStatus::Missing, None),
+            ("csharpier", ToolStatus::Drift, Some("0.29.0")),
+            ("dotnet-ef", ToolStatus::Current, Some("8.0.11")),
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+use dver_core::releases::ChannelReleases;
+use dver_core::updates::{check_updates, markdown, notice_line, overall, pending_upgrades, UpdateNotifications, UpdateStatus};
+
+const CHANNEL_8_0: &str = include_str!("fixtures/8.0/releases.json");
+
+fn sdks(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter().map(|(v, s)| (v.to_string(), s.to_string())).collect()
+}
+
+fn channels() -> Vec<ChannelReleases> {
+    vec![serde_json::from_str(CHANNEL_8_0).unwrap()]
+}
+
+#[test]
+fn sdks_behind_a_security_release_need_a_security_update() {
+    let updates = check_updates(&channels(), &sdks(&[("8.0.100", "global.json"), ("8.0.406", "installed")]));
+    assert_eq!(updates[0].status, UpdateStatus::Security);
+    assert_eq!(updates[0].update_to.as_deref(), Some("8.0.113"));
+    assert_eq!(updates[0].cves, ["CVE-2025-21172"]);
+    assert_eq!(updates[1].status, UpdateStatus::Current);
+    assert_eq!(overall(&updates), UpdateStatus::Security);
+    assert_eq!(overall(&updates).exit_code(), 2);
+}
+
+#[test]
+fn newer_sdks_without_cves_are_plain_updates() {
+    let channel: ChannelReleases = serde_json::from_value(serde_json::json!({
+        "channel-version": "9.0",
+        "releases": [
+            { "release-version": "9.0.1", "sdk": { "version": "9.0.102" }, "sdks": [{ "version": "9.0.102" }] },
+            { "release-version": "9.0.0", "sdk": { "version": "9.0.100" }, "sdks": [{ "version": "9.0.100" }] },
+        ],
+    }))
+    .unwrap();
+    let updates = check_updates(&[channel], &sdks(&[("9.0.100", "installed")]));
+    assert_eq!(updates[0].status, UpdateStatus::Update);
+    assert_eq!(updates[0].update_to.as_deref(), Some("9.0.102"));
+    assert_eq!(overall(&updates).exit_code(), 1);
+    assert!(markdown(&updates).contains("| 9.0.100 | installed | update available | 9.0.102 | - |"));
+}
+
+#[test]
+fn pending_upgrades_take_the_newest_installed_sdk_of_each_band() {
+    let installed: Vec<String> = ["8.0.100", "8.0.300", "8.0.309", "8.0.406", "9.0.100-rc.2.24474.11"].iter().map(|v| v.to_string()).collect();
+    let upgrades = pending_upgrades(&channels(), &installed, "linux-x64");
+    // 8.0.406 è già la più recente della sua band, le anteprime non si aggiornano
+    let bands: Vec<(&str, &str, &str)> = upgrades.iter().map(|u| (u.band.as_str(), u.from.as_str(), u.to.as_str())).collect();
+    assert_eq!(bands, [("8.0.1xx", "8.0.100", "8.0.113"), ("8.0.3xx", "8.0.309", "8.0.310")]);
+    assert_eq!(upgrades[0].release_date.map(|d| d.to_string()).as_deref(), Some("2025-02-11"));
+    assert_eq!(upgrades[0].cves, ["CVE-2025-21172"]);
+
+    let channel: ChannelReleases = serde_json::from_value(serde_json::json!({
+        "channel-version": "9.0",
+        "releases": [{
+            "release-version": "9.0.1",
+            "sdks": [{ "version": "9.0.102", "files": [
+                { "name": "dotnet-sdk-linux-x64.tar.gz", "rid": "linux-x64", "url": "https://example.test/sdk.tar.gz", "size": 216_000_000 },
+            ] }],
+        }],
+    }))
+    .unwrap();
+    let upgrades = pending_upgrades(&[channel], &["9.0.100".to_string()], "linux-x64");
+    assert_eq!((upgrades[0].download_bytes, upgrades[0].is_security()), (Some(216_000_000), false));
+}
+
+#[test]
+fn unknown_versions_and_empty_reports_are_up_to_date() {
+    let updates = check_updates(&channels(), &sdks(&[("7.0.100", "installed")]));
+    assert_eq!(updates[0].status, UpdateStatus::Current);
+    assert_eq!(overall(&[]), UpdateStatus::Current);
+}
+
+#[test]
+fn notices_name_a_single_update_and_count_several() {
+    let updates = check_updates(&channels(), &sdks(&[("8.0.100", "installed"), ("8.0.406", "installed")]));
+    assert_eq!(
+        notice_line(&updates, &[], UpdateNotifications::All).as_deref(),
+        Some("SDK 8.0.113 is available for 8.0.100 (security); run `dver install 8.0.113`")
+    );
+    assert_eq!(notice_line(&updates, &["8.0.113".to_string()], UpdateNotifications::All), None);
+    assert_eq!(notice_line(&updates, &[], UpdateNotifications::Off), None);
+
+    let mut several = updates.clone();
+    several[1].status = UpdateStatus::Update;
+    several[1].update_to = Some("8.0.407".to_string());
+    assert_eq!(
+        notice_line(&several, &[], UpdateNotifications::All).as_deref(),
+        Some("2 SDK updates are available (1 security); run `dver check-updates`")
+    );
+    assert_eq!(
+        notice_line(&several, &[], UpdateNotifications::Security).as_deref(),
+        Some("SDK 8.0.113 is available for 8.0.100 (security); run `dver install 8.0.113`")
+    );
+}
@@ -0,0 +1,75 @@
+use dver_core::resolve::newest_matching;
+use dver_core::version::{sort_versions, SdkVersion, VersionSpec};
+
+fn v(s: &str) -> SdkVersion {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parses_release_and_prerelease_versions() {
+    let release = v("8.0.404");
+    assert_eq!((release.major, release.minor, release.patch), (8, 0, 404));
+    assert_eq!(release.feature_band(), 400);
+    assert!(!release.is_prerelease());
+
+    let rc = v("8.0.100-rc.2.23502.2");
+    assert_eq!(rc.prerelease.as_deref(), Some("rc.2.23502.2"));
+    assert_eq!(rc.to_string(), "8.0.100-rc.2.23502.2");
+
+    for bad in ["8", "8.0", "8.0.x", "8.0.100-", "v8.0.100", "8.0.100.1"] {
+        assert!(bad.parse::<SdkVersion>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn orders_numerically_and_prereleases_first() {
+    let mut versions: Vec<String> = [
+        "10.0.100", "9.0.100", "8.0.404", "8.0.100", "8.0.100-rc.2.23502.2", "8.0.100-rc.10.1",
+        "8.0.100-preview.7.23376.3", "8.0.1100", "not-a-version",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    sort_versions(&mut versions);
+    assert_eq!(
+        versions,
+        [
+            "8.0.100-preview.7.23376.3", "8.0.100-rc.2.23502.2", "8.0.100-rc.10.1", "8.0.100", "8.0.404",
+            "8.0.1100", "9.0.100", "10.0.100", "not-a-version",
+        ]
+    );
+}
+
+#[test]
+fn specs_match_major_channel_band_and_exact() {
+    let cases = [
+        ("8", "8.0.404", true),
+        ("8", "9.0.100", false),
+        ("8.0", "8.0.307", true),
+        ("8.0", "8.1.100", false),
+        ("8.0.4xx", "8.0.404", true),
+        ("8.0.4xx", "8.0.307", false),
+        ("8.0.404", "8.0.404", true),
+        ("8.0.404", "8.0.403", false),
+    ];
+    for (spec, version, expected) in cases {
+        let spec: VersionSpec = spec.parse().unwrap();
+        assert_eq!(spec.matches(&v(version)), expected, "{:?} vs {}", spec, version);
+    }
+    assert!("8.x".parse::<VersionSpec>().is_err());
+}
+
+#[test]
+fn partial_specs_resolve_to_the_newest_stable_match() {
+    let candidates: Vec<String> = ["8.0.307", "8.0.404", "9.0.100-rc.1.24452.12", "9.0.100", "10.0.100-preview.1.25080.5"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let resolve = |spec: &str| newest_matching(&spec.parse().unwrap(), &candidates);
+    assert_eq!(resolve("8").as_deref(), Some("8.0.404"));
+    assert_eq!(resolve("8.0.3xx").as_deref(), Some("8.0.307"));
+    assert_eq!(resolve("9.0").as_deref(), Some("9.0.100"));
+    // Senza release stabili si ripiega sull'anteprima più recente
+    assert_eq!(resolve("10").as_deref(), Some("10.0.100-preview.1.25080.5"));
+    assert_eq!(resolve("7"), None);
+}
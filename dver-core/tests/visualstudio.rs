@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+
+use dver_core::platform::{Platform, Unix, Windows};
+use dver_core::process::{command_line, ScriptedRunner};
+use dver_core::registry::parse_installed_versions;
+use dver_core::visualstudio::{
+    bundled_sdks, compare_dotted, instances, is_bundled, minimum_msbuild_version, parse_uninstall_entries, VsSdk,
+};
+
+const UNINSTALL: &str = r"
+HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\{2D1E2F3A-0000-4C5B-9A6E-6E0C2A7B1F10}
+    DisplayName    REG_SZ    Microsoft .NET SDK 8.0.404 (x64) from Visual Studio
+
+HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\{7A9B0C1D-0000-4E2F-8B3C-1D2E3F4A5B6C}
+    DisplayName    REG_SZ    Microsoft Visual C++ 2022 X64 Minimum Runtime from Visual Studio
+
+End of search: 2 match(es) found.
+";
+
+const INSTALLED: &str = r"
+HKEY_LOCAL_MACHINE\SOFTWARE\dotnet\Setup\InstalledVersions\x64
+    InstallLocation    REG_SZ    C:\Program Files\dotnet\
+
+HKEY_LOCAL_MACHINE\SOFTWARE\dotnet\Setup\InstalledVersions\x64\sdk
+    8.0.404    REG_DWORD    0x1
+    9.0.100    REG_DWORD    0x1
+";
+
+const VSWHERE: &str = r#"[
+  {
+    "instanceId": "a1b2c3d4",
+    "installationPath": "C:\\Program Files\\Microsoft Visual Studio\\2022\\Community",
+    "installationVersion": "17.8.34330.188",
+    "displayName": "Visual Studio Community 2022",
+    "isPrerelease": false
+  }
+]"#;
+
+#[test]
+fn only_sdk_packages_from_visual_studio_are_kept() {
+    assert_eq!(parse_uninstall_entries(UNINSTALL), [VsSdk { version: "8.0.404".to_string(), arch: "x64".to_string() }]);
+}
+
+#[test]
+fn an_sdk_is_bundled_when_visual_studio_registered_it_in_that_root() {
+    let vs = parse_uninstall_entries(UNINSTALL);
+    let msi = parse_installed_versions(INSTALLED);
+    let program_files = Path::new(r"C:\Program Files\dotnet");
+    assert!(is_bundled(&vs, &msi, program_files, "8.0.404"));
+    // Installato dal pacchetto MSI del sito, non da Visual Studio
+    assert!(!is_bundled(&vs, &msi, program_files, "9.0.100"));
+    // La stessa versione installata da dver nella sua radice
+    assert!(!is_bundled(&vs, &msi, Path::new(r"C:\Users\ada\.dotnet"), "8.0.404"));
+}
+
+#[test]
+fn both_uninstall_views_and_vswhere_are_queried_only_on_windows() {
+    let queries = Windows.visual_studio_sdks_queries();
+    let runner = ScriptedRunner::new().with(&command_line(&queries[0]), UNINSTALL).with(&command_line(&queries[1]), UNINSTALL);
+    assert_eq!(bundled_sdks(&runner, &Windows).len(), 1);
+
+    let vswhere = command_line(&Windows.vswhere_command().unwrap());
+    let runner = ScriptedRunner::new().with(&vswhere, VSWHERE);
+    let found = instances(&runner, &Windows);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].display_name, "Visual Studio Community 2022");
+    assert_eq!(found[0].installation_version, "17.8.34330.188");
+    // Senza `vswhere` non ci sono istanze
+    assert!(instances(&ScriptedRunner::new(), &Windows).is_empty());
+
+    let runner = ScriptedRunner::new();
+    assert!(bundled_sdks(&runner, &Unix).is_empty());
+    assert!(instances(&runner, &Unix).is_empty());
+    assert!(runner.calls().is_empty());
+}
+
+#[test]
+fn the_minimum_msbuild_version_is_read_from_the_sdk_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(minimum_msbuild_version(dir.path()), None);
+    fs::write(dir.path().join("minimumMSBuildVersion"), "17.11.4\n").unwrap();
+    assert_eq!(minimum_msbuild_version(dir.path()).as_deref(), Some("17.11.4"));
+
+    assert!(compare_dotted("17.8.34330.188", "17.11.4").is_lt());
+    assert!(compare_dotted("17.12.35506.116", "17.11.4").is_gt());
+    assert!(compare_dotted("17.11", "17.11.0").is_eq());
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dver_core::workload::{self, replay_source, sdk_pin_dir, SdkWorkloads};
+use dver_core::Config;
+use tempfile::TempDir;
+
+fn record(sdk: &str, workloads: &[&str]) -> SdkWorkloads {
+    SdkWorkloads {
+        sdk: sdk.to_string(),
+        root: PathBuf::from("/opt/dotnet"),
+        workloads: workloads.iter().map(|w| w.to_string()).collect(),
+    }
+}
+
+#[test]
+fn workloads_are_recorded_per_sdk() {
+    let dir = TempDir::new().unwrap();
+    let config = Config { state_dir: Some(dir.path().to_path_buf()), ..Default::default() };
+    let root = Path::new("/opt/dotnet");
+    let ids = |ids: &[&str]| ids.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+    workload::add(&config, root, "8.0.307", &ids(&["maui", "aspire"])).unwrap();
+    workload::add(&config, root, "8.0.307", &ids(&["maui"])).unwrap();
+    workload::add(&config, root, "9.0.100", &ids(&["wasm-tools"])).unwrap();
+    assert_eq!(workload::tracked(&config, root, "8.0.307").unwrap(), ["aspire", "maui"]);
+
+    workload::remove(&config, root, "8.0.307", &ids(&["aspire"])).unwrap();
+    assert_eq!(workload::tracked(&config, root, "8.0.307").unwrap(), ["maui"]);
+    // Un SDK senza più workload perde il record
+    workload::remove(&config, root, "8.0.307", &ids(&["maui"])).unwrap();
+    workload::forget(&config, root, "9.0.100").unwrap();
+    assert!(workload::load_all(&config).unwrap().is_empty());
+}
+
+#[test]
+fn workloads_are_replayed_from_the_newest_older_band_of_the_same_channel() {
+    let records = [record("8.0.110", &["wasm-tools"]), record("8.0.307", &["maui"]), record("9.0.100", &["aspire"])];
+    let root = Path::new("/opt/dotnet");
+    assert_eq!(replay_source(&records, root, "8.0.404").map(|r| r.sdk.as_str()), Some("8.0.307"));
+    // Stessa band: i manifest sono già quelli giusti
+    assert!(replay_source(&records, root, "8.0.310").is_none());
+    // Un SDK più vecchio non eredita da uno più nuovo
+    assert_eq!(replay_source(&records, root, "8.0.200").map(|r| r.sdk.as_str()), Some("8.0.110"));
+    assert!(replay_source(&records, root, "10.0.100").is_none());
+    assert!(replay_source(&records, Path::new("/usr/share/dotnet"), "8.0.404").is_none());
+    assert!(replay_source(&records, root, "8.0.307").is_none());
+}
+
+#[test]
+fn the_pin_dir_selects_exactly_one_sdk() {
+    let dir = TempDir::new().unwrap();
+    let config = Config { cache_dir: Some(dir.path().to_path_buf()), ..Default::default() };
+    let pin = sdk_pin_dir(&config, "8.0.404").unwrap();
+    let global_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(pin.join("global.json")).unwrap()).unwrap();
+    assert_eq!(global_json["sdk"]["version"], "8.0.404");
+    assert_eq!(global_json["sdk"]["rollForward"], "disable");
+}
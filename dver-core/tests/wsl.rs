@@ -0,0 +1,39 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dver_core::wsl::{install_root_error, is_wsl_release, windows_dotnet_entry, windows_drive};
+use dver_core::DverError;
+
+#[test]
+fn wsl_kernels_are_recognized_from_their_release() {
+    assert!(is_wsl_release("5.15.153.1-microsoft-standard-WSL2\n"));
+    // WSL 1 riporta un kernel finto con `Microsoft` maiuscolo
+    assert!(is_wsl_release("4.4.0-19041-Microsoft"));
+    assert!(!is_wsl_release("6.8.0-49-generic"));
+}
+
+#[test]
+fn windows_drives_are_the_single_letter_mounts() {
+    assert_eq!(windows_drive(Path::new("/mnt/c/Program Files/dotnet")), Some('C'));
+    assert_eq!(windows_drive(Path::new("/mnt/D")), Some('D'));
+    assert_eq!(windows_drive(Path::new("/mnt/wsl/docker")), None);
+    assert_eq!(windows_drive(Path::new("/home/ada/.dotnet")), None);
+    assert_eq!(windows_drive(Path::new("mnt/c/dotnet")), None);
+}
+
+#[test]
+fn installs_on_a_windows_drive_are_refused_with_a_linux_path_hint() {
+    let e = install_root_error(Path::new("/mnt/c/Users/ada/.dotnet")).unwrap();
+    assert!(matches!(e, DverError::Usage(_)));
+    assert!(e.to_string().contains("Windows drive C:"));
+    assert!(install_root_error(Path::new("/home/ada/.dotnet")).is_none());
+}
+
+#[test]
+fn only_entries_on_a_windows_drive_count_as_windows_dotnet() {
+    // Una cartella Linux con un `dotnet.exe` non è quella di Windows
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("dotnet.exe"), "").unwrap();
+    let entries = vec![PathBuf::from("/usr/bin"), dir.path().to_path_buf()];
+    assert_eq!(windows_dotnet_entry(&entries), None);
+}
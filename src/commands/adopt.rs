@@ -0,0 +1,214 @@
+//! `dver adopt`: registra gli SDK installati senza dver, nelle posizioni note o nelle
+//! radici indicate. Con `--manage` la loro radice entra in `[roots]`, con
+//! `--relocate` vengono spostati nella radice gestita, con le stesse regole di
+//! `uninstall` per le cartelle di sistema e gli SDK degli installer; in entrambi i
+//! casi `uninstall` può poi rimuoverli. Con `--replace-system` disattiva invece lo snap e
+//! i pacchetti apt di .NET, che altrimenti si contendono `dotnet` con dver.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use dver_core::adopt::{self, ownership_of, relocate, root_name, Adoption, Ownership};
+use dver_core::config;
+use dver_core::distro::{self, SystemPackage, SYSTEM_DOTNET_LINK};
+use dver_core::inventory::{known_dotnet_roots, scan_root_sdks};
+use dver_core::macos;
+use dver_core::platform::{self, Arch};
+use dver_core::process::command_line;
+use dver_core::provenance::same_root;
+use dver_core::registry::{self, msi_owner};
+use dver_core::sandbox::is_system_path;
+use dver_core::DverError;
+
+use super::Context;
+
+pub fn run(ctx: &Context, paths: &[PathBuf], manage: bool, move_sdks: bool, force: bool) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let target = config.managed_root();
+    if move_sdks && target.is_none() {
+        return Err(DverError::Usage(
+            "--relocate needs a managed root to move the SDKs into\nhint: dver config set install_root <path>".to_string()));
+    }
+    let candidates = if paths.is_empty() { known_dotnet_roots(config) } else { paths.to_vec() };
+
+    let mut roots = config.roots.clone();
+    let mut added: Vec<String> = Vec::new();
+    let mut adoptions: Vec<Adoption> = Vec::new();
+    let mut declined = false;
+    for root in candidates {
+        let versions: Vec<String> = scan_root_sdks(&root)?.into_iter().map(|sdk| sdk.version).collect();
+        if versions.is_empty() {
+            if !paths.is_empty() {
+                eprintln!("No SDKs found in {}", root.display());
+            }
+            continue;
+        }
+
+        let mut ownership = ownership_of(config, &root);
+        let mut adopted_root = root.clone();
+        let mut moved_from = None;
+        if let Some(target) = target.as_ref().filter(|t| move_sdks && !same_root(t, &root)) {
+            let question = format!("Move {} SDK(s) from {} into {}?", versions.len(), root.display(), target.display());
+            if let Some(e) = relocation_refusal(ctx, &root, &versions, force) {
+                eprintln!("{}", e);
+            } else if !ctx.prompter.confirm(&question, false)? {
+                declined = true;
+            } else if is_system_path(platform::current(), &root)
+                && !ctx.prompter.confirm_typed(&format!("{} is in a system directory.", root.display()), "relocate")?
+            {
+                eprintln!("Not moving {}: moving a system directory needs a typed confirmation in a terminal", root.display());
+            } else {
+                relocate(&root, target)?;
+                if ctx.is_human() {
+                    println!("Moved {} into {}", root.display(), target.display());
+                }
+                ownership = Ownership::Managed;
+                adopted_root = target.clone();
+                moved_from = Some(root.clone());
+            }
+        } else if manage && ownership == Ownership::External {
+            let name = root_name(&root, &roots);
+            config::set_key(&format!("roots.{}", name), &root.to_string_lossy())?;
+            roots.insert(name.clone(), root.clone());
+            added.push(name);
+            ownership = Ownership::Managed;
+        }
+
+        if ctx.is_human() {
+            println!("{} ({}): {}", root.display(), ownership, versions.join(", "));
+        }
+        adoptions.extend(
+            versions.into_iter().map(|version| Adoption::new(version, adopted_root.clone(), ownership, moved_from.clone())),
+        );
+    }
+
+    if declined && !ctx.prompter.interactive {
+        eprintln!("hint: pass --yes to move the SDKs without a terminal");
+    }
+    if adoptions.is_empty() {
+        return Err(DverError::NotFound("No .NET SDKs found to adopt".to_string()));
+    }
+    adopt::record(config, &adoptions)?;
+
+    if !ctx.is_human() {
+        return ctx.print_structured(&adoptions, &adoptions);
+    }
+    println!("Adopted {} SDK(s)", adoptions.len());
+    if let Some(name) = added.first() {
+        println!("Added to [roots]: {}", added.join(", "));
+        eprintln!("hint: manage them with --root, e.g. dver --root {} uninstall <version>", name);
+    } else if adoptions.iter().any(|a| a.ownership == Ownership::External) {
+        eprintln!("hint: pass --manage to let dver uninstall them, or --relocate to move them into install_root");
+    }
+    Ok(())
+}
+
+/// Cosa fa (o farebbe, senza conferma) `adopt --replace-system`.
+#[derive(Debug, Serialize)]
+struct ReplaceSystem {
+    packages: Vec<SystemPackage>,
+    conflicts: Vec<String>,
+    commands: Vec<String>,
+    restore_commands: Vec<String>,
+    /// SDK dei pacchetti che dver non ha ancora installato
+    sdks_to_install: Vec<String>,
+    applied: bool,
+}
+
+/// Perché `--relocate` non deve toccare `root`: gli SDK di un MSI o del pkg di macOS
+/// resterebbero registrati ma rotti, e le cartelle di sistema richiedono `--force`.
+fn relocation_refusal(ctx: &Context, root: &Path, versions: &[String], force: bool) -> Option<DverError> {
+    let msi = registry::installed_versions(ctx.runner(), platform::current());
+    if let Some((version, owner)) = versions.iter().find_map(|v| msi_owner(&msi, root, v).map(|owner| (v, owner))) {
+        return Some(DverError::Permission(format!(
+            "Refusing to move {}: SDK {} belongs to an MSI package ({})\nhint: remove it from Settings > Apps and install it again with dver",
+            root.display(), version, owner.arch)));
+    }
+    let pkg = if macos::is_macos() { macos::pkg_sdks(ctx.runner()) } else { Vec::new() };
+    if let Some(sdk) = pkg.iter().find(|sdk| versions.contains(&sdk.version) && same_root(&sdk.root(Arch::native()), root)) {
+        return Some(DverError::Permission(format!(
+            "Refusing to move {}: SDK {} was installed by the .NET pkg installer\nhint: remove it with Microsoft's dotnet-core-uninstall tool and install it again with dver",
+            root.display(), sdk.version)));
+    }
+    if !force && is_system_path(platform::current(), root) {
+        return Some(DverError::Permission(format!(
+            "Refusing to move {}: it is a system directory\nhint: pass --force and type `relocate` to confirm",
+            root.display())));
+    }
+    None
+}
+
+/// `adopt --replace-system`: spiega i conflitti dello snap e dei pacchetti apt di .NET e
+/// ne disattiva i punti d'ingresso dopo conferma; altrimenti stampa i comandi esatti.
+pub fn replace_system(ctx: &Context) -> Result<(), DverError> {
+    let runner = ctx.runner();
+    let packages = distro::system_packages(runner);
+    let sudo = !distro::is_root(runner);
+    let link_exists = Path::new(SYSTEM_DOTNET_LINK).symlink_metadata().is_ok();
+    let mut commands = distro::replacement_commands(&packages, link_exists, sudo);
+
+    let managed: Vec<String> = ctx.config.managed_root().and_then(|root| scan_root_sdks(&root).ok()).unwrap_or_default()
+        .into_iter().map(|sdk| sdk.version).collect();
+    let mut roots: Vec<PathBuf> = packages.iter().map(SystemPackage::root).collect();
+    roots.dedup();
+    let mut sdks_to_install: Vec<String> = roots.iter()
+        .flat_map(|root| scan_root_sdks(root).unwrap_or_default())
+        .map(|sdk| sdk.version)
+        .filter(|v| !managed.contains(v))
+        .collect();
+    sdks_to_install.sort();
+    sdks_to_install.dedup();
+
+    let mut plan = ReplaceSystem {
+        conflicts: distro::conflicts(&packages),
+        commands: commands.iter().map(command_line).collect(),
+        restore_commands: distro::restore_commands(&packages, sudo).iter().map(command_line).collect(),
+        packages,
+        sdks_to_install,
+        applied: false,
+    };
+    if ctx.is_human() {
+        if plan.commands.is_empty() {
+            println!("No dotnet snap or apt packages are in the way; nothing to replace");
+            return Ok(());
+        }
+        for package in &plan.packages {
+            println!("{} {} ({})", package.name, package.version, package.source.name());
+        }
+        for conflict in &plan.conflicts {
+            println!("- {}", conflict);
+        }
+        println!("These commands disable them without uninstalling anything:");
+        for command in &plan.commands {
+            println!("  {}", command);
+        }
+        println!("To undo:");
+        for command in &plan.restore_commands {
+            println!("  {}", command);
+        }
+    }
+
+    if !plan.commands.is_empty() && ctx.prompter.confirm("Run these commands now?", false)? {
+        for (command, line) in commands.iter_mut().zip(&plan.commands) {
+            let output = runner.output(command).map_err(|e| DverError::Process(format!("Cannot run `{}`: {}", line, e)))?;
+            if !output.status.success() {
+                return Err(DverError::process(line, &output, Some("run the remaining commands above by hand")));
+            }
+        }
+        plan.applied = true;
+    }
+
+    if !ctx.is_human() {
+        return ctx.print_structured(&plan, &plan.packages);
+    }
+    if plan.applied {
+        println!("Disabled the system dotnet entry points");
+    } else {
+        eprintln!("hint: run the commands above, or pass --yes to let dver run them");
+    }
+    if !plan.sdks_to_install.is_empty() {
+        eprintln!("hint: install the same SDKs with `dver install {}`", plan.sdks_to_install.join(" "));
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! `dver asdf`: i comandi che un plugin di asdf (o di mise, che usa gli stessi plugin)
+//! invoca, e il generatore del plugin stesso. Ogni versione di asdf ha la sua radice
+//! in `$ASDF_INSTALL_PATH`, con l'eseguibile `dotnet` in cima.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dver_core::install::{install_script_url, install_sdk, InstallRequest};
+use dver_core::version::SdkVersion;
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+use crate::AsdfAction;
+
+/// Script del plugin: nome in `bin/` e contenuto. `$DVER` permette di usare un dver
+/// fuori dal `PATH`.
+const PLUGIN_SCRIPTS: &[(&str, &str)] = &[
+    ("list-all", "exec \"${DVER:-dver}\" --non-interactive asdf list-all\n"),
+    ("latest-stable", "exec \"${DVER:-dver}\" --non-interactive asdf latest-stable \"$1\"\n"),
+    ("install", "exec \"${DVER:-dver}\" --non-interactive asdf install\n"),
+    ("list-bin-paths", "echo .\n"),
+    ("exec-env", "export DOTNET_ROOT=\"$ASDF_INSTALL_PATH\"\n"),
+];
+
+pub async fn run(ctx: &Context, action: &AsdfAction) -> Result<(), DverError> {
+    match action {
+        AsdfAction::ListAll => println!("{}", ctx.remote_sdk_versions().await?.join(" ")),
+        AsdfAction::LatestStable { query } => {
+            let query = query.as_deref().unwrap_or_default();
+            let latest = ctx.remote_sdk_versions().await?
+                .into_iter()
+                .rfind(|v| v.starts_with(query) && v.parse::<SdkVersion>().is_ok_and(|v| v.prerelease.is_none()))
+                .ok_or_else(|| DverError::NotFound(format!("No stable SDK matches {:?}", query)))?;
+            println!("{}", latest);
+        }
+        AsdfAction::Install { version, path } => install(ctx, version.clone(), path.clone()).await?,
+        AsdfAction::Plugin { dir } => write_plugin(dir)?,
+    }
+    Ok(())
+}
+
+/// Installa nella radice di asdf; senza argomenti legge `ASDF_INSTALL_VERSION` e
+/// `ASDF_INSTALL_PATH`, come fa lo script `bin/install` del plugin.
+async fn install(ctx: &Context, version: Option<String>, path: Option<PathBuf>) -> Result<(), DverError> {
+    if std::env::var("ASDF_INSTALL_TYPE").is_ok_and(|t| t != "version") {
+        return Err(DverError::Usage("dver installs released SDK versions only, not refs".to_string()));
+    }
+    let version = version.or_else(|| std::env::var("ASDF_INSTALL_VERSION").ok()).ok_or_else(|| {
+        DverError::Usage("Provide the version, or set ASDF_INSTALL_VERSION".to_string())
+    })?;
+    let path = path.or_else(|| std::env::var_os("ASDF_INSTALL_PATH").map(PathBuf::from)).ok_or_else(|| {
+        DverError::Usage("Provide the install path, or set ASDF_INSTALL_PATH".to_string())
+    })?;
+    let request = InstallRequest { version: Some(version.clone()), install_dir: Some(path.clone()), lts: false };
+    let client = ctx.http_client(&install_script_url(&ctx.config))?;
+    let output = install_sdk(client, ctx.runner(), &request, &ctx.config).await?;
+    println!("{}", output);
+    println!("Installed SDK {} into {}", version, path.display());
+    Ok(())
+}
+
+/// Scrive in `dir` un plugin di asdf i cui script chiamano dver.
+fn write_plugin(dir: &Path) -> Result<(), DverError> {
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).with_context(|| format!("Failed to create {}", bin.display()))?;
+    for (name, body) in PLUGIN_SCRIPTS {
+        let path = bin.join(name);
+        let script = format!("#!/usr/bin/env bash\n# Generated by `dver asdf plugin`\n{}", body);
+        fs::write(&path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("Failed to make {} executable", path.display()))?;
+        }
+    }
+    println!("Wrote the asdf plugin to {}", dir.display());
+    println!("mise: mise plugins link dotnet {}", dir.display());
+    println!("asdf: commit it to a git repository, then asdf plugin add dotnet <repository URL>");
+    Ok(())
+}
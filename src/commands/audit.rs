@@ -0,0 +1,98 @@
+use serde::Serialize;
+
+use dver_core::audit::{audit, channels_of, AuditFinding, AuditStatus, ComponentKind};
+use dver_core::inventory::{list_installed_runtimes, list_installed_sdks};
+use dver_core::DverError;
+
+use super::Context;
+
+#[derive(Debug, Serialize)]
+struct AuditRow<'a> {
+    kind: ComponentKind,
+    name: &'a str,
+    version: &'a str,
+    status: AuditStatus,
+    cve_id: Option<&'a str>,
+    fixed_in: Option<&'a str>,
+    cve_url: Option<&'a str>,
+}
+
+fn rows(findings: &[AuditFinding]) -> Vec<AuditRow<'_>> {
+    let mut rows = Vec::new();
+    for f in findings {
+        let row = |cve_id, fixed_in, cve_url| AuditRow {
+            kind: f.kind,
+            name: &f.name,
+            version: &f.version,
+            status: f.status,
+            cve_id,
+            fixed_in,
+            cve_url,
+        };
+        if f.advisories.is_empty() {
+            rows.push(row(None, None, None));
+        }
+        for a in &f.advisories {
+            rows.push(row(Some(&a.cve_id), Some(&a.fixed_in), Some(&a.cve_url)));
+        }
+    }
+    rows
+}
+
+fn label(f: &AuditFinding) -> String {
+    match f.kind {
+        ComponentKind::Sdk => format!("SDK {}", f.version),
+        ComponentKind::Runtime => format!("{} {}", f.name, f.version),
+    }
+}
+
+/// Confronta SDK e runtime installati con le CVE dei metadati; fallisce se qualcuno è esposto.
+pub async fn run(ctx: &Context) -> Result<(), DverError> {
+    let sdks: Vec<String> = list_installed_sdks(ctx.runner(), &ctx.config)?.into_iter().map(|s| s.version).collect();
+    let runtimes: Vec<(String, String)> = list_installed_runtimes(ctx.runner(), &ctx.config)?
+        .into_iter()
+        .map(|r| (r.name, r.version))
+        .collect();
+    let wanted = channels_of(sdks.iter().chain(runtimes.iter().map(|(_, v)| v)).map(|v| v.as_str()));
+    let channels = if wanted.is_empty() { Vec::new() } else { ctx.channel_releases(&wanted).await? };
+    let findings = audit(&channels, &sdks, &runtimes);
+
+    if ctx.is_human() {
+        if findings.is_empty() {
+            println!("No installed SDKs or runtimes to audit.");
+        }
+        for f in &findings {
+            match f.status {
+                AuditStatus::Ok => println!("{}: no known CVEs", label(f)),
+                AuditStatus::Unknown => println!("{}: not found in the release metadata", label(f)),
+                AuditStatus::Affected => {
+                    let count = f.advisories.len();
+                    print!("{}: affected by {} CVE{}", label(f), count, if count == 1 { "" } else { "s" });
+                    match &f.update_to {
+                        Some(update) => println!(", update to {}", update),
+                        None => println!(),
+                    }
+                    for a in &f.advisories {
+                        let fixed = match &a.fixed_sdk {
+                            Some(sdk) => format!("{} (SDK {})", a.fixed_in, sdk),
+                            None => a.fixed_in.clone(),
+                        };
+                        println!("  {:<16} fixed in {:<24} {}", a.cve_id, fixed, a.cve_url);
+                    }
+                }
+            }
+        }
+    } else {
+        ctx.print_structured(&findings, &rows(&findings))?;
+    }
+
+    let affected = findings.iter().filter(|f| f.status == AuditStatus::Affected).count();
+    if affected > 0 {
+        return Err(DverError::Vulnerable(format!(
+            "{} installed version{} affected by known CVEs",
+            affected,
+            if affected == 1 { " is" } else { "s are" },
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! `dver build-env`: MSBuild, NuGet e resolver dell'SDK che dver risolve per la
+//! cartella, i `nuget.config` in vigore e le variabili che portano MSBuild altrove.
+
+use serde::Serialize;
+
+use dver_core::buildenv::{check_overrides, nuget_configs, sdk_tooling, user_nuget_config, BuildOverride, SdkTooling};
+use dver_core::inventory::{active_dotnet_root, current_sdk};
+use dver_core::{DverError, ResultExt};
+
+use super::env::project_config;
+use super::Context;
+
+/// Report completo, per `--json`.
+#[derive(Debug, Serialize)]
+struct BuildEnvReport {
+    sdk_version: String,
+    sdk_path: std::path::PathBuf,
+    #[serde(flatten)]
+    tooling: SdkTooling,
+    nuget_configs: Vec<std::path::PathBuf>,
+    overrides: Vec<BuildOverride>,
+}
+
+pub fn run(ctx: &Context) -> Result<(), DverError> {
+    let config = project_config(ctx)?;
+    let cwd = std::env::current_dir()?;
+    let root = active_dotnet_root(&config).ok_or_else(|| DverError::NotFound(
+        "No .NET root found for this directory\nhint: install an SDK with `dver install --from-project`".to_string()))?;
+    let version = current_sdk(ctx.runner(), &config, &cwd).context("Failed to resolve the SDK for this directory")?;
+    let sdk_path = root.join("sdk").join(&version);
+    if !sdk_path.is_dir() {
+        return Err(DverError::NotFound(format!("SDK {} is not in {}", version, root.display())));
+    }
+
+    let report = BuildEnvReport {
+        tooling: sdk_tooling(&sdk_path),
+        nuget_configs: nuget_configs(&cwd, user_nuget_config()),
+        overrides: check_overrides(|name| std::env::var(name).ok(), &root, &sdk_path),
+        sdk_version: version,
+        sdk_path,
+    };
+    if ctx.is_human() {
+        print_report(&report);
+    } else {
+        ctx.print_structured(&report, &report.overrides)?;
+    }
+
+    let stray: Vec<&str> = report.overrides.iter().filter(|o| !o.matches).map(|o| o.name.as_str()).collect();
+    if !stray.is_empty() {
+        return Err(DverError::Resolution(format!(
+            "{} point{} at a different SDK than {}\nhint: unset {}, or run builds through `dver exec`",
+            stray.join(", "),
+            if stray.len() == 1 { "s" } else { "" },
+            report.sdk_version,
+            if stray.len() == 1 { "it" } else { "them" },
+        )));
+    }
+    Ok(())
+}
+
+fn print_report(report: &BuildEnvReport) {
+    let unknown = || "unknown".to_string();
+    println!("SDK {} in {}", report.sdk_version, report.sdk_path.display());
+    println!("MSBuild {}", report.tooling.msbuild_version.clone().unwrap_or_else(unknown));
+    println!("NuGet {}", report.tooling.nuget_version.clone().unwrap_or_else(unknown));
+    if report.tooling.sdk_resolvers.is_empty() {
+        println!("SDK resolvers: none found");
+    } else {
+        println!("SDK resolvers: {}", report.tooling.sdk_resolvers.join(", "));
+    }
+    if report.nuget_configs.is_empty() {
+        println!("nuget.config: none (NuGet defaults)");
+    } else {
+        println!("nuget.config files, closest first:");
+        for path in &report.nuget_configs {
+            println!("  {}", path.display());
+        }
+    }
+    for o in &report.overrides {
+        if o.matches {
+            println!("✅ {}={} belongs to SDK {}", o.name, o.value, report.sdk_version);
+        } else {
+            println!("❌ {}={} points outside SDK {}", o.name, o.value, report.sdk_version);
+        }
+    }
+}
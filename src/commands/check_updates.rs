@@ -0,0 +1,99 @@
+//! `dver check-updates`: confronto in sola lettura, pensato per le pipeline
+//! pianificate. L'exit code dice se ci sono aggiornamenti (1) o aggiornamenti di
+//! sicurezza (2); dal 3 in su è il controllo stesso a essere fallito.
+
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+
+use dver_core::audit::channels_of;
+use dver_core::inventory::list_installed_sdks;
+use dver_core::project::{pinned_sdk, PinSource, Project};
+use dver_core::updates::{check_updates, markdown, overall, SdkUpdate, UpdateStatus};
+use dver_core::DverError;
+
+use super::Context;
+
+/// Riga CSV piatta di [`SdkUpdate`].
+#[derive(Debug, Serialize)]
+struct UpdateRow<'a> {
+    version: &'a str,
+    source: &'a str,
+    status: UpdateStatus,
+    update_to: Option<&'a str>,
+    cves: String,
+}
+
+pub async fn run(ctx: &Context, as_markdown: bool) -> Result<(), DverError> {
+    let mut sdks: Vec<(String, String)> = Vec::new();
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?;
+    if let Some((version, source)) = pinned_sdk(&cwd, project.as_ref())? {
+        let (PinSource::DverToml(path) | PinSource::GlobalJson(path)) = &source;
+        let file = path.file_name().map(Path::new).unwrap_or(path);
+        sdks.push((version, file.display().to_string()));
+    }
+    match list_installed_sdks(ctx.runner(), &ctx.config) {
+        // Il pin del progetto si controlla sempre, gli SDK installati solo nei canali seguiti
+        Ok(installed) => sdks.extend(
+            installed.into_iter().filter(|sdk| ctx.config.follows_sdk(&sdk.version)).map(|sdk| (sdk.version, "installed".to_string())),
+        ),
+        Err(e) => eprintln!("Warning: {}; only the project pin is checked", e),
+    }
+    if sdks.is_empty() {
+        let hint = match ctx.config.channels.as_slice() {
+            [] => String::new(),
+            channels => format!("\nhint: only channels {} are followed; see `channels` in the config", channels.join(", ")),
+        };
+        return Err(DverError::NotFound(format!("No pinned or installed SDKs to check{}", hint)));
+    }
+
+    let wanted = channels_of(sdks.iter().map(|(v, _)| v.as_str()));
+    let channels = if wanted.is_empty() { Vec::new() } else { ctx.channel_releases(&wanted).await? };
+    let updates = check_updates(&channels, &sdks);
+
+    if as_markdown {
+        print!("{}", markdown(&updates));
+    } else if ctx.is_human() {
+        print_diff(&updates);
+    } else {
+        let rows: Vec<UpdateRow> = updates
+            .iter()
+            .map(|u| UpdateRow {
+                version: &u.version,
+                source: &u.source,
+                status: u.status,
+                update_to: u.update_to.as_deref(),
+                cves: u.cves.join(" "),
+            })
+            .collect();
+        ctx.print_structured(&updates, &rows)?;
+    }
+
+    let status = overall(&updates);
+    if status != UpdateStatus::Current {
+        std::io::stdout().flush()?;
+        std::process::exit(i32::from(status.exit_code()));
+    }
+    Ok(())
+}
+
+/// Report in stile diff: `=` per gli SDK aggiornati, `-`/`+` per quelli da aggiornare,
+/// `!` per quelli esposti senza un SDK corretto nella stessa feature band.
+fn print_diff(updates: &[SdkUpdate]) {
+    for u in updates {
+        match (&u.status, &u.update_to) {
+            (UpdateStatus::Current, _) => println!("= {:<16} {} (up to date)", u.version, u.source),
+            (_, None) => println!(
+                "! {:<16} {} (security: {}; no patched SDK in this feature band)", u.version, u.source, u.cves.join(", ")),
+            (status, Some(to)) => {
+                println!("- {:<16} {}", u.version, u.source);
+                match status {
+                    UpdateStatus::Security => println!("+ {:<16} {} (security: {})", to, u.source, u.cves.join(", ")),
+                    _ => println!("+ {:<16} {}", to, u.source),
+                }
+            }
+        }
+    }
+}
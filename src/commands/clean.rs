@@ -0,0 +1,123 @@
+//! `dver clean caches`: mostra dove stanno la cache di dver e quelle di NuGet e quanto
+//! occupano, poi svuota quella di dver e, con `--nuget`, quelle di NuGet.
+//! `dver clean templates`: lo stesso per le cache dei template di `dotnet new`.
+
+use serde::Serialize;
+
+use dver_core::caches::{clear_dir, measured_cache_locations, CacheKind, CacheLocation, CacheSource};
+use dver_core::disk::format_size;
+use dver_core::inventory::list_installed_sdks;
+use dver_core::templates::{self, bundled_packs, engine_dir, sdk_caches, TemplateCache, TemplatePacks};
+use dver_core::DverError;
+
+use super::Context;
+use crate::CleanAction;
+
+pub fn run(ctx: &Context, action: &CleanAction) -> Result<(), DverError> {
+    match action {
+        CleanAction::Caches { nuget, dry_run } => caches(ctx, *nuget, *dry_run),
+        CleanAction::Templates { all, dry_run } => template_caches(ctx, *all, *dry_run),
+    }
+}
+
+fn caches(ctx: &Context, nuget: bool, dry_run: bool) -> Result<(), DverError> {
+    let locations = measured_cache_locations(&ctx.config);
+    if ctx.is_human() {
+        for location in &locations {
+            print_location(location);
+        }
+    } else {
+        ctx.print_structured(&locations, &locations)?;
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let (nuget_caches, dver_caches): (Vec<&CacheLocation>, Vec<&CacheLocation>) =
+        locations.iter().filter(|l| l.size_bytes.is_some()).partition(|l| l.kind != CacheKind::Dver);
+    let mut targets = dver_caches;
+    if nuget && !nuget_caches.is_empty() {
+        let total = nuget_caches.iter().filter_map(|l| l.size_bytes).sum();
+        let question = format!("Remove the NuGet caches ({})? Every package will be downloaded again.", format_size(total));
+        if ctx.prompter.confirm(&question, false)? {
+            targets.extend(nuget_caches);
+        } else {
+            eprintln!("Keeping the NuGet caches\nhint: pass --yes to remove them without a prompt");
+        }
+    }
+    for location in targets {
+        clear_dir(&location.path)?;
+        if ctx.is_human() {
+            println!("Removed the {} cache ({})", location.kind, format_size(location.size_bytes.unwrap_or(0)));
+        }
+    }
+    Ok(())
+}
+
+/// Una riga per cache: nome, spazio occupato, cartella e, se non è quella
+/// predefinita, da dove viene.
+fn print_location(location: &CacheLocation) {
+    let size = location.size_bytes.map(format_size).unwrap_or_else(|| "-".to_string());
+    let source = match location.source {
+        CacheSource::Default => String::new(),
+        source => format!(" ({})", source),
+    };
+    println!("{:<17} {:>10}  {}{}", location.kind.to_string(), size, location.path.display(), source);
+}
+
+/// Cache e pacchetti di template, per l'output strutturato.
+#[derive(Debug, Serialize)]
+struct TemplateReport<'a> {
+    caches: &'a [TemplateCache],
+    packs: &'a [TemplatePacks],
+}
+
+fn template_caches(ctx: &Context, all: bool, dry_run: bool) -> Result<(), DverError> {
+    let sdks = list_installed_sdks(ctx.runner(), &ctx.config)?;
+    let installed: Vec<String> = sdks.iter().map(|sdk| sdk.version.clone()).collect();
+    let caches = engine_dir(&ctx.config).map(|dir| sdk_caches(&dir, &installed)).unwrap_or_default();
+    let packs: Vec<TemplatePacks> = sdks.iter().filter_map(|sdk| bundled_packs(&sdk.path)).collect();
+    if ctx.is_human() {
+        for cache in &caches {
+            let state = if cache.installed { "" } else { " (SDK removed)" };
+            println!("{:<17} {:>10}  {}{}", format!("v{}", cache.sdk), format_size(cache.size_bytes), cache.path.display(), state);
+        }
+        for sdk in &packs {
+            println!("Template packs of SDK {}: {}", sdk.sdk, if sdk.packs.is_empty() { "none".to_string() } else { sdk.packs.join(", ") });
+        }
+    } else {
+        ctx.print_structured(&TemplateReport { caches: &caches, packs: &packs }, &caches)?;
+    }
+    if dry_run {
+        return Ok(());
+    }
+    for cache in caches.iter().filter(|c| all || !c.installed) {
+        templates::clear(cache)?;
+        if ctx.is_human() {
+            println!("Removed the template cache of SDK {} ({})", cache.sdk, format_size(cache.size_bytes));
+        }
+    }
+    Ok(())
+}
+
+/// Con `template_cache_cleanup`, dopo un'installazione o una rimozione svuota le
+/// cache dei template degli SDK che non ci sono più e, dopo un'installazione, di quelli
+/// che non erano in `before`. Un errore qui è solo un avviso.
+pub fn tidy_template_caches(ctx: &Context, before: Option<&[String]>) {
+    let config = &ctx.config;
+    if !config.template_cache_cleanup {
+        return;
+    }
+    let (Some(dir), Ok(sdks)) = (engine_dir(config), list_installed_sdks(ctx.runner(), config)) else {
+        return;
+    };
+    let installed: Vec<String> = sdks.into_iter().map(|sdk| sdk.version).collect();
+    let fresh = |sdk: &String| before.is_some_and(|before| !before.contains(sdk));
+    for cache in sdk_caches(&dir, &installed).iter().filter(|c| !c.installed || fresh(&c.sdk)) {
+        match templates::clear(cache) {
+            Ok(()) if ctx.is_human() => println!("Cleared the dotnet new template cache of SDK {}", cache.sdk),
+            Ok(()) => {}
+            Err(e) => eprintln!("Warning: {}", e),
+        }
+    }
+}
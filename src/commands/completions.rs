@@ -0,0 +1,95 @@
+use std::fs::{self, File};
+use std::path::Path;
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use dver_core::inventory::list_installed_sdks;
+use dver_core::DverError;
+
+use super::{block_on, Context};
+use crate::{Cli, CompletionKind};
+
+const BASH_DYNAMIC_COMPLETION: &str = r#"
+_dver_dynamic() {
+    local cur prev sub
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    sub="${COMP_WORDS[1]}"
+    case "${sub}" in
+        use|uninstall|rm)
+            if [[ ${cur} != -* && ${COMP_CWORD} -eq 2 ]]; then
+                COMPREPLY=( $(compgen -W "$(dver __complete installed 2>/dev/null)" -- "${cur}") )
+                return 0
+            fi
+            ;;
+        install|i)
+            if [[ ${prev} == "--version" ]]; then
+                COMPREPLY=( $(compgen -W "$(dver __complete remote 2>/dev/null)" -- "${cur}") )
+                return 0
+            fi
+            ;;
+    esac
+    _dver "$@"
+}
+
+complete -F _dver_dynamic -o bashdefault -o default dver
+"#;
+
+const FISH_DYNAMIC_COMPLETION: &str = r#"
+complete -c dver -n "__fish_seen_subcommand_from use uninstall rm" -f -a "(dver __complete installed 2>/dev/null)"
+complete -c dver -n "__fish_seen_subcommand_from install i" -l version -x -a "(dver __complete remote 2>/dev/null)"
+"#;
+
+pub fn print_completions(shell: Shell) {
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+    let mut out = std::io::stdout();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut out);
+
+    // Solo bash e fish permettono di agganciare facilmente un completamento dinamico
+    match shell {
+        Shell::Bash => print!("{}", BASH_DYNAMIC_COMPLETION),
+        Shell::Fish => print!("{}", FISH_DYNAMIC_COMPLETION),
+        _ => {}
+    }
+}
+
+// --- Pagine man ---
+pub fn generate_man_pages(out_dir: Option<&Path>) -> Result<(), DverError> {
+    let cmd = Cli::command();
+    let Some(dir) = out_dir else {
+        clap_mangen::Man::new(cmd).render(&mut std::io::stdout())?;
+        return Ok(());
+    };
+
+    fs::create_dir_all(dir)?;
+    let name = cmd.get_name().to_string();
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        let page_name = format!("{}-{}", name, sub.get_name());
+        let mut file = File::create(dir.join(format!("{}.1", page_name)))?;
+        let sub = sub.clone()
+            .display_name(page_name)
+            .bin_name(format!("{} {}", name, sub.get_name()))
+            .version(env!("CARGO_PKG_VERSION"));
+        clap_mangen::Man::new(sub).render(&mut file)?;
+    }
+    let mut file = File::create(dir.join(format!("{}.1", name)))?;
+    clap_mangen::Man::new(cmd).render(&mut file)?;
+    println!("Man pages written to {:?}", dir);
+    Ok(())
+}
+
+/// Versioni per il completamento dinamico (`dver __complete`).
+pub fn complete(ctx: &Context, kind: CompletionKind) {
+    // Gli errori vengono ignorati: la shell non deve mostrare messaggi durante il completamento
+    let versions = match kind {
+        CompletionKind::Installed => list_installed_sdks(ctx.runner(), &ctx.config)
+            .map(|sdks| sdks.into_iter().map(|sdk| sdk.version).collect())
+            .unwrap_or_default(),
+        CompletionKind::Remote => block_on(ctx.remote_sdk_versions()).and_then(|r| r).unwrap_or_default(),
+    };
+    for v in versions {
+        println!("{}", v);
+    }
+}
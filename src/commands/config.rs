@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde_json::json;
+
+use dver_core::config;
+use dver_core::{Config, DverError};
+
+use super::Context;
+use crate::ConfigAction;
+
+pub fn run(ctx: &Context, action: &ConfigAction) -> Result<(), DverError> {
+    let config = &ctx.config;
+    match action {
+        ConfigAction::Get { key } => println!("{}", config::get_key(key)?),
+        ConfigAction::Set { key, value } => {
+            config::set_key(key, value)?;
+            println!("Set {} = {}", key, value);
+        }
+        ConfigAction::Unset { key } => {
+            config::unset_key(key)?;
+            println!("Unset {}", key);
+        }
+        ConfigAction::List => {
+            let entries = config::list_entries()?;
+            if ctx.is_human() {
+                for (key, value) in &entries {
+                    println!("{} = {}", key, value);
+                }
+            } else {
+                let map: BTreeMap<&str, &str> = entries
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect();
+                let rows: Vec<serde_json::Value> = entries
+                    .iter()
+                    .map(|(k, v)| json!({ "key": k, "value": v }))
+                    .collect();
+                ctx.print_structured(&map, &rows)?;
+            }
+        }
+        ConfigAction::Path { all } => {
+            let path = Config::path()
+                .ok_or_else(|| DverError::NotFound("Cannot determine the config directory (HOME/APPDATA not set)".to_string()))?;
+            if *all {
+                let show = |dir: Option<PathBuf>| dir.map(|d| d.display().to_string()).unwrap_or_else(|| "unknown".to_string());
+                println!("config: {}", path.display());
+                println!("cache:  {}", show(config.cache_dir()));
+                println!("state:  {}", show(config.state_dir()));
+            } else {
+                println!("{}", path.display());
+            }
+        }
+        ConfigAction::Edit => {
+            let path = config::edit()?;
+            println!("Config file {:?} is valid", path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dver_core::container::{
+    container_rid, devcontainer_block, devcontainer_feature, devcontainer_install_script, dockerfile, images_for,
+    write_bundle,
+};
+use dver_core::platform;
+use dver_core::project::{pinned_sdk, Project};
+use dver_core::version::SdkVersion;
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+
+/// Stampa il Dockerfile (o le immagini, in JSON) per l'SDK fissato dal progetto;
+/// con `bundle` prepara anche il bundle offline da copiare nell'immagine.
+pub async fn run(ctx: &Context, bundle: Option<PathBuf>) -> Result<(), DverError> {
+    let version = pinned_version()?;
+
+    let channels = ctx.channel_releases(&[format!("{}.{}", version.major, version.minor)]).await?;
+    let images = images_for(&channels, &version)?;
+
+    if let Some(dir) = &bundle {
+        let rid = container_rid();
+        let client = ctx.http_client(ctx.config.index_url())?;
+        let archive = write_bundle(client, &ctx.config, &version, &rid, dir).await?;
+        eprintln!("Wrote {} to the bundle in {}", archive.file_name().unwrap_or_default().to_string_lossy(), dir.display());
+        copy_dver(dir)?;
+    }
+
+    if !ctx.is_human() {
+        return ctx.print_structured(&images, std::slice::from_ref(&images));
+    }
+    print!("{}", dockerfile(&images, bundle.as_deref()));
+    Ok(())
+}
+
+/// Stampa il blocco per devcontainer.json; con `feature` scrive anche la cartella
+/// della feature locale che installa l'SDK fissato con dver.
+pub fn devcontainer(feature: Option<&Path>) -> Result<(), DverError> {
+    let version = pinned_version()?.to_string();
+    let id = feature.and_then(Path::file_name).map_or_else(|| "dver".to_string(), |n| n.to_string_lossy().into_owned());
+    if let Some(dir) = feature {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let manifest = dir.join("devcontainer-feature.json");
+        fs::write(&manifest, serde_json::to_string_pretty(&devcontainer_feature(&id, &version))? + "\n")
+            .with_context(|| format!("Failed to write {}", manifest.display()))?;
+        let script = dir.join("install.sh");
+        fs::write(&script, devcontainer_install_script()).with_context(|| format!("Failed to write {}", script.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("Failed to make {} executable", script.display()))?;
+        }
+        copy_dver(dir)?;
+        eprintln!("Wrote the {} feature to {}", id, dir.display());
+    } else {
+        eprintln!("hint: write the feature next to devcontainer.json with --feature .devcontainer/{}", id);
+    }
+    println!("{}", serde_json::to_string_pretty(&devcontainer_block(&format!("./{}", id), &version))?);
+    Ok(())
+}
+
+/// SDK fissato da `.dver.toml` o global.json nella cartella corrente.
+fn pinned_version() -> Result<SdkVersion, DverError> {
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?;
+    let (pinned, source) = pinned_sdk(&cwd, project.as_ref())?
+        .ok_or_else(|| DverError::Resolution("No SDK pinned for this project: set `sdk` in .dver.toml or create a global.json".to_string()))?;
+    let version: SdkVersion = pinned.parse()?;
+    eprintln!("Using SDK {} pinned in {}", version, source);
+    Ok(version)
+}
+
+/// Copia il binario di dver in `dir`, ma solo se gira anche nel container.
+fn copy_dver(dir: &Path) -> Result<(), DverError> {
+    let rid = container_rid();
+    if platform::rid() != rid {
+        eprintln!("Warning: copy a {} build of dver to {} before building the container", rid, dir.join("dver").display());
+        return Ok(());
+    }
+    let exe = std::env::current_exe()?;
+    let dest = dir.join("dver");
+    fs::copy(&exe, &dest).with_context(|| format!("Failed to copy {} to {}", exe.display(), dest.display()))?;
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use dver_core::audit::check_sdk_in_use;
+use dver_core::inventory::current_sdk;
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+
+pub fn run(ctx: &Context) -> Result<(), DverError> {
+    let version = current_sdk(ctx.runner(), &ctx.config, &std::env::current_dir()?)
+        .context("Failed to get the current dotnet version")?;
+    println!("Current dotnet version: {}", version);
+    check_sdk_in_use(&ctx.config, &version)
+}
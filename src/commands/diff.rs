@@ -0,0 +1,115 @@
+//! `dver diff`: confronta due SDK con i metadati dei rilasci, per motivare un
+//! aggiornamento; con `--files` confronta anche i file di due SDK installati.
+
+use serde::Serialize;
+
+use dver_core::audit::channels_of;
+use dver_core::diff::{diff_files, diff_sdks, FileDiff, SdkDiff, SdkRelease};
+use dver_core::inventory::list_installed_sdks;
+use dver_core::resolve::not_found_message;
+use dver_core::{manifest, DverError};
+
+use super::list::root_of;
+use super::Context;
+
+#[derive(Debug, Serialize)]
+struct DiffReport {
+    #[serde(flatten)]
+    sdks: SdkDiff,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<FileDiff>,
+}
+
+/// Riga CSV piatta: un campo con i valori dei due SDK.
+#[derive(Debug, Serialize)]
+struct DiffRow {
+    field: &'static str,
+    from: String,
+    to: String,
+}
+
+pub async fn run(ctx: &Context, from: &str, to: &str, files: bool) -> Result<(), DverError> {
+    let channels = ctx.channel_releases(&channels_of([from, to])).await?;
+    let sdks = diff_sdks(&channels, from, to)?;
+    let files = if files { Some(installed_files(ctx, from, to)?) } else { None };
+    let report = DiffReport { sdks, files };
+
+    if !ctx.is_human() {
+        return ctx.print_structured(&report, &rows(&report.sdks));
+    }
+    println!("  {:<14} {:<16} {}", "SDK", from, to);
+    for row in rows(&report.sdks).iter().skip(1) {
+        let marker = if row.from == row.to { ' ' } else { '~' };
+        println!("{} {:<14} {:<16} {}", marker, row.field, row.from, row.to);
+    }
+    match report.sdks.fixed_cves.as_slice() {
+        [] => println!("No known CVEs are fixed by moving from {} to {}.", from, to),
+        cves => {
+            println!("Fixed CVEs:");
+            for cve in cves {
+                println!("  {} (fixed in {}) {}", cve.cve_id, cve.fixed_in, cve.cve_url);
+            }
+        }
+    }
+    if let Some(files) = &report.files {
+        println!(
+            "Files: {} added, {} removed, {} changed, {} unchanged",
+            files.added.len(),
+            files.removed.len(),
+            files.changed.len(),
+            files.unchanged
+        );
+        for path in &files.added {
+            println!("+ {}", path);
+        }
+        for path in &files.removed {
+            println!("- {}", path);
+        }
+    }
+    Ok(())
+}
+
+/// Legge un campo di un SDK.
+type Field = fn(&SdkRelease) -> Option<String>;
+
+/// I campi confrontati, nell'ordine in cui si stampano.
+fn rows(diff: &SdkDiff) -> Vec<DiffRow> {
+    let fields: [(&'static str, Field); 10] = [
+        ("sdk", |r| Some(r.version.clone())),
+        ("channel", |r| r.channel.clone()),
+        ("release", |r| r.release.clone()),
+        ("release_date", |r| r.release_date.map(|d| d.to_string())),
+        ("runtime", |r| r.runtime.clone()),
+        ("aspnetcore", |r| r.aspnetcore.clone()),
+        ("windowsdesktop", |r| r.windowsdesktop.clone()),
+        ("csharp", |r| r.csharp.clone()),
+        ("fsharp", |r| r.fsharp.clone()),
+        ("vb", |r| r.vb.clone()),
+    ];
+    let value = |r: &SdkRelease, field: Field| field(r).unwrap_or_else(|| "-".to_string());
+    fields
+        .into_iter()
+        .map(|(field, get)| DiffRow { field, from: value(&diff.from, get), to: value(&diff.to, get) })
+        .collect()
+}
+
+/// Confronta i file dei due SDK installati, dai manifest salvati o dal disco.
+fn installed_files(ctx: &Context, from: &str, to: &str) -> Result<FileDiff, DverError> {
+    let config = &ctx.config;
+    let installed = list_installed_sdks(ctx.runner(), config)?;
+    let manifest_of = |version: &str| {
+        let Some(sdk) = installed.iter().find(|sdk| sdk.version == version) else {
+            let versions: Vec<String> = installed.iter().map(|sdk| sdk.version.clone()).collect();
+            return Err(DverError::NotFound(format!(
+                "{}\nhint: --files compares installed SDKs; drop it to compare the release metadata only",
+                not_found_message("Installed SDK", version, &versions)
+            )));
+        };
+        let root = root_of(sdk);
+        match manifest::load(config, root, version)? {
+            Some(recorded) => Ok(recorded),
+            None => manifest::build(root, version),
+        }
+    };
+    Ok(diff_files(&manifest_of(from)?, &manifest_of(to)?))
+}
@@ -0,0 +1,26 @@
+//! `dver direnv`: integrazione con direnv. Entrambe le varianti delegano a
+//! `dver env --project`, così direnv attiva la radice e l'SDK del progetto entrando
+//! nella cartella senza l'hook di shell di dver.
+
+/// Blocco da mettere nel `.envrc` del progetto.
+const ENVRC: &str = r#"# Activate the .NET SDK pinned by global.json or .dver.toml (generated by `dver direnv`)
+watch_file global.json .dver.toml
+eval "$(dver env --shell bash --project)"
+"#;
+
+/// Libreria per `~/.config/direnv/lib/`, che rende disponibile `use dotnet`.
+const LIBRARY: &str = r#"# direnv library for dver (generated by `dver direnv --lib`).
+# Save it as ~/.config/direnv/lib/dver.sh and add `use dotnet` to a project's .envrc.
+use_dotnet() {
+  if ! has dver; then
+    log_error "use dotnet: dver is not in PATH"
+    return 1
+  fi
+  watch_file global.json .dver.toml
+  eval "$(dver env --shell bash --project)"
+}
+"#;
+
+pub fn run(lib: bool) {
+    print!("{}", if lib { LIBRARY } else { ENVRC });
+}
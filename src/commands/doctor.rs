@@ -0,0 +1,398 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use dver_core::config::{dotnet_install_dir, DOTNET_INSTALL_DIR};
+use dver_core::disk::format_size;
+use dver_core::distro;
+use dver_core::environment::{path_entries, path_position};
+use dver_core::inventory::{
+    active_dotnet_root, dotnet_dir_on_path, installed_versions_newest_first, known_dotnet_roots, installed_workloads, is_dotnet_installed, list_installed_runtimes,
+    list_installed_sdks, list_installed_workloads, resolve_in_sdk_paths,
+};
+use dver_core::journal::journal_file;
+use dver_core::macos;
+use dver_core::paths;
+use dver_core::project::{find_global_json, infer_sdk, pinned_sdk, PinSource, Project};
+use dver_core::provenance::same_root;
+use dver_core::proxy::{self, SystemProxy};
+use dver_core::registry;
+use dver_core::report::{CheckStatus, DoctorCheck, HostInfo};
+use dver_core::setup::shims_dir;
+use dver_core::support::{scrub_config, scrub_environment, scrub_text, write_bundle, BundleFile, REDACTED};
+use dver_core::version::SdkVersion;
+use dver_core::visualstudio::{self, compare_dotted};
+use dver_core::wsl;
+use dver_core::{platform, Config, DverError};
+
+use super::Context;
+
+/// File della cartella di stato inclusi nel pacchetto di supporto.
+const STATE_FILES: &[(&str, &str)] = &[
+    ("installs.json", "install provenance records"),
+    ("adopted.json", "SDKs registered with dver adopt"),
+    ("workloads.json", "workloads recorded per SDK"),
+];
+
+/// Operazioni del giornale incluse nel pacchetto di supporto, dalla più recente.
+const JOURNAL_LINES: usize = 200;
+
+fn project_checks(ctx: &Context, checks: &mut Vec<DoctorCheck>) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?;
+    let inferred = infer_sdk(&cwd)?;
+    let installed = installed_versions_newest_first(ctx.runner(), config).unwrap_or_default();
+    let Some((pinned, source)) = pinned_sdk(&cwd, project.as_ref())? else {
+        // Senza global.json decide il target framework dei progetti
+        if let Some(inferred) = inferred {
+            let targets = inferred.target_frameworks.join(", ");
+            match inferred.satisfied_by(&installed) {
+                Some(sdk) => checks.push(DoctorCheck::ok(format!("Projects target {}: SDK {} builds them.", targets, sdk))),
+                None => checks.push(DoctorCheck::error(format!(
+                    "Projects target {} but no SDK {} or later is installed. Run `dver install --for-project`.",
+                    targets, inferred.channel))),
+            }
+        }
+        return Ok(());
+    };
+    let global_json = match &source {
+        PinSource::GlobalJson(_) => find_global_json(&cwd)?,
+        PinSource::DverToml(_) => None,
+    };
+    // Con `sdk.paths` dotnet cerca solo in quelle radici, nell'ordine dato
+    let search = global_json.as_ref().filter(|g| !g.paths.is_empty());
+    let hint = global_json.as_ref().and_then(|g| g.error_message.clone()).unwrap_or_else(|| "Run `dver install --from-project`.".to_string());
+    if let Some(global_json) = search {
+        let host = active_dotnet_root(config);
+        for root in global_json.sdk_roots(host.as_deref()).iter().filter(|root| !root.is_dir()) {
+            checks.push(DoctorCheck::warning(format!("{} lists SDK path {}, which does not exist.", source, root.display())));
+        }
+        match resolve_in_sdk_paths(global_json, host.as_deref())? {
+            Some((sdk, root)) => checks.push(DoctorCheck::ok(format!(
+                "SDK {} pinned in {} resolves to SDK {} in {}.", pinned, source, sdk, root.display()))),
+            None => checks.push(DoctorCheck::error(format!(
+                "SDK {} pinned in {} is in none of its sdk.paths ({}). {}", pinned, source, global_json.paths.join(", "), hint))),
+        }
+    } else if installed.contains(&pinned) {
+        checks.push(DoctorCheck::ok(format!("SDK {} pinned in {} is installed.", pinned, source)));
+        visual_studio_checks(ctx, &pinned, checks);
+    } else {
+        checks.push(DoctorCheck::error(format!("SDK {} pinned in {} is not installed. {}", pinned, source, hint)));
+    }
+    let exact = pinned.parse::<SdkVersion>().is_ok();
+    if let Some(inferred) = inferred.filter(|i| exact && i.satisfied_by(std::slice::from_ref(&pinned)).is_none()) {
+        checks.push(DoctorCheck::error(format!(
+            "SDK {} pinned in {} cannot build {}: pin SDK {} or later.",
+            pinned, source, inferred.target_frameworks.join(", "), inferred.channel)));
+    }
+
+    let required = project.map(|p| p.config.workloads).unwrap_or_default();
+    if !required.is_empty() {
+        let present = installed_workloads(ctx.runner(), config).unwrap_or_default();
+        let missing: Vec<&String> = required.iter().filter(|w| !present.contains(w)).collect();
+        if missing.is_empty() {
+            checks.push(DoctorCheck::ok(format!("Required workloads are installed: {}.", required.join(", "))));
+        } else {
+            let missing: Vec<&str> = missing.iter().map(|w| w.as_str()).collect();
+            checks.push(DoctorCheck::error(format!(
+                "Missing workloads: {}. Run `dver workload install {}`.", missing.join(", "), missing.join(" "))));
+        }
+    }
+    Ok(())
+}
+
+/// Il `dotnet` che vince nel `PATH` confrontato con le radici plausibili: con una radice
+/// gestita devono venire prima i suoi shim o la radice stessa, altrimenti basta una tra
+/// `DOTNET_ROOT`, quella dell'utente e quelle di sistema.
+fn path_check(config: &Config) -> DoctorCheck {
+    let platform = platform::current();
+    let entries = path_entries();
+    let Some(root) = config.managed_root() else {
+        let candidates = known_dotnet_roots(config);
+        let on_path = candidates.iter().find(|root| path_position(&entries, root).is_some()).cloned();
+        return match on_path.or_else(|| dotnet_dir_on_path(platform)) {
+            Some(root) => DoctorCheck::ok(format!("dotnet in PATH comes from {}.", root.display())),
+            None => DoctorCheck::warning(format!(
+                "None of the .NET roots is in PATH ({}).",
+                candidates.iter().map(|r| r.display().to_string()).collect::<Vec<_>>().join(", "))),
+        };
+    };
+    let shims = shims_dir(config).filter(|dir| dir.join(platform.shim_name()).is_file());
+    let wanted: Vec<PathBuf> = shims.into_iter().chain(config.managed_roots()).collect();
+    let Some((position, dir)) = wanted.iter().filter_map(|dir| path_position(&entries, dir).map(|p| (p, dir))).min() else {
+        return DoctorCheck::warning(format!(
+            "{} is not in PATH. Run `dver setup`, or add `dver env` to your shell profile.", root.display()));
+    };
+    let other = entries[..position]
+        .iter()
+        .find(|entry| entry.join(platform.dotnet_exe()).is_file() && !wanted.iter().any(|w| same_root(w, entry)));
+    match other {
+        Some(other) => DoctorCheck::warning(format!(
+            "{} comes before {} in PATH, so its dotnet runs instead of dver's.", other.display(), dir.display())),
+        None => DoctorCheck::ok(format!("{} is in your PATH.", dir.display())),
+    }
+}
+
+/// Visual Studio carica i progetti con il suo MSBuild e l'SDK scelto da `global.json`:
+/// un'istanza più vecchia del `minimumMSBuildVersion` dell'SDK fissato non li apre.
+fn visual_studio_checks(ctx: &Context, pinned: &str, checks: &mut Vec<DoctorCheck>) {
+    let instances = visualstudio::instances(ctx.runner(), platform::current());
+    if instances.is_empty() {
+        return;
+    }
+    let sdks = list_installed_sdks(ctx.runner(), &ctx.config).unwrap_or_default();
+    let Some(minimum) = sdks.iter().find(|sdk| sdk.version == pinned).and_then(|sdk| visualstudio::minimum_msbuild_version(&sdk.path)) else {
+        return;
+    };
+    for instance in &instances {
+        if compare_dotted(&instance.installation_version, &minimum).is_lt() {
+            checks.push(DoctorCheck::warning(format!(
+                "{} {} cannot load projects pinned to SDK {}, which needs MSBuild {} or later; update Visual Studio or pin an older SDK in global.json.",
+                instance.display_name, instance.installation_version, pinned, minimum)));
+        } else {
+            checks.push(DoctorCheck::ok(format!(
+                "{} {} can load projects pinned to SDK {} (needs MSBuild {}).",
+                instance.display_name, instance.installation_version, pinned, minimum)));
+        }
+    }
+}
+
+/// La `DOTNET_INSTALL_DIR` degli script ufficiali: senza `install_root` dver la usa
+/// come radice, ma una radice diversa fa installare gli stessi SDK in due posti.
+fn install_dir_check(config: &Config) -> Option<DoctorCheck> {
+    let dir = dotnet_install_dir()?;
+    let root = config.arch_root(None)?;
+    Some(if same_root(&dir, &root) && config.install_root.is_none() {
+        DoctorCheck::ok(format!(
+            "{} ({}) is dver's install root; dver removes SDKs there only once install_root is set to it.",
+            DOTNET_INSTALL_DIR, dir.display()))
+    } else if same_root(&dir, &root) {
+        DoctorCheck::ok(format!("{} ({}) is dver's install root.", DOTNET_INSTALL_DIR, dir.display()))
+    } else {
+        DoctorCheck::warning(format!(
+            "{} points to {} but dver installs into {}, so the official install scripts and dver install SDKs twice; set install_root to the same folder or unset {}.",
+            DOTNET_INSTALL_DIR, dir.display(), root.display(), DOTNET_INSTALL_DIR))
+    })
+}
+
+/// Il proxy preso dalle impostazioni di sistema, o lo script PAC che dver non sa usare.
+fn proxy_check(system: &SystemProxy) -> DoctorCheck {
+    match &system.url {
+        Some(url) => DoctorCheck::ok(format!("Downloads go through the proxy {} from the {}.", url, system.source)),
+        None => DoctorCheck::warning(format!(
+            "The {} use the proxy script {}, which dver cannot evaluate; if downloads fail, set `proxy` in the config to the proxy it picks.",
+            system.source, system.pac_url.as_deref().unwrap_or(""))),
+    }
+}
+
+/// Controlli che riguardano solo WSL: il `dotnet.exe` di Windows portato nel `PATH`
+/// dall'interop e le cartelle sui dischi di Windows montati in `/mnt`.
+fn wsl_checks(config: &Config, checks: &mut Vec<DoctorCheck>) {
+    let distro = wsl::distro_name().map(|name| format!(" ({})", name)).unwrap_or_default();
+    checks.push(DoctorCheck::ok(format!("Running inside WSL{}.", distro)));
+
+    let entries = path_entries();
+    let linux = entries
+        .iter()
+        .position(|entry| wsl::windows_drive(entry).is_none() && entry.join(platform::current().dotnet_exe()).is_file());
+    if let Some((position, windows)) = wsl::windows_dotnet_entry(&entries) {
+        if linux.is_some_and(|linux| linux < position) {
+            checks.push(DoctorCheck::ok(format!(
+                "Windows' dotnet.exe in {} comes after the Linux dotnet in PATH; run `dotnet`, not `dotnet.exe`.", windows.display())));
+        } else {
+            checks.push(DoctorCheck::warning(format!(
+                "Windows' dotnet.exe in {} is in PATH through WSL interop and no Linux dotnet comes before it: tools that find it only see the Windows SDKs. Put the dver root first in PATH, or set `appendWindowsPath = false` under [interop] in /etc/wsl.conf.",
+                windows.display())));
+        }
+    }
+
+    let root = config.managed_root().or_else(|| paths::home_dir().map(|home| platform::current().user_dotnet_root(&home)));
+    if let Some(e) = root.as_deref().and_then(wsl::install_root_error) {
+        checks.push(DoctorCheck::error(e.to_string().replace("\nhint: ", ": ")));
+    }
+    if let Some(drive) = std::env::current_dir().ok().as_deref().and_then(wsl::windows_drive) {
+        checks.push(DoctorCheck::warning(format!(
+            "The current directory is on the Windows drive {}:; builds there are much slower than in the Linux file system (e.g. ~/src).", drive)));
+    }
+}
+
+/// Controlli che riguardano solo macOS: Rosetta 2, la quarantena di Gatekeeper e gli
+/// SDK del pacchetto `.pkg`.
+fn macos_checks(ctx: &Context, checks: &mut Vec<DoctorCheck>) {
+    if platform::rosetta_translated() {
+        let arch = ctx.config.arch.map(|a| a.name()).unwrap_or("arm64");
+        checks.push(DoctorCheck::warning(format!(
+            "dver runs under Rosetta 2 (an x86_64 shell on Apple silicon); SDKs are installed for {}. Pass --arch x64 for the emulated ones.",
+            arch)));
+    }
+    if let Some(root) = active_dotnet_root(&ctx.config) {
+        let dotnet = root.join(platform::current().dotnet_exe());
+        if macos::is_quarantined(ctx.runner(), &dotnet) {
+            checks.push(DoctorCheck::warning(format!(
+                "{} is quarantined by Gatekeeper and may be blocked. Run `xattr -dr {} {}`.",
+                dotnet.display(), macos::QUARANTINE_ATTR, root.display())));
+        }
+    }
+    let pkg = macos::pkg_sdks(ctx.runner());
+    if !pkg.is_empty() {
+        let versions: Vec<&str> = pkg.iter().map(|sdk| sdk.version.as_str()).collect();
+        checks.push(DoctorCheck::ok(format!(
+            "SDK(s) {} in {} come from the .NET pkg installer; remove them with dotnet-core-uninstall, not with dver.",
+            versions.join(", "), macos::PKG_ROOT)));
+    }
+}
+
+/// Controlli di `doctor`, nell'ordine in cui vengono mostrati; usati anche da `report`.
+pub fn checks(ctx: &Context) -> Vec<DoctorCheck> {
+    let config = &ctx.config;
+    let mut checks = Vec::new();
+    if let Some(message) = platform::unsupported_host_message(platform::current_support(), &platform::rid_for(config.arch)) {
+        checks.push(DoctorCheck::warning(message.replace("\nhint: ", ": ")));
+    }
+    // Prima del controllo su dotnet: dentro WSL spesso c'è solo il `dotnet.exe` di Windows
+    if wsl::is_wsl() {
+        wsl_checks(config, &mut checks);
+    }
+    checks.extend(install_dir_check(config));
+    if is_dotnet_installed(ctx.runner(), config) {
+        checks.push(DoctorCheck::ok("dotnet command is available in your PATH."));
+    } else {
+        checks.push(DoctorCheck::error("dotnet command not found. Please install .NET and ensure PATH is correct."));
+        return checks;
+    }
+
+    checks.push(path_check(config));
+    if let Some(system) = proxy::system_proxy(config) {
+        checks.push(proxy_check(system));
+    }
+
+    for install in registry::installed_versions(ctx.runner(), platform::current()) {
+        let location = install.install_location.as_deref().map(|l| l.display().to_string()).unwrap_or_default();
+        let missing = install.missing_sdks();
+        if !missing.is_empty() {
+            checks.push(DoctorCheck::warning(format!(
+                "The registry lists {} SDK(s) {} in {} that are no longer on disk; repair or remove them from Settings > Apps.",
+                install.arch, missing.join(", "), location)));
+        } else if !install.sdks.is_empty() {
+            checks.push(DoctorCheck::ok(format!(
+                "{} {} SDK(s) in {} belong to MSI packages (Visual Studio, winget); remove them from Settings > Apps, not with dver.",
+                install.sdks.len(), install.arch, location)));
+        }
+    }
+
+    let bundled = visualstudio::bundled_sdks(ctx.runner(), platform::current());
+    if !bundled.is_empty() {
+        let versions: Vec<String> = bundled.iter().map(|sdk| format!("{} ({})", sdk.version, sdk.arch)).collect();
+        checks.push(DoctorCheck::ok(format!(
+            "Visual Studio installed SDK(s) {}; it updates them itself, so dver leaves them out of uninstall --all and --prune.",
+            versions.join(", "))));
+    }
+
+    if macos::is_macos() {
+        macos_checks(ctx, &mut checks);
+    }
+    if std::env::consts::OS == "linux" {
+        for conflict in distro::conflicts(&distro::system_packages(ctx.runner())) {
+            checks.push(DoctorCheck::warning(format!("{} See `dver adopt --replace-system`.", conflict)));
+        }
+    }
+
+    if let Err(e) = project_checks(ctx, &mut checks) {
+        checks.push(DoctorCheck::error(format!("Could not check the project pin: {}", e)));
+    }
+    checks
+}
+
+pub fn run(ctx: &Context) {
+    print_checks(&checks(ctx));
+}
+
+/// Icona di un controllo nell'output leggibile; usata anche da `pin`.
+pub fn status_icon(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Ok => "✅",
+        CheckStatus::Warning => "⚠️",
+        CheckStatus::Error => "❌",
+    }
+}
+
+fn print_checks(checks: &[DoctorCheck]) {
+    println!("Checking for common issues...");
+    for check in checks {
+        println!("{} {}", status_icon(check.status), check.message);
+    }
+}
+
+/// `doctor --bundle`: i controlli, poi lo zip da allegare a una segnalazione con il
+/// riepilogo di cosa contiene.
+pub fn bundle(ctx: &Context, path: &Path) -> Result<(), DverError> {
+    let checks = checks(ctx);
+    print_checks(&checks);
+    let files = bundle_files(ctx, &checks)?;
+    write_bundle(path, &files)?;
+    println!("Wrote {}:", path.display());
+    for file in &files {
+        println!("  {:<22} {:>10}  {}", file.name, format_size(file.contents.len() as u64), file.description);
+    }
+    println!("Secrets in the config, environment and state files were replaced by {}; review the bundle before sharing it.", REDACTED);
+    Ok(())
+}
+
+/// I file del pacchetto di supporto; quelli che non esistono restano fuori.
+fn bundle_files(ctx: &Context, checks: &[DoctorCheck]) -> Result<Vec<BundleFile>, DverError> {
+    let config = &ctx.config;
+    let mut files = Vec::new();
+
+    let report: String = checks
+        .iter()
+        .map(|check| {
+            let status = match check.status {
+                CheckStatus::Ok => "ok:",
+                CheckStatus::Warning => "warning:",
+                CheckStatus::Error => "error:",
+            };
+            format!("{:<8} {}\n", status, check.message)
+        })
+        .collect();
+    files.push(BundleFile::new("doctor.txt", report, "doctor checks"));
+
+    let inventory = json!({
+        "dver_version": env!("CARGO_PKG_VERSION"),
+        "host": HostInfo::current(),
+        "sdks": list_installed_sdks(ctx.runner(), config).unwrap_or_default(),
+        "runtimes": list_installed_runtimes(ctx.runner(), config).unwrap_or_default(),
+        "workloads": list_installed_workloads(ctx.runner(), config).unwrap_or_default(),
+    });
+    files.push(BundleFile::new("inventory.json", serde_json::to_vec_pretty(&inventory)?, "dver version, host, SDKs, runtimes and workloads"));
+
+    if let Some(content) = Config::path().and_then(|path| fs::read_to_string(path).ok()) {
+        let (content, description) = match scrub_config(&content) {
+            Ok(scrubbed) => (scrubbed, "config.toml, secrets removed"),
+            Err(_) => (scrub_text(&content), "config.toml (invalid TOML), URLs scrubbed"),
+        };
+        files.push(BundleFile::new("config.toml", content, description));
+    }
+
+    let environment: String =
+        scrub_environment(std::env::vars()).into_iter().map(|(name, value)| format!("{}={}\n", name, value)).collect();
+    files.push(BundleFile::new("environment.txt", environment, "dotnet, dver, NuGet, proxy and shell variables, secrets removed"));
+
+    if let Some(state) = config.state_dir() {
+        for (name, description) in STATE_FILES {
+            if let Ok(content) = fs::read_to_string(state.join(name)) {
+                files.push(BundleFile::new(format!("state/{}", name), scrub_text(&content), *description));
+            }
+        }
+    }
+    if let Some(content) = journal_file(config).and_then(|path| fs::read_to_string(path).ok()) {
+        let lines: Vec<&str> = content.lines().collect();
+        let recent = lines[lines.len().saturating_sub(JOURNAL_LINES)..].join("\n");
+        if !recent.is_empty() {
+            let description = format!("the last {} operations of dver history", lines.len().min(JOURNAL_LINES));
+            files.push(BundleFile::new("journal.jsonl", scrub_text(&recent) + "\n", description));
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,78 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::Command;
+
+use dver_core::ci::{render_env, CiProvider};
+use dver_core::environment::{DotnetEnvironment, ShellKind};
+use dver_core::inventory::installed_versions_newest_first;
+use dver_core::project::{pinned_sdk, Project};
+use dver_core::{Config, DverError, ResultExt};
+
+use super::Context;
+
+/// Stampa le istruzioni per configurare la shell corrente. Con `project` usa la radice
+/// di `.dver.toml` e avvisa su stderr se l'SDK fissato per la cartella non è installato.
+pub fn env(ctx: &Context, shell: Option<ShellKind>, project: bool) -> Result<(), DverError> {
+    let env = if project {
+        DotnetEnvironment::from_config(&project_config(ctx)?)
+    } else {
+        DotnetEnvironment::from_config(&ctx.config)
+    };
+    print!("{}", env.render(shell.unwrap_or_else(ShellKind::detect)));
+    Ok(())
+}
+
+/// La configurazione con la radice scelta dal `.dver.toml` della cartella corrente.
+pub(super) fn project_config(ctx: &Context) -> Result<Config, DverError> {
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?;
+    let mut config = ctx.config.clone();
+    if let Some(root) = project.as_ref().and_then(|p| p.config.install.install_root.clone()) {
+        config.install_root = Some(root);
+    }
+    if let Some((version, source)) = pinned_sdk(&cwd, project.as_ref())? {
+        let installed = installed_versions_newest_first(ctx.runner(), &config).unwrap_or_default();
+        if !installed.contains(&version) {
+            eprintln!("Warning: SDK {} pinned in {} is not installed", version, source);
+            eprintln!("hint: run dver install --from-project");
+        }
+    }
+    Ok(config)
+}
+
+/// Passa l'ambiente ai passi successivi del build con i comandi dell'agent di CI.
+pub fn env_ci(ctx: &Context) -> Result<(), DverError> {
+    let provider = CiProvider::detect().ok_or_else(|| DverError::Usage(
+        "No CI system detected\nhint: --ci works on Azure Pipelines, GitHub Actions and TeamCity".to_string()))?;
+    let env = DotnetEnvironment::from_config(&ctx.config);
+    let rendered = render_env(provider, &env, |name| std::env::var(name).ok()).ok_or_else(|| DverError::Usage(format!(
+        "{} has no agent command to set variables for later steps\nhint: run eval \"$(dver env)\" in the same step",
+        provider.name())))?;
+    for command in &rendered.commands {
+        println!("{}", command);
+    }
+    for (path, lines) in &rendered.files {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        for line in lines {
+            writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+    if !rendered.files.is_empty() {
+        eprintln!("Exported the .NET environment for the next steps of the {} job", provider.name());
+    }
+    Ok(())
+}
+
+/// Esegue `command` con l'ambiente di dver e ne propaga l'exit code.
+pub fn exec(ctx: &Context, command: &[String]) -> Result<(), DverError> {
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..]);
+    DotnetEnvironment::from_config(&ctx.config).apply(&mut cmd);
+    let status = cmd.status()
+        .map_err(|e| DverError::Process(format!("Failed to run {:?}: {}", command[0], e)))?;
+    std::process::exit(status.code().unwrap_or(1));
+}
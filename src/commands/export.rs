@@ -0,0 +1,95 @@
+//! `dver export` e `dver import`: il manifest della toolchain per allineare più
+//! macchine (nuovi colleghi, agent di build).
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use dver_core::config;
+use dver_core::inventory::{
+    dotnet_output, installed_versions_newest_first, installed_workloads, list_installed_runtimes, list_installed_sdks,
+};
+use dver_core::toolchain::Toolchain;
+use dver_core::{DverError, ResultExt};
+
+use super::install::install_many;
+use super::{uninstall, Context};
+
+/// Stampa il manifest della toolchain installata.
+pub fn export(ctx: &Context) -> Result<(), DverError> {
+    println!("{}", serde_json::to_string_pretty(&capture(ctx)?)?);
+    Ok(())
+}
+
+/// Il manifest della toolchain installata; runtime e workload illeggibili restano fuori.
+pub fn capture(ctx: &Context) -> Result<Toolchain, DverError> {
+    let config = &ctx.config;
+    let sdks = list_installed_sdks(ctx.runner(), config)?;
+    let runtimes = list_installed_runtimes(ctx.runner(), config).unwrap_or_else(|e| {
+        eprintln!("Warning: {}; runtimes are left out", e);
+        Vec::new()
+    });
+    let workloads = installed_workloads(ctx.runner(), config).unwrap_or_else(|e| {
+        eprintln!("Warning: {}; workloads are left out", e);
+        Vec::new()
+    });
+    Ok(Toolchain::capture(&sdks, &runtimes, &workloads, config))
+}
+
+/// Porta la macchina allo stato descritto dal manifest `file`.
+pub async fn import(ctx: &Context, file: &Path, prune: bool) -> Result<(), DverError> {
+    let content = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let toolchain = Toolchain::parse(&content).with_context(|| format!("Cannot import {}", file.display()))?;
+    import_toolchain(ctx, &toolchain, &file.display().to_string(), prune).await
+}
+
+/// Porta la macchina allo stato di `toolchain`; `source` dice da dove arriva nei messaggi.
+pub async fn import_toolchain(ctx: &Context, toolchain: &Toolchain, source: &str, prune: bool) -> Result<(), DverError> {
+    let config = &ctx.config;
+
+    let installed = installed_versions_newest_first(ctx.runner(), config).unwrap_or_default();
+    let workloads = installed_workloads(ctx.runner(), config).unwrap_or_default();
+    let plan = toolchain.plan(&installed, &workloads);
+    let mut changed = false;
+
+    if !plan.install.is_empty() {
+        install_many(ctx, plan.install.clone(), None).await?;
+        changed = true;
+    }
+    if !plan.workloads.is_empty() {
+        println!("Installing workloads {}...", plan.workloads.join(", "));
+        let args: Vec<&str> = ["workload", "install"].into_iter().chain(plan.workloads.iter().map(String::as_str)).collect();
+        dotnet_output(ctx.runner(), config, &args).context("Failed to install workloads")?;
+        changed = true;
+    }
+    if let Some(channel) = toolchain.default_channel.as_ref().filter(|c| config.default_channel.as_ref() != Some(*c)) {
+        config::set_key("default_channel", channel)?;
+        println!("Set default_channel to {}", channel);
+        changed = true;
+    }
+
+    if prune {
+        let bundled = uninstall::visual_studio_versions(ctx);
+        for version in &plan.extra {
+            if bundled.contains(version) {
+                println!("Keeping {}: it belongs to Visual Studio", version);
+                continue;
+            }
+            uninstall::run(ctx, Some(version.clone()), false, false, Duration::ZERO)?;
+            changed = true;
+        }
+    } else if !plan.extra.is_empty() {
+        println!("Not in {}: {} (pass --prune to remove them)", source, plan.extra.join(", "));
+    }
+
+    // I runtime arrivano con gli SDK: se ne manca uno, l'SDK che lo porta non è nel manifest
+    let runtimes = list_installed_runtimes(ctx.runner(), config).unwrap_or_default();
+    for runtime in toolchain.missing_runtimes(&runtimes) {
+        eprintln!("Warning: runtime {} {} from the manifest is not installed", runtime.name, runtime.version);
+    }
+
+    if !changed {
+        println!("The toolchain already matches {}", source);
+    }
+    Ok(())
+}
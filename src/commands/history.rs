@@ -0,0 +1,62 @@
+//! `dver history`: le operazioni registrate nel giornale, dalla più vecchia, filtrate
+//! per tipo, esito e data. È la prima cosa da allegare a una richiesta di supporto.
+
+use serde::Serialize;
+
+use dver_core::journal::{self, parse_day, JournalEntry, JournalFilter, Operation};
+use dver_core::DverError;
+
+use super::Context;
+
+/// Una riga piatta per il CSV: argomenti e percorsi in un solo campo.
+#[derive(Debug, Serialize)]
+struct HistoryRow<'a> {
+    at: String,
+    operation: Operation,
+    success: bool,
+    args: String,
+    paths: String,
+    error: Option<&'a str>,
+    user: Option<&'a str>,
+}
+
+pub fn run(ctx: &Context, operation: Option<Operation>, failed: bool, since: Option<&str>, limit: usize) -> Result<(), DverError> {
+    let filter = JournalFilter { operation, failed, since: since.map(parse_day).transpose()? };
+    let entries: Vec<JournalEntry> = journal::load(&ctx.config)?.into_iter().filter(|e| filter.matches(e)).collect();
+    let shown = &entries[entries.len().saturating_sub(limit)..];
+    if !ctx.is_human() {
+        let rows: Vec<HistoryRow> = shown
+            .iter()
+            .map(|e| HistoryRow {
+                at: e.at.to_rfc3339(),
+                operation: e.operation,
+                success: e.success,
+                args: e.args.join(" "),
+                paths: e.paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(";"),
+                error: e.error.as_deref(),
+                user: e.user.as_deref(),
+            })
+            .collect();
+        return ctx.print_structured(&shown, &rows);
+    }
+    if shown.is_empty() {
+        println!("No operations recorded.");
+    }
+    for entry in shown {
+        let outcome = if entry.success { "ok" } else { "failed" };
+        println!(
+            "{}  {:<9} {:<6} dver {}",
+            entry.at.format("%Y-%m-%d %H:%M:%S"),
+            entry.operation,
+            outcome,
+            entry.args.join(" ")
+        );
+        for path in &entry.paths {
+            println!("    {}", path.display());
+        }
+        if let Some(error) = &entry.error {
+            println!("    error: {}", error.lines().next().unwrap_or_default());
+        }
+    }
+    Ok(())
+}
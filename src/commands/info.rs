@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use dver_core::inventory::list_installed_sdks;
+use dver_core::provenance::{self, Provenance};
+use dver_core::resolve::not_found_message;
+use dver_core::{disk, manifest, DverError};
+
+use super::list::root_of;
+use super::Context;
+
+#[derive(Debug, Serialize)]
+struct SdkInfo<'a> {
+    version: &'a str,
+    path: &'a Path,
+    root: &'a Path,
+    /// Vero se c'è un manifest per `dver verify`
+    manifest: bool,
+    /// Spazio occupato dalla cartella dell'SDK
+    size_bytes: u64,
+    /// Numero di file, noto solo dal manifest
+    files: Option<usize>,
+    provenance: Option<Provenance>,
+}
+
+/// Riga CSV piatta di [`SdkInfo`].
+#[derive(Debug, Serialize)]
+struct InfoRow<'a> {
+    version: &'a str,
+    path: &'a Path,
+    root: &'a Path,
+    manifest: bool,
+    size_bytes: u64,
+    files: Option<usize>,
+    installed_at: Option<String>,
+    installed_by: Option<&'a str>,
+    feed_url: Option<&'a str>,
+    script_url: Option<&'a str>,
+    script_sha256: Option<&'a str>,
+    dver_version: Option<&'a str>,
+    daily: bool,
+}
+
+/// Mostra percorso, dimensione, manifest e provenienza dell'SDK `version` installato.
+pub fn run(ctx: &Context, version: &str) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let sdks = list_installed_sdks(ctx.runner(), config)?;
+    let Some(sdk) = sdks.iter().find(|sdk| sdk.version == version) else {
+        let installed: Vec<String> = sdks.iter().map(|sdk| sdk.version.clone()).collect();
+        return Err(DverError::NotFound(not_found_message("Installed SDK", version, &installed)));
+    };
+    let root = root_of(sdk);
+    let recorded = manifest::load(config, root, version)?;
+    let info = SdkInfo {
+        version,
+        path: &sdk.path,
+        root,
+        manifest: recorded.is_some(),
+        size_bytes: disk::sizes(config, std::slice::from_ref(&sdk.path))?.first().copied().unwrap_or(0),
+        files: recorded.map(|m| m.files.len()),
+        provenance: provenance::find(config, root, version)?,
+    };
+
+    if !ctx.is_human() {
+        let p = info.provenance.as_ref();
+        let row = InfoRow {
+            version,
+            path: info.path,
+            root,
+            manifest: info.manifest,
+            size_bytes: info.size_bytes,
+            files: info.files,
+            installed_at: p.map(|p| p.installed_at.to_rfc3339()),
+            installed_by: p.and_then(|p| p.installed_by.as_deref()),
+            feed_url: p.map(|p| p.feed_url.as_str()),
+            script_url: p.map(|p| p.script_url.as_str()),
+            script_sha256: p.map(|p| p.script_sha256.as_str()),
+            dver_version: p.map(|p| p.dver_version.as_str()),
+            daily: p.is_some_and(|p| p.daily),
+        };
+        return ctx.print_structured(&info, &[row]);
+    }
+
+    println!("SDK {}", version);
+    println!("  path:          {}", info.path.display());
+    println!("  root:          {}", root.display());
+    println!("  manifest:      {}", if info.manifest { "recorded" } else { "none" });
+    match info.files {
+        Some(files) => println!("  size:          {} in {} files", disk::format_size(info.size_bytes), files),
+        None => println!("  size:          {}", disk::format_size(info.size_bytes)),
+    }
+    match &info.provenance {
+        Some(p) => {
+            println!("  installed at:  {}", p.installed_at.format("%Y-%m-%d %H:%M:%S UTC"));
+            println!("  installed by:  {}", p.installed_by.as_deref().unwrap_or("unknown"));
+            println!("  feed:          {}", p.feed_url);
+            println!("  script:        {}", p.script_url);
+            println!("  script sha256: {}", p.script_sha256);
+            println!("  dver version:  {}", p.dver_version);
+            if p.daily {
+                println!("  quality:       daily build (unsupported, no security fixes)");
+            }
+        }
+        None => println!("  provenance:    not installed by dver (no install record)"),
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! `dver init`: porta un repository sul flusso con SDK fissato in un passo solo.
+//!
+//! Scrive global.json con la versione e il `rollForward`, un `.dver.toml` di partenza
+//! e, se richiesto, il manifest degli strumenti locali; poi stampa i comandi che
+//! servono ai colleghi dopo il clone.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use dver_core::metadata::fetch_release_index;
+use dver_core::project::PROJECT_FILE;
+use dver_core::resolve::newest_matching;
+use dver_core::version::VersionSpec;
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+
+/// Valori di `rollForward` accettati da dotnet.
+pub const ROLL_FORWARD: &[&str] = &[
+    "patch", "feature", "minor", "major", "latestPatch", "latestFeature", "latestMinor", "latestMajor", "disable",
+];
+
+/// `.dver.toml` di partenza: la versione resta in global.json, che anche dotnet legge.
+const PROJECT_TEMPLATE: &str = r#"# dver settings for this repository. The SDK itself is pinned in global.json.
+
+# Workloads the repository needs, e.g. ["maui", "wasm-tools"]
+workloads = []
+
+[install]
+# Install the SDK inside the repository instead of the user-wide root
+# install_root = ".dotnet"
+"#;
+
+pub async fn run(ctx: &Context, channel: Option<String>, roll_forward: &str, tools: bool, force: bool) -> Result<(), DverError> {
+    let dir = std::env::current_dir()?;
+    let global_json = dir.join("global.json");
+    let project_file = dir.join(PROJECT_FILE);
+    if !force {
+        if let Some(existing) = [&global_json, &project_file].into_iter().find(|p| p.exists()) {
+            return Err(DverError::Usage(format!(
+                "{} already exists; pass --force to overwrite it",
+                existing.display()
+            )));
+        }
+    }
+
+    let Some(version) = target_sdk(ctx, channel).await? else {
+        return Ok(());
+    };
+    let tools = tools || ctx.prompter.confirm("Create .config/dotnet-tools.json for local .NET tools?", false)?;
+
+    let mut created = Vec::new();
+    backup(&global_json);
+    let document = json!({
+        "sdk": {
+            "version": version,
+            "rollForward": roll_forward,
+        }
+    });
+    write_file(&global_json, &format!("{}\n", serde_json::to_string_pretty(&document)?))?;
+    created.push("global.json");
+
+    backup(&project_file);
+    write_file(&project_file, PROJECT_TEMPLATE)?;
+    created.push(PROJECT_FILE);
+
+    let tools_manifest = dir.join(".config").join("dotnet-tools.json");
+    if tools {
+        if tools_manifest.exists() {
+            println!("Keeping the existing {}", tools_manifest.display());
+        } else {
+            let manifest = json!({ "version": 1, "isRoot": true, "tools": {} });
+            write_file(&tools_manifest, &format!("{}\n", serde_json::to_string_pretty(&manifest)?))?;
+            created.push(".config/dotnet-tools.json");
+        }
+    }
+
+    println!("Pinned SDK {} (rollForward {}) in {}", version, roll_forward, dir.display());
+    println!("Created {}", created.join(", "));
+    println!();
+    println!("Teammates set up the repository with:");
+    println!("  dver install --from-project");
+    if tools || tools_manifest.exists() {
+        println!("  dotnet tool restore");
+    }
+    Ok(())
+}
+
+/// SDK da fissare. `channel` può essere una versione esatta, una parziale (`8`, `8.0`,
+/// `8.0.4xx`, risolta con l'SDK pubblicato più recente) o `lts`; senza, in un terminale
+/// si sceglie il canale da un elenco. `None` se l'utente annulla la scelta.
+async fn target_sdk(ctx: &Context, channel: Option<String>) -> Result<Option<String>, DverError> {
+    let channel = match channel {
+        Some(channel) => channel,
+        None if ctx.prompter.interactive => match pick_channel(ctx).await? {
+            Some(channel) => channel,
+            None => return Ok(None),
+        },
+        None => return Err(DverError::Usage("Provide the target channel with --channel (e.g. 8.0 or lts).".to_string())),
+    };
+    let channel = if channel.eq_ignore_ascii_case("lts") {
+        let client = ctx.http_client(ctx.config.index_url())?;
+        let index = fetch_release_index(client, &ctx.config).await?;
+        // L'indice elenca i canali dal più recente
+        index.releases_index
+            .iter()
+            .find(|c| c.is_lts())
+            .and_then(|c| c.channel_version.clone())
+            .ok_or_else(|| DverError::NotFound("No LTS channel in the release index".to_string()))?
+    } else {
+        channel
+    };
+    let spec: VersionSpec = channel.parse()?;
+    if spec.is_exact() {
+        return Ok(Some(channel));
+    }
+    let resolved = newest_matching(&spec, &ctx.remote_sdk_versions().await?)
+        .ok_or_else(|| DverError::NotFound(format!("No published SDK matches {}", channel)))?;
+    println!("Resolved {} to SDK {}", channel, resolved);
+    Ok(Some(resolved))
+}
+
+/// Chiede il canale tra quelli dell'indice dei rilasci, dal più recente.
+async fn pick_channel(ctx: &Context) -> Result<Option<String>, DverError> {
+    let client = ctx.http_client(ctx.config.index_url())?;
+    let index = fetch_release_index(client, &ctx.config).await?;
+    let channels: Vec<String> = index.releases_index
+        .iter()
+        .filter_map(|c| c.channel_version.clone())
+        .collect();
+    ctx.prompter.pick_version("Select the target channel", &channels)
+}
+
+/// Come `dver use`, conserva il file che si sta per sovrascrivere con `--force`.
+fn backup(path: &Path) {
+    if path.exists() {
+        let mut backup = PathBuf::from(path);
+        backup.as_mut_os_string().push(".bak");
+        let _ = fs::copy(path, &backup);
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), DverError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
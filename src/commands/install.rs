@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use dver_core::disk::{dir_size, format_size};
+use dver_core::install::{estimate_install, install_script_url, install_sdk, install_sdks, InstallEstimate, InstallRequest};
+use dver_core::inventory::{dotnet_version, installed_versions_newest_first, is_dotnet_installed, scan_root_sdks, InstalledSdk};
+use dver_core::outcome::{ItemOutcome, ItemStatus};
+use dver_core::project::{infer_sdk, pinned_sdk, required_workloads, Project};
+use dver_core::resolve::{newest_matching, not_found_message};
+use dver_core::version::VersionSpec;
+use dver_core::DverError;
+
+use super::{clean, workload, Context};
+
+pub async fn run(
+    ctx: &Context,
+    lts: bool,
+    versions: Vec<String>,
+    install_path: Option<PathBuf>,
+    from_project: bool,
+    for_project: bool,
+) -> Result<(), DverError> {
+    if versions.len() > 1 && !lts {
+        return install_many(ctx, versions, install_path).await;
+    }
+    let config = &ctx.config;
+    let mut version = versions.into_iter().next();
+    let mut install_path = install_path;
+    // Workload dei progetti (da `.dver.toml` e dai file di progetto), da ripristinare
+    // sull'SDK installato perché un solo comando prepari la macchina
+    let mut workloads = Vec::new();
+    if from_project || for_project {
+        workloads = required_workloads(&std::env::current_dir()?)?;
+    }
+    if from_project {
+        let cwd = std::env::current_dir()?;
+        let project = Project::find(&cwd)?;
+        let (pinned, source) = pinned_sdk(&cwd, project.as_ref())?
+            .ok_or_else(|| DverError::Resolution("No SDK pinned for this project: set `sdk` in .dver.toml or create a global.json".to_string()))?;
+        ctx.note(format!("Using SDK {} pinned in {}", pinned, source));
+        version = Some(pinned);
+        if let Some(project) = project {
+            install_path = install_path.or(project.config.install.install_root);
+            workloads.extend(project.config.workloads);
+            workloads.sort();
+            workloads.dedup();
+        }
+    }
+
+    if for_project {
+        let cwd = std::env::current_dir()?;
+        let inferred = infer_sdk(&cwd)?.ok_or_else(|| DverError::Resolution(
+            "No project in this repository targets .NET: no TargetFramework such as net8.0 was found".to_string()))?;
+        ctx.note(format!(
+            "Projects target {} ({} file(s)): SDK {} or later is needed",
+            inferred.target_frameworks.join(", "), inferred.files.len(), inferred.channel));
+        let installed = installed_versions_newest_first(ctx.runner(), config).unwrap_or_default();
+        if let Some(sdk) = inferred.satisfied_by(&installed) {
+            ctx.note(format!("SDK {} is already installed and builds them.", sdk));
+            ctx.outcome(ItemOutcome::new(sdk, ItemStatus::Unchanged));
+            return restore_workloads(ctx, sdk, &workloads);
+        }
+        version = Some(inferred.channel);
+        if let Some(project) = Project::find(&cwd)? {
+            install_path = install_path.or(project.config.install.install_root);
+        }
+    }
+
+    // Una versione parziale diventa l'SDK pubblicato più recente che la soddisfa
+    if let Some(spec) = version.as_deref().and_then(|v| v.parse::<VersionSpec>().ok()).filter(|s| !s.is_exact()) {
+        let requested = version.take().unwrap_or_default();
+        let resolved = newest_matching(&spec, &ctx.remote_sdk_versions().await?)
+            .ok_or_else(|| DverError::NotFound(format!("No published SDK matches {}", requested)))?;
+        ctx.note(format!("Resolved {} to SDK {}", requested, resolved));
+        version = Some(resolved);
+    }
+
+    if let Some(v) = &version {
+        if installed_versions_newest_first(ctx.runner(), config).is_ok_and(|installed| installed.contains(v)) {
+            ctx.note(format!("SDK {} is already installed.", v));
+            ctx.outcome(ItemOutcome::new(v, ItemStatus::Unchanged));
+            return restore_workloads(ctx, v, &workloads);
+        }
+    }
+
+    if version.is_none() && config.default_channel.is_none() && is_dotnet_installed(ctx.runner(), config) {
+        ctx.note("dotnet is already installed.");
+        let current = dotnet_version(ctx.runner(), config)?;
+        ctx.note(format!("Current version: {}", current));
+        ctx.outcome(ItemOutcome::new(current, ItemStatus::Unchanged));
+    } else {
+        // Con un canale predefinito in configurazione non serve chiedere
+        let version = if version.is_none() && !lts && config.default_channel.is_none() && ctx.prompter.interactive {
+            let mut remote = ctx.remote_sdk_versions().await?;
+            remote.reverse();
+            match ctx.prompter.pick_version("Select the SDK to install", &remote)? {
+                Some(v) => Some(v),
+                None => return Ok(()),
+            }
+        } else {
+            version
+        };
+        if let Some(v) = &version {
+            if !confirm_download(ctx, std::slice::from_ref(v)).await? {
+                ctx.note("Aborted.");
+                return Ok(());
+            }
+        }
+        match (&version, &config.default_channel) {
+            (None, Some(channel)) if !lts => ctx.note(format!(
+                "Installing dotnet from channel {} ({})...",
+                channel,
+                config.quality.map(|q| q.as_script_arg()).unwrap_or("GA"),
+            )),
+            _ => ctx.note("Installing dotnet..."),
+        }
+        let before = installed_versions_newest_first(ctx.runner(), config).unwrap_or_default();
+        let request = InstallRequest { lts, version: version.clone(), install_dir: install_path };
+        if request.is_daily(config) {
+            eprintln!("Warning: daily builds are unsupported: they are untested, may not work and get no security fixes");
+        }
+        if let Err(e) = install_and_report(ctx, &request).await {
+            // Se la versione richiesta non esiste, suggerisce quelle pubblicate più simili
+            if let Some(v) = &version {
+                if let Ok(remote) = ctx.remote_sdk_versions().await {
+                    if !remote.contains(v) {
+                        return Err(DverError::NotFound(not_found_message("SDK", v, &remote)));
+                    }
+                }
+            }
+            return Err(e);
+        }
+        ctx.note("dotnet installation completed.");
+        workload::replay(ctx, &before);
+        clean::tidy_template_caches(ctx, Some(&before));
+        if let Some(v) = &version {
+            restore_workloads(ctx, v, &workloads)?;
+        }
+    }
+    Ok(())
+}
+
+/// Installa sull'SDK `sdk` i workload dei progetti che mancano.
+fn restore_workloads(ctx: &Context, sdk: &str, workloads: &[String]) -> Result<(), DverError> {
+    if workloads.is_empty() {
+        return Ok(());
+    }
+    ctx.note(format!("The projects require workloads: {}", workloads.join(", ")));
+    workload::restore(ctx, sdk, workloads)
+}
+
+/// Installa più SDK in parallelo; le versioni parziali si risolvono prima, quelle
+/// già installate si saltano. Usato anche da `import`.
+pub async fn install_many(ctx: &Context, requested: Vec<String>, install_path: Option<PathBuf>) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let mut remote: Option<Vec<String>> = None;
+    let mut versions: Vec<String> = Vec::new();
+    for requested in requested {
+        let spec: VersionSpec = requested.parse()?;
+        let version = if spec.is_exact() {
+            requested
+        } else {
+            if remote.is_none() {
+                remote = Some(ctx.remote_sdk_versions().await?);
+            }
+            let resolved = newest_matching(&spec, remote.as_deref().unwrap_or_default())
+                .ok_or_else(|| DverError::NotFound(format!("No published SDK matches {}", requested)))?;
+            ctx.note(format!("Resolved {} to SDK {}", requested, resolved));
+            resolved
+        };
+        if !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+
+    let installed = installed_versions_newest_first(ctx.runner(), config).unwrap_or_default();
+    versions.retain(|v| {
+        let present = installed.contains(v);
+        if present {
+            ctx.note(format!("SDK {} is already installed.", v));
+            ctx.outcome(ItemOutcome::new(v, ItemStatus::Unchanged));
+        }
+        !present
+    });
+    if versions.is_empty() {
+        return Ok(());
+    }
+    if !confirm_download(ctx, &versions).await? {
+        ctx.note("Aborted.");
+        return Ok(());
+    }
+
+    ctx.note(format!("Installing SDKs {} ({} at a time)...", versions.join(", "), config.install_jobs().min(versions.len())));
+    let root = install_path.clone().or_else(|| config.managed_root());
+    let before = sdks_in(root.as_deref());
+    let client = ctx.http_client(&install_script_url(config))?;
+    let outcomes = install_sdks(client, ctx.runner(), &versions, install_path, config).await?;
+    touch_new_sdks(ctx, root.as_deref(), &before);
+    let mut first_failure = None;
+    for outcome in outcomes {
+        match outcome.result {
+            Ok(_) => {
+                ctx.note(format!("Installed SDK {}", outcome.version));
+                let mut item = ItemOutcome::new(&outcome.version, ItemStatus::Installed).duration(outcome.elapsed);
+                if let Some(path) = root.as_deref().map(|root| root.join("sdk").join(&outcome.version)) {
+                    item = item.size(dir_size(&path).ok()).path(path);
+                }
+                ctx.outcome(item);
+            }
+            Err(e) => {
+                eprintln!("Failed to install SDK {}: {}", outcome.version, e);
+                ctx.outcome(ItemOutcome::new(&outcome.version, ItemStatus::Failed).duration(outcome.elapsed).error(&e));
+                first_failure.get_or_insert(e);
+            }
+        }
+    }
+    match first_failure {
+        Some(e) => Err(e),
+        None => {
+            ctx.note("dotnet installation completed.");
+            workload::replay(ctx, &installed);
+            clean::tidy_template_caches(ctx, Some(&installed));
+            Ok(())
+        }
+    }
+}
+
+/// Stima di download e spazio su disco per tutte le `versions`; `None` se una non si
+/// può stimare (offline, archivio assente dai metadati, server che non ne dichiara la dimensione).
+async fn estimate_all(ctx: &Context, versions: &[String]) -> Option<InstallEstimate> {
+    let client = ctx.http_client(ctx.config.index_url()).ok()?;
+    let mut total = InstallEstimate { download_bytes: 0, extracted_bytes: 0 };
+    for version in versions {
+        let estimate = estimate_install(client, &ctx.config, version).await.ok()??;
+        total.download_bytes += estimate.download_bytes;
+        total.extracted_bytes += estimate.extracted_bytes;
+    }
+    Some(total)
+}
+
+/// Mostra quanto si scaricherà ed estrarrà e chiede conferma (`--yes` la salta);
+/// falso se l'utente rinuncia. Senza una stima si procede senza chiedere; senza
+/// terminale né `--yes` (script, CI) non si stima nulla, per non fare richieste in più.
+async fn confirm_download(ctx: &Context, versions: &[String]) -> Result<bool, DverError> {
+    if !ctx.is_human() || !(ctx.prompter.interactive || ctx.prompter.assume_yes()) {
+        return Ok(true);
+    }
+    let Some(estimate) = estimate_all(ctx, versions).await else {
+        return Ok(true);
+    };
+    if estimate.download_bytes == 0 {
+        println!("Archive already downloaded, will extract to ~{}", format_size(estimate.extracted_bytes));
+    } else {
+        println!("Will download {}, extract to ~{}", format_size(estimate.download_bytes), format_size(estimate.extracted_bytes));
+    }
+    ctx.prompter.confirm("Continue?", true)
+}
+
+/// Installa e mostra l'output dello script; usato anche da `use`. Gli SDK comparsi
+/// nella radice diventano l'esito del comando.
+pub async fn install_and_report(ctx: &Context, request: &InstallRequest) -> Result<(), DverError> {
+    let root = request.install_dir.clone().or_else(|| ctx.config.managed_root());
+    let before = sdks_in(root.as_deref());
+    let client = ctx.http_client(&install_script_url(&ctx.config))?;
+    let started = Instant::now();
+    let output = match install_sdk(client, ctx.runner(), request, &ctx.config).await {
+        Ok(output) => output,
+        Err(e) => {
+            let item = ItemOutcome::new(request.version.as_deref().unwrap_or("latest"), ItemStatus::Failed);
+            ctx.outcome(item.duration(started.elapsed()).error(&e));
+            return Err(e);
+        }
+    };
+    let elapsed = started.elapsed();
+    for sdk in sdks_in(root.as_deref()).into_iter().filter(|sdk| !before.iter().any(|b| b.version == sdk.version)) {
+        let size = dir_size(&sdk.path).ok();
+        ctx.outcome(ItemOutcome::new(&sdk.version, ItemStatus::Installed).path(&sdk.path).size(size).duration(elapsed));
+        ctx.touch(sdk.path);
+    }
+    ctx.note(output);
+    Ok(())
+}
+
+pub fn sdks_in(root: Option<&Path>) -> Vec<InstalledSdk> {
+    root.and_then(|root| scan_root_sdks(root).ok()).unwrap_or_default()
+}
+
+/// Annota per il giornale gli SDK comparsi in `root` rispetto a `before`.
+pub fn touch_new_sdks(ctx: &Context, root: Option<&Path>, before: &[InstalledSdk]) {
+    for sdk in sdks_in(root) {
+        if !before.iter().any(|b| b.version == sdk.version) {
+            ctx.touch(sdk.path);
+        }
+    }
+}
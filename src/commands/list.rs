@@ -0,0 +1,266 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use dver_core::adopt::{self, Adoption};
+use dver_core::config;
+use dver_core::disk::{self, format_size};
+use dver_core::inventory::{list_installed_sdks, scan_root_sdks, InstalledSdk};
+use dver_core::platform::{self, Arch};
+use dver_core::provenance::{self, Provenance};
+use dver_core::registry::{self, msi_owner};
+use dver_core::version::compare_version_strings;
+use dver_core::visualstudio;
+use dver_core::DverError;
+
+use super::Context;
+
+pub fn run(ctx: &Context, show_provenance: bool, show_size: bool) -> Result<(), DverError> {
+    let config = &ctx.config;
+    if show_provenance || show_size {
+        let sdks = if config.install_root.is_none() && config.env_install_root.is_none() && !config.roots.is_empty() {
+            sdks_of_all_roots(ctx)?
+        } else {
+            sdks_of_all_arches(ctx)?
+        };
+        return if show_provenance { list_provenance(ctx, sdks) } else { list_sizes(ctx, sdks) };
+    }
+    // Senza una radice scelta, elenca gli SDK di tutte le radici con nome
+    if config.install_root.is_none() && config.env_install_root.is_none() && !config.roots.is_empty() {
+        return list_all_roots(ctx);
+    }
+    let mut sdks = sdks_of_all_arches(ctx)?;
+    if !ctx.is_human() {
+        return ctx.print_structured(&sdks, &sdks);
+    }
+    sdks.sort_by(|a, b| compare_version_strings(&a.version, &b.version).then(a.arch.cmp(&b.arch)));
+    // L'architettura si mostra solo quando ce n'è più d'una
+    let mixed = sdks.iter().any(|sdk| sdk.arch != sdks[0].arch);
+    let msi = registry::installed_versions(ctx.runner(), platform::current());
+    let vs = visualstudio::bundled_sdks(ctx.runner(), platform::current());
+    let records = provenance::load_all(config).unwrap_or_default();
+    for sdk in &sdks {
+        let mut labels: Vec<String> = sdk.arch.filter(|_| mixed).map(|arch| arch.to_string()).into_iter().collect();
+        if visualstudio::is_bundled(&vs, &msi, root_of(sdk), &sdk.version) {
+            labels.push("Visual Studio".to_string());
+        }
+        if records.iter().any(|p| p.daily && p.version == sdk.version && provenance::same_root(&p.root, root_of(sdk))) {
+            labels.push("unsupported daily build".to_string());
+        }
+        if labels.is_empty() {
+            println!("{}", sdk.version);
+        } else {
+            println!("{:<16} {}", sdk.version, labels.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// Gli SDK della radice attiva più quelli delle radici delle altre architetture
+/// (es. `<radice>/x64` su un host ARM), che il `dotnet` nativo non elenca.
+fn sdks_of_all_arches(ctx: &Context) -> Result<Vec<InstalledSdk>, DverError> {
+    let config = &ctx.config;
+    let mut sdks = list_installed_sdks(ctx.runner(), config)?;
+    let mut seen: Vec<PathBuf> = sdks.iter().map(|sdk| root_of(sdk).to_path_buf()).collect();
+    seen.extend(config.managed_root());
+    seen.dedup();
+    let base = config.arch_root(None).or_else(|| seen.first().cloned());
+    let candidates = Arch::ALL.into_iter().flat_map(|arch| {
+        config.arch_root(Some(arch)).into_iter().chain(base.as_ref().map(|b| b.join(arch.name())))
+    });
+    for root in candidates {
+        if seen.iter().any(|s| provenance::same_root(s, &root)) || !root.join("sdk").is_dir() {
+            continue;
+        }
+        let root_name = config.install_root_name();
+        sdks.extend(scan_root_sdks(&root)?.into_iter().map(|sdk| InstalledSdk { root: root_name.clone(), ..sdk }));
+        seen.push(root);
+    }
+    // Su Windows anche gli SDK dei pacchetti MSI nelle radici che dotnet non elenca, es. quella x86
+    for install in registry::installed_versions(ctx.runner(), platform::current()) {
+        let Some(root) = install.install_location.filter(|root| !seen.iter().any(|s| provenance::same_root(s, root))) else {
+            continue;
+        };
+        let registered = scan_root_sdks(&root).unwrap_or_default().into_iter().filter(|sdk| install.sdks.contains(&sdk.version));
+        sdks.extend(registered);
+        seen.push(root);
+    }
+    Ok(sdks)
+}
+
+fn sdks_of_all_roots(ctx: &Context) -> Result<Vec<InstalledSdk>, DverError> {
+    let mut sdks = Vec::new();
+    for (name, path) in &ctx.config.roots {
+        for sdk in scan_root_sdks(&config::expand_home(path))? {
+            sdks.push(InstalledSdk { root: Some(name.clone()), ..sdk });
+        }
+    }
+    Ok(sdks)
+}
+
+fn list_all_roots(ctx: &Context) -> Result<(), DverError> {
+    let sdks = sdks_of_all_roots(ctx)?;
+    if ctx.is_human() {
+        for sdk in &sdks {
+            println!("{:<16} [{}]", sdk.version, sdk.root.as_deref().unwrap_or(""));
+        }
+        Ok(())
+    } else {
+        ctx.print_structured(&sdks, &sdks)
+    }
+}
+
+/// Un SDK con il record di provenienza, se installato da dver.
+#[derive(Debug, Serialize)]
+struct SdkProvenance {
+    #[serde(flatten)]
+    sdk: InstalledSdk,
+    provenance: Option<Provenance>,
+    /// Registrato da un pacchetto MSI su Windows (Visual Studio, winget, installer)
+    msi: bool,
+    /// Installato da Visual Studio, che lo aggiorna e lo rimuove con il suo installer
+    visual_studio: bool,
+    /// Record di `dver adopt`, per gli SDK installati senza dver
+    adopted: Option<Adoption>,
+}
+
+/// Riga CSV piatta di [`SdkProvenance`].
+#[derive(Debug, Serialize)]
+struct ProvenanceRow<'a> {
+    version: &'a str,
+    path: &'a Path,
+    root: Option<&'a str>,
+    msi: bool,
+    visual_studio: bool,
+    installed_at: Option<String>,
+    installed_by: Option<&'a str>,
+    feed_url: Option<&'a str>,
+    script_url: Option<&'a str>,
+    script_sha256: Option<&'a str>,
+    daily: bool,
+}
+
+/// Radice di un SDK in `<root>/sdk/<versione>`.
+pub fn root_of(sdk: &InstalledSdk) -> &Path {
+    sdk.path.parent().and_then(Path::parent).unwrap_or(Path::new(""))
+}
+
+fn list_provenance(ctx: &Context, sdks: Vec<InstalledSdk>) -> Result<(), DverError> {
+    let records = provenance::load_all(&ctx.config)?;
+    let adoptions = adopt::load_all(&ctx.config)?;
+    let msi = registry::installed_versions(ctx.runner(), platform::current());
+    let vs = visualstudio::bundled_sdks(ctx.runner(), platform::current());
+    let entries: Vec<SdkProvenance> = sdks
+        .into_iter()
+        .map(|sdk| {
+            let provenance = records
+                .iter()
+                .find(|p| p.version == sdk.version && provenance::same_root(&p.root, root_of(&sdk)))
+                .cloned();
+            let adopted = adoptions
+                .iter()
+                .find(|a| a.version == sdk.version && provenance::same_root(&a.root, root_of(&sdk)))
+                .cloned();
+            let visual_studio = visualstudio::is_bundled(&vs, &msi, root_of(&sdk), &sdk.version);
+            let msi = msi_owner(&msi, root_of(&sdk), &sdk.version).is_some();
+            SdkProvenance { sdk, provenance, msi, visual_studio, adopted }
+        })
+        .collect();
+
+    if ctx.is_human() {
+        for entry in &entries {
+            match &entry.provenance {
+                Some(p) => println!(
+                    "{:<16} installed {} by {} from {} (script {}, sha256 {}{})",
+                    entry.sdk.version,
+                    p.installed_at.format("%Y-%m-%d %H:%M UTC"),
+                    p.installed_by.as_deref().unwrap_or("unknown user"),
+                    p.feed_url,
+                    p.script_url,
+                    &p.script_sha256[..p.script_sha256.len().min(12)],
+                    if p.daily { ", unsupported daily build" } else { "" },
+                ),
+                None if entry.visual_studio => println!(
+                    "{:<16} installed by Visual Studio (update or remove it with the Visual Studio Installer)",
+                    entry.sdk.version
+                ),
+                None if entry.msi => println!(
+                    "{:<16} installed by an MSI package (Visual Studio, winget or the .NET installer)",
+                    entry.sdk.version
+                ),
+                None => match &entry.adopted {
+                    Some(a) => println!(
+                        "{:<16} adopted {} ({})",
+                        entry.sdk.version,
+                        a.adopted_at.format("%Y-%m-%d %H:%M UTC"),
+                        a.ownership,
+                    ),
+                    None => println!("{:<16} not installed by dver", entry.sdk.version),
+                },
+            }
+        }
+        return Ok(());
+    }
+    let rows: Vec<ProvenanceRow> = entries
+        .iter()
+        .map(|e| ProvenanceRow {
+            version: &e.sdk.version,
+            path: &e.sdk.path,
+            root: e.sdk.root.as_deref(),
+            msi: e.msi,
+            visual_studio: e.visual_studio,
+            installed_at: e.provenance.as_ref().map(|p| p.installed_at.to_rfc3339()),
+            installed_by: e.provenance.as_ref().and_then(|p| p.installed_by.as_deref()),
+            feed_url: e.provenance.as_ref().map(|p| p.feed_url.as_str()),
+            script_url: e.provenance.as_ref().map(|p| p.script_url.as_str()),
+            script_sha256: e.provenance.as_ref().map(|p| p.script_sha256.as_str()),
+            daily: e.provenance.as_ref().is_some_and(|p| p.daily),
+        })
+        .collect();
+    ctx.print_structured(&entries, &rows)
+}
+
+/// Un SDK con lo spazio che occupa sul disco.
+#[derive(Debug, Serialize)]
+struct SdkSize {
+    #[serde(flatten)]
+    sdk: InstalledSdk,
+    size_bytes: u64,
+}
+
+/// Riga CSV piatta di [`SdkSize`].
+#[derive(Debug, Serialize)]
+struct SizeRow<'a> {
+    version: &'a str,
+    path: &'a Path,
+    root: Option<&'a str>,
+    size_bytes: u64,
+}
+
+fn list_sizes(ctx: &Context, sdks: Vec<InstalledSdk>) -> Result<(), DverError> {
+    let dirs: Vec<_> = sdks.iter().map(|sdk| sdk.path.clone()).collect();
+    let sizes = disk::sizes(&ctx.config, &dirs)?;
+    let entries: Vec<SdkSize> = sdks.into_iter().zip(sizes).map(|(sdk, size_bytes)| SdkSize { sdk, size_bytes }).collect();
+
+    if ctx.is_human() {
+        for entry in &entries {
+            match &entry.sdk.root {
+                Some(root) => println!("{:<16} {:>10}  [{}]", entry.sdk.version, format_size(entry.size_bytes), root),
+                None => println!("{:<16} {:>10}", entry.sdk.version, format_size(entry.size_bytes)),
+            }
+        }
+        let total: u64 = entries.iter().map(|e| e.size_bytes).sum();
+        println!("{:<16} {:>10}", "total", format_size(total));
+        return Ok(());
+    }
+    let rows: Vec<SizeRow> = entries
+        .iter()
+        .map(|e| SizeRow {
+            version: &e.sdk.version,
+            path: &e.sdk.path,
+            root: e.sdk.root.as_deref(),
+            size_bytes: e.size_bytes,
+        })
+        .collect();
+    ctx.print_structured(&entries, &rows)
+}
@@ -0,0 +1,197 @@
+//! `dver maintain`: un solo comando idempotente da pianificare sugli agent di build.
+//! Aggiorna la cache dei metadati, installa le patch mancanti, rimuove le patch
+//! superate secondo `keep_patches` e svuota la cache di dver oltre `cache_budget_mb`,
+//! poi riassume cosa ha fatto (in JSON con `--json`).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use dver_core::audit::channels_of;
+use dver_core::caches::clear_dir;
+use dver_core::disk::{dir_size, format_size};
+use dver_core::install::{install_script_url, install_sdks};
+use dver_core::inventory::scan_root_sdks;
+use dver_core::maintain::{default_channel_version, patches_to_install, superseded};
+use dver_core::metadata::{fetch_channel, fetch_release_index};
+use dver_core::project::{pinned_sdk, Project};
+use dver_core::sandbox::{check_removal, Removal};
+use dver_core::{paths, platform, DverError};
+
+use super::install::{sdks_in, touch_new_sdks};
+use super::uninstall::remove_sdk;
+use super::Context;
+
+/// Cosa ha fatto (o, con `--dry-run`, farebbe) `maintain`.
+#[derive(Debug, Default, Serialize)]
+struct MaintainSummary {
+    dry_run: bool,
+    /// Canali di cui sono stati aggiornati i metadati
+    channels: Vec<String>,
+    installed: Vec<String>,
+    pruned: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache: Option<CacheBudget>,
+    errors: Vec<String>,
+}
+
+/// La cache di dver confrontata con `cache_budget_mb`.
+#[derive(Debug, Serialize)]
+struct CacheBudget {
+    size_bytes: u64,
+    budget_bytes: u64,
+    cleared: bool,
+}
+
+/// Riga CSV piatta: un'azione e il suo oggetto.
+#[derive(Debug, Serialize)]
+struct MaintainRow<'a> {
+    action: &'static str,
+    target: &'a str,
+}
+
+pub async fn run(ctx: &Context, dry_run: bool) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let root = config
+        .managed_root()
+        .or_else(|| paths::home_dir().map(|home| platform::current().user_dotnet_root(&home)))
+        .ok_or_else(|| DverError::NotFound("Cannot determine the install root\nhint: set install_root".to_string()))?;
+    let mut summary = MaintainSummary { dry_run, ..MaintainSummary::default() };
+    // Un passo che fallisce non ferma gli altri
+    let mut errors: Vec<DverError> = Vec::new();
+    let installed: Vec<String> = sdks_in(Some(&root)).into_iter().map(|sdk| sdk.version).collect();
+
+    // Metadati: l'indice e i canali degli SDK installati e di quello predefinito
+    let client = ctx.http_client(config.index_url())?;
+    let index = fetch_release_index(client, config).await?;
+    let default_channel = config.default_channel.as_deref().and_then(|c| default_channel_version(&index, c));
+    let mut wanted = channels_of(installed.iter().map(String::as_str));
+    wanted.extend(default_channel.iter().filter(|c| !wanted.contains(c)).cloned().collect::<Vec<_>>());
+    let mut channels = Vec::new();
+    for channel in index.releases_index.iter().filter(|c| c.channel_version.as_ref().is_some_and(|v| wanted.contains(v))) {
+        match fetch_channel(client, config, channel).await {
+            Ok(releases) => {
+                summary.channels.extend(channel.channel_version.clone());
+                channels.push(releases);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    // Patch mancanti
+    // `LTS` e `STS` diventano il loro canale, `A.B` e `A.B.Cxx` restano come sono
+    let default_spec = match config.default_channel.as_deref() {
+        Some(channel) if channel.contains('.') => Some(channel),
+        _ => default_channel.as_deref(),
+    };
+    let targets = patches_to_install(&channels, &installed, default_spec);
+    if dry_run {
+        summary.installed = targets;
+    } else if !targets.is_empty() {
+        let before = sdks_in(Some(&root));
+        let client = ctx.http_client(&install_script_url(config))?;
+        let outcomes = install_sdks(client, ctx.runner(), &targets, None, config).await?;
+        touch_new_sdks(ctx, Some(&root), &before);
+        for outcome in outcomes {
+            match outcome.result {
+                Ok(_) => summary.installed.push(outcome.version),
+                Err(e) => {
+                    let context = format!("Failed to install SDK {}", outcome.version);
+                    errors.push(DverError::Context { context, source: Box::new(e) })
+                }
+            }
+        }
+    }
+
+    // Patch superate, mai quella fissata nella cartella corrente
+    if let Some(keep) = config.keep_patches {
+        let present: Vec<String> = installed.iter().chain(&summary.installed).cloned().collect();
+        let cwd = std::env::current_dir()?;
+        let pinned = pinned_sdk(&cwd, Project::find(&cwd)?.as_ref())?.map(|(version, _)| version);
+        let roots = config.managed_roots();
+        let sdks = scan_root_sdks(&root).unwrap_or_default();
+        for version in superseded(&present, keep as usize).into_iter().filter(|v| pinned.as_ref() != Some(v)) {
+            let path: PathBuf = match sdks.iter().find(|sdk| sdk.version == version) {
+                Some(sdk) => sdk.path.clone(),
+                None if dry_run => root.join("sdk").join(&version),
+                None => continue,
+            };
+            match check_removal(platform::current(), &path, &roots) {
+                Ok(Removal::System(path)) => errors.push(DverError::Permission(format!(
+                    "Not pruning SDK {}: {} is in a system directory",
+                    version,
+                    path.display()
+                ))),
+                Ok(_) if dry_run => summary.pruned.push(version),
+                Ok(_) => match remove_sdk(ctx, &version, &path, Duration::ZERO) {
+                    Ok(()) => summary.pruned.push(version),
+                    Err(e) => errors.push(e),
+                },
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    // Cache di dver oltre il budget
+    if let (Some(budget_mb), Some(cache)) = (config.cache_budget_mb, config.cache_dir()) {
+        let size_bytes = dir_size(&cache).unwrap_or(0);
+        let budget_bytes = budget_mb * 1024 * 1024;
+        let over = size_bytes > budget_bytes;
+        if over && !dry_run {
+            if let Err(e) = clear_dir(&cache) {
+                errors.push(e);
+            }
+        }
+        summary.cache = Some(CacheBudget { size_bytes, budget_bytes, cleared: over });
+    }
+
+    summary.errors = errors.iter().map(|e| e.to_string()).collect();
+    print_summary(ctx, &summary)?;
+    match errors.into_iter().next() {
+        Some(first) => Err(first),
+        None => Ok(()),
+    }
+}
+
+fn print_summary(ctx: &Context, summary: &MaintainSummary) -> Result<(), DverError> {
+    if !ctx.is_human() {
+        let cache = summary.cache.as_ref().filter(|c| c.cleared).map(|_| "dver");
+        let rows: Vec<MaintainRow> = summary
+            .channels
+            .iter()
+            .map(|c| MaintainRow { action: "refresh", target: c })
+            .chain(summary.installed.iter().map(|v| MaintainRow { action: "install", target: v }))
+            .chain(summary.pruned.iter().map(|v| MaintainRow { action: "prune", target: v }))
+            .chain(cache.map(|target| MaintainRow { action: "clear-cache", target }))
+            .chain(summary.errors.iter().map(|e| MaintainRow { action: "error", target: e }))
+            .collect();
+        return ctx.print_structured(summary, &rows);
+    }
+    let (install, prune, clear) =
+        if summary.dry_run { ("Would install", "Would prune", "Would clear") } else { ("Installed", "Pruned", "Cleared") };
+    println!("Refreshed metadata for {}", list_or_none(&summary.channels));
+    println!("{} {}", install, list_or_none(&summary.installed));
+    println!("{} {}", prune, list_or_none(&summary.pruned));
+    if let Some(cache) = &summary.cache {
+        let size = format_size(cache.size_bytes);
+        let budget = format_size(cache.budget_bytes);
+        if cache.cleared {
+            println!("{} the dver cache ({} over the {} budget)", clear, size, budget);
+        } else {
+            println!("The dver cache ({}) is within the {} budget", size, budget);
+        }
+    }
+    for error in &summary.errors {
+        eprintln!("{}", error);
+    }
+    Ok(())
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "nothing".to_string()
+    } else {
+        items.join(", ")
+    }
+}
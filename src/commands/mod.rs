@@ -0,0 +1,214 @@
+//! Un modulo per ogni sottocomando; `main` si limita a leggere la configurazione
+//! e a chiamare la funzione `run` del comando scelto.
+
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use dver_core::http::{http_client, ReqwestClient};
+use dver_core::journal::{self, JournalEntry, Operation};
+use dver_core::metadata::{fetch_channel, fetch_release_index, fetch_remote_sdk_versions};
+use dver_core::outcome::{CommandOutcome, ItemOutcome};
+use dver_core::process::ProcessRunner;
+use dver_core::releases::ChannelReleases;
+use dver_core::{Config, DverError, OutputFormat, ResultExt};
+
+use crate::prompt::Prompter;
+
+pub mod adopt;
+pub mod asdf;
+pub mod audit;
+pub mod build_env;
+pub mod check_updates;
+pub mod clean;
+pub mod completions;
+pub mod config;
+pub mod containerize;
+pub mod current;
+pub mod diff;
+pub mod direnv;
+pub mod doctor;
+pub mod env;
+pub mod export;
+pub mod history;
+pub mod info;
+pub mod init;
+pub mod install;
+pub mod list;
+pub mod maintain;
+pub mod pin;
+pub mod remote;
+pub mod report;
+pub mod runtime;
+pub mod sbom;
+pub mod segment;
+pub mod self_uninstall;
+pub mod setup;
+pub mod status;
+pub mod sync;
+pub mod tool;
+pub mod uninstall;
+pub mod upgrade;
+pub mod r#use;
+pub mod verify;
+pub mod vscode;
+pub mod workload;
+
+/// Esegue un comando che usa la rete. Il runtime tokio si crea solo qui: `current`,
+/// `list`, `env` e gli altri comandi locali non lo avviano mai, così restano abbastanza
+/// veloci da girare nel prompt della shell.
+pub fn block_on<F: std::future::Future>(future: F) -> Result<F::Output, DverError> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("Failed to start the async runtime")?;
+    Ok(runtime.block_on(future))
+}
+
+/// Stato condiviso da tutti i comandi: configurazione effettiva, domande all'utente,
+/// esecuzione dei processi esterni e il client HTTP.
+pub struct Context {
+    pub config: Config,
+    pub prompter: Prompter,
+    pub runner: Box<dyn ProcessRunner>,
+    /// Creato alla prima richiesta e riusato da tutte le successive, così le
+    /// connessioni restano nel pool invece di rifare DNS e handshake TLS ogni volta
+    http: OnceLock<ReqwestClient>,
+    /// Percorsi creati o rimossi dal comando, per il giornale delle operazioni
+    touched: Mutex<Vec<PathBuf>>,
+    /// Esito per SDK di `install`, `uninstall` e `upgrade`, per il documento di `--json`
+    outcomes: Mutex<Vec<ItemOutcome>>,
+}
+
+impl Context {
+    pub fn new(config: Config, prompter: Prompter, runner: Box<dyn ProcessRunner>) -> Context {
+        Context {
+            config,
+            prompter,
+            runner,
+            http: OnceLock::new(),
+            touched: Mutex::new(Vec::new()),
+            outcomes: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Annota cosa è successo a un SDK, per il documento [`CommandOutcome`].
+    pub fn outcome(&self, item: ItemOutcome) {
+        if let Ok(mut outcomes) = self.outcomes.lock() {
+            outcomes.push(item);
+        }
+    }
+
+    /// Stampa il documento con l'esito di `command`, concluso con `result` dopo `elapsed`.
+    pub fn print_outcome(&self, command: &'static str, result: Result<(), &DverError>, elapsed: Duration) -> Result<(), DverError> {
+        let items = self.outcomes.lock().map(|mut o| std::mem::take(&mut *o)).unwrap_or_default();
+        let outcome = CommandOutcome::new(command, items, result, elapsed);
+        self.print_structured(&outcome, &outcome.rows())
+    }
+
+    /// Un messaggio di avanzamento: su stdout per le persone, su stderr con un output
+    /// strutturato, così stdout resta un documento solo.
+    pub fn note(&self, message: impl std::fmt::Display) {
+        if self.is_human() {
+            println!("{}", message);
+        } else {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// Annota un percorso creato o rimosso, che finirà nel giornale.
+    pub fn touch(&self, path: PathBuf) {
+        if let Ok(mut touched) = self.touched.lock() {
+            touched.push(path);
+        }
+    }
+
+    /// Registra nel giornale l'operazione conclusa con `result` e i percorsi annotati;
+    /// un giornale che non si scrive non fa fallire il comando.
+    pub fn record(&self, operation: Operation, result: Result<(), &DverError>) {
+        let paths = self.touched.lock().map(|mut t| std::mem::take(&mut *t)).unwrap_or_default();
+        let args = std::env::args().skip(1).collect();
+        if let Err(e) = journal::append(&self.config, &JournalEntry::new(operation, args, result, paths)) {
+            eprintln!("Warning: {}", e);
+        }
+    }
+
+    pub fn runner(&self) -> &dyn ProcessRunner {
+        self.runner.as_ref()
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.config.output.unwrap_or(OutputFormat::Human)
+    }
+
+    pub fn is_human(&self) -> bool {
+        self.output_format() == OutputFormat::Human
+    }
+
+    /// Client HTTP condiviso; fallisce subito con `--no-network`.
+    pub fn http_client(&self, url: &str) -> Result<&ReqwestClient, DverError> {
+        if let Some(client) = self.http.get() {
+            return Ok(client);
+        }
+        let client = http_client(&self.config, url)?;
+        Ok(self.http.get_or_init(|| client))
+    }
+
+    /// Tutte le versioni SDK pubblicate, per i suggerimenti e i selettori.
+    pub async fn remote_sdk_versions(&self) -> Result<Vec<String>, DverError> {
+        let client = self.http_client(self.config.index_url())?;
+        fetch_remote_sdk_versions(client, &self.config).await
+    }
+
+    /// Rilasci dei soli canali in `wanted` (es. "8.0"); un canale che non si scarica
+    /// viene saltato con un avviso.
+    pub async fn channel_releases(&self, wanted: &[String]) -> Result<Vec<ChannelReleases>, DverError> {
+        let client = self.http_client(self.config.index_url())?;
+        let index = fetch_release_index(client, &self.config).await?;
+
+        let mut channels = Vec::new();
+        for channel in &index.releases_index {
+            if !channel.channel_version.as_ref().is_some_and(|c| wanted.contains(c)) {
+                continue;
+            }
+            match fetch_channel(client, &self.config, channel).await {
+                Ok(releases) => channels.push(releases),
+                Err(e) => eprintln!("Warning: {}; skipping this channel", e),
+            }
+        }
+        Ok(channels)
+    }
+
+    /// Scrive `value` nel formato strutturato scelto; per CSV usa `rows`, che deve essere una lista piatta.
+    #[cfg_attr(not(feature = "formats"), allow(unused_variables))]
+    pub fn print_structured<T: Serialize, R: Serialize>(&self, value: &T, rows: &[R]) -> Result<(), DverError> {
+        match self.output_format() {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            #[cfg(feature = "formats")]
+            OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value).map_err(parse_error)?),
+            #[cfg(feature = "formats")]
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(std::io::stdout());
+                for row in rows {
+                    writer.serialize(row).map_err(parse_error)?;
+                }
+                writer.flush()?;
+            }
+            #[cfg(not(feature = "formats"))]
+            OutputFormat::Yaml | OutputFormat::Csv => {
+                return Err(DverError::Usage(
+                    "YAML and CSV output are not available in this build of dver\nhint: use --output json".to_string()));
+            }
+            OutputFormat::Human => unreachable!("human output is printed by each command"),
+        }
+        Ok(())
+    }
+}
+
+/// Gli errori delle librerie usate solo dalla CLI non hanno un `From` in dver-core.
+#[cfg(feature = "formats")]
+fn parse_error(e: impl std::fmt::Display) -> DverError {
+    DverError::Parse(e.to_string())
+}
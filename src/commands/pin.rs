@@ -0,0 +1,136 @@
+//! `dver pin`: controlla, senza modificare nulla né usare la rete, che la macchina
+//! soddisfi ciò che il repository fissa: l'SDK di global.json o `.dver.toml` (e della
+//! proprietà props allineata a global.json), i workload richiesti e gli strumenti
+//! locali ripristinati. Con `--check` fallisce se manca qualcosa, per fermare subito
+//! una pipeline.
+
+use std::fs;
+use std::path::Path;
+
+use dver_core::caches::nuget_packages_dir;
+use dver_core::inventory::{active_dotnet_root, installed_versions_newest_first, installed_workloads, resolve_in_sdk_paths, roll_forward_sdk};
+use dver_core::project::{find_global_json, pinned_sdk, props_property, required_workloads, PinSource, Project};
+use dver_core::report::{CheckStatus, DoctorCheck};
+use dver_core::resolve::newest_matching;
+use dver_core::tools::{find_tool_manifest, local_drift, ToolStatus};
+use dver_core::version::VersionSpec;
+use dver_core::DverError;
+
+use super::doctor::status_icon;
+use super::Context;
+
+pub fn run(ctx: &Context, check: bool) -> Result<(), DverError> {
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?;
+    let mut checks = vec![sdk_check(ctx, &cwd, project.as_ref())?];
+    checks.extend(props_check(&cwd, project.as_ref())?);
+    checks.extend(workload_check(ctx, &cwd, project.as_ref())?);
+    checks.extend(tool_check(ctx, &cwd)?);
+
+    if ctx.is_human() {
+        for check in &checks {
+            println!("{} {}", status_icon(check.status), check.message);
+        }
+    } else {
+        ctx.print_structured(&checks, &checks)?;
+    }
+    let failed = checks.iter().filter(|c| c.status == CheckStatus::Error).count();
+    if check && failed > 0 {
+        return Err(DverError::Resolution(format!("{} pin(s) not satisfied on this machine", failed)));
+    }
+    Ok(())
+}
+
+/// L'SDK fissato deve corrispondere a uno installato: per global.json secondo il suo
+/// `rollForward` e nelle sue `sdk.paths`, per `.dver.toml` la versione esatta o la più
+/// recente che soddisfa. Se manca, si aggiunge l'`errorMessage` del global.json.
+fn sdk_check(ctx: &Context, cwd: &Path, project: Option<&Project>) -> Result<DoctorCheck, DverError> {
+    let Some((pinned, source)) = pinned_sdk(cwd, project)? else {
+        return Ok(DoctorCheck::error("No SDK is pinned: create a global.json with `dver use` or set `sdk` in .dver.toml."));
+    };
+    let installed = installed_versions_newest_first(ctx.runner(), &ctx.config).unwrap_or_default();
+    let mut error_message = None;
+    let (resolved, policy) = match &source {
+        PinSource::GlobalJson(_) => {
+            let global_json = find_global_json(cwd)?.unwrap_or_default();
+            let policy = format!(" (rollForward {})", global_json.roll_forward.as_deref().unwrap_or("latestPatch"));
+            let resolved = if global_json.paths.is_empty() {
+                roll_forward_sdk(&installed, &pinned, global_json.roll_forward.as_deref())
+            } else {
+                resolve_in_sdk_paths(&global_json, active_dotnet_root(&ctx.config).as_deref())?
+                    .map(|(sdk, root)| format!("{} in {}", sdk, root.display()))
+            };
+            error_message = global_json.error_message;
+            (resolved, policy)
+        }
+        PinSource::DverToml(_) => {
+            let spec: VersionSpec = pinned.parse()?;
+            (newest_matching(&spec, &installed), String::new())
+        }
+    };
+    Ok(match (resolved, error_message) {
+        (Some(sdk), _) => DoctorCheck::ok(format!("SDK {} pinned in {}{} resolves to installed SDK {}.", pinned, source, policy, sdk)),
+        (None, Some(message)) => DoctorCheck::error(format!(
+            "SDK {} pinned in {}{} matches no installed SDK: {}", pinned, source, policy, message)),
+        (None, None) => DoctorCheck::error(format!(
+            "SDK {} pinned in {}{} matches no installed SDK. Run `dver install --from-project`.", pinned, source, policy)),
+    })
+}
+
+/// Con `[props]` in `.dver.toml`, la proprietà del file props deve fissare lo stesso SDK
+/// di global.json.
+fn props_check(cwd: &Path, project: Option<&Project>) -> Result<Option<DoctorCheck>, DverError> {
+    let Some(props) = project.and_then(|p| p.config.props.as_ref()) else {
+        return Ok(None);
+    };
+    let value = fs::read_to_string(&props.file).ok().and_then(|content| props_property(&content, &props.property));
+    let pinned = find_global_json(cwd)?.and_then(|g| g.version);
+    let location = format!("<{}> in {}", props.property, props.file.display());
+    Ok(Some(match (value, pinned) {
+        (None, _) => DoctorCheck::error(format!("{} is not set. Run `dver use <version>` to write it.", location)),
+        (Some(value), Some(pinned)) if value != pinned => DoctorCheck::error(format!(
+            "{} is {} but global.json pins {}. Run `dver use {}` to align them.", location, value, pinned, pinned)),
+        (Some(value), _) => DoctorCheck::ok(format!("{} pins SDK {}.", location, value)),
+    }))
+}
+
+/// I workload di `.dver.toml` e quelli che i progetti richiedono devono essere installati.
+fn workload_check(ctx: &Context, cwd: &Path, project: Option<&Project>) -> Result<Option<DoctorCheck>, DverError> {
+    let mut required = required_workloads(cwd)?;
+    required.extend(project.map(|p| p.config.workloads.clone()).unwrap_or_default());
+    required.sort();
+    required.dedup();
+    if required.is_empty() {
+        return Ok(None);
+    }
+    let present = match installed_workloads(ctx.runner(), &ctx.config) {
+        Ok(present) => present,
+        Err(e) => return Ok(Some(DoctorCheck::error(format!("Cannot list the installed workloads: {}", e)))),
+    };
+    let missing: Vec<&str> = required.iter().filter(|w| !present.contains(w)).map(String::as_str).collect();
+    Ok(Some(if missing.is_empty() {
+        DoctorCheck::ok(format!("Required workloads are installed: {}.", required.join(", ")))
+    } else {
+        DoctorCheck::error(format!("Missing workloads: {}. Run `dver install --for-project`.", missing.join(", ")))
+    }))
+}
+
+/// Gli strumenti del manifest locale devono essere ripristinati alle versioni dichiarate.
+fn tool_check(ctx: &Context, cwd: &Path) -> Result<Option<DoctorCheck>, DverError> {
+    let Some((path, manifest)) = find_tool_manifest(cwd)? else {
+        return Ok(None);
+    };
+    let Some((packages, _)) = nuget_packages_dir(&ctx.config) else {
+        return Ok(Some(DoctorCheck::error("Cannot locate the NuGet package folder to check the local tools.")));
+    };
+    let pending: Vec<String> = local_drift(&manifest, &packages)
+        .into_iter()
+        .filter(|d| d.status != ToolStatus::Current)
+        .map(|d| format!("{} {}", d.id, d.wanted.unwrap_or_default()))
+        .collect();
+    Ok(Some(if pending.is_empty() {
+        DoctorCheck::ok(format!("Local tools in {} are restored.", path.display()))
+    } else {
+        DoctorCheck::error(format!("Local tools not restored: {}. Run `dotnet tool restore`.", pending.join(", ")))
+    }))
+}
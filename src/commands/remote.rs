@@ -0,0 +1,130 @@
+use std::io::IsTerminal;
+
+use serde::Serialize;
+
+use dver_core::journal::parse_day;
+use dver_core::metadata::{fetch_channel, fetch_release_index, RemoteChannel, RemoteQuery, RemoteSort};
+use dver_core::DverError;
+
+use super::Context;
+
+#[cfg(feature = "interactive")]
+fn channel_spinner(enabled: bool, channel: &str) -> indicatif::ProgressBar {
+    use indicatif::{ProgressBar, ProgressStyle};
+
+    if !enabled {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::with_template("{spinner} {msg}").unwrap());
+    pb.set_message(format!("Fetching {} releases…", channel));
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    pb
+}
+
+/// Senza la feature `interactive` non c'è spinner: restano le righe su stderr.
+#[cfg(not(feature = "interactive"))]
+struct NoSpinner;
+
+#[cfg(not(feature = "interactive"))]
+impl NoSpinner {
+    fn finish_and_clear(&self) {}
+}
+
+#[cfg(not(feature = "interactive"))]
+fn channel_spinner(_: bool, _: &str) -> NoSpinner {
+    NoSpinner
+}
+
+async fn fetch_remote_channels(
+    ctx: &Context,
+    lts_only: bool,
+    only: Option<&str>,
+    query: &RemoteQuery,
+    show_progress: bool,
+) -> Result<Vec<RemoteChannel>, DverError> {
+    let config = &ctx.config;
+    let client = ctx.http_client(config.index_url())?;
+    let index = fetch_release_index(client, config).await?;
+    if let Some(only) = only.filter(|only| !index.releases_index.iter().any(|c| c.channel_version.as_deref() == Some(*only))) {
+        let known: Vec<&str> = index.releases_index.iter().filter_map(|c| c.channel_version.as_deref()).collect();
+        return Err(DverError::NotFound(format!("Unknown channel '{}'\nhint: available channels are {}", only, known.join(", "))));
+    }
+
+    let mut channels = Vec::new();
+    let selected = index.releases_index.iter().filter(|c| {
+        // `--channel` vale anche per un canale che la configurazione non segue
+        let followed = match only {
+            Some(only) => c.channel_version.as_deref() == Some(only),
+            None => c.channel_version.as_deref().is_none_or(|v| config.follows_channel(v)),
+        };
+        (!lts_only || c.is_lts()) && followed && query.may_match(c)
+    });
+    for channel in selected {
+        let channel_version = channel.channel_version.as_deref().unwrap_or("unknown");
+        let pb = channel_spinner(show_progress, channel_version);
+        let channel_releases = match fetch_channel(client, config, channel).await {
+            Ok(releases) => releases,
+            Err(e) => {
+                pb.finish_and_clear();
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        let remote = RemoteChannel::query(channel, &channel_releases, query);
+
+        pb.finish_and_clear();
+        if show_progress {
+            eprintln!("Fetching {} releases… done, {} releases", channel_version, remote.releases.len());
+        }
+        // Con `--since` i canali senza rilasci recenti non si mostrano
+        if query.since.is_none() || !remote.releases.is_empty() {
+            channels.push(remote);
+        }
+    }
+
+    Ok(channels)
+}
+
+#[derive(Debug, Serialize)]
+struct RemoteRow<'a> {
+    channel: &'a str,
+    release_type: &'a str,
+    version: &'a str,
+}
+
+/// Elenca le patch disponibili per ogni canale, filtrate e ordinate come chiesto.
+pub async fn run(
+    ctx: &Context,
+    lts_only: bool,
+    channel: Option<&str>,
+    sort: Option<RemoteSort>,
+    since: Option<&str>,
+    limit: Option<usize>,
+) -> Result<(), DverError> {
+    let query = RemoteQuery { sort, since: since.map(parse_day).transpose()?, limit };
+    let show_progress = ctx.is_human() && std::io::stderr().is_terminal();
+    let channels = fetch_remote_channels(ctx, lts_only, channel, &query, show_progress).await?;
+
+    if !ctx.is_human() {
+        let rows: Vec<RemoteRow> = channels
+            .iter()
+            .flat_map(|c| c.releases.iter().map(move |v| RemoteRow {
+                channel: &c.channel,
+                release_type: &c.release_type,
+                version: v,
+            }))
+            .collect();
+        return ctx.print_structured(&channels, &rows);
+    }
+
+    println!("Remote .NET SDK versions available:");
+    for channel in &channels {
+        println!("Channel: {} ({})", channel.channel, channel.release_type);
+        for release in &channel.releases {
+            println!("{}", release);
+        }
+    }
+
+    Ok(())
+}
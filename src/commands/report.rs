@@ -0,0 +1,109 @@
+//! `dver report`: istantanea della macchina da raccogliere su molte macchine. In
+//! Markdown per chi la legge, con `--output json` (o yaml) come dati; in CSV una riga
+//! per componente, con il nome della macchina su ogni riga.
+
+use serde::Serialize;
+
+use dver_core::caches::measured_cache_locations;
+use dver_core::inventory::{current_sdk, list_installed_runtimes, list_installed_sdks, list_installed_workloads};
+use dver_core::metadata::{cached_release_index, fetch_release_index};
+use dver_core::project::{pinned_sdk, PinSource, Project};
+use dver_core::releases::ReleaseIndex;
+use dver_core::report::{report_runtimes, report_sdks, ActivePin, DoctorSummary, FleetReport};
+use dver_core::{adopt, platform, provenance, registry, DverError};
+
+use super::{doctor, Context};
+
+/// Riga CSV: un SDK, un runtime o un workload della macchina.
+#[derive(Debug, Serialize)]
+struct ComponentRow<'a> {
+    hostname: Option<&'a str>,
+    kind: &'static str,
+    name: &'a str,
+    version: &'a str,
+    origin: Option<String>,
+    is_eol: Option<bool>,
+}
+
+/// Indice dei rilasci per la fine del supporto: scaricato, o dalla cache se la rete
+/// non è disponibile.
+async fn release_index(ctx: &Context) -> Option<ReleaseIndex> {
+    let fetched = async {
+        let client = ctx.http_client(ctx.config.index_url())?;
+        fetch_release_index(client, &ctx.config).await
+    };
+    match fetched.await {
+        Ok(index) => Some(index),
+        Err(e) => {
+            let cached = cached_release_index(&ctx.config);
+            if cached.is_none() {
+                eprintln!("Warning: {}; end of support is left unknown", e);
+            }
+            cached
+        }
+    }
+}
+
+pub async fn run(ctx: &Context) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?;
+    let (pinned, source) = match pinned_sdk(&cwd, project.as_ref())? {
+        Some((version, PinSource::DverToml(path) | PinSource::GlobalJson(path))) => (Some(version), Some(path)),
+        None => (None, None),
+    };
+    let pin = ActivePin { directory: cwd.clone(), pinned, source, resolved: current_sdk(ctx.runner(), config, &cwd).ok() };
+    let mut report = FleetReport::new(env!("CARGO_PKG_VERSION"), pin);
+    let today = report.generated_at.date_naive();
+
+    let sdks = list_installed_sdks(ctx.runner(), config).unwrap_or_else(|e| {
+        eprintln!("Warning: {}; SDKs are left out", e);
+        Vec::new()
+    });
+    let runtimes = list_installed_runtimes(ctx.runner(), config).unwrap_or_else(|e| {
+        eprintln!("Warning: {}; runtimes are left out", e);
+        Vec::new()
+    });
+    let index = if sdks.is_empty() && runtimes.is_empty() { None } else { release_index(ctx).await };
+    let msi = registry::installed_versions(ctx.runner(), platform::current());
+    report.sdks = report_sdks(&sdks, &provenance::load_all(config)?, &msi, &adopt::load_all(config)?, index.as_ref(), today);
+    report.runtimes = report_runtimes(&runtimes, index.as_ref(), today);
+    report.workloads = list_installed_workloads(ctx.runner(), config).unwrap_or_default();
+    report.caches = measured_cache_locations(config);
+    report.doctor = DoctorSummary::new(&doctor::checks(ctx));
+
+    if ctx.is_human() {
+        print!("{}", report.markdown());
+        return Ok(());
+    }
+    let hostname = report.host.hostname.as_deref();
+    let rows: Vec<ComponentRow> = report
+        .sdks
+        .iter()
+        .map(|s| ComponentRow {
+            hostname,
+            kind: "sdk",
+            name: "dotnet-sdk",
+            version: &s.version,
+            origin: Some(s.origin.to_string()),
+            is_eol: s.is_eol,
+        })
+        .chain(report.runtimes.iter().map(|r| ComponentRow {
+            hostname,
+            kind: "runtime",
+            name: &r.name,
+            version: &r.version,
+            origin: None,
+            is_eol: r.is_eol,
+        }))
+        .chain(report.workloads.iter().map(|w| ComponentRow {
+            hostname,
+            kind: "workload",
+            name: &w.id,
+            version: w.manifest_version.as_deref().unwrap_or(""),
+            origin: w.source.clone(),
+            is_eol: None,
+        }))
+        .collect();
+    ctx.print_structured(&report, &rows)
+}
@@ -0,0 +1,156 @@
+//! `dver runtime`: i runtime condivisi per le macchine che eseguono app pubblicate.
+//! `list` li elenca, `use` fissa i `*.runtimeconfig.json` di un'app a un runtime e
+//! `install` installa i runtime che quei file richiedono.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dver_core::install::{install_runtime, install_script_url};
+use dver_core::inventory::{list_installed_runtimes, InstalledRuntime};
+use dver_core::runtimeconfig::{
+    find_runtime_configs, pin_runtime_config, read_runtime_config, resolve_framework, RollForward, RuntimeConfig,
+    RuntimeKind,
+};
+use dver_core::version::SdkVersion;
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+use crate::RuntimeAction;
+
+/// Un framework richiesto da un'app e il runtime installato che lo soddisfa.
+#[derive(Debug)]
+struct FrameworkRow {
+    name: String,
+    requested: String,
+    roll_forward: &'static str,
+    resolved: Option<String>,
+}
+
+pub async fn run(ctx: &Context, action: &RuntimeAction) -> Result<(), DverError> {
+    match action {
+        RuntimeAction::List => list(ctx),
+        RuntimeAction::Use { version, dir, roll_forward } => pin(ctx, version, dir.as_deref(), *roll_forward),
+        RuntimeAction::Install { dir } => install(ctx, dir.as_deref()).await,
+    }
+}
+
+fn list(ctx: &Context) -> Result<(), DverError> {
+    let runtimes = list_installed_runtimes(ctx.runner(), &ctx.config)?;
+    if !ctx.is_human() {
+        return ctx.print_structured(&runtimes, &runtimes);
+    }
+    if runtimes.is_empty() {
+        println!("No shared runtimes installed.");
+    }
+    for runtime in &runtimes {
+        println!("{} {} [{}]", runtime.name, runtime.version, runtime.path.display());
+    }
+    Ok(())
+}
+
+fn pin(ctx: &Context, version: &str, dir: Option<&Path>, roll_forward: RollForward) -> Result<(), DverError> {
+    let version = runtime_version(version)?;
+    let configs = app_configs(dir)?;
+    for path in &configs {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let pinned = pin_runtime_config(&content, &version, roll_forward)
+            .with_context(|| format!("Failed to pin {}", path.display()))?;
+        fs::write(path, pinned).with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("Pinned {} to {} (rollForward: {})", path.display(), version, roll_forward.as_config_value());
+    }
+    let installed = list_installed_runtimes(ctx.runner(), &ctx.config).unwrap_or_default();
+    for path in &configs {
+        for row in framework_rows(&read_runtime_config(path)?, &installed) {
+            if row.resolved.is_none() {
+                eprintln!(
+                    "Warning: no installed {} satisfies {} with rollForward {}",
+                    row.name, row.requested, row.roll_forward
+                );
+                eprintln!("hint: run dver runtime install");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn install(ctx: &Context, dir: Option<&Path>) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let installed = list_installed_runtimes(ctx.runner(), config).unwrap_or_default();
+    let mut rows = Vec::new();
+    for path in app_configs(dir)? {
+        rows.extend(framework_rows(&read_runtime_config(&path)?, &installed));
+    }
+    let mut missing: Vec<(RuntimeKind, String)> = Vec::new();
+    for row in &rows {
+        match (&row.resolved, RuntimeKind::from_framework(&row.name)) {
+            (Some(resolved), _) => println!("{} {} is satisfied by {}", row.name, row.requested, resolved),
+            (None, Some(kind)) => {
+                if !missing.contains(&(kind, row.requested.clone())) {
+                    missing.push((kind, row.requested.clone()));
+                }
+            }
+            (None, None) => eprintln!("Warning: {} is not a runtime dver can install, skipping", row.name),
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let client = ctx.http_client(&install_script_url(config))?;
+    for (kind, version) in &missing {
+        println!("Installing {} {}...", kind.framework_name(), version);
+        let output = install_runtime(client, ctx.runner(), *kind, version, None, config).await?;
+        print!("{}", output);
+    }
+    println!("Runtime installation completed.");
+    Ok(())
+}
+
+/// Una versione di runtime completa: `8.0` diventa `8.0.0`, come la scrive l'SDK.
+fn runtime_version(version: &str) -> Result<String, DverError> {
+    let version = match version.matches('.').count() {
+        1 => format!("{}.0", version),
+        _ => version.to_string(),
+    };
+    version
+        .parse::<SdkVersion>()
+        .map(|_| version.clone())
+        .map_err(|_| DverError::Usage(format!("Invalid runtime version '{}'\nhint: use a version like 8.0.11", version)))
+}
+
+/// I runtimeconfig dell'app in `dir`, o nella cartella corrente.
+fn app_configs(dir: Option<&Path>) -> Result<Vec<PathBuf>, DverError> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let configs = find_runtime_configs(&dir)?;
+    if configs.is_empty() {
+        return Err(DverError::NotFound(format!(
+            "No *.runtimeconfig.json in {}\nhint: point --dir at the folder of a published app",
+            dir.display()
+        )));
+    }
+    Ok(configs)
+}
+
+/// Per ogni framework del runtimeconfig, il runtime installato che l'host sceglierebbe.
+fn framework_rows(config: &RuntimeConfig, installed: &[InstalledRuntime]) -> Vec<FrameworkRow> {
+    let roll_forward = config.effective_roll_forward();
+    config
+        .frameworks
+        .iter()
+        .map(|framework| {
+            let versions: Vec<String> = installed
+                .iter()
+                .filter(|r| r.name.eq_ignore_ascii_case(&framework.name))
+                .map(|r| r.version.clone())
+                .collect();
+            FrameworkRow {
+                name: framework.name.clone(),
+                requested: framework.version.clone(),
+                roll_forward: roll_forward.as_config_value(),
+                resolved: resolve_framework(&versions, &framework.version, roll_forward),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,37 @@
+use std::time::SystemTime;
+
+use dver_core::audit::channels_of;
+use dver_core::inventory::{list_installed_runtimes, list_installed_sdks, list_installed_workloads};
+use dver_core::platform;
+use dver_core::sbom::{components, Sbom, SbomFormat};
+use dver_core::DverError;
+
+use super::Context;
+
+/// Stampa la distinta degli SDK, runtime e workload installati nel formato scelto.
+pub async fn run(ctx: &Context, format: SbomFormat) -> Result<(), DverError> {
+    let sdks = list_installed_sdks(ctx.runner(), &ctx.config)?;
+    let runtimes = list_installed_runtimes(ctx.runner(), &ctx.config)?;
+    let workloads = list_installed_workloads(ctx.runner(), &ctx.config).unwrap_or_else(|e| {
+        eprintln!("Warning: {}; workloads are left out", e);
+        Vec::new()
+    });
+
+    let wanted = channels_of(sdks.iter().map(|s| s.version.as_str()).chain(runtimes.iter().map(|r| r.version.as_str())));
+    let channels = if wanted.is_empty() {
+        Vec::new()
+    } else {
+        ctx.channel_releases(&wanted).await.unwrap_or_else(|e| {
+            eprintln!("Warning: {}; download URLs and hashes are left out", e);
+            Vec::new()
+        })
+    };
+
+    let sbom = Sbom {
+        components: components(&sdks, &runtimes, &workloads, &channels, &platform::rid()),
+        created: SystemTime::now(),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    println!("{}", serde_json::to_string_pretty(&sbom.render(format))?);
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! `dver prompt`: l'SDK della cartella per i prompt di shell, come testo semplice,
+//! come JSON (`--json`) o come variabili (`--env`) secondo il contratto di
+//! [`dver_core::segment`]. Senza SDK non stampa nulla e termina con successo.
+
+use dver_core::segment::PromptSegment;
+use dver_core::DverError;
+
+use super::Context;
+
+pub fn run(ctx: &Context, env: bool) -> Result<(), DverError> {
+    let segment = PromptSegment::current(&ctx.config, &std::env::current_dir()?)?;
+    if env {
+        print!("{}", segment.map(|s| s.render_env()).unwrap_or_default());
+    } else if !ctx.is_human() {
+        ctx.print_structured(&segment, segment.as_slice())?;
+    } else if let Some(segment) = segment {
+        println!("{}", segment.version);
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! `dver self-uninstall`: toglie dalla macchina tutto ciò che dver ha creato, così
+//! provarlo non lascia tracce. Elenca prima gli SDK installati da dver (con la loro
+//! radice, se non contiene altro), shim, blocchi nei file di avvio della shell, cache,
+//! stato e configurazione, poi chiede conferma. Gli SDK adottati restano.
+
+use dver_core::paths;
+use dver_core::setup::{footprint, remove_footprint, FootprintKind};
+use dver_core::DverError;
+
+use super::Context;
+
+pub fn run(ctx: &Context, keep_sdks: bool, dry_run: bool) -> Result<(), DverError> {
+    let home = paths::home_dir().ok_or_else(|| DverError::NotFound("Cannot find the home directory".to_string()))?;
+    let items = footprint(&ctx.config, &home, keep_sdks);
+    if ctx.is_human() {
+        if items.is_empty() {
+            println!("Nothing to remove: dver has left nothing on this machine.");
+        }
+        for item in &items {
+            println!("{:<13} {}", item.kind.to_string(), item.path.display());
+        }
+    } else {
+        ctx.print_structured(&items, &items)?;
+    }
+    if dry_run || items.is_empty() {
+        return Ok(());
+    }
+
+    if !ctx.prompter.confirm("Remove everything listed above?", false)? {
+        if !ctx.prompter.interactive {
+            return Err(DverError::Usage("Refusing to uninstall dver without confirmation; pass --yes".to_string()));
+        }
+        println!("Aborted.");
+        return Ok(());
+    }
+    for item in &items {
+        remove_footprint(item)?;
+        if ctx.is_human() {
+            match item.kind {
+                FootprintKind::ShellHook => println!("Removed the dver hook from {}", item.path.display()),
+                kind => println!("Removed {} ({})", item.path.display(), kind),
+            }
+        }
+    }
+    if let (true, Ok(exe)) = (ctx.is_human(), std::env::current_exe()) {
+        println!("dver itself is still at {}; delete it to finish.", exe.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! `dver setup`: prepara una macchina nuova in un comando. Scrive la radice di
+//! installazione nella configurazione, installa lo shim di `dotnet` e il blocco nel
+//! file di avvio della shell, se richiesto installa l'ultimo SDK LTS e chiude con
+//! `doctor`. Rilanciarlo aggiorna quello che c'è già invece di duplicarlo.
+
+use std::path::PathBuf;
+
+use dver_core::environment::ShellKind;
+use dver_core::process::SystemRunner;
+use dver_core::setup::{hook_block, install_hook, rc_file, shims_dir, write_shim};
+use dver_core::{config, paths, platform, Config, DverError, ResultExt};
+
+use super::{doctor, install, Context};
+
+pub async fn run(ctx: &Context, defaults: bool, no_sdk: bool, shell: Option<ShellKind>) -> Result<(), DverError> {
+    let ask = |question: &str, default: bool| if defaults { Ok(default) } else { ctx.prompter.confirm(question, default) };
+    let home = paths::home_dir().ok_or_else(|| DverError::NotFound("Cannot find the home directory".to_string()))?;
+
+    // --- Configurazione ---
+    let suggested = match &ctx.config.install_root {
+        Some(root) => root.display().to_string(),
+        None => platform::current().user_dotnet_root(&home).display().to_string(),
+    };
+    let root = if defaults { suggested } else { ctx.prompter.input("Install root for .NET SDKs", &suggested)? };
+    config::set_key("install_root", &root)?;
+    if let Some(path) = Config::path() {
+        println!("Set install_root = {} in {}", root, path.display());
+    }
+    let mut config = ctx.config.clone();
+    config.install_root = Some(PathBuf::from(&root));
+
+    // --- Shim e shell ---
+    let dver = std::env::current_exe().context("Failed to locate the dver executable")?;
+    let shims = shims_dir(&config).ok_or_else(|| DverError::NotFound("Cannot find the state directory".to_string()))?;
+    if ask(&format!("Install the dotnet shim in {}?", shims.display()), true)? {
+        let shim = write_shim(&shims, &dver)?;
+        println!("Installed the dotnet shim {}", shim.display());
+    }
+    let shell = shell.unwrap_or_else(ShellKind::detect);
+    match rc_file(shell, &home) {
+        Some(rc) => {
+            if ask(&format!("Add the dver hook to {}?", rc.display()), true)? {
+                install_hook(&rc, &hook_block(shell, &dver, &shims))?;
+                println!("Added the dver hook to {}", rc.display());
+            }
+        }
+        None => {
+            eprintln!("Warning: this shell has no startup file dver can edit");
+            eprintln!("hint: run dver env --shell cmd in each new session");
+        }
+    }
+
+    // --- SDK e controllo finale ---
+    let ctx = Context::new(config, ctx.prompter, Box::new(SystemRunner));
+    if !no_sdk && ask("Install the latest LTS SDK?", true)? {
+        install::run(&ctx, true, Vec::new(), None, false, false).await?;
+    }
+    doctor::run(&ctx);
+    println!("Setup complete. Open a new shell to start using dver.");
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! `dver status`: una schermata con radice in uso, SDK attivo e pin, quanto è
+//! installato, aggiornamenti noti alla cache e stato di shim e blocco della shell.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use dver_core::disk::format_size;
+use dver_core::environment::ShellKind;
+use dver_core::status::{Health, Status};
+use dver_core::updates::UpdateStatus;
+use dver_core::DverError;
+
+use super::Context;
+
+/// Riga CSV piatta di [`Status`]: degli aggiornamenti resta il conteggio.
+#[derive(Debug, Serialize)]
+struct StatusRow<'a> {
+    install_root: Option<&'a Path>,
+    active_sdk: Option<&'a str>,
+    pin: Option<&'a str>,
+    pin_source: Option<&'a Path>,
+    sdks: usize,
+    runtimes: usize,
+    size_bytes: u64,
+    updates: Option<usize>,
+    security_updates: Option<usize>,
+    shim: Health,
+    hook: Health,
+}
+
+pub fn run(ctx: &Context) -> Result<(), DverError> {
+    let status = Status::collect(&ctx.config, &std::env::current_dir()?, ShellKind::detect())?;
+    if !ctx.is_human() {
+        let row = StatusRow {
+            install_root: status.install_root.as_deref(),
+            active_sdk: status.active_sdk.as_deref(),
+            pin: status.pin.as_ref().map(|p| p.version.as_str()),
+            pin_source: status.pin.as_ref().map(|p| p.source.as_path()),
+            sdks: status.sdks,
+            runtimes: status.runtimes,
+            size_bytes: status.size_bytes,
+            updates: status.updates.as_ref().map(Vec::len),
+            security_updates: status
+                .updates
+                .as_ref()
+                .map(|u| u.iter().filter(|u| u.status == UpdateStatus::Security).count()),
+            shim: status.shim,
+            hook: status.hook,
+        };
+        return ctx.print_structured(&status, &[row]);
+    }
+
+    let root = status.install_root.as_ref().map_or_else(|| "none found".to_string(), |r| r.display().to_string());
+    println!("{:<14} {}", "Install root", root);
+    let active = match (&status.active_sdk, &status.pin) {
+        (Some(sdk), Some(pin)) if *sdk == pin.version => format!("{} (pinned in {})", sdk, pin.source.display()),
+        (Some(sdk), Some(pin)) => format!("{} ({} pinned in {} is not installed)", sdk, pin.version, pin.source.display()),
+        (Some(sdk), None) => format!("{} (newest installed, no pin)", sdk),
+        (None, Some(pin)) => format!("none ({} pinned in {} is not installed)", pin.version, pin.source.display()),
+        (None, None) => "none".to_string(),
+    };
+    println!("{:<14} {}", "Active SDK", active);
+    println!(
+        "{:<14} {} SDK(s), {} runtime(s), {}",
+        "Installed", status.sdks, status.runtimes, format_size(status.size_bytes)
+    );
+    match &status.updates {
+        None => println!("{:<14} unknown (no cached metadata; run dver check-updates)", "Updates"),
+        Some(updates) if updates.is_empty() => println!("{:<14} up to date", "Updates"),
+        Some(updates) => {
+            for (i, update) in updates.iter().enumerate() {
+                let label = if i == 0 { "Updates" } else { "" };
+                let kind = if update.status == UpdateStatus::Security { " (security)" } else { "" };
+                let target = update.update_to.as_deref().unwrap_or("a newer SDK");
+                println!("{:<14} {} -> {}{}", label, update.version, target, kind);
+            }
+        }
+    }
+    let shim = match status.shim {
+        Health::Missing => "missing (run dver setup)".to_string(),
+        health => health.to_string(),
+    };
+    println!("{:<14} {}", "Shim", shim);
+    let hook = match (&status.hook_file, status.hook) {
+        (None, _) => "not supported by this shell".to_string(),
+        (Some(file), Health::Ok) => format!("ok ({})", file.display()),
+        (Some(file), _) => format!("missing from {} (run dver setup)", file.display()),
+    };
+    println!("{:<14} {}", "Shell hook", hook);
+    Ok(())
+}
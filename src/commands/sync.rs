@@ -0,0 +1,127 @@
+//! `dver sync push|pull`: porta gli SDK, i workload e le impostazioni di una macchina
+//! sulle altre attraverso un repository git o una cartella HTTP(S) scelta dall'utente.
+
+use std::fs;
+use std::path::PathBuf;
+
+use dver_core::support::scrub_value;
+use dver_core::sync::{
+    checkout_dir, file_url, git_clone, git_commit_and_push, merge_config, portable_config, read_checkout_file,
+    SyncBackend, CONFIG_FILE, TOOLCHAIN_FILE,
+};
+use dver_core::toolchain::Toolchain;
+use dver_core::{config, Config, DverError, ResultExt};
+
+use super::export::{capture, import_toolchain};
+use super::Context;
+use crate::SyncAction;
+
+pub async fn run(ctx: &Context, action: &SyncAction) -> Result<(), DverError> {
+    let remote = ctx.config.sync_remote.as_deref().ok_or_else(|| {
+        DverError::Usage(
+            "No sync remote is configured\nhint: run `dver config set sync_remote <git repository or https:// folder>`"
+                .to_string(),
+        )
+    })?;
+    let backend = SyncBackend::parse(remote)?;
+    // I token SAS e le password nell'URL non finiscono nei messaggi
+    let shown = scrub_value(remote);
+    match action {
+        SyncAction::Push => push(ctx, &backend, &shown).await,
+        SyncAction::Pull { prune } => pull(ctx, &backend, &shown, *prune).await,
+    }
+}
+
+async fn push(ctx: &Context, backend: &SyncBackend, shown: &str) -> Result<(), DverError> {
+    let toolchain = capture(ctx)?;
+    let manifest = format!("{}\n", serde_json::to_string_pretty(&toolchain)?);
+    let settings = portable_config(&local_config()?)?;
+    let changed = match backend {
+        SyncBackend::Git(url) => {
+            let dir = checkout(ctx, url)?;
+            let written = fs::write(dir.join(TOOLCHAIN_FILE), &manifest)
+                .and_then(|_| fs::write(dir.join(CONFIG_FILE), &settings))
+                .with_context(|| format!("Failed to write the profile in {}", dir.display()));
+            let pushed = written.and_then(|_| git_commit_and_push(ctx.runner(), &dir, "Update the dver profile"));
+            let _ = fs::remove_dir_all(&dir);
+            pushed?
+        }
+        SyncBackend::Http(base) => {
+            let client = ctx.http_client(base)?;
+            client.put(&file_url(base, TOOLCHAIN_FILE), manifest.into_bytes()).await?;
+            client.put(&file_url(base, CONFIG_FILE), settings.into_bytes()).await?;
+            true
+        }
+    };
+    if changed {
+        println!(
+            "Pushed {} SDK(s), {} workload(s) and the settings to {}",
+            toolchain.sdks.len(),
+            toolchain.workloads.len(),
+            shown
+        );
+    } else {
+        println!("{} is already up to date", shown);
+    }
+    Ok(())
+}
+
+async fn pull(ctx: &Context, backend: &SyncBackend, shown: &str, prune: bool) -> Result<(), DverError> {
+    let (manifest, settings) = match backend {
+        SyncBackend::Git(url) => {
+            let dir = checkout(ctx, url)?;
+            let files = read_checkout_file(&dir, TOOLCHAIN_FILE).and_then(|manifest| {
+                read_checkout_file(&dir, CONFIG_FILE).map(|settings| (manifest, settings))
+            });
+            let _ = fs::remove_dir_all(&dir);
+            files?
+        }
+        SyncBackend::Http(base) => {
+            let client = ctx.http_client(base)?;
+            let manifest = client.get_text_if_exists(&file_url(base, TOOLCHAIN_FILE)).await?;
+            let settings = client.get_text_if_exists(&file_url(base, CONFIG_FILE)).await?;
+            (manifest, settings)
+        }
+    };
+    let manifest = manifest.ok_or_else(|| {
+        DverError::NotFound(format!(
+            "Nothing has been pushed to {} yet\nhint: run `dver sync push` on the machine to copy from",
+            shown
+        ))
+    })?;
+    let toolchain = Toolchain::parse(&manifest).with_context(|| format!("Cannot pull from {}", shown))?;
+    import_toolchain(ctx, &toolchain, shown, prune).await?;
+
+    if let Some(settings) = settings {
+        // Letta dopo l'import, che può aver già impostato `default_channel`
+        let local = local_config()?;
+        let merged = merge_config(&local, &settings)?;
+        if Some(&merged) != local.parse().ok().as_ref() {
+            config::save_table(&merged)?;
+            println!("Updated the settings from {}", shown);
+        }
+    }
+    Ok(())
+}
+
+/// Il `config.toml` di questa macchina, vuoto se non c'è.
+fn local_config() -> Result<String, DverError> {
+    match Config::path().filter(|path| path.exists()) {
+        Some(path) => fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display())),
+        None => Ok(String::new()),
+    }
+}
+
+/// Una copia appena clonata del repository del profilo.
+fn checkout(ctx: &Context, url: &str) -> Result<PathBuf, DverError> {
+    let dir = checkout_dir(&ctx.config)
+        .ok_or_else(|| DverError::NotFound("Cannot determine the state directory (HOME not set)".to_string()))?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    }
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    git_clone(ctx.runner(), url, &dir)?;
+    Ok(dir)
+}
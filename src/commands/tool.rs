@@ -0,0 +1,96 @@
+//! `dver tool sync`: porta gli strumenti .NET dichiarati (globali in `[tools]`, locali
+//! nel manifest del repository) alle versioni volute, con l'SDK attivo nella cartella.
+//! Con `--check` riporta soltanto la differenza e fallisce se c'è.
+
+use dver_core::caches::nuget_packages_dir;
+use dver_core::inventory::{current_sdk, dotnet_output};
+use dver_core::tools::{find_tool_manifest, global_drift, local_drift, parse_tool_list, ToolDrift, ToolScope, ToolStatus};
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+use crate::ToolAction;
+
+pub fn run(ctx: &Context, action: &ToolAction) -> Result<(), DverError> {
+    match action {
+        ToolAction::Sync { check } => sync(ctx, *check),
+    }
+}
+
+fn sync(ctx: &Context, check: bool) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let cwd = std::env::current_dir()?;
+    let mut drifts = Vec::new();
+    if !config.tools.is_empty() {
+        let listed = dotnet_output(ctx.runner(), config, &["tool", "list", "--global"])
+            .context("Failed to list global tools")?;
+        drifts.extend(global_drift(&config.tools, &parse_tool_list(&listed)));
+    }
+    let manifest = find_tool_manifest(&cwd)?;
+    if let (Some((_, manifest)), Some((packages, _))) = (&manifest, nuget_packages_dir(config)) {
+        drifts.extend(local_drift(manifest, &packages));
+    }
+    if drifts.is_empty() {
+        return Err(DverError::NotFound(
+            "No tools declared\nhint: add global tools with `dver config set tools.<id> <version>` \
+             or local ones to .config/dotnet-tools.json"
+                .to_string(),
+        ));
+    }
+
+    if ctx.is_human() {
+        if let Ok(sdk) = current_sdk(ctx.runner(), config, &cwd) {
+            println!("Tools for SDK {}:", sdk);
+        }
+        for drift in &drifts {
+            print_drift(drift);
+        }
+    } else {
+        ctx.print_structured(&drifts, &drifts)?;
+    }
+
+    let pending: Vec<&ToolDrift> = drifts.iter().filter(|d| d.status != ToolStatus::Current).collect();
+    if check {
+        return match pending.len() {
+            0 => Ok(()),
+            n => Err(DverError::Resolution(format!(
+                "{} tool(s) differ from the declared versions\nhint: run `dver tool sync`", n))),
+        };
+    }
+    for drift in pending.iter().filter(|d| d.scope == ToolScope::Global) {
+        let verb = if drift.status == ToolStatus::Missing { "install" } else { "update" };
+        let mut args = vec!["tool", verb, "--global", drift.id.as_str()];
+        if let Some(version) = &drift.wanted {
+            args.extend(["--version", version.as_str()]);
+            if drift.status == ToolStatus::Drift {
+                args.push("--allow-downgrade");
+            }
+        }
+        let output = dotnet_output(ctx.runner(), config, &args)?;
+        if ctx.is_human() {
+            print!("{}", output);
+        }
+    }
+    if pending.iter().any(|d| d.scope == ToolScope::Local) {
+        let output = dotnet_output(ctx.runner(), config, &["tool", "restore"])?;
+        if ctx.is_human() {
+            print!("{}", output);
+        }
+    }
+    Ok(())
+}
+
+/// Una riga del confronto: `=` allineato, `~` versione diversa, `+` da installare.
+fn print_drift(drift: &ToolDrift) {
+    let scope = match drift.scope {
+        ToolScope::Global => "global",
+        ToolScope::Local => "local",
+    };
+    let wanted = drift.wanted.as_deref().unwrap_or("any version");
+    match (drift.status, &drift.installed) {
+        (ToolStatus::Current, installed) => {
+            println!("= {:<24} {} ({})", drift.id, installed.as_deref().unwrap_or(wanted), scope)
+        }
+        (ToolStatus::Drift, Some(installed)) => println!("~ {:<24} {} -> {} ({})", drift.id, installed, wanted, scope),
+        _ => println!("+ {:<24} {} ({}, missing)", drift.id, wanted, scope),
+    }
+}
@@ -0,0 +1,213 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use dver_core::adopt;
+use dver_core::disk::dir_size;
+use dver_core::inventory::{installed_versions_newest_first, list_installed_sdks};
+use dver_core::macos;
+use dver_core::manifest;
+use dver_core::outcome::{ItemOutcome, ItemStatus};
+use dver_core::platform::{self, Arch};
+use dver_core::provenance;
+use dver_core::registry::{self, msi_owner};
+use dver_core::removal::remove_tree;
+use dver_core::resolve::not_found_message;
+use dver_core::sandbox::{check_removal, Removal};
+use dver_core::version::{SdkVersion, VersionSpec};
+use dver_core::visualstudio;
+use dver_core::workload;
+use dver_core::{DverError, ResultExt};
+
+use super::{clean, Context};
+
+/// Le versioni installate che appartengono a Visual Studio, che `--prune` lascia stare.
+pub fn visual_studio_versions(ctx: &Context) -> Vec<String> {
+    let vs = visualstudio::bundled_sdks(ctx.runner(), platform::current());
+    if vs.is_empty() {
+        return Vec::new();
+    }
+    let msi = registry::installed_versions(ctx.runner(), platform::current());
+    list_installed_sdks(ctx.runner(), &ctx.config)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|sdk| {
+            let root = sdk.path.parent().and_then(Path::parent).unwrap_or(Path::new(""));
+            visualstudio::is_bundled(&vs, &msi, root, &sdk.version)
+        })
+        .map(|sdk| sdk.version)
+        .collect()
+}
+
+pub fn run(ctx: &Context, version: Option<String>, all: bool, force: bool, wait: Duration) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let version = if version.is_none() && !all && ctx.prompter.interactive {
+        match ctx.prompter.pick_version("Select the SDK to uninstall", &installed_versions_newest_first(ctx.runner(), config)?)? {
+            Some(v) => Some(v),
+            None => return Ok(()),
+        }
+    } else {
+        version
+    };
+    let sdks: Vec<(String, PathBuf)> = list_installed_sdks(ctx.runner(), config)?
+        .into_iter()
+        .map(|sdk| (sdk.version, sdk.path))
+        .collect();
+    let installed: Vec<String> = sdks.iter().map(|(v, _)| v.clone()).collect();
+    let targets: Vec<(String, PathBuf)> = if all {
+        sdks
+    } else if let Some(v) = &version {
+        // `8` e `8.0` selezionano tutti gli SDK della major o del canale, `8.0.4xx` una feature band
+        let spec: VersionSpec = v.parse()?;
+        sdks.into_iter()
+            .filter(|(ver, _)| ver.parse::<SdkVersion>().is_ok_and(|ver| spec.matches(&ver)))
+            .collect()
+    } else {
+        return Err(DverError::Usage("Provide a version or --all to uninstall.".to_string()));
+    };
+
+    if targets.is_empty() {
+        return Err(match &version {
+            Some(v) if v.parse::<VersionSpec>().is_ok_and(|s| s.is_exact()) => {
+                DverError::NotFound(not_found_message("Installed SDK", v, &installed))
+            }
+            _ => DverError::NotFound("No matching SDKs found.".to_string()),
+        });
+    }
+
+    // Prima di chiedere conferma si scartano i percorsi che non si possono toccare
+    let roots = config.managed_roots();
+    let msi = registry::installed_versions(ctx.runner(), platform::current());
+    let vs = visualstudio::bundled_sdks(ctx.runner(), platform::current());
+    // `--all` e gli intervalli saltano gli SDK di Visual Studio; solo una versione esatta li chiede
+    let exact = !all && version.as_deref().is_some_and(|v| v.parse::<VersionSpec>().is_ok_and(|s| s.is_exact()));
+    let pkg = if macos::is_macos() { macos::pkg_sdks(ctx.runner()) } else { Vec::new() };
+    let mut first_failure: Option<DverError> = None;
+    let mut removals: Vec<(String, Removal)> = Vec::new();
+    for (ver, path) in targets {
+        if path.symlink_metadata().is_err() {
+            ctx.note(format!("Directory for {} not found", ver));
+            ctx.outcome(ItemOutcome::new(&ver, ItemStatus::Skipped).path(&path));
+            continue;
+        }
+        let root = path.parent().and_then(Path::parent).unwrap_or(Path::new(""));
+        if visualstudio::is_bundled(&vs, &msi, root, &ver) {
+            if !exact {
+                ctx.note(format!("Skipping {}: it belongs to Visual Studio", ver));
+                ctx.outcome(ItemOutcome::new(&ver, ItemStatus::Skipped).path(&path));
+                continue;
+            }
+            let e = DverError::Permission(format!(
+                "Refusing to remove SDK {}: Visual Studio installed it and builds with it\nhint: update or remove it with the Visual Studio Installer (Modify > Individual components)",
+                ver));
+            refuse(ctx, &ver, &path, e, &mut first_failure);
+            continue;
+        }
+        // Cancellare i file di un pacchetto MSI lo lascerebbe registrato ma rotto
+        if let Some(owner) = path.parent().and_then(Path::parent).and_then(|root| msi_owner(&msi, root, &ver)) {
+            let e = DverError::Permission(format!(
+                "Refusing to remove SDK {}: it belongs to an MSI package ({})\nhint: remove it from Settings > Apps or with `winget uninstall`, so that Windows forgets it too",
+                ver, owner.arch));
+            refuse(ctx, &ver, &path, e, &mut first_failure);
+            continue;
+        }
+        if pkg.iter().any(|sdk| sdk.version == ver && path.parent().and_then(Path::parent) == Some(sdk.root(Arch::native()).as_path())) {
+            let e = DverError::Permission(format!(
+                "Refusing to remove SDK {}: it was installed by the .NET pkg installer\nhint: remove it with Microsoft's dotnet-core-uninstall tool, so that pkgutil forgets it too",
+                ver));
+            refuse(ctx, &ver, &path, e, &mut first_failure);
+            continue;
+        }
+        let checked = check_removal(platform::current(), &path, &roots).and_then(|removal| match removal {
+            Removal::System(path) if !force => Err(DverError::Permission(format!(
+                "Refusing to remove SDK {}: {} is in a system directory\nhint: pass --force and type the version to confirm",
+                ver, path.display()))),
+            removal => Ok(removal),
+        });
+        match checked {
+            Ok(removal) => removals.push((ver, removal)),
+            Err(e) => refuse(ctx, &ver, &path, e, &mut first_failure),
+        }
+    }
+    if removals.is_empty() {
+        return match first_failure {
+            Some(e) => Err(e),
+            None => Ok(()),
+        };
+    }
+
+    let names: Vec<&str> = removals.iter().map(|(v, _)| v.as_str()).collect();
+    let question = format!("Remove {} SDK(s): {}?", names.len(), names.join(", "));
+    // Rimuovere tutto richiede una conferma esplicita anche negli script
+    if !ctx.prompter.confirm(&question, !all)? {
+        if all && !ctx.prompter.interactive {
+            return Err(DverError::Usage("Refusing to remove all SDKs without confirmation; pass --yes".to_string()));
+        }
+        ctx.note("Aborted.");
+        return Ok(());
+    }
+
+    for (ver, removal) in removals {
+        let path = removal.path();
+        if let Removal::System(_) = removal {
+            let message = format!("{} is in a system directory.", path.display());
+            if !ctx.prompter.confirm_typed(&message, &ver)? {
+                let e = DverError::Usage(format!(
+                    "Not removing SDK {}: removing from a system directory needs a typed confirmation in a terminal", ver));
+                refuse(ctx, &ver, path, e, &mut first_failure);
+                continue;
+            }
+        }
+        let size = dir_size(path).ok();
+        let started = Instant::now();
+        match remove_sdk(ctx, &ver, path, wait) {
+            Ok(_) => {
+                ctx.note(format!("Removed {}", ver));
+                ctx.outcome(ItemOutcome::new(&ver, ItemStatus::Removed).path(path).size(size).duration(started.elapsed()));
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                if matches!(e.code(), "permission") && !e.to_string().contains("\nhint: ") {
+                    eprintln!("hint: re-run with write access to {}", path.display());
+                }
+                ctx.outcome(ItemOutcome::new(&ver, ItemStatus::Failed).path(path).duration(started.elapsed()).error(&e));
+                first_failure.get_or_insert(e);
+            }
+        }
+    }
+    clean::tidy_template_caches(ctx, None);
+    match first_failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Un SDK che non si rimuove: l'errore si mostra, finisce nell'esito e, se è il primo,
+/// diventa quello del comando.
+fn refuse(ctx: &Context, version: &str, path: &Path, e: DverError, first_failure: &mut Option<DverError>) {
+    eprintln!("{}", e);
+    ctx.outcome(ItemOutcome::new(version, ItemStatus::Failed).path(path).error(&e));
+    first_failure.get_or_insert(e);
+}
+
+/// Rimuove la cartella dell'SDK `version` e i record che lo riguardano; con file
+/// bloccati riprova per al massimo `wait`. Usato anche da `maintain`.
+pub fn remove_sdk(ctx: &Context, version: &str, path: &Path, wait: Duration) -> Result<(), DverError> {
+    let config = &ctx.config;
+    remove_tree(ctx.runner(), platform::current(), path, wait)
+        .with_context(|| format!("Failed to remove SDK {} at {}", version, path.display()))?;
+    ctx.touch(path.to_path_buf());
+    // I record di un SDK che non c'è più non servono; un errore qui non è grave
+    if let Some(root) = path.parent().and_then(Path::parent) {
+        for forgotten in [
+            manifest::forget(config, root, version),
+            provenance::forget(config, root, version),
+            adopt::forget(config, root, version),
+            workload::forget(config, root, version),
+        ] {
+            if let Err(e) = forgotten {
+                eprintln!("Warning: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! `dver upgrade`: installa la patch più recente di ogni feature band installata. Con
+//! `--interactive` gli aggiornamenti si scelgono da un elenco, con data di rilascio,
+//! CVE corrette e dimensione, invece di installarli tutti.
+
+use dver_core::audit::channels_of;
+use dver_core::disk::format_size;
+use dver_core::inventory::list_installed_sdks;
+use dver_core::outcome::{ItemOutcome, ItemStatus};
+use dver_core::platform;
+use dver_core::updates::{pending_upgrades, PendingUpgrade};
+use dver_core::DverError;
+
+use super::install::install_many;
+use super::Context;
+
+pub async fn run(ctx: &Context, interactive: bool) -> Result<(), DverError> {
+    if interactive && !ctx.prompter.interactive {
+        return Err(DverError::Usage(
+            "--interactive needs a terminal\nhint: run `dver upgrade` without it to install every pending upgrade".to_string(),
+        ));
+    }
+    let installed: Vec<String> = list_installed_sdks(ctx.runner(), &ctx.config)?
+        .into_iter()
+        .map(|sdk| sdk.version)
+        .filter(|version| ctx.config.follows_sdk(version))
+        .collect();
+    let wanted = channels_of(installed.iter().map(String::as_str));
+    let channels = if wanted.is_empty() { Vec::new() } else { ctx.channel_releases(&wanted).await? };
+    let pending = pending_upgrades(&channels, &installed, &platform::rid());
+    if pending.is_empty() {
+        ctx.note("Every installed feature band is up to date.");
+        return Ok(());
+    }
+
+    let selected: Vec<&PendingUpgrade> = if interactive {
+        let items: Vec<String> = pending.iter().map(describe).collect();
+        let checked = vec![true; pending.len()];
+        match ctx.prompter.pick_many("Select the upgrades to install (space toggles, enter confirms)", &items, &checked)? {
+            Some(chosen) => chosen.into_iter().map(|i| &pending[i]).collect(),
+            None => {
+                ctx.note("Aborted.");
+                return Ok(());
+            }
+        }
+    } else {
+        pending.iter().collect()
+    };
+    for upgrade in &pending {
+        if selected.contains(&upgrade) {
+            ctx.note(format!("Upgrading {}", describe(upgrade)));
+        } else {
+            ctx.note(format!("Skipping {}", describe(upgrade)));
+            ctx.outcome(ItemOutcome::new(&upgrade.to, ItemStatus::Skipped));
+        }
+    }
+    if selected.is_empty() {
+        return Ok(());
+    }
+    install_many(ctx, selected.iter().map(|upgrade| upgrade.to.clone()).collect(), None).await
+}
+
+/// Una riga per aggiornamento, es. `8.0.4xx: 8.0.403 -> 8.0.404, released 2025-02-11,
+/// security: CVE-2025-21172, 214.6 MB`.
+fn describe(upgrade: &PendingUpgrade) -> String {
+    let mut line = format!("{}: {} -> {}", upgrade.band, upgrade.from, upgrade.to);
+    if let Some(date) = upgrade.release_date {
+        line.push_str(&format!(", released {}", date));
+    }
+    if upgrade.is_security() {
+        line.push_str(&format!(", security: {}", upgrade.cves.join(", ")));
+    }
+    if let Some(size) = upgrade.download_bytes {
+        line.push_str(&format!(", {}", format_size(size)));
+    }
+    line
+}
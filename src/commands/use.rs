@@ -0,0 +1,58 @@
+use std::fs;
+
+use dver_core::audit::check_sdk_in_use;
+use dver_core::install::InstallRequest;
+use dver_core::inventory::installed_versions_newest_first;
+use dver_core::project::{write_global_json_version, write_props_property, Project};
+use dver_core::resolve::{newest_matching, not_found_message};
+use dver_core::version::VersionSpec;
+use dver_core::DverError;
+
+use super::install::install_and_report;
+use super::Context;
+
+pub async fn run(ctx: &Context, version: Option<String>) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let version = match version {
+        Some(v) => v,
+        None if ctx.prompter.interactive => {
+            match ctx.prompter.pick_version("Select the SDK to use", &installed_versions_newest_first(ctx.runner(), config)?)? {
+                Some(v) => v,
+                None => return Ok(()),
+            }
+        }
+        None => return Err(DverError::Usage("Provide a version to use.".to_string())),
+    };
+    let mut version = version;
+    if let Ok(installed) = installed_versions_newest_first(ctx.runner(), config) {
+        // Una versione parziale (`8`, `8.0`, `8.0.4xx`) diventa l'SDK installato più recente che la soddisfa
+        let resolved = version.parse::<VersionSpec>().ok()
+            .filter(|spec| !spec.is_exact())
+            .and_then(|spec| newest_matching(&spec, &installed));
+        if let Some(resolved) = resolved {
+            println!("Resolved {} to installed SDK {}", version, resolved);
+            version = resolved;
+        } else if !installed.contains(&version) {
+            eprintln!("Warning: {}", not_found_message("Installed SDK", &version, &installed));
+            if ctx.prompter.confirm(&format!("Install SDK {} now?", version), false)? {
+                install_and_report(ctx, &InstallRequest { version: Some(version.clone()), ..Default::default() }).await?;
+            }
+        }
+    }
+    check_sdk_in_use(config, &version)?;
+    let file_path = std::env::current_dir()?.join("global.json");
+    if file_path.exists() {
+        let backup = file_path.with_extension("json.bak");
+        let _ = fs::copy(&file_path, &backup);
+    }
+    write_global_json_version(&file_path, &version)?;
+    ctx.touch(file_path.clone());
+    println!("SDK version set to {} in {:?}", version, file_path);
+    // Con `[props]` in `.dver.toml` anche la proprietà MSBuild segue global.json
+    if let Some(props) = Project::find(&std::env::current_dir()?)?.and_then(|project| project.config.props) {
+        write_props_property(&props.file, &props.property, &version)?;
+        ctx.touch(props.file.clone());
+        println!("SDK version set to {} in <{}> of {:?}", version, props.property, props.file);
+    }
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use dver_core::install::InstallRequest;
+use dver_core::inventory::list_installed_sdks;
+use dver_core::manifest::{self, VerifyReport};
+use dver_core::platform;
+use dver_core::resolve::not_found_message;
+use dver_core::removal::remove_tree;
+use dver_core::sandbox::{check_removal, Removal};
+use dver_core::{DverError, ResultExt};
+
+use super::install::install_and_report;
+use super::Context;
+
+#[derive(Debug, Serialize)]
+struct Verification<'a> {
+    version: &'a str,
+    path: &'a Path,
+    intact: bool,
+    #[serde(flatten)]
+    report: &'a VerifyReport,
+}
+
+#[derive(Debug, Serialize)]
+struct VerificationRow<'a> {
+    version: &'a str,
+    file: &'a str,
+    problem: &'a str,
+}
+
+fn rows<'a>(version: &'a str, report: &'a VerifyReport) -> Vec<VerificationRow<'a>> {
+    let problems = [("missing", &report.missing), ("modified", &report.modified), ("added", &report.added)];
+    problems
+        .into_iter()
+        .flat_map(|(problem, files)| files.iter().map(move |file| VerificationRow { version, file, problem }))
+        .collect()
+}
+
+fn print_files(label: &str, files: &[String]) {
+    if files.is_empty() {
+        return;
+    }
+    println!("{} ({}):", label, files.len());
+    for file in files {
+        println!("  {}", file);
+    }
+}
+
+/// Controlla i file dell'SDK `version` contro il manifest salvato all'installazione
+/// e, se qualcosa manca o è cambiato, propone di reinstallarlo.
+pub async fn run(ctx: &Context, version: &str, repair: bool) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let sdks = list_installed_sdks(ctx.runner(), config)?;
+    let Some(sdk) = sdks.iter().find(|sdk| sdk.version == version) else {
+        let installed: Vec<String> = sdks.iter().map(|sdk| sdk.version.clone()).collect();
+        return Err(DverError::NotFound(not_found_message("Installed SDK", version, &installed)));
+    };
+    // `<root>/sdk/<versione>`
+    let root: PathBuf = sdk.path.parent().and_then(Path::parent).map(Path::to_path_buf).unwrap_or_default();
+
+    let Some(recorded) = manifest::load(config, &root, version)? else {
+        if repair {
+            return reinstall(ctx, version, &root).await;
+        }
+        return Err(DverError::NotFound(format!(
+            "No install manifest for SDK {} in {}: it was not installed by this version of dver\n\
+             hint: run `dver verify {} --repair` to reinstall it and record one",
+            version, root.display(), version)));
+    };
+    let report = manifest::verify(&recorded)?;
+
+    if ctx.is_human() {
+        if report.is_intact() {
+            println!("SDK {}: all {} files match the install manifest", version, recorded.files.len());
+        } else {
+            println!("SDK {}: {} missing and {} modified files in {}",
+                version, report.missing.len(), report.modified.len(), sdk.path.display());
+        }
+        print_files("Missing", &report.missing);
+        print_files("Modified", &report.modified);
+        print_files("Not in the manifest", &report.added);
+    } else {
+        let result = Verification { version, path: &sdk.path, intact: report.is_intact(), report: &report };
+        ctx.print_structured(&result, &rows(version, &report))?;
+    }
+
+    if report.is_intact() {
+        return Ok(());
+    }
+    if repair || ctx.prompter.confirm(&format!("Reinstall SDK {} now?", version), false)? {
+        return reinstall(ctx, version, &root).await;
+    }
+    Err(DverError::HashMismatch(format!(
+        "SDK {} does not match its install manifest\nhint: run `dver verify {} --repair` to reinstall it", version, version)))
+}
+
+/// Rimuove l'SDK (con gli stessi controlli di `uninstall`) e lo installa di nuovo nella stessa radice.
+async fn reinstall(ctx: &Context, version: &str, root: &Path) -> Result<(), DverError> {
+    let path = manifest::sdk_dir(root, version);
+    match check_removal(platform::current(), &path, &ctx.config.managed_roots())? {
+        Removal::Allowed(path) => {
+            remove_tree(ctx.runner(), platform::current(), &path, Duration::ZERO)
+                .with_context(|| format!("Failed to remove SDK {} at {}", version, path.display()))?
+        }
+        Removal::System(path) => {
+            return Err(DverError::Permission(format!(
+                "Refusing to repair SDK {}: {} is in a system directory\nhint: reinstall it with the system package manager",
+                version, path.display())))
+        }
+    }
+    println!("Reinstalling SDK {}...", version);
+    let request = InstallRequest { version: Some(version.to_string()), install_dir: Some(root.to_path_buf()), ..Default::default() };
+    install_and_report(ctx, &request).await?;
+    println!("SDK {} repaired.", version);
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! `dver vscode`: punta l'estensione C# e il terminale integrato di VS Code alla
+//! radice che dver usa per la cartella, così editor e riga di comando usano lo stesso SDK.
+
+use std::fs;
+use std::io::ErrorKind;
+
+use dver_core::environment::{merge_vscode_settings, DotnetEnvironment};
+use dver_core::inventory::active_dotnet_root;
+use dver_core::{DverError, ResultExt};
+
+use super::env::project_config;
+use super::Context;
+
+pub fn run(ctx: &Context, print: bool) -> Result<(), DverError> {
+    let config = project_config(ctx)?;
+    let root = active_dotnet_root(&config).ok_or_else(|| DverError::NotFound(
+        "No .NET root found for this workspace\nhint: install an SDK with `dver install --from-project`".to_string()))?;
+    let settings = DotnetEnvironment::from_config(&config).vscode_settings(&root);
+    if print {
+        println!("{}", serde_json::to_string_pretty(&settings)?);
+        return Ok(());
+    }
+
+    let dir = std::env::current_dir()?.join(".vscode");
+    let path = dir.join("settings.json");
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let merged = merge_vscode_settings(existing.as_deref(), settings).map_err(|e| DverError::Parse(format!(
+        "{}: {}\nhint: run `dver vscode --print` and copy the keys by hand", path.display(), e)))?;
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&path, merged).with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Updated {} to use the .NET root {}", path.display(), root.display());
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! `dver workload`: i comandi `dotnet workload` lanciati su un SDK preciso, quello
+//! della cartella o quello scelto con `--sdk`, con i workload installati registrati
+//! per SDK. `install` li ripristina quando arriva un SDK di una feature band nuova.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use dver_core::inventory::{current_sdk, dotnet_output_in, list_installed_sdks, parse_workload_list};
+use dver_core::workload::{self, replay_source, sdk_pin_dir};
+use dver_core::{DverError, ResultExt};
+
+use super::Context;
+use crate::WorkloadAction;
+
+/// Un workload dell'SDK, con l'indicazione se l'ha installato dver.
+#[derive(Debug, Serialize)]
+struct WorkloadRow {
+    id: String,
+    manifest_version: Option<String>,
+    source: Option<String>,
+    tracked: bool,
+}
+
+pub fn run(ctx: &Context, sdk: Option<&str>, action: &WorkloadAction) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let (version, root) = target_sdk(ctx, sdk)?;
+    let dir = sdk_pin_dir(config, &version)?;
+    match action {
+        WorkloadAction::List => {
+            let stdout = dotnet_output_in(ctx.runner(), config, &dir, &["workload", "list"])
+                .context("Failed to list installed workloads")?;
+            let tracked = workload::tracked(config, &root, &version)?;
+            let rows: Vec<WorkloadRow> = parse_workload_list(&stdout)
+                .into_iter()
+                .map(|w| WorkloadRow {
+                    tracked: tracked.contains(&w.id),
+                    id: w.id,
+                    manifest_version: w.manifest_version,
+                    source: w.source,
+                })
+                .collect();
+            if !ctx.is_human() {
+                return ctx.print_structured(&rows, &rows);
+            }
+            if rows.is_empty() {
+                println!("No workloads installed on SDK {}.", version);
+            }
+            for row in &rows {
+                let manifest = row.manifest_version.as_deref().unwrap_or("");
+                println!("{:<24} {:<24}{}", row.id, manifest, if row.tracked { " (dver)" } else { "" });
+            }
+        }
+        WorkloadAction::Install { ids } => {
+            println!("Installing workloads {} on SDK {}...", ids.join(", "), version);
+            workload_command(ctx, &dir, "install", ids)?;
+            workload::add(config, &root, &version, ids)?;
+        }
+        WorkloadAction::Uninstall { ids } => {
+            println!("Uninstalling workloads {} from SDK {}...", ids.join(", "), version);
+            workload_command(ctx, &dir, "uninstall", ids)?;
+            workload::remove(config, &root, &version, ids)?;
+        }
+        WorkloadAction::Update => {
+            println!("Updating the workloads of SDK {}...", version);
+            workload_command(ctx, &dir, "update", &[])?;
+        }
+    }
+    Ok(())
+}
+
+/// L'SDK richiesto, o quello in uso nella cartella, con la radice che lo contiene.
+fn target_sdk(ctx: &Context, sdk: Option<&str>) -> Result<(String, PathBuf), DverError> {
+    let version = match sdk {
+        Some(version) => version.to_string(),
+        None => current_sdk(ctx.runner(), &ctx.config, &std::env::current_dir()?)?,
+    };
+    list_installed_sdks(ctx.runner(), &ctx.config)?
+        .into_iter()
+        .find(|s| s.version == version)
+        .and_then(|s| s.path.parent().and_then(Path::parent).map(Path::to_path_buf))
+        .map(|root| (version.clone(), root))
+        .ok_or_else(|| DverError::NotFound(format!(
+            "SDK {} is not installed\nhint: run `dver install --version {}`", version, version)))
+}
+
+/// Esegue `dotnet workload <verb> <ids>` nella cartella `dir` e ne mostra l'output.
+fn workload_command(ctx: &Context, dir: &Path, verb: &str, ids: &[String]) -> Result<(), DverError> {
+    let args: Vec<&str> = ["workload", verb].into_iter().chain(ids.iter().map(String::as_str)).collect();
+    let output = dotnet_output_in(ctx.runner(), &ctx.config, dir, &args)?;
+    if ctx.is_human() {
+        print!("{}", output);
+    } else {
+        eprint!("{}", output);
+    }
+    Ok(())
+}
+
+/// Dopo un'installazione, reinstalla sugli SDK nuovi (quelli non in `before`) i
+/// workload registrati per l'SDK precedente della stessa linea. Un errore qui lascia
+/// l'SDK installato e diventa un avviso.
+pub fn replay(ctx: &Context, before: &[String]) {
+    let config = &ctx.config;
+    let (Ok(records), Ok(installed)) = (workload::load_all(config), list_installed_sdks(ctx.runner(), config)) else {
+        return;
+    };
+    for sdk in installed.iter().filter(|s| !before.contains(&s.version)) {
+        let Some(root) = sdk.path.parent().and_then(Path::parent) else { continue };
+        let Some(source) = replay_source(&records, root, &sdk.version) else { continue };
+        ctx.note(format!("Restoring workloads {} from SDK {} on SDK {}...", source.workloads.join(", "), source.sdk, sdk.version));
+        let restored = sdk_pin_dir(config, &sdk.version)
+            .and_then(|dir| workload_command(ctx, &dir, "install", &source.workloads))
+            .and_then(|_| workload::add(config, root, &sdk.version, &source.workloads));
+        if let Err(e) = restored {
+            eprintln!("Warning: {}", e);
+            eprintln!("hint: run dver workload --sdk {} install {}", sdk.version, source.workloads.join(" "));
+        }
+    }
+}
+
+/// Installa sull'SDK `sdk` i workload `ids` che non ha ancora, come `dotnet workload
+/// restore` ma senza dipendere dall'SDK scelto nella cartella; usato da `install
+/// --from-project` e `--for-project`.
+pub fn restore(ctx: &Context, sdk: &str, ids: &[String]) -> Result<(), DverError> {
+    let config = &ctx.config;
+    let (version, root) = target_sdk(ctx, Some(sdk))?;
+    let dir = sdk_pin_dir(config, &version)?;
+    let listed = dotnet_output_in(ctx.runner(), config, &dir, &["workload", "list"])
+        .context("Failed to list installed workloads")?;
+    let present: Vec<String> = parse_workload_list(&listed).into_iter().map(|w| w.id).collect();
+    let missing: Vec<String> = ids.iter().filter(|id| !present.contains(id)).cloned().collect();
+    if missing.is_empty() {
+        ctx.note(format!("Workloads {} are installed on SDK {}.", ids.join(", "), version));
+        return Ok(());
+    }
+    ctx.note(format!("Restoring workloads {} on SDK {}...", missing.join(", "), version));
+    workload_command(ctx, &dir, "install", &missing)?;
+    workload::add(config, &root, &version, &missing)
+}
@@ -1,531 +1,782 @@
-use clap::{Parser, Subcommand};
-use std::process::Command;
+use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use commands::{block_on, Context};
+use dver_core::environment::ShellKind;
+use dver_core::journal::Operation;
+use dver_core::metadata::RemoteSort;
+use dver_core::platform::Arch;
+use dver_core::process::SystemRunner;
+use dver_core::runtimeconfig::RollForward;
+use dver_core::sbom::SbomFormat;
+use dver_core::updates::update_notice;
+use dver_core::ci::CiProvider;
+use dver_core::config::{is_valid_channel, Quality};
+use dver_core::{Config, DverError, OutputFormat};
+use prompt::Prompter;
 use serde_json::json;
-use std::fs::{self, File, remove_file, remove_dir_all};
-use std::path::{Path, PathBuf};
-use reqwest::header;
-use serde::Deserialize;
-use serde::de::Deserializer;
-//use std::error::Error;
-//use std::fmt;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime};
+
+mod commands;
+mod prompt;
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = "Manage .NET SDK versions: install and uninstall SDKs, list what is installed \
+                  or published by Microsoft, and pin a project to a specific SDK through global.json.",
+    after_help = "Run `dver doctor` after installing to check that your PATH is set up correctly."
+)]
 struct Cli {
+    /// Answer yes to every confirmation prompt
+    #[arg(short = 'y', long, global = true)]
+    yes: bool,
+
+    /// Never prompt; use each prompt's default answer and skip interactive pickers
+    #[arg(long, global = true)]
+    non_interactive: bool,
+
+    /// Fail immediately on any attempted network access (no cache fallback)
+    #[arg(long, global = true)]
+    no_network: bool,
+
+    /// Use only cached release metadata; never download it
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Print the raw dotnet output to stderr when it cannot be parsed, for bug reports
+    #[arg(long, global = true)]
+    debug_parse: bool,
+
+    /// PEM file with extra trusted root certificates (e.g. a corporate TLS proxy)
+    #[arg(long, global = true)]
+    ca_bundle: Option<PathBuf>,
+
+    /// Named root from the config `[roots]` table to install into, list or uninstall from
+    #[arg(long, global = true)]
+    root: Option<String>,
+
+    /// Architecture of the SDKs to install, list or run; a non-native one lives in <root>/<arch>
+    #[arg(long, global = true, value_enum)]
+    arch: Option<Arch>,
+
+    /// Output format for commands that print listings
+    #[arg(long, global = true, value_enum)]
+    output: Option<OutputFormat>,
+
+    /// Shorthand for `--output json`
+    #[arg(long, global = true, conflicts_with = "output")]
+    json: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Cli {
+    fn output_format(&self) -> Option<OutputFormat> {
+        if self.json {
+            Some(OutputFormat::Json)
+        } else {
+            self.output
+        }
+    }
+
+    /// Applica i flag della riga di comando sopra la configurazione letta da file.
+    fn apply_overrides(&self, mut config: Config) -> Result<Config, DverError> {
+        if let Some(name) = &self.root {
+            if !config.roots.contains_key(name) {
+                let names: Vec<&str> = config.roots.keys().map(|k| k.as_str()).collect();
+                return Err(DverError::Usage(format!(
+                    "Unknown root {:?}; roots defined in the config: {}",
+                    name,
+                    if names.is_empty() { "none".to_string() } else { names.join(", ") },
+                )));
+            }
+            config.install_root = Some(PathBuf::from(name));
+        }
+        if let Some(arch) = self.arch {
+            config.arch = Some(arch);
+        }
+        if self.no_network {
+            config.no_network = true;
+        }
+        if self.offline {
+            config.offline = true;
+        }
+        if self.debug_parse {
+            config.debug_parse = true;
+        }
+        if let Some(bundle) = &self.ca_bundle {
+            config.ca_bundle = Some(bundle.clone());
+        }
+        if let Some(format) = self.output_format() {
+            config.output = Some(format);
+        }
+        if let Commands::Install { channel, quality, .. } = &self.command {
+            if let Some(channel) = channel.as_deref().filter(|c| !is_valid_channel(c)) {
+                return Err(DverError::Usage(format!("Invalid channel {:?}: expected LTS, STS, A.B or A.B.Cxx", channel)));
+            }
+            config.default_channel = channel.clone().or(config.default_channel);
+            config.quality = quality.or(config.quality);
+        }
+        Ok(config)
+    }
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Get current dotnet version
     Current,
+    /// One-screen summary: install root, active SDK and pin, installed totals, known updates, shim and shell hook
+    Status,
+    /// Print the SDK in use for shell prompts (starship, oh-my-posh); see --json and --env
+    Prompt {
+        /// Print DVER_PROMPT_* variables instead of the version
+        #[arg(long)]
+        env: bool,
+    },
     /// List installed SDK versions
-    List,
+    #[command(visible_alias = "ls")]
+    List {
+        /// Show where, when and by whom each SDK was installed
+        #[arg(long, conflicts_with = "size")]
+        provenance: bool,
+        /// Show the disk space used by each SDK
+        #[arg(long)]
+        size: bool,
+    },
     /// Set SDK version via global.json
-    Use { version: String },
+    Use {
+        /// SDK version to pin (exact, or 8, 8.0, 8.0.4xx for the newest installed match); opens a picker when omitted
+        version: Option<String>,
+    },
+    /// Pin an SDK for a new repository: writes global.json and .dver.toml
+    Init {
+        /// Channel to pin (8.0, 8.0.4xx, lts or an exact SDK version); opens a picker when omitted
+        #[arg(long)]
+        channel: Option<String>,
+        /// rollForward policy written to global.json
+        #[arg(long, default_value = "latestFeature", value_parser = clap::builder::PossibleValuesParser::new(commands::init::ROLL_FORWARD))]
+        roll_forward: String,
+        /// Also create .config/dotnet-tools.json for local .NET tools
+        #[arg(long)]
+        tools: bool,
+        /// Overwrite an existing global.json and .dver.toml (keeping a .bak copy)
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check that this machine satisfies the repository's pins: SDK, workloads and local tools
+    Pin {
+        /// Exit with 4 if any pin is not satisfied, e.g. as the first step of a CI pipeline
+        #[arg(long)]
+        check: bool,
+    },
     /// Check if dotnet is installed and install if not
+    #[command(visible_alias = "i")]
     Install {
         /// Install LTS version
         #[arg(long)]
         lts: bool,
-        /// Specific version to install
-        #[arg(long)]
-        version: Option<String>,
+        /// Version to install (exact, or 8, 8.0, 8.0.4xx for the newest published match); opens a picker when omitted.
+        /// Pass several to install them in parallel
+        #[arg(long, num_args = 1..)]
+        version: Vec<String>,
         /// The path to install the SDK to
         #[arg(long)]
-        install_path: Option<String>,
+        install_path: Option<PathBuf>,
+        /// Install the SDK pinned by .dver.toml or global.json
+        #[arg(long, conflicts_with_all = ["lts", "version"])]
+        from_project: bool,
+        /// Install the newest SDK of the channel the projects' TargetFramework(s) need, unless one already builds them
+        #[arg(long, conflicts_with_all = ["lts", "version", "from_project"])]
+        for_project: bool,
+        /// Install the newest SDK of this channel (e.g. 10.0 or 10.0.1xx) instead of default_channel
+        #[arg(long, conflicts_with_all = ["lts", "version", "from_project", "for_project"])]
+        channel: Option<String>,
+        /// Build quality of the channel instead of the quality setting; daily builds are unsupported
+        #[arg(long, value_enum, conflicts_with_all = ["lts", "version", "from_project", "for_project"])]
+        quality: Option<Quality>,
     },
     /// Uninstall SDK versions
+    #[command(visible_alias = "rm")]
     Uninstall {
-        /// Version to uninstall (full or major)
+        /// Version to uninstall (exact, or 8, 8.0, 8.0.4xx for every match); opens a picker when omitted
         version: Option<String>,
         /// Remove all SDKs managed by this tool
         #[arg(long)]
         all: bool,
+        /// Allow removing SDKs under system directories (asks to type the version)
+        #[arg(long)]
+        force: bool,
+        /// Keep retrying for up to this many seconds while running dotnet or MSBuild processes hold SDK files
+        #[arg(long, value_name = "SECONDS", default_value_t = 0)]
+        wait: u64,
+    },
+    /// Register SDKs installed without dver, optionally moving them under dver's management
+    Adopt {
+        /// Roots to scan instead of the well-known locations (DOTNET_ROOT, PATH, ~/.dotnet, system roots)
+        paths: Vec<PathBuf>,
+        /// Add each root to the config `[roots]` so that `--root <name> uninstall` can remove its SDKs
+        #[arg(long, conflicts_with = "relocate")]
+        manage: bool,
+        /// Move the SDKs into install_root, leaving a link at the old location (asks first; pass --yes in scripts)
+        #[arg(long)]
+        relocate: bool,
+        /// With --relocate, also move roots in system directories (asks to type `relocate`)
+        #[arg(long, requires = "relocate")]
+        force: bool,
+        /// Disable the dotnet snap and the apt /usr/bin/dotnet so that only dver's SDKs are used
+        #[arg(long, conflicts_with_all = ["paths", "manage", "relocate"])]
+        replace_system: bool,
+    },
+    /// Run `dotnet workload` against a specific SDK and track what it installs
+    Workload {
+        /// SDK to act on (defaults to the SDK in use in the current directory)
+        #[arg(long)]
+        sdk: Option<String>,
+        #[command(subcommand)]
+        action: WorkloadAction,
+    },
+    /// Manage .NET tools declared in the config `[tools]` table and .config/dotnet-tools.json
+    Tool {
+        #[command(subcommand)]
+        action: ToolAction,
+    },
+    /// List shared runtimes and pin or provision the runtimes of published apps
+    Runtime {
+        #[command(subcommand)]
+        action: RuntimeAction,
+    },
+    /// Report and remove cached downloads
+    Clean {
+        #[command(subcommand)]
+        action: CleanAction,
+    },
+    /// Show the journal of install, uninstall and use operations, oldest first
+    History {
+        /// Only show this operation
+        #[arg(long, value_enum)]
+        operation: Option<Operation>,
+        /// Only show failed operations
+        #[arg(long)]
+        failed: bool,
+        /// Only show operations on or after this day (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        since: Option<String>,
+        /// Show at most the last N matching operations
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Set up this machine: config, install root, dotnet shim, shell hook, latest LTS SDK and a doctor run
+    Setup {
+        /// Accept every default without asking
+        #[arg(long)]
+        defaults: bool,
+        /// Skip installing the latest LTS SDK
+        #[arg(long)]
+        no_sdk: bool,
+        /// Shell whose startup file gets the hook; detected from $SHELL when omitted
+        #[arg(long, value_enum)]
+        shell: Option<ShellKind>,
+    },
+    /// Remove everything dver created: install roots, shims, shell hooks, cache, state and config
+    SelfUninstall {
+        /// Keep the install roots and the SDKs in them
+        #[arg(long)]
+        keep_sdks: bool,
+        /// Only list what would be removed
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Check for common issues
-    Doctor,
+    Doctor {
+        /// Also write a support bundle (checks, inventory, scrubbed config and environment, state, recent history) to this zip
+        #[arg(long, value_name = "ZIP", num_args = 0..=1, default_missing_value = "dver-support.zip")]
+        bundle: Option<PathBuf>,
+    },
+    /// Show details and install provenance of an installed SDK
+    Info {
+        /// Exact SDK version
+        version: String,
+    },
+    /// Check an installed SDK's files against the manifest recorded at install time
+    Verify {
+        /// Exact SDK version to check
+        version: String,
+        /// Reinstall the SDK if files are missing or modified, without asking
+        #[arg(long)]
+        repair: bool,
+    },
+    /// Report installed SDKs and runtimes affected by known CVEs; exits with 13 if any are
+    Audit,
+    /// Check pinned and installed SDKs for updates (exit 1: updates, 2: security updates)
+    CheckUpdates {
+        /// Print a Markdown table, e.g. for a pull request body
+        #[arg(long)]
+        markdown: bool,
+    },
+    /// Compare two SDK releases: runtime, bundled frameworks, language versions and the CVEs fixed in between
+    Diff {
+        /// The SDK to compare from, e.g. 8.0.403
+        from: String,
+        /// The SDK to compare to, e.g. 8.0.404
+        to: String,
+        /// Also compare the files of the two installed SDKs
+        #[arg(long)]
+        files: bool,
+    },
+    /// Install the newest patch of every installed feature band
+    Upgrade {
+        /// Choose the upgrades to install from a list with release dates, security fixes and sizes
+        #[arg(short, long)]
+        interactive: bool,
+    },
+    /// Refresh metadata, install missing patches, prune superseded ones and trim the cache; for scheduled jobs on build agents
+    Maintain {
+        /// Show what would change without installing or removing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print a machine snapshot for fleet inventories: Markdown, or data with --output json
+    Report,
+    /// Print a software bill of materials of the installed SDKs, runtimes and workloads
+    Sbom {
+        /// Document format
+        #[arg(long, value_enum, default_value = "cyclonedx")]
+        format: SbomFormat,
+    },
+    /// Print a Dockerfile that builds with the pinned SDK and runs on the matching runtime image
+    Containerize {
+        /// Also write an offline bundle (metadata, SDK archive and dver) to this directory for air-gapped builds
+        #[arg(long)]
+        bundle: Option<PathBuf>,
+    },
+    /// Print a devcontainer.json block that installs the pinned SDK with dver
+    Devcontainer {
+        /// Also write the local feature (devcontainer-feature.json, install.sh and dver) to this directory
+        #[arg(long)]
+        feature: Option<PathBuf>,
+    },
+    /// Print a manifest of the installed SDKs, runtimes, workloads and default channel
+    Export,
+    /// Install what a manifest from `dver export` lists and is missing here
+    Import {
+        /// Manifest written by `dver export`
+        file: PathBuf,
+        /// Also uninstall SDKs that the manifest does not list
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Share the SDK set and settings between machines through the `sync_remote` git repository or https:// folder
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
     /// List all SDK versions available on Microsoft repository
+    #[command(visible_alias = "rl")]
     Remote {
         /// Show only LTS versions
         #[arg(long)]
         lts: bool,
+        /// Show only this channel, e.g. 8.0
+        #[arg(long, value_name = "CHANNEL")]
+        channel: Option<String>,
+        /// Order each channel's releases newest first by version or by release date
+        #[arg(long, value_enum)]
+        sort: Option<RemoteSort>,
+        /// Show only releases published on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        /// Show at most N releases per channel
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+    /// Print shell commands that set up the .NET environment (eval "$(dver env)")
+    Env {
+        /// Shell syntax to emit; detected from $SHELL when omitted
+        #[arg(long, value_enum, conflicts_with = "ci")]
+        shell: Option<ShellKind>,
+        /// Export the environment to later CI steps with the agent's logging commands
+        #[arg(long)]
+        ci: bool,
+        /// Use the install root of .dver.toml and warn if the SDK pinned here is missing
+        #[arg(long, conflicts_with = "ci")]
+        project: bool,
+    },
+    /// Print a direnv .envrc snippet, or with --lib a `use dotnet` function, that activates the pinned SDK
+    Direnv {
+        /// Print the `use_dotnet` function for ~/.config/direnv/lib instead
+        #[arg(long)]
+        lib: bool,
+    },
+    /// Run a command with the .NET environment applied
+    Exec {
+        /// Command and arguments to run
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Read and modify the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Report the MSBuild, NuGet and SDK resolvers of the resolved SDK, nuget.config files and stray MSBuild overrides
+    BuildEnv,
+    /// Point the VS Code C# extension and integrated terminal at the SDK root dver uses here
+    Vscode {
+        /// Print the settings instead of updating .vscode/settings.json
+        #[arg(long)]
+        print: bool,
+    },
+    /// Commands behind an asdf or mise plugin, and a generator for the plugin
+    Asdf {
+        #[command(subcommand)]
+        action: AsdfAction,
+    },
+    /// Generate shell completion scripts
+    Completions {
+        /// Target shell
+        shell: Shell,
+    },
+    /// Generate man pages for dver and each subcommand
+    #[command(hide = true)]
+    Mangen {
+        /// Directory to write the pages to; prints the main page to stdout when omitted
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+    /// Print version candidates for dynamic shell completion
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// Which set of versions to print
+        kind: CompletionKind,
     },
 }
 
-// Structs per releases JSON
-#[derive(Debug, Deserialize)]
-pub struct ReleaseIndex {
-    #[serde(rename = "releases-index")]
-    pub releases_index: Vec<ReleaseChannel>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ReleaseChannel {
-    #[serde(rename = "channel-version")]
-    pub channel_version: Option<String>,
-
-    #[serde(rename = "latest-release")]
-    pub latest_release: Option<String>,
-
-    #[serde(rename = "release-type")]
-    pub release_type: Option<String>, // "lts" o "sts"
-
-    #[serde(rename = "releases.json")]
-    pub releases_json: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ChannelReleases {
-    #[serde(default)]
-    pub releases: Vec<Release>, // sempre un vecchio anche se null nel JSON
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Release {
-    #[serde(default)]
-    pub release_date: Option<String>,
-
-    #[serde(rename = "release-version")]
-    pub version: Option<String>,
-
-    #[serde(default)]
-    pub lts: Option<bool>,
-
-    #[serde(default)]
-    pub security: Option<bool>,
-
-    #[serde(rename = "cve-list", default, deserialize_with = "null_to_vec")]
-    pub cve_list: Vec<CVE>,
-
-    #[serde(rename = "release-notes", default)]
-    pub release_notes: Option<String>,
-
-    #[serde(default)]
-    pub runtime: Option<DotnetRuntime>,
-
-    #[serde(default)]
-    pub sdk: Option<DotnetSdk>,
-
-    #[serde(default, deserialize_with = "null_to_vec")]
-    pub sdks: Vec<DotnetSdk>, // può essere vuoto se null nel JSON
-
-    #[serde(rename = "aspnetcore-runtime", default)]
-    pub aspnetcore_runtime: Option<AspNetCoreRuntime>,
-
-    #[serde(default)]
-    pub windowsdesktop: Option<WindowsDesktop>,
-}
+impl Commands {
+    /// L'operazione da registrare nel giornale, per i comandi che cambiano gli SDK o i pin.
+    fn operation(&self) -> Option<Operation> {
+        match self {
+            Commands::Install { .. } => Some(Operation::Install),
+            Commands::Uninstall { .. } => Some(Operation::Uninstall),
+            Commands::Use { .. } => Some(Operation::Use),
+            Commands::Maintain { dry_run: false } => Some(Operation::Maintain),
+            Commands::Upgrade { .. } => Some(Operation::Upgrade),
+            _ => None,
+        }
+    }
 
-#[derive(Debug, Deserialize)]
-pub struct CVE {
-    #[serde(rename = "cve-id")]
-    pub cve_id: String,
+    /// Il nome nel documento con l'esito per SDK, per i comandi che lo stampano.
+    fn outcome_name(&self) -> Option<&'static str> {
+        match self {
+            Commands::Install { .. } => Some("install"),
+            Commands::Uninstall { .. } => Some("uninstall"),
+            Commands::Upgrade { .. } => Some("upgrade"),
+            _ => None,
+        }
+    }
 
-    #[serde(rename = "cve-url")]
-    pub cve_url: String,
+    /// I comandi dopo cui può comparire l'avviso sugli aggiornamenti: non quelli letti
+    /// da shell e strumenti, né quelli che già mostrano gli aggiornamenti.
+    fn shows_update_notice(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Prompt { .. }
+                | Commands::Status
+                | Commands::CheckUpdates { .. }
+                | Commands::Maintain { .. }
+                | Commands::SelfUninstall { .. }
+                | Commands::Export
+                | Commands::Env { .. }
+                | Commands::Direnv { .. }
+                | Commands::Exec { .. }
+                | Commands::Config { .. }
+                | Commands::BuildEnv
+                | Commands::Asdf { .. }
+                | Commands::Completions { .. }
+                | Commands::Mangen { .. }
+                | Commands::Complete { .. }
+        )
+    }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct FileInfo {
-    pub name: String,
-    pub rid: Option<String>,
-    pub url: String,
-    pub hash: Option<String>,
-    #[serde(default)]
-    pub akams: Option<String>,
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Print the value of a key
+    Get { key: String },
+    /// Set a key (use `env.<VAR>` for environment variables, `roots.<NAME>` for named roots and `tools.<ID>` for global tools)
+    Set { key: String, value: String },
+    /// Remove a key
+    Unset { key: String },
+    /// List all keys set in the file
+    List,
+    /// Print the path of the configuration file
+    Path {
+        /// Also print the cache and state directories
+        #[arg(long)]
+        all: bool,
+    },
+    /// Open the configuration file in $VISUAL or $EDITOR
+    Edit,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DotnetRuntime {
-    pub version: Option<String>,
-    #[serde(rename = "version-display")]
-    pub version_display: Option<String>,
-    #[serde(rename = "vs-version")]
-    pub vs_version: Option<String>,
-    #[serde(rename = "vs-mac-version")]
-    pub vs_mac_version: Option<String>,
-    #[serde(default)]
-    pub files: Vec<FileInfo>, // può essere vuoto se null
+#[derive(Subcommand, Debug)]
+enum WorkloadAction {
+    /// List the workloads installed on the SDK
+    List,
+    /// Install workloads and replay them on later feature bands of the SDK
+    Install {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Uninstall workloads
+    Uninstall {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Update the installed workloads to the newest manifests of the SDK's band
+    Update,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DotnetSdk {
-    pub version: Option<String>,
-    #[serde(rename = "version-display")]
-    pub version_display: Option<String>,
-    #[serde(rename = "runtime-version")]
-    pub runtime_version: Option<String>,
-    #[serde(rename = "vs-version")]
-    pub vs_version: Option<String>,
-    #[serde(rename = "vs-mac-version")]
-    pub vs_mac_version: Option<String>,
-    #[serde(rename = "vs-support")]
-    pub vs_support: Option<String>,
-    #[serde(rename = "vs-mac-support")]
-    pub vs_mac_support: Option<String>,
-    #[serde(rename = "csharp-version")]
-    pub csharp_version: Option<String>,
-    #[serde(rename = "fsharp-version")]
-    pub fsharp_version: Option<String>,
-    #[serde(rename = "vb-version")]
-    pub vb_version: Option<String>,
-    #[serde(default)]
-    pub files: Vec<FileInfo>,
+#[derive(Subcommand, Debug)]
+enum ToolAction {
+    /// Install or update the declared tools with the active SDK and report version drift
+    Sync {
+        /// Only report the drift; exit with 4 if any tool differs
+        #[arg(long)]
+        check: bool,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-pub struct AspNetCoreRuntime {
-    pub version: Option<String>,
-    #[serde(rename = "version-display")]
-    pub version_display: Option<String>,
-    #[serde(rename = "version-aspnetcoremodule", default, deserialize_with = "null_to_vec")]
-    pub version_aspnetcoremodule: Vec<String>, // può essere vuoto se null
-    #[serde(rename = "vs-version")]
-    pub vs_version: Option<String>,
-    #[serde(default)]
-    pub files: Vec<FileInfo>,
+#[derive(Subcommand, Debug)]
+enum RuntimeAction {
+    /// List the installed shared runtimes
+    List,
+    /// Pin the app's *.runtimeconfig.json files to a runtime version
+    Use {
+        /// Runtime version, e.g. 8.0.11
+        version: String,
+        /// Folder of the published app (defaults to the current directory)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// How the host may roll forward from the pinned version
+        #[arg(long, value_enum, default_value = "latest-patch")]
+        roll_forward: RollForward,
+    },
+    /// Install the runtimes required by the app's *.runtimeconfig.json files
+    Install {
+        /// Folder of the published app (defaults to the current directory)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-pub struct WindowsDesktop {
-    pub version: Option<String>,
-    #[serde(rename = "version-display")]
-    pub version_display: Option<String>,
-    #[serde(default)]
-    pub files: Vec<FileInfo>,
+#[derive(Subcommand, Debug)]
+enum CleanAction {
+    /// Show the dver and NuGet cache locations and sizes, then empty the dver cache
+    Caches {
+        /// Also empty the NuGet package folder and HTTP cache
+        #[arg(long)]
+        nuget: bool,
+        /// Only show the locations and sizes
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show the dotnet new template caches and bundled template packs per SDK, then clear the caches of removed SDKs
+    Templates {
+        /// Clear the caches of every SDK, so that dotnet new rebuilds them
+        #[arg(long)]
+        all: bool,
+        /// Only show the caches and template packs
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
-// --- Funzioni di utilità ---
-fn get_home_dir() -> Option<PathBuf> {
-    if cfg!(windows) {
-        std::env::var_os("USERPROFILE").map(PathBuf::from)
-    } else {
-        std::env::var_os("HOME").map(PathBuf::from)
-    }
+#[derive(Subcommand, Debug)]
+enum SyncAction {
+    /// Upload this machine's toolchain manifest and portable settings
+    Push,
+    /// Install what the synced manifest lists and apply its settings
+    Pull {
+        /// Also uninstall SDKs that the synced manifest does not list
+        #[arg(long)]
+        prune: bool,
+    },
 }
 
-fn is_dotnet_installed() -> bool {
-    Command::new("dotnet")
-        .arg("--version")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+#[derive(Subcommand, Debug)]
+enum AsdfAction {
+    /// Print every published SDK version, oldest first, on one line
+    ListAll,
+    /// Print the newest stable SDK version starting with the query
+    LatestStable { query: Option<String> },
+    /// Install an SDK into an asdf install path (defaults to ASDF_INSTALL_VERSION and ASDF_INSTALL_PATH)
+    Install {
+        version: Option<String>,
+        path: Option<PathBuf>,
+    },
+    /// Write an asdf/mise plugin whose scripts call dver
+    Plugin {
+        /// Directory to create the plugin in
+        dir: PathBuf,
+    },
 }
 
-fn null_to_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
-where
-    D: Deserializer<'de>,
-    T: serde::Deserialize<'de>,
-{
-    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CompletionKind {
+    /// Installed SDK versions
+    Installed,
+    /// SDK versions published in the Microsoft release metadata
+    Remote,
 }
 
-
-fn list_installed_sdks() -> Result<Vec<(String, PathBuf)>, Box<dyn std::error::Error>> {
-    let output = Command::new("dotnet")
-        .args(["--list-sdks"])
-        .output()?;
-    if !output.status.success() {
-        return Err("Failed to list SDKs".into());
-    }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut sdks = Vec::new();
-    for line in stdout.lines() {
-        if let Some((ver_part, path_part)) = line.split_once('[') {
-            let version = ver_part.trim().split_whitespace().next().unwrap_or("").to_string();
-            let base = path_part.trim().trim_end_matches(']').trim();
-            if version.is_empty() || base.is_empty() { continue; }
-            let mut pb = PathBuf::from(base);
-            pb.push(&version);
-            sdks.push((version, pb));
-        }
+// --- Errori ---
+fn report_error(err: &DverError, format: OutputFormat) {
+    // Il rapporto strutturato di `audit` è già stato stampato: basta l'exit code
+    if format != OutputFormat::Human && matches!(err, DverError::Vulnerable(_)) {
+        return;
     }
-    Ok(sdks)
-}
-
-// --- Download e installazione ---
-async fn download_install_script() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let script_url = if cfg!(windows) {
-        "https://dotnet.microsoft.com/download/dotnet/scripts/v1/dotnet-install.ps1"
+    if format == OutputFormat::Json {
+        let doc = json!({
+            "error": {
+                "code": err.code(),
+                "exit_code": err.exit_code(),
+                "message": err.to_string(),
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&doc).unwrap_or_default());
     } else {
-        "https://dotnet.microsoft.com/download/dotnet/scripts/v1/dotnet-install.sh"
-    };
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
-
-    let response = client
-        .get(script_url)
-        .header(header::USER_AGENT, "dver/0.1 (dotnet-version-manager)")
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        return Err(format!("Failed to download installer script: HTTP {}", response.status()).into());
-    }
-
-    let script_content = response.bytes().await?;
-
-    let mut file_path = std::env::temp_dir();
-    let script_name = if cfg!(windows) { "dotnet-install.ps1" } else { "dotnet-install.sh" };
-    let unique = format!("{}_{}", script_name, std::process::id());
-    file_path.push(unique);
-
-    let mut file = File::create(&file_path)?;
-    std::io::Write::write_all(&mut file, &script_content)?;
-
-    if !cfg!(windows) {
-        let mut perms = fs::metadata(&file_path)?.permissions();
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            perms.set_mode(0o755);
-            fs::set_permissions(&file_path, perms)?;
+        eprintln!("Error [{}]: {}", err.code(), err);
+        // In CI l'errore compare anche tra i problemi del build
+        if let Some(annotation) = CiProvider::detect().and_then(|ci| ci.error_annotation(&err.to_string())) {
+            println!("{}", annotation);
         }
     }
-
-    Ok(file_path)
 }
 
-async fn install_dotnet(lts: bool, version: Option<String>, install_path: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-    let script_path = download_install_script().await?;
+// --- MAIN ---
+fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    let mut command = if cfg!(windows) {
-        let mut cmd = Command::new("powershell");
-        cmd.arg("-NoLogo").arg("-NoProfile").arg("-NonInteractive");
-        cmd.arg("-ExecutionPolicy").arg("Bypass");
-        cmd.arg("-File").arg(&script_path);
-        cmd
-    } else {
-        let mut cmd = Command::new("bash");
-        cmd.arg(&script_path);
-        cmd
+    let loaded = Config::load()
+        .and_then(Config::with_env_overrides)
+        .and_then(|config| cli.apply_overrides(config));
+    let config = match loaded {
+        Ok(config) => config,
+        // `dver config` deve funzionare anche per riparare un file non valido
+        Err(_) if matches!(cli.command, Commands::Config { .. }) => Config::default(),
+        Err(e) => {
+            report_error(&e, cli.output_format().unwrap_or(OutputFormat::Human));
+            return ExitCode::from(e.exit_code());
+        }
     };
+    let prompter = Prompter::new(cli.yes, cli.non_interactive, config.color);
+    let ctx = Context::new(config, prompter, Box::new(SystemRunner));
 
-    if lts {
-        command.arg("-Channel").arg("LTS");
-    } else if let Some(v) = version {
-        command.arg("-Version").arg(v);
-    }
-
-    if let Some(path) = install_path {
-        command.arg("-InstallDir").arg(path);
+    let started = Instant::now();
+    let result = run(&cli, &ctx);
+    if let Some(operation) = cli.command.operation() {
+        ctx.record(operation, result.as_ref().map(|_| ()));
     }
-
-    let output = command.output()?;
-    let _ = remove_file(&script_path);
-
-    if !output.status.success() {
-        eprintln!("dotnet-install script failed with status: {:?}", output.status.code());
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if !stderr.is_empty() { eprintln!("{}", stderr.trim()); }
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if !stdout.is_empty() { eprintln!("{}", stdout.trim()); }
-        return Err("dotnet installation failed".into());
-    }
-
-    println!("{}", String::from_utf8_lossy(&output.stdout));
-    Ok(())
-}
-
-// --- Controlli comuni ---
-fn run_doctor_checks() {
-    println!("Checking for common issues...");
-    if is_dotnet_installed() {
-        println!("✅ dotnet command is available in your PATH.");
-    } else {
-        println!("❌ dotnet command not found. Please install .NET and ensure PATH is correct.");
-        return;
+    // Con un output strutturato l'esito, anche di un errore, è un documento solo
+    let reported = match cli.command.outcome_name().filter(|_| !ctx.is_human()) {
+        Some(command) => match ctx.print_outcome(command, result.as_ref().map(|_| ()), started.elapsed()) {
+            Ok(()) => true,
+            Err(e) => {
+                report_error(&e, ctx.output_format());
+                return ExitCode::from(e.exit_code());
+            }
+        },
+        None => false,
+    };
+    if result.is_ok() && ctx.is_human() && cli.command.shows_update_notice() && CiProvider::detect().is_none() {
+        if let Some(notice) = update_notice(&ctx.config, SystemTime::now()) {
+            eprintln!("{}", notice);
+        }
     }
-
-    if let Some(home_dir) = get_home_dir() {
-        let dotnet_dir = home_dir.join(".dotnet");
-        if let Ok(path_var) = std::env::var("PATH") {
-            if path_var.split(':').any(|p| Path::new(p) == dotnet_dir) {
-                println!("✅ .NET SDK installation directory is in your PATH.");
-            } else {
-                println!("⚠️ .NET SDK installation directory (~/.dotnet) might not be in PATH.");
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if !reported {
+                report_error(&e, ctx.output_format());
             }
+            ExitCode::from(e.exit_code())
         }
     }
 }
 
-// --- Funzione Remote (tutte le patch disponibili) ---
-pub async fn list_remote_patch_sdks(lts_only: bool) -> Result<(), Box<dyn std::error::Error>> {
-    let index_url = "https://dotnetcli.blob.core.windows.net/dotnet/release-metadata/releases-index.json";
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
-
-    let resp = client.get(index_url)
-        .header(reqwest::header::USER_AGENT, "dver/0.1 (dotnet-version-manager)")
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        return Err(format!("Failed to fetch releases-index.json: HTTP {}", resp.status()).into());
-    }
-
-    let body = resp.text().await?;
-    let index: ReleaseIndex = serde_json::from_str(&body)?;
-
-    println!("Remote .NET SDK versions available:");
-
-    for channel in &index.releases_index {
-        // sicuro perché usiamo default se mancante
-        let channel_version = channel.channel_version.as_deref().unwrap_or("unknown");
-        let release_type = channel.release_type.as_deref().unwrap_or("unknown");
-
-        if lts_only && release_type != "lts" {
-            continue;
+fn run(cli: &Cli, ctx: &Context) -> Result<(), DverError> {
+    match &cli.command {
+        Commands::Current => commands::current::run(ctx),
+        Commands::Status => commands::status::run(ctx),
+        Commands::List { provenance, size } => commands::list::run(ctx, *provenance, *size),
+        Commands::Use { version } => block_on(commands::r#use::run(ctx, version.clone()))?,
+        Commands::Init { channel, roll_forward, tools, force } => {
+            block_on(commands::init::run(ctx, channel.clone(), roll_forward, *tools, *force))?
         }
-
-        println!("Channel: {} ({})", channel_version, release_type);
-        println!("Fetching releases from: {}", channel.releases_json);
-
-        let releases_resp = client.get(&channel.releases_json)
-            .header(reqwest::header::USER_AGENT, "dver/0.1 (dotnet-version-manager)")
-            .send()
-            .await?;
-
-        if !releases_resp.status().is_success() {
-            eprintln!("Failed to fetch {}: HTTP {}", channel.releases_json, releases_resp.status());
-            continue;
+        Commands::Pin { check } => commands::pin::run(ctx, *check),
+        Commands::Install { lts, version, install_path, from_project, for_project, .. } => {
+            block_on(commands::install::run(ctx, *lts, version.clone(), install_path.clone(), *from_project, *for_project))?
         }
-
-        let releases_body = releases_resp.text().await?;
-        let channel_releases: ChannelReleases = serde_json::from_str(&releases_body)?;
-
-        for release in &channel_releases.releases {
-            println!("{}", release.version.as_deref().unwrap_or("unknown"));
+        Commands::Uninstall { version, all, force, wait } => {
+            commands::uninstall::run(ctx, version.clone(), *all, *force, Duration::from_secs(*wait))
         }
-
-    }
-
-    Ok(())
-}
-
-
-// --- MAIN ---
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-
-    match &cli.command {
-        Commands::Current => {
-            let output = Command::new("dotnet")
-                .arg("--version")
-                .output()?;
-            if output.status.success() {
-                let version = String::from_utf8_lossy(&output.stdout);
-                println!("Current dotnet version: {}", version.trim());
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                eprintln!("Failed to get current dotnet version{}{}",
-                          if stderr.trim().is_empty() { "" } else { ": " }, stderr.trim());
-            }
+        Commands::Adopt { replace_system: true, .. } => commands::adopt::replace_system(ctx),
+        Commands::Adopt { paths, manage, relocate, force, .. } => commands::adopt::run(ctx, paths, *manage, *relocate, *force),
+        Commands::Workload { sdk, action } => commands::workload::run(ctx, sdk.as_deref(), action),
+        Commands::Tool { action } => commands::tool::run(ctx, action),
+        Commands::Runtime { action } => block_on(commands::runtime::run(ctx, action))?,
+        Commands::Clean { action } => commands::clean::run(ctx, action),
+        Commands::History { operation, failed, since, limit } => {
+            commands::history::run(ctx, *operation, *failed, since.as_deref(), *limit)
         }
-        Commands::List => {
-            let output = Command::new("dotnet")
-                .args(["--list-sdks"])
-                .output()?;
-            if output.status.success() {
-                let sdks = String::from_utf8_lossy(&output.stdout);
-                let mut versions: Vec<String> = sdks
-                    .lines()
-                    .filter_map(|line| line.split_whitespace().next().map(|s| s.to_string()))
-                    .collect();
-                versions.sort();
-                versions.dedup();
-                for v in versions {
-                    println!("{}", v);
-                }
-            } else {
-                eprintln!("Failed to list SDK versions");
-            }
+        Commands::Setup { defaults, no_sdk, shell } => block_on(commands::setup::run(ctx, *defaults, *no_sdk, *shell))?,
+        Commands::SelfUninstall { keep_sdks, dry_run } => commands::self_uninstall::run(ctx, *keep_sdks, *dry_run),
+        Commands::Doctor { bundle: Some(path) } => commands::doctor::bundle(ctx, path),
+        Commands::Doctor { bundle: None } => {
+            commands::doctor::run(ctx);
+            Ok(())
         }
-        Commands::Use { version } => {
-            let json_data = json!({
-                "sdk": {
-                    "version": version
-                }
-            });
-            let file_path = std::env::current_dir()?.join("global.json");
-            if file_path.exists() {
-                let backup = file_path.with_extension("json.bak");
-                let _ = fs::copy(&file_path, &backup);
-            }
-            let file = File::create(&file_path)?;
-            serde_json::to_writer_pretty(file, &json_data)?;
-            println!("SDK version set to {} in {:?}", version, file_path);
+        Commands::Info { version } => commands::info::run(ctx, version),
+        Commands::Verify { version, repair } => block_on(commands::verify::run(ctx, version, *repair))?,
+        Commands::Audit => block_on(commands::audit::run(ctx))?,
+        Commands::CheckUpdates { markdown } => block_on(commands::check_updates::run(ctx, *markdown))?,
+        Commands::Report => block_on(commands::report::run(ctx))?,
+        Commands::Sbom { format } => block_on(commands::sbom::run(ctx, *format))?,
+        Commands::Containerize { bundle } => block_on(commands::containerize::run(ctx, bundle.clone()))?,
+        Commands::Devcontainer { feature } => commands::containerize::devcontainer(feature.as_deref()),
+        Commands::Export => commands::export::export(ctx),
+        Commands::Import { file, prune } => block_on(commands::export::import(ctx, file, *prune))?,
+        Commands::Sync { action } => block_on(commands::sync::run(ctx, action))?,
+        Commands::Maintain { dry_run } => block_on(commands::maintain::run(ctx, *dry_run))?,
+        Commands::Upgrade { interactive } => block_on(commands::upgrade::run(ctx, *interactive))?,
+        Commands::Diff { from, to, files } => block_on(commands::diff::run(ctx, from, to, *files))?,
+        Commands::Remote { lts, channel, sort, since, limit } => {
+            block_on(commands::remote::run(ctx, *lts, channel.as_deref(), *sort, since.as_deref(), *limit))?
         }
-        Commands::Install { lts, version, install_path } => {
-            if is_dotnet_installed() {
-                println!("dotnet is already installed.");
-                let output = Command::new("dotnet")
-                    .arg("--version")
-                    .output()?;
-                println!("Current version: {}", String::from_utf8_lossy(&output.stdout).trim());
-            } else {
-                println!("Installing dotnet...");
-                install_dotnet(*lts, version.clone(), install_path.clone()).await?;
-                println!("dotnet installation completed.");
-            }
+        Commands::Env { ci: true, .. } => commands::env::env_ci(ctx),
+        Commands::Env { shell, project, .. } => commands::env::env(ctx, *shell, *project),
+        Commands::Direnv { lib } => {
+            commands::direnv::run(*lib);
+            Ok(())
         }
-        Commands::Uninstall { version, all } => {
-            let sdks = list_installed_sdks()?;
-            let mut roots: Vec<PathBuf> = sdks
-                .iter()
-                .filter_map(|(_, p)| p.parent().map(|pp| pp.to_path_buf()))
-                .collect();
-            roots.sort();
-            roots.dedup();
-
-            let targets: Vec<(String, PathBuf)> = if *all {
-                sdks
-            } else if let Some(v) = version {
-                if v.contains('.') {
-                    sdks.into_iter().filter(|(ver, _)| ver == v).collect()
-                } else {
-                    let prefix = format!("{}.", v);
-                    sdks.into_iter().filter(|(ver, _)| ver.starts_with(&prefix)).collect()
-                }
-            } else {
-                eprintln!("Provide a version or --all to uninstall.");
-                Vec::new()
-            };
-
-            if targets.is_empty() {
-                println!("No matching SDKs found.");
-            } else {
-                for (ver, path) in targets {
-                    let is_under_root = roots.iter().any(|r| path.starts_with(r));
-                    if !is_under_root {
-                        eprintln!("Skipping {}: path {:?} outside known SDK roots", ver, path);
-                        continue;
-                    }
-                    if path.exists() {
-                        match remove_dir_all(&path) {
-                            Ok(_) => println!("Removed {}", ver),
-                            Err(e) => eprintln!("Failed to remove {}: {}", ver, e),
-                        }
-                    } else {
-                        println!("Directory for {} not found", ver);
-                    }
-                }
-            }
+        Commands::Exec { command } => commands::env::exec(ctx, command),
+        Commands::BuildEnv => commands::build_env::run(ctx),
+        Commands::Prompt { env } => commands::segment::run(ctx, *env),
+        Commands::Vscode { print } => commands::vscode::run(ctx, *print),
+        Commands::Asdf { action } => block_on(commands::asdf::run(ctx, action))?,
+        Commands::Config { action } => commands::config::run(ctx, action),
+        Commands::Completions { shell } => {
+            commands::completions::print_completions(*shell);
+            Ok(())
         }
-        Commands::Doctor => run_doctor_checks(),
-        Commands::Remote { lts } => {
-            if let Err(e) = list_remote_patch_sdks(*lts).await {
-                eprintln!("Failed to list remote SDKs: {}", e);
-            }
+        Commands::Mangen { out_dir } => commands::completions::generate_man_pages(out_dir.as_deref()),
+        Commands::Complete { kind } => {
+            commands::completions::complete(ctx, *kind);
+            Ok(())
         }
     }
-
-    Ok(())
 }
@@ -0,0 +1,165 @@
+//! Domande all'utente: conferme, selettori di versione e scelte multiple.
+
+use std::io::IsTerminal;
+
+use dver_core::ci::CiProvider;
+use dver_core::config::ColorMode;
+use dver_core::DverError;
+
+/// Tutte le domande all'utente passano da qui, così `--yes`, `--non-interactive`
+/// e gli ambienti senza TTY/CI non restano mai in attesa di input. Le build senza la
+/// feature `interactive` non fanno mai domande.
+#[derive(Debug, Clone, Copy)]
+pub struct Prompter {
+    assume_yes: bool,
+    pub interactive: bool,
+    colorful: bool,
+}
+
+impl Prompter {
+    pub fn new(assume_yes: bool, non_interactive: bool, color: ColorMode) -> Self {
+        let interactive = cfg!(feature = "interactive")
+            && !non_interactive
+            && CiProvider::detect().is_none()
+            && std::io::stdin().is_terminal()
+            && std::io::stderr().is_terminal();
+        let colorful = match color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => interactive && std::env::var_os("NO_COLOR").is_none(),
+        };
+        Prompter { assume_yes, interactive, colorful }
+    }
+
+    /// Vero con `--yes`.
+    pub fn assume_yes(&self) -> bool {
+        self.assume_yes
+    }
+
+    /// Chiede conferma; senza terminale restituisce `default` (o `true` con `--yes`).
+    pub fn confirm(&self, message: &str, default: bool) -> Result<bool, DverError> {
+        if self.assume_yes {
+            return Ok(true);
+        }
+        if !self.interactive {
+            return Ok(default);
+        }
+        terminal::confirm(self.colorful, message, default)
+    }
+
+    /// Chiede un valore; senza terminale, con `--yes` o con una risposta vuota
+    /// restituisce `default`.
+    pub fn input(&self, message: &str, default: &str) -> Result<String, DverError> {
+        if self.assume_yes || !self.interactive {
+            return Ok(default.to_string());
+        }
+        let answer = terminal::input(self.colorful, &format!("{} [{}]", message, default))?;
+        Ok(Some(answer.trim()).filter(|a| !a.is_empty()).unwrap_or(default).to_string())
+    }
+
+    /// Chiede di digitare `expected` per confermare un'operazione pericolosa. Né `--yes`
+    /// né l'assenza di un terminale bastano: senza terminale la risposta è no.
+    pub fn confirm_typed(&self, message: &str, expected: &str) -> Result<bool, DverError> {
+        if !self.interactive {
+            return Ok(false);
+        }
+        let answer = terminal::input(self.colorful, &format!("{} Type {} to confirm", message, expected))?;
+        Ok(answer.trim() == expected)
+    }
+
+    /// Apre un selettore fuzzy; restituisce `None` se l'utente annulla con Esc.
+    pub fn pick_version(&self, prompt: &str, versions: &[String]) -> Result<Option<String>, DverError> {
+        if versions.is_empty() {
+            return Ok(None);
+        }
+        let selection = terminal::fuzzy_select(self.colorful, prompt, versions)?;
+        Ok(selection.map(|i| versions[i].clone()))
+    }
+
+    /// Apre un elenco in cui spuntare più voci, con `checked` spuntate all'inizio;
+    /// restituisce gli indici scelti, o `None` se l'utente annulla con Esc.
+    pub fn pick_many(&self, prompt: &str, items: &[String], checked: &[bool]) -> Result<Option<Vec<usize>>, DverError> {
+        if items.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+        terminal::multi_select(self.colorful, prompt, items, checked)
+    }
+}
+
+#[cfg(feature = "interactive")]
+mod terminal {
+    use dver_core::DverError;
+
+    fn theme(colorful: bool) -> Box<dyn dialoguer::theme::Theme> {
+        if colorful {
+            Box::new(dialoguer::theme::ColorfulTheme::default())
+        } else {
+            Box::new(dialoguer::theme::SimpleTheme)
+        }
+    }
+
+    fn prompt_error(e: dialoguer::Error) -> DverError {
+        match e {
+            dialoguer::Error::IO(io) => io.into(),
+        }
+    }
+
+    pub fn confirm(colorful: bool, message: &str, default: bool) -> Result<bool, DverError> {
+        dialoguer::Confirm::with_theme(theme(colorful).as_ref())
+            .with_prompt(message)
+            .default(default)
+            .interact()
+            .map_err(prompt_error)
+    }
+
+    pub fn input(colorful: bool, message: &str) -> Result<String, DverError> {
+        dialoguer::Input::with_theme(theme(colorful).as_ref())
+            .with_prompt(message)
+            .allow_empty(true)
+            .interact_text()
+            .map_err(prompt_error)
+    }
+
+    pub fn fuzzy_select(colorful: bool, prompt: &str, items: &[String]) -> Result<Option<usize>, DverError> {
+        dialoguer::FuzzySelect::with_theme(theme(colorful).as_ref())
+            .with_prompt(prompt)
+            .items(items)
+            .default(0)
+            .max_length(15)
+            .interact_opt()
+            .map_err(prompt_error)
+    }
+
+    pub fn multi_select(colorful: bool, prompt: &str, items: &[String], checked: &[bool]) -> Result<Option<Vec<usize>>, DverError> {
+        dialoguer::MultiSelect::with_theme(theme(colorful).as_ref())
+            .with_prompt(prompt)
+            .items(items)
+            .defaults(checked)
+            .max_length(15)
+            .interact_opt()
+            .map_err(prompt_error)
+    }
+}
+
+/// Senza la feature `interactive` `Prompter::interactive` è sempre falso e queste
+/// funzioni non vengono mai chiamate.
+#[cfg(not(feature = "interactive"))]
+mod terminal {
+    use dver_core::DverError;
+
+    pub fn confirm(_: bool, _: &str, default: bool) -> Result<bool, DverError> {
+        Ok(default)
+    }
+
+    pub fn input(_: bool, _: &str) -> Result<String, DverError> {
+        Ok(String::new())
+    }
+
+    pub fn fuzzy_select(_: bool, _: &str, _: &[String]) -> Result<Option<usize>, DverError> {
+        Ok(None)
+    }
+
+    pub fn multi_select(_: bool, _: &str, _: &[String], _: &[bool]) -> Result<Option<Vec<usize>>, DverError> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,117 @@
+//! Ambiente isolato per i test end-to-end: una HOME temporanea, una radice di
+//! installazione vuota e un server HTTP locale che serve i metadati di `tests/fixtures`.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use tempfile::TempDir;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+/// Server HTTP che serve i file di `tests/fixtures`, sostituendo `{{base}}` con il suo indirizzo.
+pub struct FixtureServer {
+    pub base: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FixtureServer {
+    pub fn start() -> FixtureServer {
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("start fixture server");
+        let base = format!("http://{}", server.server_addr().to_ip().expect("ip address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let (served_base, log) = (base.clone(), Arc::clone(&requests));
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let path = request.url().trim_start_matches('/').to_string();
+                log.lock().unwrap().push(path.clone());
+                let file = Path::new(FIXTURES).join(&path);
+                let response = match std::fs::read_to_string(&file) {
+                    Ok(body) if !path.contains("..") => {
+                        tiny_http::Response::from_string(body.replace("{{base}}", &served_base))
+                    }
+                    _ => tiny_http::Response::from_string("not found").with_status_code(404),
+                };
+                let _ = request.respond(response);
+            }
+        });
+
+        FixtureServer { base, requests }
+    }
+
+    /// Percorsi richiesti finora, senza la `/` iniziale.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Una HOME temporanea con la configurazione che punta al server di fixture.
+pub struct TestEnv {
+    pub home: TempDir,
+    pub server: FixtureServer,
+}
+
+impl TestEnv {
+    pub fn new() -> TestEnv {
+        TestEnv { home: TempDir::new().expect("temp home"), server: FixtureServer::start() }
+    }
+
+    /// Radice gestita in cui il finto dotnet-install crea gli SDK.
+    pub fn install_root(&self) -> PathBuf {
+        self.home.path().join("dotnet")
+    }
+
+    /// Cartella di lavoro in cui gira il comando (per global.json e .dver.toml).
+    pub fn work_dir(&self) -> PathBuf {
+        let dir = self.home.path().join("work");
+        std::fs::create_dir_all(&dir).expect("work dir");
+        dir
+    }
+
+    /// Comando `dver` con un ambiente pulito: nessuna variabile ereditata tranne PATH.
+    pub fn command(&self) -> Command {
+        let home = self.home.path();
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_dver"));
+        cmd.env_clear()
+            .current_dir(self.work_dir())
+            .env("PATH", "/usr/bin:/bin")
+            .env("HOME", home)
+            .env("XDG_CONFIG_HOME", home.join("config"))
+            .env("XDG_CACHE_HOME", home.join("cache"))
+            .env("XDG_STATE_HOME", home.join("state"))
+            .env("DVER_INDEX_URL", format!("{}/releases-index.json", self.server.base))
+            .env("DVER_INSTALL_SCRIPT_URL", format!("{}/dotnet-install.sh", self.server.base))
+            .env("DVER_INSTALL_ROOT", self.install_root())
+            .env("DVER_RETRIES", "0")
+            .arg("--non-interactive");
+        cmd
+    }
+
+    pub fn run(&self, args: &[&str]) -> Output {
+        self.command().args(args).output().expect("run dver")
+    }
+
+    /// Esegue il comando aspettandosi successo e ne restituisce lo stdout.
+    pub fn stdout(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        assert!(
+            output.status.success(),
+            "dver {:?} failed with {:?}\nstdout: {}\nstderr: {}",
+            args,
+            output.status.code(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+        String::from_utf8(output.stdout).expect("utf-8 stdout")
+    }
+
+    pub fn json(&self, args: &[&str]) -> serde_json::Value {
+        let mut all = vec!["--json"];
+        all.extend_from_slice(args);
+        serde_json::from_str(&self.stdout(&all)).expect("valid JSON output")
+    }
+}
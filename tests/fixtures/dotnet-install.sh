@@ -0,0 +1,109 @@
+#!/usr/bin/env bash
+# Finto dotnet-install per i test: crea la struttura di una radice .NET
+# (sdk/<versione>, shared/Microsoft.NETCore.App/<runtime> e un eseguibile dotnet) senza scaricare nulla.
+set -e
+
+version=""
+channel=""
+quality=""
+install_dir=""
+runtime_kind=""
+while [ $# -gt 0 ]; do
+    case "$1" in
+        -Version) version="$2"; shift 2 ;;
+        -Channel) channel="$2"; shift 2 ;;
+        -Quality) quality="$2"; shift 2 ;;
+        -InstallDir) install_dir="$2"; shift 2 ;;
+        -Runtime) runtime_kind="$2"; shift 2 ;;
+        *) shift ;;
+    esac
+done
+
+if [ -n "$runtime_kind" ]; then
+    # Solo il runtime condiviso, senza SDK
+    case "$runtime_kind" in
+        dotnet) framework="Microsoft.NETCore.App" ;;
+        aspnetcore) framework="Microsoft.AspNetCore.App" ;;
+        *) echo "dotnet-install: unknown runtime '$runtime_kind'" >&2; exit 1 ;;
+    esac
+    mkdir -p "$install_dir/shared/$framework/$version"
+    echo "dotnet-install: Installed $framework $version"
+    exit 0
+fi
+
+if [ -z "$version" ]; then
+    case "$channel" in
+        LTS|8.0) version="8.0.404" ;;
+        9.0) version="9.0.100" ;;
+        # Il canale 10.0 ha solo build giornaliere
+        10.0) [ "$quality" = daily ] && version="10.0.100-alpha.1.25501.1" ;;
+        *) echo "dotnet-install: unknown channel '$channel'" >&2; exit 1 ;;
+    esac
+fi
+case "$version" in
+    8.0.404|8.0.307) runtime="8.0.11" ;;
+    8.0.403) runtime="8.0.10" ;;
+    9.0.100) runtime="9.0.0" ;;
+    10.0.100) runtime="10.0.0" ;;
+    10.0.100-alpha.1.25501.1) runtime="10.0.0-alpha.1.25501.1" ;;
+    *) echo "dotnet-install: Could not find version $version" >&2; exit 1 ;;
+esac
+
+mkdir -p "$install_dir/sdk/$version/Sdks" "$install_dir/shared/Microsoft.NETCore.App/$runtime"
+echo "sdk $version" > "$install_dir/sdk/$version/dotnet.dll"
+echo "<Project />" > "$install_dir/sdk/$version/Sdks/Microsoft.NET.Sdk.props"
+mkdir -p "$install_dir/sdk/$version/SdkResolvers/Microsoft.DotNet.MSBuildSdkResolver"
+echo '{"libraries":{"Microsoft.Build/17.11.4":{},"NuGet.Build.Tasks/6.11.1":{}}}' > "$install_dir/sdk/$version/MSBuild.deps.json"
+cat > "$install_dir/dotnet" <<'SCRIPT'
+#!/usr/bin/env bash
+root="$(cd "$(dirname "$0")" && pwd)"
+case "$1" in
+    --version) ls "$root/sdk" | sort -V | tail -n 1 ;;
+    --list-sdks) for v in $(ls "$root/sdk" | sort -V); do echo "$v [$root/sdk]"; done ;;
+    --list-runtimes)
+        for f in $(ls "$root/shared"); do
+            for v in $(ls "$root/shared/$f" | sort -V); do echo "$f $v [$root/shared/$f]"; done
+        done ;;
+    tool)
+        # Strumenti globali in tools.txt, una riga "<id> <versione> <comando>"
+        tools="$root/tools.txt"
+        touch "$tools"
+        case "$2" in
+            list)
+                printf 'Package Id      Version      Commands\n'
+                printf -- '-------------------------------------\n'
+                cat "$tools" ;;
+            install|update)
+                shift 2
+                id=""
+                version="1.0.0"
+                while [ $# -gt 0 ]; do
+                    case "$1" in
+                        --version) version="$2"; shift 2 ;;
+                        --*) shift ;;
+                        *) id="$1"; shift ;;
+                    esac
+                done
+                grep -v "^$id " "$tools" > "$tools.new" || true
+                mv "$tools.new" "$tools"
+                echo "$id $version $id" >> "$tools"
+                echo "Tool '$id' (version '$version') was successfully installed." ;;
+            restore) echo "Restore was successful." ;;
+        esac ;;
+    workload)
+        if [ "$2" != "list" ]; then
+            # Registra l'SDK scelto dal global.json della cartella, come farebbe dotnet
+            sdk="$(ls "$root/sdk" | sort -V | tail -n 1)"
+            if [ -f global.json ]; then sdk="$(sed -n 's/.*"version": *"\([^"]*\)".*/\1/p' global.json)"; fi
+            echo "$sdk $*" >> "$root/workload.log"
+            echo "Successfully ran workload $2."
+            exit 0
+        fi
+        printf '\nInstalled Workload Id      Manifest Version       Installation Source\n'
+        printf -- '--------------------------------------------------------------------\n'
+        printf 'wasm-tools                 8.0.11/8.0.100         SDK 8.0.400\n\n'
+        printf 'Use `dotnet workload search` to find additional workloads to install.\n' ;;
+esac
+SCRIPT
+chmod +x "$install_dir/dotnet"
+echo "dotnet-install: Installed version $version"
@@ -0,0 +1,70 @@
+//! Snapshot dell'output pensato per le persone: `--help` di ogni comando e le
+//! tabelle di list, remote e doctor. Aggiornali con `cargo insta review` quando
+//! un cambiamento è voluto.
+
+#![cfg(unix)]
+
+mod common;
+
+use common::TestEnv;
+
+const SUBCOMMANDS: &[&str] = &[
+    "current", "list", "use", "install", "uninstall", "doctor", "remote", "env", "exec", "config", "completions",
+];
+
+#[test]
+fn help_text() {
+    let env = TestEnv::new();
+    insta::assert_snapshot!("help", env.stdout(&["--help"]));
+    for sub in SUBCOMMANDS {
+        insta::assert_snapshot!(format!("help_{}", sub), env.stdout(&[sub, "--help"]));
+    }
+    insta::assert_snapshot!("help_config_set", env.stdout(&["config", "set", "--help"]));
+}
+
+#[test]
+fn list_output() {
+    let env = TestEnv::new();
+    env.stdout(&["install", "--version", "9.0.100"]);
+    env.stdout(&["install", "--version", "8.0.404"]);
+    insta::assert_snapshot!(env.stdout(&["list"]));
+}
+
+#[test]
+fn list_output_across_named_roots() {
+    let env = TestEnv::new();
+    let (main, ci) = (env.home.path().join("main"), env.home.path().join("ci"));
+    for dir in [main.join("sdk/8.0.404"), main.join("sdk/9.0.100"), ci.join("sdk/8.0.307")] {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    let config_dir = env.home.path().join("config/dver");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!("[roots]\nmain = {:?}\nci = {:?}\n", main.to_str().unwrap(), ci.to_str().unwrap()),
+    )
+    .unwrap();
+
+    let output = env.command().env_remove("DVER_INSTALL_ROOT").arg("list").output().unwrap();
+    assert!(output.status.success());
+    insta::assert_snapshot!(String::from_utf8(output.stdout).unwrap());
+}
+
+#[test]
+fn remote_output() {
+    let env = TestEnv::new();
+    insta::assert_snapshot!(env.stdout(&["remote"]));
+    insta::assert_snapshot!("remote_lts", env.stdout(&["remote", "--lts"]));
+}
+
+#[test]
+fn doctor_output() {
+    let env = TestEnv::new();
+    insta::assert_snapshot!("doctor_without_dotnet", env.stdout(&["doctor"]));
+
+    env.stdout(&["install", "--version", "8.0.404"]);
+    std::fs::write(env.work_dir().join("global.json"), r#"{ "sdk": { "version": "9.0.100" } }"#).unwrap();
+    let output = env.stdout(&["doctor"]);
+    let output = output.replace(env.work_dir().to_str().unwrap(), "[WORK]").replace(env.install_root().to_str().unwrap(), "[ROOT]");
+    insta::assert_snapshot!("doctor_with_project_pin", output);
+}